@@ -0,0 +1,13 @@
+#!/bin/bash
+# Start a local validator for the integration tests, cloning the programs the
+# consumer builds transactions for from mainnet
+set -eux
+
+solana-test-validator \
+  --reset \
+  --url mainnet-beta \
+  --clone-upgradeable-program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s \
+  --clone-upgradeable-program BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY \
+  --clone-upgradeable-program cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS4EPT4Mp8GK \
+  --clone-upgradeable-program noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV \
+  "$@"
@@ -0,0 +1,436 @@
+use std::fmt;
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{NftEventKey, NftEvents},
+    DeadLetter as DeadLetterModel, EventRequest, MultisigProposal, TransactionAudit,
+    TransactionFailure,
+};
+use holaplex_hub_nfts_solana_entity::{
+    dead_letters, multisig_proposals, sea_orm_active_enums::SolanaCluster, transaction_audit,
+    transaction_failures,
+};
+use hub_core::{prelude::*, uuid::Uuid};
+use poem::{
+    get, handler,
+    http::StatusCode,
+    middleware::AddData,
+    post,
+    web::{Data, Json, Path, Query},
+    Endpoint, EndpointExt, Error, Route,
+};
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{auth, events::Processor, import};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Clone)]
+struct AdminState {
+    db: Connection,
+    processor: Processor,
+    import: import::Processor,
+}
+
+/// Routes for operators to inspect and resolve failed events and dead
+/// letters, and to pool durable nonce accounts. Every request must carry
+/// `Authorization: Bearer <api_key>`.
+pub fn routes(
+    db: Connection,
+    processor: Processor,
+    import: import::Processor,
+    api_key: String,
+) -> impl Endpoint {
+    Route::new()
+        .at("/failures", get(list_failures))
+        .at("/events/:id/retry", post(retry_event))
+        .at("/events/:id/fail", post(fail_event))
+        .at("/dead-letters", get(list_dead_letters))
+        .at("/dead-letters/:id/replay", post(replay_dead_letter))
+        .at("/audit", get(list_audit))
+        .at("/events/:id/proposals", get(list_proposals))
+        .at("/nonce-accounts", post(register_nonce_account))
+        .with(AddData::new(AdminState {
+            db,
+            processor,
+            import,
+        }))
+        .with(auth::RequireSecret::bearer(api_key))
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Page {
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Failure {
+    id: Uuid,
+    event_id: Uuid,
+    event_kind: String,
+    reason: String,
+    message: String,
+    logs: serde_json::Value,
+    signature: Option<String>,
+    created_at: String,
+}
+
+impl From<transaction_failures::Model> for Failure {
+    fn from(model: transaction_failures::Model) -> Self {
+        let transaction_failures::Model {
+            id,
+            event_id,
+            event_kind,
+            reason,
+            message,
+            logs,
+            signature,
+            created_at,
+        } = model;
+
+        Self {
+            id,
+            event_id,
+            event_kind,
+            reason,
+            message,
+            logs,
+            signature,
+            created_at: created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DeadLetter {
+    id: Uuid,
+    topic: String,
+    error: String,
+    created_at: String,
+    replayed_at: Option<String>,
+}
+
+impl From<dead_letters::Model> for DeadLetter {
+    fn from(model: dead_letters::Model) -> Self {
+        let dead_letters::Model {
+            id,
+            topic,
+            error,
+            created_at,
+            replayed_at,
+            ..
+        } = model;
+
+        Self {
+            id,
+            topic,
+            error,
+            created_at: created_at.to_string(),
+            replayed_at: replayed_at.map(|at| at.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AuditQuery {
+    event_id: Option<Uuid>,
+    signature: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct AuditEntry {
+    id: Uuid,
+    event_id: Uuid,
+    event_kind: String,
+    user_id: String,
+    project_id: String,
+    status: String,
+    message_hash: Option<String>,
+    signers: serde_json::Value,
+    signature: Option<String>,
+    created_at: String,
+}
+
+impl From<transaction_audit::Model> for AuditEntry {
+    fn from(model: transaction_audit::Model) -> Self {
+        let transaction_audit::Model {
+            id,
+            event_id,
+            event_kind,
+            user_id,
+            project_id,
+            status,
+            message_hash,
+            signers,
+            signature,
+            created_at,
+        } = model;
+
+        Self {
+            id,
+            event_id,
+            event_kind,
+            user_id,
+            project_id,
+            status,
+            message_hash,
+            signers,
+            signature,
+            created_at: created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ProposalEntry {
+    id: Uuid,
+    event_id: Uuid,
+    event_kind: String,
+    project_id: String,
+    multisig: String,
+    transaction_index: i64,
+    proposal: String,
+    status: String,
+    signature: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<multisig_proposals::Model> for ProposalEntry {
+    fn from(model: multisig_proposals::Model) -> Self {
+        let multisig_proposals::Model {
+            id,
+            event_id,
+            event_kind,
+            project_id,
+            multisig,
+            transaction_index,
+            proposal,
+            status,
+            signature,
+            created_at,
+            updated_at,
+        } = model;
+
+        Self {
+            id,
+            event_id,
+            event_kind,
+            project_id,
+            multisig,
+            transaction_index,
+            proposal,
+            status,
+            signature,
+            created_at: created_at.to_string(),
+            updated_at: updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NonceAccountRequest {
+    address: String,
+    cluster: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct NonceAccountEntry {
+    address: String,
+    cluster: String,
+    authority: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct FailRequest {
+    message: Option<String>,
+}
+
+fn internal(e: impl fmt::Debug) -> Error {
+    error!("admin request failed: {e:?}");
+    Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[handler]
+async fn list_failures(
+    Data(state): Data<&AdminState>,
+    Query(Page { limit, offset }): Query<Page>,
+) -> poem::Result<Json<Vec<Failure>>> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let failures = TransactionFailure::list(state.db.get(), limit, offset.unwrap_or(0))
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(failures.into_iter().map(Into::into).collect()))
+}
+
+async fn find_request(state: &AdminState, id: Uuid) -> poem::Result<(NftEventKey, NftEvents)> {
+    let request = EventRequest::find_by_event_id(state.db.get(), id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| Error::from_status(StatusCode::NOT_FOUND))?;
+
+    EventRequest::decode(&request).map_err(internal)
+}
+
+#[handler]
+async fn retry_event(
+    Data(state): Data<&AdminState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<StatusCode> {
+    let (key, msg) = find_request(state, id).await?;
+
+    info!("replaying event {id} from admin API");
+
+    state.processor.replay(key, msg).await.map_err(internal)?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[handler]
+async fn fail_event(
+    Data(state): Data<&AdminState>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<FailRequest>>,
+) -> poem::Result<StatusCode> {
+    let (key, msg) = find_request(state, id).await?;
+    let message = body
+        .and_then(|Json(FailRequest { message })| message)
+        .unwrap_or_else(|| "failed by operator".to_owned());
+
+    info!("force-failing event {id} from admin API");
+
+    let failed = state
+        .processor
+        .force_fail(key, &msg, message)
+        .await
+        .map_err(internal)?;
+
+    if failed {
+        Ok(StatusCode::ACCEPTED)
+    } else {
+        Err(Error::from_string(
+            "event cannot be failed manually",
+            StatusCode::CONFLICT,
+        ))
+    }
+}
+
+#[handler]
+async fn list_dead_letters(
+    Data(state): Data<&AdminState>,
+    Query(Page { limit, offset }): Query<Page>,
+) -> poem::Result<Json<Vec<DeadLetter>>> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let letters = DeadLetterModel::list(state.db.get(), limit, offset.unwrap_or(0))
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(letters.into_iter().map(Into::into).collect()))
+}
+
+#[handler]
+async fn replay_dead_letter(
+    Data(state): Data<&AdminState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<StatusCode> {
+    let letter = DeadLetterModel::find_by_id(state.db.get(), id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| Error::from_status(StatusCode::NOT_FOUND))?;
+    let msg = DeadLetterModel::decode(&letter).map_err(internal)?;
+
+    info!("replaying dead letter {id} from admin API");
+
+    let imported = state.import.process(&msg).await.map_err(internal)?;
+
+    if imported.is_none() {
+        state.processor.process(msg).await.map_err(internal)?;
+    }
+
+    DeadLetterModel::mark_replayed(state.db.get(), letter)
+        .await
+        .map_err(internal)?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// The audit trail of an event, or of the event that signed a transaction
+#[handler]
+async fn list_audit(
+    Data(state): Data<&AdminState>,
+    Query(AuditQuery {
+        event_id,
+        signature,
+    }): Query<AuditQuery>,
+) -> poem::Result<Json<Vec<AuditEntry>>> {
+    let entries = match (event_id, signature) {
+        (Some(event_id), _) => TransactionAudit::find_by_event_id(state.db.get(), event_id).await,
+        (None, Some(signature)) => {
+            let signed = TransactionAudit::find_by_signature(state.db.get(), signature)
+                .await
+                .map_err(internal)?;
+
+            match signed.first() {
+                Some(entry) => {
+                    TransactionAudit::find_by_event_id(state.db.get(), entry.event_id).await
+                },
+                None => Ok(signed),
+            }
+        },
+        (None, None) => {
+            return Err(Error::from_string(
+                "event_id or signature is required",
+                StatusCode::BAD_REQUEST,
+            ));
+        },
+    }
+    .map_err(internal)?;
+
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+/// The proposals made to a multisig treasury for an event
+#[handler]
+async fn list_proposals(
+    Data(state): Data<&AdminState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<Json<Vec<ProposalEntry>>> {
+    let proposals = MultisigProposal::find_by_event_id(state.db.get(), id)
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(proposals.into_iter().map(Into::into).collect()))
+}
+
+/// Add a durable nonce account to the pool of the treasury which is its
+/// nonce authority
+#[handler]
+async fn register_nonce_account(
+    Data(state): Data<&AdminState>,
+    Json(NonceAccountRequest { address, cluster }): Json<NonceAccountRequest>,
+) -> poem::Result<Json<NonceAccountEntry>> {
+    let bad_request = |e: String| Error::from_string(e, StatusCode::BAD_REQUEST);
+    let pubkey: Pubkey = address.parse().map_err(|e| bad_request(format!("{e}")))?;
+    let solana_cluster: SolanaCluster = cluster.parse().map_err(bad_request)?;
+
+    info!("registering nonce account {address} on {cluster} from admin API");
+
+    let authority = state
+        .processor
+        .register_nonce_account(solana_cluster, pubkey)
+        .await
+        .map_err(|e| bad_request(format!("{e:#}")))?
+        .ok_or_else(|| bad_request(format!("cluster {cluster} is not served")))?;
+
+    Ok(Json(NonceAccountEntry {
+        address,
+        cluster: solana_cluster.to_string(),
+        authority: authority.to_string(),
+    }))
+}
@@ -1,8 +1,21 @@
-use std::{collections::HashMap, fmt};
-
-use hub_core::bs58;
+use std::{collections::HashMap, fmt, future::Future, sync::Arc, time::Duration};
+
+use hub_core::{
+    anyhow::{Context, Result},
+    backon::Retryable,
+    bs58, clap,
+    futures_util::future::select_ok,
+    prelude::{async_trait, warn},
+    tokio,
+};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use mpl_bubblegum::state::metaplex_adapter::{
+    Collection, Creator, MetadataArgs, TokenProgramVersion, TokenStandard,
+};
 use solana_program::pubkey::Pubkey;
 
+use crate::retry::{Retries, RetryClass, Upstream};
+
 mod b58 {
     use hub_core::bs58;
     use serde::{de::Visitor, Deserializer, Serializer};
@@ -72,6 +85,104 @@ pub struct Asset {
     pub ownership: AssetOwnership,
     pub supply: Option<AssetSupply>,
     pub mutable: bool,
+    #[serde(default)]
+    pub burnt: bool,
+}
+
+impl Asset {
+    /// Whether the asset has been burned. Providers which omit `burnt` leave
+    /// burned assets without an owner.
+    #[must_use]
+    pub fn is_burned(&self) -> bool {
+        self.burnt || self.ownership.owner.0.is_empty()
+    }
+
+    /// Whether the asset is a fungible token rather than an NFT
+    #[must_use]
+    pub fn is_fungible(&self) -> bool {
+        matches!(self.interface.as_str(), "FungibleAsset" | "FungibleToken")
+    }
+
+    /// Whether the asset is a verified member of the collection `mint`.
+    /// Groupings without a verification status are trusted, since not every
+    /// provider reports one.
+    #[must_use]
+    pub fn in_verified_collection(&self, mint: &str) -> bool {
+        self.grouping.iter().any(|g| {
+            g.group_key == "collection"
+                && g.group_value.to_string() == mint
+                && g.verified.unwrap_or(true)
+        })
+    }
+
+    /// Rebuild the bubblegum metadata args of a compressed asset so the leaf
+    /// data hash can be recomputed by instructions that modify the leaf.
+    ///
+    /// # Errors
+    /// This function fails if an address is not a valid public key or a value
+    /// exceeds the range of its on-chain representation
+    pub fn into_metadata_args(self) -> Result<MetadataArgs> {
+        let Self {
+            content,
+            grouping,
+            royalty,
+            creators,
+            supply,
+            mutable,
+            ..
+        } = self;
+
+        let collection = grouping
+            .into_iter()
+            .find(|g| g.group_key == "collection")
+            .map(|g| {
+                Ok::<_, hub_core::anyhow::Error>(Collection {
+                    verified: g.verified.unwrap_or(true),
+                    key: g.group_value.try_into()?,
+                })
+            })
+            .transpose()?;
+
+        let token_standard = content
+            .metadata
+            .extra
+            .get("token_standard")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|s| match s {
+                "NonFungible" => Some(TokenStandard::NonFungible),
+                "FungibleAsset" => Some(TokenStandard::FungibleAsset),
+                "Fungible" => Some(TokenStandard::Fungible),
+                "NonFungibleEdition" => Some(TokenStandard::NonFungibleEdition),
+                _ => None,
+            });
+
+        Ok(MetadataArgs {
+            name: content.metadata.name.unwrap_or_default(),
+            symbol: content.metadata.symbol.unwrap_or_default(),
+            uri: content.json_uri,
+            seller_fee_basis_points: royalty.basis_points.try_into()?,
+            primary_sale_happened: royalty.primary_sale_happened,
+            is_mutable: mutable,
+            edition_nonce: supply
+                .and_then(|s| s.edition_nonce)
+                .map(TryInto::try_into)
+                .transpose()?,
+            token_standard,
+            collection,
+            uses: None,
+            token_program_version: TokenProgramVersion::Original,
+            creators: creators
+                .into_iter()
+                .map(|c| {
+                    Ok(Creator {
+                        address: c.address.try_into()?,
+                        verified: c.verified,
+                        share: c.share.try_into()?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -103,6 +214,8 @@ pub struct AssetCompression {
 pub struct AssetGrouping {
     pub group_key: String,
     pub group_value: Base58,
+    #[serde(default)]
+    pub verified: Option<bool>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -140,6 +253,12 @@ pub struct AssetProof {
     pub tree_id: Base58,
 }
 
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayOptions {
+    pub show_unverified_collections: bool,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchAssetsResult {
     pub total: u64,
@@ -168,7 +287,7 @@ pub struct File {
 pub struct Metadata {
     pub attributes: Option<Vec<Attribute>>,
     pub description: Option<String>,
-    pub name: String,
+    pub name: Option<String>,
     pub symbol: Option<String>,
     pub image: Option<String>,
     pub external_url: Option<String>,
@@ -200,12 +319,189 @@ pub trait Rpc {
     // #[method(name = "getAssetsByOwner")]
     // fn get_assets_by_owner(&self);
 
-    // #[method(name = "getAssetsByGroup")]
-    // fn get_assets_by_group(&self);
-
     // #[method(name = "getAssetsByCreator")]
     // fn get_assets_by_creator(&self);
 
     #[method(name = "searchAssets", param_kind = map)]
-    fn search_assets(&self, grouping: Vec<&str>, page: u64) -> Result<SearchAssetsResult, Error>;
+    fn search_assets(
+        &self,
+        grouping: Vec<&str>,
+        page: u64,
+        #[argument(rename = "displayOptions")] display_options: DisplayOptions,
+    ) -> Result<SearchAssetsResult, Error>;
+
+    #[method(name = "getAssetsByGroup", param_kind = map)]
+    fn get_assets_by_group(
+        &self,
+        #[argument(rename = "groupKey")] group_key: &str,
+        #[argument(rename = "groupValue")] group_value: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<SearchAssetsResult, Error>;
+}
+
+/// Digital asset API vendors, whose methods take slightly different params
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DasProviderKind {
+    Helius,
+    Triton,
+}
+
+/// The digital asset API calls used by this service, adapted to the quirks
+/// of each vendor
+#[async_trait]
+pub trait DasProvider: Send + Sync {
+    async fn get_asset(&self, id: &str) -> Result<Asset, jsonrpsee::core::Error>;
+
+    async fn get_asset_proof(&self, id: &str) -> Result<AssetProof, jsonrpsee::core::Error>;
+
+    /// One page of the assets grouped into the collection `mint`. Pages are
+    /// numbered from 1 and hold up to 1000 assets.
+    async fn collection_assets(
+        &self,
+        mint: &str,
+        page: u64,
+    ) -> Result<SearchAssetsResult, jsonrpsee::core::Error>;
+}
+
+/// Build the client for `kind` against `endpoint`
+///
+/// # Errors
+/// This function fails if the HTTP client cannot be built
+pub fn provider(kind: DasProviderKind, endpoint: &str) -> Result<Arc<dyn DasProvider>> {
+    let client = HttpClientBuilder::default()
+        .request_timeout(Duration::from_secs(15))
+        .build(endpoint)
+        .context("Failed to initialize asset API client")?;
+
+    Ok(match kind {
+        DasProviderKind::Helius => Arc::new(Helius(client)),
+        DasProviderKind::Triton => Arc::new(Triton(client)),
+    })
+}
+
+/// Helius, which filters collections with `searchAssets` display options
+pub struct Helius(HttpClient);
+
+#[async_trait]
+impl DasProvider for Helius {
+    async fn get_asset(&self, id: &str) -> Result<Asset, jsonrpsee::core::Error> {
+        RpcClient::get_asset(&self.0, id).await
+    }
+
+    async fn get_asset_proof(&self, id: &str) -> Result<AssetProof, jsonrpsee::core::Error> {
+        RpcClient::get_asset_proof(&self.0, id).await
+    }
+
+    async fn collection_assets(
+        &self,
+        mint: &str,
+        page: u64,
+    ) -> Result<SearchAssetsResult, jsonrpsee::core::Error> {
+        self.0
+            .search_assets(vec!["collection", mint], page, DisplayOptions::default())
+            .await
+    }
+}
+
+/// Triton, which follows the Metaplex reference API and pages groups with
+/// `getAssetsByGroup`
+pub struct Triton(HttpClient);
+
+#[async_trait]
+impl DasProvider for Triton {
+    async fn get_asset(&self, id: &str) -> Result<Asset, jsonrpsee::core::Error> {
+        RpcClient::get_asset(&self.0, id).await
+    }
+
+    async fn get_asset_proof(&self, id: &str) -> Result<AssetProof, jsonrpsee::core::Error> {
+        RpcClient::get_asset_proof(&self.0, id).await
+    }
+
+    async fn collection_assets(
+        &self,
+        mint: &str,
+        page: u64,
+    ) -> Result<SearchAssetsResult, jsonrpsee::core::Error> {
+        self.0
+            .get_assets_by_group("collection", mint, page, 1000)
+            .await
+    }
+}
+
+/// A provider whose calls are retried under the DAS retry policy and fail
+/// fast while the DAS circuit breaker is open. With a hedge delay, a request
+/// which has not been answered in time is sent again and the first success
+/// of either is taken.
+pub struct Guarded {
+    inner: Arc<dyn DasProvider>,
+    retries: Retries,
+    hedge_delay: Option<Duration>,
+}
+
+impl Guarded {
+    #[must_use]
+    pub fn new(
+        inner: Arc<dyn DasProvider>,
+        retries: Retries,
+        hedge_delay: Option<Duration>,
+    ) -> Self {
+        Self {
+            inner,
+            retries,
+            hedge_delay,
+        }
+    }
+
+    async fn call<T, F: Future<Output = Result<T, jsonrpsee::core::Error>>>(
+        &self,
+        f: impl Fn() -> F,
+    ) -> Result<T, jsonrpsee::core::Error> {
+        let breaker = self.retries.breaker(RetryClass::Das);
+
+        (|| breaker.call(self.hedged(&f)))
+            .retry(&self.retries.policy(RetryClass::Das).backoff())
+            .when(Upstream::is_upstream_failure)
+            .notify(|err: &jsonrpsee::core::Error, dur: Duration| {
+                warn!("retrying DAS error {err:?} in {dur:?}");
+            })
+            .await
+    }
+
+    async fn hedged<T, F: Future<Output = Result<T, jsonrpsee::core::Error>>>(
+        &self,
+        f: &impl Fn() -> F,
+    ) -> Result<T, jsonrpsee::core::Error> {
+        let mut first = Box::pin(f());
+
+        let Some(delay) = self.hedge_delay else {
+            return first.await;
+        };
+
+        tokio::select! {
+            res = &mut first => return res,
+            () = tokio::time::sleep(delay) => {},
+        }
+
+        select_ok([first, Box::pin(f())]).await.map(|(res, _)| res)
+    }
+}
+
+#[async_trait]
+impl DasProvider for Guarded {
+    async fn get_asset(&self, id: &str) -> Result<Asset, jsonrpsee::core::Error> {
+        self.call(|| self.inner.get_asset(id)).await
+    }
+
+    async fn get_asset_proof(&self, id: &str) -> Result<AssetProof, jsonrpsee::core::Error> {
+        self.call(|| self.inner.get_asset_proof(id)).await
+    }
+
+    async fn collection_assets(
+        &self,
+        mint: &str,
+        page: u64,
+    ) -> Result<SearchAssetsResult, jsonrpsee::core::Error> {
+        self.call(|| self.inner.collection_assets(mint, page)).await
+    }
 }
@@ -0,0 +1,136 @@
+//! Compressed mints are reported with an asset id predicted from the leaf
+//! count of their merkle tree. The indexer confirms most predictions as it
+//! observes the mints land; the rest are confirmed here by reading the leaf
+//! nonce from each mint's transaction. A wrong prediction is replaced and
+//! reported with a `CompressedAssetIdCorrected` event.
+
+use std::str::FromStr;
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{
+        solana_nft_events::Event as SolanaNftEvent, SolanaCompressedAssetIdCorrection,
+        SolanaNftEventKey, SolanaNftEvents,
+    },
+    Collection, CompressionLeaf, MerkleTree, Outbox,
+};
+use holaplex_hub_nfts_solana_entity::compression_leafs;
+use hub_core::{anyhow::Result, prelude::*, tokio};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::cluster::Clusters;
+
+/// Periodically confirms the predicted asset ids of compressed mints
+#[derive(Clone)]
+pub struct AssetIdReconciler {
+    db: Connection,
+    clusters: Clusters,
+    interval: Duration,
+    batch_size: u64,
+}
+
+impl AssetIdReconciler {
+    #[must_use]
+    pub fn new(db: Connection, clusters: Clusters, interval: Duration, batch_size: u64) -> Self {
+        Self {
+            db,
+            clusters,
+            interval,
+            batch_size,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.reconcile().await {
+                error!("{:?}", e.context("failed to confirm predicted asset ids"));
+            }
+        }
+    }
+
+    /// Confirm the oldest predicted asset ids.
+    ///
+    /// # Errors
+    /// This function fails if the predicted leaves cannot be loaded
+    pub async fn reconcile(&self) -> Result<()> {
+        let leaves = CompressionLeaf::find_predicted(self.db.get(), self.batch_size).await?;
+
+        for leaf in leaves {
+            let id = leaf.id;
+
+            if let Err(e) = self.confirm(leaf).await {
+                warn!(
+                    "{:?}",
+                    e.context(format!("failed to confirm asset id of mint {id}"))
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn confirm(&self, leaf: compression_leafs::Model) -> Result<()> {
+        let collection = Collection::find_by_id(self.db.get(), leaf.collection_id)
+            .await?
+            .context("collection not found")?;
+        let solana = self
+            .clusters
+            .get(collection.cluster)
+            .with_context(|| format!("{} cluster is not served", collection.cluster))?;
+
+        let signature = leaf
+            .mint_signature
+            .clone()
+            .context("mint signature not found")?;
+        let nonce = solana
+            .extract_compression_nonce(&Signature::from_str(&signature)?)
+            .await?;
+        let asset_id =
+            mpl_bubblegum::utils::get_asset_id(&Pubkey::from_str(&leaf.merkle_tree)?, nonce.into())
+                .to_string();
+
+        let txn = self.db.begin().await?;
+
+        // The indexer may have confirmed the leaf in the meantime
+        let Some(leaf) =
+            CompressionLeaf::find_unconfirmed_by_signature(&txn, signature.clone()).await?
+        else {
+            return Ok(());
+        };
+
+        MerkleTree::observe_leaf(&txn, leaf.merkle_tree.clone(), nonce.into()).await?;
+
+        let id = leaf.id;
+
+        if let Some(predicted) =
+            CompressionLeaf::confirm_asset_id(&txn, leaf, asset_id.clone()).await?
+        {
+            warn!("asset id of mint {id} was mispredicted as {predicted}, corrected to {asset_id}");
+
+            let key = SolanaNftEventKey {
+                id: id.to_string(),
+                correlation_id: id.to_string(),
+                ..Default::default()
+            };
+            let event = SolanaNftEvents::new(SolanaNftEvent::CompressedAssetIdCorrected(
+                SolanaCompressedAssetIdCorrection {
+                    collection_mint_id: id.to_string(),
+                    predicted_address: predicted,
+                    address: asset_id,
+                    signature,
+                },
+            ));
+
+            Outbox::enqueue(&txn, &key, &event).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
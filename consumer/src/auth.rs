@@ -0,0 +1,119 @@
+//! Checks of the shared secrets guarding the HTTP routes
+
+use hub_core::prelude::*;
+use poem::{
+    http::{header::AUTHORIZATION, StatusCode},
+    Endpoint, Error, Middleware, Request,
+};
+use subtle::ConstantTimeEq;
+
+/// Whether `token` is `secret`. The comparison takes as long wherever the
+/// two differ, so response times do not reveal how much of a guess matched.
+pub(crate) fn secret_matches(token: &str, secret: &str) -> bool {
+    token.as_bytes().ct_eq(secret.as_bytes()).into()
+}
+
+/// Middleware rejecting requests whose `Authorization` header is not the
+/// configured secret, after an optional scheme such as `Bearer `
+#[derive(Debug, Clone)]
+pub(crate) struct RequireSecret {
+    secret: String,
+    scheme: &'static str,
+}
+
+impl RequireSecret {
+    /// Require `Authorization: Bearer <secret>`
+    pub(crate) fn bearer(secret: String) -> Self {
+        Self {
+            secret,
+            scheme: "Bearer ",
+        }
+    }
+
+    /// Require the `Authorization` header to be the secret itself, as sent
+    /// by webhook providers
+    pub(crate) fn raw(secret: String) -> Self {
+        Self { secret, scheme: "" }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequireSecret {
+    type Output = RequireSecretEndpoint<E>;
+
+    fn transform(&self, inner: E) -> Self::Output {
+        RequireSecretEndpoint {
+            inner,
+            auth: self.clone(),
+        }
+    }
+}
+
+pub(crate) struct RequireSecretEndpoint<E> {
+    inner: E,
+    auth: RequireSecret,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for RequireSecretEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let RequireSecret { secret, scheme } = &self.auth;
+        let authorized = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix(*scheme))
+            .map_or(false, |token| secret_matches(token, secret));
+
+        if !authorized {
+            return Err(Error::from_status(StatusCode::UNAUTHORIZED));
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{handler, test::TestClient, EndpointExt};
+
+    use super::{secret_matches, RequireSecret};
+
+    #[handler]
+    fn ok() -> &'static str {
+        "ok"
+    }
+
+    #[test]
+    fn matches_only_the_whole_secret() {
+        assert!(secret_matches("s3cret", "s3cret"));
+        assert!(!secret_matches("s3cre", "s3cret"));
+        assert!(!secret_matches("s3cret!", "s3cret"));
+        assert!(!secret_matches("", "s3cret"));
+    }
+
+    #[tokio::test]
+    async fn bearer_requires_the_scheme_and_secret() {
+        let cli = TestClient::new(ok.with(RequireSecret::bearer("s3cret".into())));
+
+        cli.get("/")
+            .header("Authorization", "Bearer s3cret")
+            .send()
+            .await
+            .assert_status_is_ok();
+
+        for header in ["s3cret", "Bearer s3cre", "Basic s3cret"] {
+            cli.get("/")
+                .header("Authorization", header)
+                .send()
+                .await
+                .assert_status(poem::http::StatusCode::UNAUTHORIZED);
+        }
+
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(poem::http::StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,10 +1,26 @@
-use holaplex_hub_nfts_solana_core::proto::{
-    MetaplexMasterEditionTransaction, SolanaPendingTransaction, TransferMetaplexAssetTransaction,
-    UpdateSolanaMintPayload,
+use holaplex_hub_nfts_solana_core::{
+    proto::{
+        MetaplexMasterEditionTransaction, MintMetaplexEditionTransaction,
+        MintMetaplexMetadataTransaction, SolanaPendingTransaction,
+        TransferMetaplexAssetTransaction, TransferMetaplexAssetsTransaction,
+        UpdateSolanaMintPayload,
+    },
+    Mint,
+};
+use holaplex_hub_nfts_solana_entity::{
+    candy_machines, collection_mints, collections, compression_leafs, listings, royalty_splits,
+    update_revisions,
 };
-use holaplex_hub_nfts_solana_entity::{collection_mints, collections, update_revisions};
 use hub_core::prelude::*;
 use solana_program::{hash::Hash, pubkey::Pubkey};
+
+use crate::{
+    candy_machine::{CandyMachineData, ConfigLine, Guards},
+    solana::{
+        CandyMachineRef, CompressedRef, EditionRef, HydraRef, Solana, SquadsRef, UncompressedRef,
+    },
+    trees::TreeShape,
+};
 #[derive(Clone)]
 pub struct MasterEditionAddresses {
     pub metadata: Pubkey,
@@ -61,6 +77,14 @@ pub struct UpdateCollectionMintAddresses {
     pub update_authority: Pubkey,
 }
 
+#[derive(Clone)]
+pub struct UpdateCompressedMintAddresses {
+    pub payer: Pubkey,
+    pub update_authority: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub leaf_owner: Pubkey,
+}
+
 #[derive(Clone)]
 pub struct SwitchCollectionAddresses {
     pub payer: Pubkey,
@@ -75,6 +99,58 @@ pub struct TransferAssetAddresses {
     pub owner_associated_token_account: Pubkey,
 }
 
+#[derive(Clone)]
+pub struct TransferAssetsAddresses {
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub mints: Vec<Pubkey>,
+}
+
+#[derive(Clone)]
+pub struct LockMetadataAddresses {
+    pub update_authority: Pubkey,
+    pub metadata: Vec<Pubkey>,
+}
+
+#[derive(Clone)]
+pub struct MultisigProposalAddresses {
+    pub multisig: Pubkey,
+    pub vault: Pubkey,
+    pub member: Pubkey,
+    pub transaction: Pubkey,
+    pub proposal: Pubkey,
+    pub transaction_index: u64,
+}
+
+#[derive(Clone)]
+pub struct RoyaltySplitAddresses {
+    pub payer: Pubkey,
+    pub fanout: Pubkey,
+    pub holding_account: Pubkey,
+}
+
+#[derive(Clone)]
+pub struct CreateTreeAddresses {
+    pub merkle_tree: Pubkey,
+    pub tree_authority: Pubkey,
+    pub tree_creator: Pubkey,
+}
+
+#[derive(Clone)]
+pub struct CandyMachineAddresses {
+    pub candy_machine: Pubkey,
+    pub candy_guard: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[derive(Clone)]
+pub struct ListingAddresses {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub trade_state: Pubkey,
+}
+
 /// Represents a response from a transaction on the blockchain. This struct
 /// provides the serialized message and the signatures of the signed message.
 
@@ -86,10 +162,32 @@ pub struct TransactionResponse<A> {
     /// The signatures of the signed message or the public keys of wallets that should sign the transaction. Order matters.
     pub signatures_or_signers_public_keys: Vec<String>,
 
+    /// Keypairs of accounts created by the transaction which signed it, kept
+    /// so the transaction can be signed again with a fresh blockhash.
+    pub ephemeral_keypairs: Vec<[u8; 64]>,
+
     /// Addresses that are related to the transaction.
     pub addresses: A,
 }
 
+impl<A> TransactionResponse<A> {
+    pub fn map_addresses<B>(self, f: impl FnOnce(A) -> B) -> TransactionResponse<B> {
+        let Self {
+            serialized_message,
+            signatures_or_signers_public_keys,
+            ephemeral_keypairs,
+            addresses,
+        } = self;
+
+        TransactionResponse {
+            serialized_message,
+            signatures_or_signers_public_keys,
+            ephemeral_keypairs,
+            addresses: f(addresses),
+        }
+    }
+}
+
 impl<A> From<TransactionResponse<A>> for SolanaPendingTransaction {
     fn from(
         TransactionResponse {
@@ -101,12 +199,13 @@ impl<A> From<TransactionResponse<A>> for SolanaPendingTransaction {
         Self {
             serialized_message,
             signatures_or_signers_public_keys,
+            estimated_cost: None,
         }
     }
 }
 
 #[async_trait]
-pub trait CollectionBackend {
+pub trait CollectionBackend: Send + Sync {
     async fn create(
         &self,
         txn: MetaplexMasterEditionTransaction,
@@ -118,15 +217,37 @@ pub trait CollectionBackend {
         txn: MetaplexMasterEditionTransaction,
     ) -> Result<TransactionResponse<UpdateMasterEditionAddresses>>;
 
-    async fn update_mint(
+    /// Point the collection NFT at `metadata_uri`, keeping the rest of its
+    /// on-chain metadata as it is
+    async fn update_assets(
+        &self,
+        collection: &collections::Model,
+        metadata_uri: String,
+    ) -> Result<TransactionResponse<UpdateMasterEditionAddresses>>;
+
+    /// Make the on-chain metadata of `mints`, along with that of the
+    /// collection NFT if `lock_collection` is set, immutable
+    async fn lock_metadata(
+        &self,
+        collection: &collections::Model,
+        mints: &[collection_mints::Model],
+        lock_collection: bool,
+    ) -> Result<TransactionResponse<LockMetadataAddresses>>;
+
+    /// Set the `primary_sale_happened` flag of the metadata of `mint`
+    async fn mark_primary_sale(
         &self,
         collection: &collections::Model,
         mint: &collection_mints::Model,
-        txn: UpdateSolanaMintPayload,
     ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>>;
 
+    /// Sign the stored message of an update again with a fresh blockhash.
+    /// Fails with [`StaleRevision`](crate::solana::StaleRevision) if its
+    /// accounts no longer match the mint, the collection or the fee payer.
     async fn retry_update_mint(
         &self,
+        collection: &collections::Model,
+        collection_mint: &collection_mints::Model,
         revision: &update_revisions::Model,
     ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>>;
 
@@ -139,7 +260,7 @@ pub trait CollectionBackend {
 }
 
 #[async_trait]
-pub trait MintBackend<T, R> {
+pub trait MintBackend<T, R>: Send + Sync {
     async fn mint(
         &self,
         collection: &collections::Model,
@@ -149,7 +270,17 @@ pub trait MintBackend<T, R> {
 }
 
 #[async_trait]
-pub trait TransferBackend<M, R> {
+pub trait UpdateMintBackend<M, R>: Send + Sync {
+    async fn update_mint(
+        &self,
+        collection: &collections::Model,
+        mint: &M,
+        txn: UpdateSolanaMintPayload,
+    ) -> Result<TransactionResponse<R>>;
+}
+
+#[async_trait]
+pub trait TransferBackend<M, R>: Send + Sync {
     async fn transfer(
         &self,
         collection_mint: &M,
@@ -157,3 +288,292 @@ pub trait TransferBackend<M, R> {
         txn: TransferMetaplexAssetTransaction,
     ) -> Result<TransactionResponse<R>>;
 }
+
+#[async_trait]
+pub trait BatchTransferBackend: Send + Sync {
+    /// Transfer assets in as few transactions as they fit in, each listing
+    /// the assets it moves in its addresses
+    async fn transfer_many(
+        &self,
+        collection_mints: &[collection_mints::Model],
+        compression_leafs: &[compression_leafs::Model],
+        txn: TransferMetaplexAssetsTransaction,
+    ) -> Result<Vec<TransactionResponse<TransferAssetsAddresses>>>;
+}
+
+/// Wraps transactions paid by the vault of a multisig treasury into
+/// proposals made by one of its members
+#[async_trait]
+pub trait ProposalBackend: Send + Sync {
+    /// Propose `tx` as the next vault transaction of the multisig, skipping
+    /// indices up to `reserved` which are taken by proposals not created yet
+    async fn propose(
+        &self,
+        tx: SolanaPendingTransaction,
+        reserved: Option<u64>,
+    ) -> Result<TransactionResponse<MultisigProposalAddresses>>;
+}
+
+/// Assembles the transactions of the fanout wallets splitting the royalties
+/// of a collection between its members
+#[async_trait]
+pub trait RoyaltySplitBackend: Send + Sync {
+    /// Create a fanout named `name`, administered by the payer, splitting
+    /// what it receives between `members`
+    async fn create_split(
+        &self,
+        name: String,
+        members: &[royalty_splits::RoyaltySplitMember],
+    ) -> Result<TransactionResponse<RoyaltySplitAddresses>>;
+
+    /// Pay out the balance of the fanout to its members
+    async fn distribute(
+        &self,
+        split: &royalty_splits::Model,
+    ) -> Result<TransactionResponse<RoyaltySplitAddresses>>;
+}
+
+/// Assembles the transactions listing mints on the auction house of their
+/// project. Auction houses only trade Token Metadata NFTs, so compressed
+/// mints cannot be listed.
+#[async_trait]
+pub trait ListingBackend: Send + Sync {
+    /// List `collection_mint`, held by `seller`, at `price` base units of the
+    /// auction house's treasury mint
+    async fn list(
+        &self,
+        collection_mint: &collection_mints::Model,
+        seller: Pubkey,
+        price: u64,
+    ) -> Result<TransactionResponse<ListingAddresses>>;
+
+    /// Close a live listing of `collection_mint`
+    async fn cancel(
+        &self,
+        collection_mint: &collection_mints::Model,
+        listing: &listings::Model,
+    ) -> Result<TransactionResponse<ListingAddresses>>;
+}
+
+/// Assembles the transactions creating merkle trees for compressed mints
+#[async_trait]
+pub trait TreeBackend: Send + Sync {
+    /// Create a tree of `shape` delegated to the configured tree delegate
+    async fn create_tree(
+        &self,
+        shape: TreeShape,
+    ) -> Result<TransactionResponse<CreateTreeAddresses>>;
+}
+
+/// Assembles the transactions deploying candy machines for collections and
+/// loading them with their config lines
+#[async_trait]
+pub trait CandyMachineBackend: Send + Sync {
+    /// Create a candy machine minting `data` into `collection`, wrapped in a
+    /// candy guard enforcing `guards`. The collection's update authority
+    /// administers both.
+    async fn deploy(
+        &self,
+        collection: &collections::Model,
+        data: CandyMachineData,
+        guards: &Guards,
+    ) -> Result<TransactionResponse<CandyMachineAddresses>>;
+
+    /// Insert the stripped `lines` into `candy_machine` from `index` on
+    async fn insert_items(
+        &self,
+        candy_machine: &candy_machines::Model,
+        index: u32,
+        lines: Vec<ConfigLine>,
+    ) -> Result<TransactionResponse<CandyMachineAddresses>>;
+}
+
+/// The token standard an asset is minted under, which decides the backend
+/// assembling its transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetStandard {
+    /// A Token Metadata NFT with its own mint account
+    NonFungible,
+    /// A Bubblegum leaf in the configured merkle tree
+    Compressed,
+}
+
+impl AssetStandard {
+    /// The standard requested by a mint payload
+    #[must_use]
+    pub fn new(compressed: bool) -> Self {
+        if compressed {
+            Self::Compressed
+        } else {
+            Self::NonFungible
+        }
+    }
+}
+
+impl From<&Mint> for AssetStandard {
+    fn from(mint: &Mint) -> Self {
+        match mint {
+            Mint::Uncompressed(_) => Self::NonFungible,
+            Mint::Compressed(_) => Self::Compressed,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum MintAddresses {
+    NonFungible(MintMetaplexAddresses),
+    Compressed(MintCompressedMintV1Addresses),
+}
+
+#[derive(Clone)]
+pub enum UpdateMintAddresses {
+    NonFungible(UpdateCollectionMintAddresses),
+    Compressed(UpdateCompressedMintAddresses),
+}
+
+/// Resolves the backend for each request from the standard of the asset it
+/// targets, so handlers do not pick backends themselves. Supporting a new
+/// standard means adding an [`AssetStandard`] variant and routing it here.
+pub struct Backends<'a> {
+    uncompressed: UncompressedRef<'a>,
+    compressed: CompressedRef<'a>,
+    edition: EditionRef<'a>,
+    hydra: HydraRef<'a>,
+    candy_machine: CandyMachineRef<'a>,
+    squads: Option<SquadsRef<'a>>,
+}
+
+impl<'a> Backends<'a> {
+    #[must_use]
+    pub fn new(solana: &'a Solana) -> Self {
+        Self {
+            uncompressed: UncompressedRef(solana),
+            compressed: CompressedRef(solana),
+            edition: EditionRef(solana),
+            hydra: HydraRef(solana),
+            candy_machine: CandyMachineRef(solana),
+            squads: solana.multisig().map(|_| SquadsRef(solana)),
+        }
+    }
+
+    /// Proposes assembled transactions to the multisig treasury of the
+    /// project, or `None` if its treasury signs them directly
+    #[must_use]
+    pub fn proposal(&self) -> Option<&dyn ProposalBackend> {
+        self.squads.as_ref().map(|s| s as &dyn ProposalBackend)
+    }
+
+    /// Collections are always Token Metadata NFTs, whatever the standard of
+    /// the assets minted into them
+    #[must_use]
+    pub fn collection(&self) -> &dyn CollectionBackend {
+        &self.uncompressed
+    }
+
+    #[must_use]
+    pub fn edition(
+        &self,
+    ) -> &dyn MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> {
+        &self.edition
+    }
+
+    #[must_use]
+    pub fn batch_transfer(&self) -> &dyn BatchTransferBackend {
+        &self.uncompressed
+    }
+
+    #[must_use]
+    pub fn royalty_split(&self) -> &dyn RoyaltySplitBackend {
+        &self.hydra
+    }
+
+    #[must_use]
+    pub fn listing(&self) -> &dyn ListingBackend {
+        &self.uncompressed
+    }
+
+    #[must_use]
+    pub fn candy_machine(&self) -> &dyn CandyMachineBackend {
+        &self.candy_machine
+    }
+
+    #[must_use]
+    pub fn tree(&self) -> &dyn TreeBackend {
+        &self.compressed
+    }
+
+    /// Mint an asset of `standard` into `collection`
+    ///
+    /// # Errors
+    /// This function fails if the backend for `standard` cannot assemble the
+    /// transaction
+    pub async fn mint(
+        &self,
+        standard: AssetStandard,
+        collection: &collections::Model,
+        blockhash: Option<Hash>,
+        txn: MintMetaplexMetadataTransaction,
+    ) -> Result<TransactionResponse<MintAddresses>> {
+        Ok(match standard {
+            AssetStandard::NonFungible => self
+                .uncompressed
+                .mint(collection, blockhash, txn)
+                .await?
+                .map_addresses(MintAddresses::NonFungible),
+            AssetStandard::Compressed => self
+                .compressed
+                .mint(collection, blockhash, txn)
+                .await?
+                .map_addresses(MintAddresses::Compressed),
+        })
+    }
+
+    /// Update the metadata of a previously minted asset
+    ///
+    /// # Errors
+    /// This function fails if the backend for the mint cannot assemble the
+    /// transaction
+    pub async fn update_mint(
+        &self,
+        collection: &collections::Model,
+        mint: &Mint,
+        payload: UpdateSolanaMintPayload,
+    ) -> Result<TransactionResponse<UpdateMintAddresses>> {
+        Ok(match mint {
+            Mint::Uncompressed(collection_mint) => self
+                .uncompressed
+                .update_mint(collection, collection_mint, payload)
+                .await?
+                .map_addresses(UpdateMintAddresses::NonFungible),
+            Mint::Compressed(compression_leaf) => self
+                .compressed
+                .update_mint(collection, compression_leaf, payload)
+                .await?
+                .map_addresses(UpdateMintAddresses::Compressed),
+        })
+    }
+
+    /// Transfer a previously minted asset
+    ///
+    /// # Errors
+    /// This function fails if the backend for the mint cannot assemble the
+    /// transaction
+    pub async fn transfer(
+        &self,
+        mint: &Mint,
+        txn: TransferMetaplexAssetTransaction,
+    ) -> Result<TransactionResponse<()>> {
+        Ok(match mint {
+            Mint::Uncompressed(collection_mint) => self
+                .uncompressed
+                .transfer(collection_mint, txn)
+                .await?
+                .map_addresses(|_| ()),
+            Mint::Compressed(compression_leaf) => self
+                .compressed
+                .transfer(compression_leaf, txn)
+                .await?
+                .map_addresses(|_| ()),
+        })
+    }
+}
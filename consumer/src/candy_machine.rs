@@ -0,0 +1,525 @@
+//! Candy Machine v3 deployments for existing collections.
+//!
+//! A candy machine mints the items of a manifest into a collection, one
+//! config line per item. Lines are inserted after the candy machine is
+//! created, a batch per transaction, and share the prefix common to every
+//! name and uri so that each line only stores what sets it apart. The candy
+//! machine is wrapped by a candy guard, which enforces the mint window, price
+//! and per wallet limit of the collection on whoever mints from it.
+
+use anchor_lang::{prelude::AccountMeta, AnchorSerialize};
+use hub_core::thiserror;
+use solana_program::{instruction::Instruction, pubkey, pubkey::Pubkey, system_program};
+
+use crate::squads::instruction_data;
+
+/// The Candy Machine Core program
+pub const PROGRAM_ID: Pubkey = pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR");
+
+/// The Candy Guard program
+pub const GUARD_PROGRAM_ID: Pubkey = pubkey!("Guard1JwRhJkVH6XZhzoYxeBVQe872VH6QggF4BWmS9g");
+
+/// Bytes of config lines inserted by a single transaction, leaving room for
+/// its accounts, signatures and compute budget instructions
+pub const MAX_BATCH_BYTES: usize = 800;
+
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_URI_LENGTH: usize = 200;
+const MAX_SYMBOL_LENGTH: usize = 10;
+const MAX_CREATOR_LIMIT: usize = 5;
+const MAX_CREATOR_LEN: usize = 32 + 1 + 1;
+
+/// Size of a candy machine account before its config lines, as laid out by
+/// the program: its header and authorities, then candy machine data with
+/// room for both config line and hidden settings
+const CANDY_MACHINE_SIZE: usize = HEADER_SIZE + DATA_SIZE;
+
+/// Discriminator, version, features, authority, mint authority, collection
+/// mint and items redeemed
+const HEADER_SIZE: usize = 8 + 1 + 6 + 32 + 32 + 32 + 8;
+
+/// Items available, symbol, seller fee basis points, max supply, mutability
+/// and creators, then both optional settings
+const DATA_SIZE: usize = 8
+    + (4 + MAX_SYMBOL_LENGTH)
+    + 2
+    + 8
+    + 1
+    + (4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN)
+    + CONFIG_LINE_SETTINGS_SIZE
+    + HIDDEN_SETTINGS_SIZE;
+
+const CONFIG_LINE_SETTINGS_SIZE: usize =
+    1 + (4 + MAX_NAME_LENGTH) + 4 + (4 + MAX_URI_LENGTH) + 4 + 1;
+
+const HIDDEN_SETTINGS_SIZE: usize = 1 + (4 + MAX_NAME_LENGTH) + (4 + MAX_URI_LENGTH) + 32;
+
+const SEED_CANDY_MACHINE: &[u8] = b"candy_machine";
+const SEED_CANDY_GUARD: &[u8] = b"candy_guard";
+
+/// Positions of the supported guards in the feature flags of a guard set
+const GUARD_SOL_PAYMENT: u32 = 1;
+const GUARD_START_DATE: u32 = 3;
+const GUARD_END_DATE: u32 = 7;
+const GUARD_MINT_LIMIT: u32 = 9;
+
+/// Why a candy machine could not be deployed
+#[derive(Debug, thiserror::Error)]
+pub enum CandyMachineError {
+    #[error("Candy machine manifest has no items")]
+    EmptyManifest,
+    #[error("Candy machine manifest has {0} items, more than can be inserted")]
+    TooManyItems(usize),
+    #[error("Item name {0:?} is longer than {MAX_NAME_LENGTH} bytes")]
+    NameTooLong(String),
+    #[error("Item uri {0:?} is longer than {MAX_URI_LENGTH} bytes")]
+    UriTooLong(String),
+    #[error("Symbol {0:?} is longer than {MAX_SYMBOL_LENGTH} bytes")]
+    SymbolTooLong(String),
+    #[error("Candy machines accept at most {MAX_CREATOR_LIMIT} creators")]
+    TooManyCreators,
+    #[error("Creator share {0} is not a percentage")]
+    InvalidShare(u32),
+    #[error("Mint limit {0} is out of range for a candy guard")]
+    MintLimit(i32),
+}
+
+/// An item of the manifest a candy machine is loaded with
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize)]
+pub struct ConfigLine {
+    pub name: String,
+    pub uri: String,
+}
+
+/// How the config lines of a candy machine are stored. The prefixes are
+/// stored once, and each line holds the rest of its name and uri padded to
+/// the longest of them.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize)]
+pub struct ConfigLineSettings {
+    pub prefix_name: String,
+    pub name_length: u32,
+    pub prefix_uri: String,
+    pub uri_length: u32,
+    pub is_sequential: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub percentage_share: u8,
+}
+
+/// What every mint of a candy machine is created with
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize)]
+pub struct CandyMachineData {
+    pub items_available: u64,
+    pub symbol: String,
+    pub seller_fee_basis_points: u16,
+    pub max_supply: u64,
+    pub is_mutable: bool,
+    pub creators: Vec<Creator>,
+    pub config_line_settings: Option<ConfigLineSettings>,
+    pub hidden_settings: Option<HiddenSettings>,
+}
+
+/// Settings minting every item with the same name and uri, to be revealed
+/// later. Deployments always insert config lines instead.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize)]
+pub struct HiddenSettings {
+    pub name: String,
+    pub uri: String,
+    pub hash: [u8; 32],
+}
+
+/// The default guard set of a candy guard. Unset guards are disabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Guards {
+    /// Lamports paid by each mint, and the wallet receiving them
+    pub sol_payment: Option<(u64, Pubkey)>,
+    /// Unix timestamp before which minting is closed
+    pub start_date: Option<i64>,
+    /// Unix timestamp after which minting is closed
+    pub end_date: Option<i64>,
+    /// Mints allowed per wallet
+    pub mint_limit: Option<u16>,
+}
+
+/// A candy machine, identified by the keypair its account was created with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CandyMachineAccounts {
+    pub address: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// The accounts of the collection a candy machine mints into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollectionAccounts {
+    pub mint: Pubkey,
+    pub metadata: Pubkey,
+    pub master_edition: Pubkey,
+    pub update_authority: Pubkey,
+}
+
+#[derive(AnchorSerialize)]
+struct InitializeArgs {
+    data: CandyMachineData,
+}
+
+#[derive(AnchorSerialize)]
+struct AddConfigLinesArgs {
+    index: u32,
+    config_lines: Vec<ConfigLine>,
+}
+
+#[derive(AnchorSerialize)]
+struct InitializeGuardArgs {
+    data: Vec<u8>,
+}
+
+#[derive(AnchorSerialize)]
+struct WrapArgs {}
+
+impl ConfigLineSettings {
+    /// Settings storing `items` with their common prefixes factored out
+    ///
+    /// # Errors
+    /// This function fails if `items` is empty or any of its names or uris
+    /// is too long to be minted
+    pub fn from_manifest(
+        items: &[ConfigLine],
+        is_sequential: bool,
+    ) -> Result<Self, CandyMachineError> {
+        let first = items.first().ok_or(CandyMachineError::EmptyManifest)?;
+
+        for item in items {
+            if item.name.len() > MAX_NAME_LENGTH {
+                return Err(CandyMachineError::NameTooLong(item.name.clone()));
+            }
+
+            if item.uri.len() > MAX_URI_LENGTH {
+                return Err(CandyMachineError::UriTooLong(item.uri.clone()));
+            }
+        }
+
+        let prefix_name = common_prefix(items.iter().map(|i| i.name.as_str()), &first.name);
+        let prefix_uri = common_prefix(items.iter().map(|i| i.uri.as_str()), &first.uri);
+        let name_length = items.iter().map(|i| i.name.len() - prefix_name.len()).max();
+        let uri_length = items.iter().map(|i| i.uri.len() - prefix_uri.len()).max();
+        let length = |len: Option<usize>| {
+            len.and_then(|l| u32::try_from(l).ok())
+                .unwrap_or_else(|| unreachable!("lengths were checked against their maximum"))
+        };
+
+        Ok(Self {
+            prefix_name: prefix_name.to_owned(),
+            name_length: length(name_length),
+            prefix_uri: prefix_uri.to_owned(),
+            uri_length: length(uri_length),
+            is_sequential,
+        })
+    }
+
+    /// `line` with the prefixes stripped, as it is inserted
+    #[must_use]
+    pub fn strip(&self, line: &ConfigLine) -> ConfigLine {
+        ConfigLine {
+            name: line.name[self.prefix_name.len()..].to_owned(),
+            uri: line.uri[self.prefix_uri.len()..].to_owned(),
+        }
+    }
+
+    /// Bytes taken by each line in the candy machine account
+    #[must_use]
+    pub fn line_size(&self) -> usize {
+        self.name_length as usize + self.uri_length as usize
+    }
+
+    /// Number of the stripped `lines` inserted by the next batch, at least
+    /// one as long as any are left
+    #[must_use]
+    pub fn batch_len(&self, lines: &[ConfigLine]) -> usize {
+        let mut bytes = 0;
+
+        lines
+            .iter()
+            .take_while(|line| {
+                // Each string is prefixed with its length
+                bytes += 4 + line.name.len() + 4 + line.uri.len();
+                bytes <= MAX_BATCH_BYTES
+            })
+            .count()
+            .max(lines.len().min(1))
+    }
+}
+
+/// The longest prefix `first` shares with every string of `all`, cut at a
+/// character boundary
+fn common_prefix<'a>(all: impl Iterator<Item = &'a str>, first: &'a str) -> &'a str {
+    let len = all.fold(first.len(), |len, s| {
+        first
+            .bytes()
+            .zip(s.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count()
+    });
+    let len = (0..=len)
+        .rev()
+        .find(|i| first.is_char_boundary(*i))
+        .unwrap_or(0);
+
+    &first[..len]
+}
+
+impl Guards {
+    /// The guard set in the layout read by the Candy Guard program: the
+    /// feature flags of the enabled guards followed by each of their
+    /// arguments, then the number of guard groups
+    #[must_use]
+    pub fn data(&self) -> Vec<u8> {
+        let mut features = 0u64;
+        let mut args = Vec::new();
+
+        if let Some((lamports, destination)) = self.sol_payment {
+            features |= 1 << GUARD_SOL_PAYMENT;
+            args.extend_from_slice(&lamports.to_le_bytes());
+            args.extend_from_slice(destination.as_ref());
+        }
+
+        if let Some(date) = self.start_date {
+            features |= 1 << GUARD_START_DATE;
+            args.extend_from_slice(&date.to_le_bytes());
+        }
+
+        if let Some(date) = self.end_date {
+            features |= 1 << GUARD_END_DATE;
+            args.extend_from_slice(&date.to_le_bytes());
+        }
+
+        if let Some(limit) = self.mint_limit {
+            features |= 1 << GUARD_MINT_LIMIT;
+            // Mints are counted under limit id 0
+            args.push(0);
+            args.extend_from_slice(&limit.to_le_bytes());
+        }
+
+        let mut data = features.to_le_bytes().to_vec();
+        data.extend(args);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data
+    }
+}
+
+impl CandyMachineData {
+    /// Check the fields of the data the program limits
+    ///
+    /// # Errors
+    /// This function fails if the symbol is too long or there are too many
+    /// creators
+    pub fn check(&self) -> Result<(), CandyMachineError> {
+        if self.symbol.len() > MAX_SYMBOL_LENGTH {
+            return Err(CandyMachineError::SymbolTooLong(self.symbol.clone()));
+        }
+
+        if self.creators.len() > MAX_CREATOR_LIMIT {
+            return Err(CandyMachineError::TooManyCreators);
+        }
+
+        Ok(())
+    }
+
+    /// Size of the candy machine account holding this data and its config
+    /// lines
+    #[must_use]
+    pub fn space(&self) -> usize {
+        let Some(settings) = &self.config_line_settings else {
+            return CANDY_MACHINE_SIZE;
+        };
+        let items = usize::try_from(self.items_available).unwrap_or(usize::MAX);
+        // The number of lines inserted, the lines, a bit mask of the
+        // inserted lines and the indices of the lines not minted yet
+        let lines = 4 + items * settings.line_size();
+        let mask = 4 + items / 8 + 1;
+        let indices = 4 + items * 4;
+
+        CANDY_MACHINE_SIZE + lines + mask + indices
+    }
+}
+
+impl CandyMachineAccounts {
+    /// The candy machine at `address`, administered by `authority`
+    #[must_use]
+    pub fn new(address: Pubkey, authority: Pubkey) -> Self {
+        Self { address, authority }
+    }
+
+    /// The PDA the candy machine mints and verifies collection items as
+    #[must_use]
+    pub fn authority_pda(&self) -> Pubkey {
+        Pubkey::find_program_address(&[SEED_CANDY_MACHINE, self.address.as_ref()], &PROGRAM_ID).0
+    }
+
+    /// The candy guard wrapping the candy machine. The candy machine's
+    /// address doubles as the base of the guard.
+    #[must_use]
+    pub fn candy_guard(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[SEED_CANDY_GUARD, self.address.as_ref()],
+            &GUARD_PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Instructions initializing the candy machine in its already created
+    /// account, then wrapping it in a candy guard enforcing `guards`. The
+    /// candy machine's keypair and the collection's update authority both
+    /// sign.
+    #[must_use]
+    pub fn initialize(
+        &self,
+        payer: Pubkey,
+        collection: &CollectionAccounts,
+        data: CandyMachineData,
+        guards: &Guards,
+    ) -> Vec<Instruction> {
+        let authority_pda = self.authority_pda();
+        let candy_guard = self.candy_guard();
+        let collection_authority_record = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                mpl_token_metadata::ID.as_ref(),
+                collection.mint.as_ref(),
+                b"collection_authority",
+                authority_pda.as_ref(),
+            ],
+            &mpl_token_metadata::ID,
+        )
+        .0;
+
+        vec![
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(self.address, false),
+                    AccountMeta::new(authority_pda, false),
+                    AccountMeta::new_readonly(self.authority, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(collection.metadata, false),
+                    AccountMeta::new_readonly(collection.mint, false),
+                    AccountMeta::new_readonly(collection.master_edition, false),
+                    AccountMeta::new(collection.update_authority, true),
+                    AccountMeta::new(collection_authority_record, false),
+                    AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+                data: instruction_data("initialize", &InitializeArgs { data }),
+            },
+            Instruction {
+                program_id: GUARD_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(candy_guard, false),
+                    AccountMeta::new_readonly(self.address, true),
+                    AccountMeta::new_readonly(self.authority, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+                data: instruction_data("initialize", &InitializeGuardArgs {
+                    data: guards.data(),
+                }),
+            },
+            Instruction {
+                program_id: GUARD_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(candy_guard, false),
+                    AccountMeta::new_readonly(self.authority, true),
+                    AccountMeta::new(self.address, false),
+                    AccountMeta::new_readonly(PROGRAM_ID, false),
+                    AccountMeta::new_readonly(self.authority, true),
+                ],
+                data: instruction_data("wrap", &WrapArgs {}),
+            },
+        ]
+    }
+
+    /// Instruction inserting the stripped `lines` from `index` on, signed by
+    /// the authority
+    #[must_use]
+    pub fn add_config_lines(&self, index: u32, lines: Vec<ConfigLine>) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(self.address, false),
+                AccountMeta::new_readonly(self.authority, true),
+            ],
+            data: instruction_data("add_config_lines", &AddConfigLinesArgs {
+                index,
+                config_lines: lines,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(name: &str, uri: &str) -> ConfigLine {
+        ConfigLine {
+            name: name.to_owned(),
+            uri: uri.to_owned(),
+        }
+    }
+
+    #[test]
+    fn factors_out_common_prefixes() {
+        let items = [
+            line("Hub #1", "https://arweave.net/a"),
+            line("Hub #10", "https://arweave.net/bcd"),
+        ];
+
+        let settings = ConfigLineSettings::from_manifest(&items, false).unwrap();
+        assert_eq!(settings.prefix_name, "Hub #1");
+        assert_eq!(settings.name_length, 1);
+        assert_eq!(settings.prefix_uri, "https://arweave.net/");
+        assert_eq!(settings.uri_length, 3);
+        assert_eq!(settings.strip(&items[1]), line("0", "bcd"));
+
+        assert!(matches!(
+            ConfigLineSettings::from_manifest(&[], false),
+            Err(CandyMachineError::EmptyManifest)
+        ));
+        assert!(matches!(
+            ConfigLineSettings::from_manifest(&[line(&"a".repeat(33), "")], false),
+            Err(CandyMachineError::NameTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn batches_lines_by_size() {
+        let settings = ConfigLineSettings::from_manifest(&[line("a", "b")], true).unwrap();
+        let lines = vec![line("", &"u".repeat(92)); 20];
+
+        assert_eq!(settings.batch_len(&lines), 8);
+        assert_eq!(settings.batch_len(&lines[..3]), 3);
+        assert_eq!(settings.batch_len(&[]), 0);
+    }
+
+    #[test]
+    fn encodes_enabled_guards() {
+        let guards = Guards {
+            start_date: Some(1_700_000_000),
+            mint_limit: Some(3),
+            ..Guards::default()
+        };
+
+        let data = guards.data();
+        assert_eq!(
+            &data[..8],
+            &((1u64 << GUARD_START_DATE) | (1 << GUARD_MINT_LIMIT)).to_le_bytes()
+        );
+        assert_eq!(&data[8..16], &1_700_000_000i64.to_le_bytes());
+        assert_eq!(&data[16..19], &[0, 3, 0]);
+        assert_eq!(&data[19..], &0u32.to_le_bytes());
+    }
+}
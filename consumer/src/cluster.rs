@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+
+use holaplex_hub_nfts_solana_entity::sea_orm_active_enums::SolanaCluster;
+use hub_core::{anyhow::Result, clap, prelude::*, uuid::Uuid};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    metrics::Metrics,
+    retry::Retries,
+    solana::{Solana, SolanaArgs},
+};
+
+/// A second cluster served alongside the primary one, typically devnet for
+/// test projects. Only enabled when `test_solana_endpoint` is set.
+#[derive(Debug, clap::Args)]
+pub struct TestClusterArgs {
+    #[arg(long, env, default_value = "devnet")]
+    pub test_solana_cluster: SolanaCluster,
+
+    #[arg(long, env)]
+    pub test_solana_endpoint: Option<String>,
+
+    #[arg(long, env)]
+    pub test_digital_asset_api_endpoint: Option<String>,
+
+    #[arg(long, env)]
+    pub test_tree_authority: Option<Pubkey>,
+    #[arg(long, env)]
+    pub test_merkle_tree: Option<Pubkey>,
+    #[arg(long, env)]
+    pub test_tree_delegate: Option<Pubkey>,
+
+    /// Projects whose first collection is created on the test cluster.
+    /// Projects with collections stay on the cluster of their newest one.
+    #[arg(long, env, value_delimiter = ',')]
+    pub test_cluster_projects: Vec<Uuid>,
+}
+
+/// The Solana clusters served by this consumer, and which projects use each
+#[derive(Clone)]
+pub struct Clusters {
+    primary: Solana,
+    test: Option<Solana>,
+    test_projects: Arc<HashSet<Uuid>>,
+}
+
+impl Clusters {
+    /// Build clients for the primary cluster and, if configured, the test
+    /// cluster.
+    ///
+    /// # Errors
+    /// This function fails if a client cannot be built for either cluster, or
+    /// the test cluster is enabled without its DAS endpoint and merkle tree
+    pub fn new(primary: SolanaArgs, test: TestClusterArgs, metrics: &Metrics) -> Result<Self> {
+        let TestClusterArgs {
+            test_solana_cluster,
+            test_solana_endpoint,
+            test_digital_asset_api_endpoint,
+            test_tree_authority,
+            test_merkle_tree,
+            test_tree_delegate,
+            test_cluster_projects,
+        } = test;
+
+        let test = test_solana_endpoint
+            .map(|solana_endpoint| {
+                ensure!(
+                    test_solana_cluster != primary.solana_cluster,
+                    "test cluster must differ from the primary cluster"
+                );
+
+                Solana::new(
+                    SolanaArgs {
+                        solana_endpoint,
+                        solana_ws_endpoint: None,
+                        solana_cluster: test_solana_cluster,
+                        solana_fallback_endpoints: vec![],
+                        solana_health_check_interval: primary.solana_health_check_interval,
+                        solana_rpc_requests_per_second: primary.solana_rpc_requests_per_second,
+                        solana_simulate_transactions: primary.solana_simulate_transactions,
+                        solana_treasury_wallet_address: primary.solana_treasury_wallet_address,
+                        digital_asset_api_endpoint: test_digital_asset_api_endpoint
+                            .context("test cluster requires a digital asset API endpoint")?,
+                        digital_asset_api_provider: primary.digital_asset_api_provider,
+                        digital_asset_api_hedge_delay: primary.digital_asset_api_hedge_delay,
+                        tree_authority: test_tree_authority
+                            .context("test cluster requires a tree authority")?,
+                        merkle_tree: test_merkle_tree
+                            .context("test cluster requires a merkle tree")?,
+                        tree_delegate: test_tree_delegate,
+                        submit_commitment: primary.submit_commitment,
+                    },
+                    metrics.clone(),
+                )
+            })
+            .transpose()?;
+
+        Ok(Self {
+            primary: Solana::new(primary, metrics.clone())?,
+            test,
+            test_projects: Arc::new(test_cluster_projects.into_iter().collect()),
+        })
+    }
+
+    /// Apply the retry policies of `retries` to both clusters. Each cluster
+    /// trips its circuit breakers separately.
+    #[must_use]
+    pub fn with_retries(self, retries: &Retries) -> Self {
+        Self {
+            primary: self.primary.with_retries(retries.clone()),
+            test: self.test.map(|test| test.with_retries(retries.separate())),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn primary(&self) -> &Solana {
+        &self.primary
+    }
+
+    /// The cluster configured for `project_id`, falling back to the primary
+    /// cluster for unknown or malformed project ids.
+    #[must_use]
+    pub fn for_project(&self, project_id: &str) -> &Solana {
+        match (&self.test, Uuid::parse_str(project_id)) {
+            (Some(test), Ok(id)) if self.test_projects.contains(&id) => test,
+            _ => &self.primary,
+        }
+    }
+
+    /// The client for `cluster`, if this consumer serves it
+    #[must_use]
+    pub fn get(&self, cluster: SolanaCluster) -> Option<&Solana> {
+        self.iter().find(|solana| solana.cluster() == cluster)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Solana> {
+        std::iter::once(&self.primary).chain(&self.test)
+    }
+}
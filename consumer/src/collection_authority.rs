@@ -0,0 +1,160 @@
+//! Proof that a project controls a collection before importing it. The
+//! update authority of the collection signs a challenge naming the project,
+//! so a foreign collection cannot be imported by anyone who merely knows its
+//! address.
+
+use std::str::FromStr;
+
+use holaplex_hub_nfts_solana_entity::collection_authorities;
+use hub_core::{
+    chrono::{Duration, NaiveDateTime, Utc},
+    thiserror,
+    uuid::Uuid,
+};
+use solana_program::pubkey::{ParsePubkeyError, Pubkey};
+use solana_sdk::signature::{ParseSignatureError, Signature};
+
+/// How long an issued challenge may be signed for
+const CHALLENGE_TTL_MINUTES: i64 = 10;
+
+/// Why a signed challenge was rejected
+#[derive(Debug, thiserror::Error)]
+pub enum AuthorityError {
+    #[error("No challenge was issued for collection {0}")]
+    NoChallenge(String),
+    #[error("The challenge for collection {0} has expired")]
+    Expired(String),
+    #[error("The challenge was issued to {expected}, not {found}")]
+    WrongAuthority { expected: String, found: String },
+    #[error("Invalid update authority")]
+    InvalidAuthority(#[from] ParsePubkeyError),
+    #[error("Invalid signature")]
+    MalformedSignature(#[from] ParseSignatureError),
+    #[error("Challenge was not signed by {0}")]
+    InvalidSignature(Pubkey),
+}
+
+/// A message for the update authority of a collection to sign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityChallenge {
+    pub message: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl AuthorityChallenge {
+    /// A fresh challenge binding `update_authority` of `mint_address` to
+    /// `project_id`
+    #[must_use]
+    pub fn new(project_id: Uuid, mint_address: &str, update_authority: &str) -> Self {
+        let message = [
+            format!("Import collection {mint_address} into Holaplex Hub project {project_id}"),
+            String::new(),
+            format!("Update authority: {update_authority}"),
+            format!("Nonce: {}", Uuid::new_v4()),
+        ]
+        .join("\n");
+
+        Self {
+            message,
+            expires_at: Utc::now().naive_utc() + Duration::minutes(CHALLENGE_TTL_MINUTES),
+        }
+    }
+}
+
+/// Check that `signature` is the signature of `update_authority` over the
+/// challenge `issued` to it, before the challenge expired
+///
+/// # Errors
+/// This function fails if the challenge expired, was issued to another
+/// wallet or was signed by another key or over another message
+pub fn verify(
+    issued: &collection_authorities::Model,
+    update_authority: &str,
+    signature: &str,
+) -> Result<(), AuthorityError> {
+    if issued.expires_at <= Utc::now().naive_utc() {
+        return Err(AuthorityError::Expired(issued.mint_address.clone()));
+    }
+
+    if issued.update_authority != update_authority {
+        return Err(AuthorityError::WrongAuthority {
+            expected: issued.update_authority.clone(),
+            found: update_authority.to_owned(),
+        });
+    }
+
+    let authority = Pubkey::from_str(update_authority)?;
+    let signature = Signature::from_str(signature)?;
+
+    if signature.verify(authority.as_ref(), issued.challenge.as_bytes()) {
+        Ok(())
+    } else {
+        Err(AuthorityError::InvalidSignature(authority))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signer::{keypair::Keypair, Signer};
+
+    use super::*;
+
+    fn issue(authority: &Keypair) -> collection_authorities::Model {
+        let update_authority = authority.pubkey().to_string();
+        let challenge = AuthorityChallenge::new(Uuid::new_v4(), "mint", &update_authority);
+
+        collection_authorities::Model {
+            project_id: Uuid::new_v4(),
+            mint_address: "mint".to_owned(),
+            update_authority,
+            challenge: challenge.message,
+            expires_at: challenge.expires_at,
+            verified_at: None,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn verifies_signed_challenges() {
+        let authority = Keypair::new();
+        let issued = issue(&authority);
+        let signature = authority.sign_message(issued.challenge.as_bytes());
+
+        verify(&issued, &issued.update_authority, &signature.to_string()).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_signers_and_messages() {
+        let authority = Keypair::new();
+        let other = Keypair::new();
+        let issued = issue(&authority);
+        let forged = other.sign_message(issued.challenge.as_bytes());
+        let replayed = authority.sign_message(b"another challenge");
+
+        assert!(matches!(
+            verify(&issued, &issued.update_authority, &forged.to_string()),
+            Err(AuthorityError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            verify(&issued, &issued.update_authority, &replayed.to_string()),
+            Err(AuthorityError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            verify(&issued, &other.pubkey().to_string(), &forged.to_string()),
+            Err(AuthorityError::WrongAuthority { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_expired_challenges() {
+        let authority = Keypair::new();
+        let mut issued = issue(&authority);
+        let signature = authority.sign_message(issued.challenge.as_bytes());
+        issued.expires_at = Utc::now().naive_utc() - Duration::seconds(1);
+
+        assert!(matches!(
+            verify(&issued, &issued.update_authority, &signature.to_string()),
+            Err(AuthorityError::Expired(_))
+        ));
+    }
+}
@@ -0,0 +1,41 @@
+use holaplex_hub_nfts_solana_core::Services;
+use hub_core::{
+    chrono::Utc,
+    consumer::{Message, MessageGroup, RecvError},
+    metrics::KeyValue,
+};
+
+use crate::metrics::Metrics;
+
+/// A consumed message along with the time it was produced, which is used to
+/// report how far the consumer is behind each topic
+#[derive(Debug, Clone)]
+pub struct Consumed {
+    pub msg: Services,
+    produced_at_ms: Option<i64>,
+}
+
+impl MessageGroup for Consumed {
+    const REQUESTED_TOPICS: &'static [&'static str] = Services::REQUESTED_TOPICS;
+
+    fn from_message<M: Message>(msg: &M) -> Result<Self, RecvError> {
+        Ok(Self {
+            produced_at_ms: msg.timestamp().to_millis(),
+            msg: Services::from_message(msg)?,
+        })
+    }
+}
+
+impl Consumed {
+    /// Count the message and record the time it spent in its topic
+    pub fn observe(&self, metrics: &Metrics) {
+        let topic = [KeyValue::new("topic", self.msg.topic())];
+
+        metrics.messages_consumed_counter.add(1, &topic);
+
+        if let Some(produced_at) = self.produced_at_ms {
+            let lag = (Utc::now().timestamp_millis() - produced_at).max(0);
+            metrics.consumer_lag_ms_bucket.record(lag, &topic);
+        }
+    }
+}
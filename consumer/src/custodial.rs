@@ -0,0 +1,123 @@
+//! Recipients given as `email:<address>` receive mints in a custodial wallet
+//! held for that address. Wallets are looked up through a
+//! [`WalletResolver`] before a mint is assembled and cached in the
+//! `custodial_wallets` table.
+
+use holaplex_hub_nfts_solana_core::{db::Connection, CustodialWallet};
+use hub_core::{prelude::*, reqwest, util::DebugShim};
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+
+/// Prefix marking a recipient as an email address to resolve rather than a
+/// wallet address
+pub const EMAIL_PREFIX: &str = "email:";
+
+/// Maps the email address of a custodial recipient to the wallet held for it
+#[async_trait]
+pub trait WalletResolver: Send + Sync {
+    /// The wallet held for `email`, creating it if the provider does so on
+    /// first use
+    async fn resolve(&self, email: &str) -> Result<Pubkey>;
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletResponse {
+    address: String,
+}
+
+/// A [`WalletResolver`] calling an HTTP API which answers
+/// `GET <endpoint>?email=<address>` with `{"address": "<wallet>"}`
+#[derive(Debug, Clone)]
+pub struct HttpWalletResolver {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpWalletResolver {
+    /// Build a resolver whose requests time out after `timeout`, sending
+    /// `api_key` as a bearer token if given.
+    ///
+    /// # Errors
+    /// This function fails if the HTTP client cannot be built
+    pub fn new(endpoint: String, api_key: Option<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("failed to build custodial wallet resolver client")?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+        })
+    }
+}
+
+#[async_trait]
+impl WalletResolver for HttpWalletResolver {
+    async fn resolve(&self, email: &str) -> Result<Pubkey> {
+        let mut req = self.client.get(&self.endpoint).query(&[("email", email)]);
+
+        if let Some(api_key) = &self.api_key {
+            req = req.bearer_auth(api_key);
+        }
+
+        let WalletResponse { address } = req
+            .send()
+            .await
+            .context("custodial wallet request failed")?
+            .error_for_status()
+            .context("custodial wallet request was rejected")?
+            .json()
+            .await
+            .context("invalid custodial wallet response")?;
+
+        address
+            .parse()
+            .with_context(|| format!("invalid custodial wallet address {address:?}"))
+    }
+}
+
+/// Resolves custodial recipients, caching the wallet found for each email
+#[derive(Debug, Clone)]
+pub struct CustodialWallets {
+    db: Connection,
+    resolver: DebugShim<Arc<dyn WalletResolver>>,
+}
+
+impl CustodialWallets {
+    #[must_use]
+    pub fn new(db: Connection, resolver: Arc<dyn WalletResolver>) -> Self {
+        Self {
+            db,
+            resolver: DebugShim(resolver),
+        }
+    }
+
+    /// The email address of a custodial recipient, normalized for lookup
+    #[must_use]
+    pub fn email(recipient: &str) -> Option<String> {
+        recipient
+            .strip_prefix(EMAIL_PREFIX)
+            .map(|email| email.trim().to_lowercase())
+    }
+
+    /// The wallet held for `email`, from the cache if it has been resolved
+    /// before
+    ///
+    /// # Errors
+    /// This function fails if the cache cannot be read or written, or the
+    /// wallet cannot be resolved
+    pub async fn resolve(&self, email: String) -> Result<String> {
+        if let Some(wallet) = CustodialWallet::find_by_email(self.db.get(), &email).await? {
+            return Ok(wallet.wallet_address);
+        }
+
+        let wallet = self.resolver.0.resolve(&email).await?.to_string();
+
+        CustodialWallet::upsert(self.db.get(), email, wallet.clone()).await?;
+
+        Ok(wallet)
+    }
+}
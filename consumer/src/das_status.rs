@@ -0,0 +1,70 @@
+//! Compressed transfers, mints and imports depend on the digital asset API.
+//! While the DAS circuit breaker of a cluster is open those operations are
+//! paused, so a `DigitalAssetApiStatusChanged` event is emitted each time a
+//! breaker trips or closes to let operators know.
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{
+        solana_nft_events::Event as SolanaNftEvent, SolanaDigitalAssetApiStatus,
+        SolanaNftEventKey, SolanaNftEvents,
+    },
+    Outbox,
+};
+use hub_core::{anyhow::Result, futures_util::future::join_all, prelude::*, uuid::Uuid};
+
+use crate::{cluster::Clusters, retry::RetryClass, solana::Solana};
+
+/// Emits an event whenever the DAS circuit breaker of a cluster changes state
+#[derive(Clone)]
+pub struct DasStatusMonitor {
+    db: Connection,
+    clusters: Clusters,
+}
+
+impl DasStatusMonitor {
+    #[must_use]
+    pub fn new(db: Connection, clusters: Clusters) -> Self {
+        Self { db, clusters }
+    }
+
+    pub async fn run(self) {
+        join_all(self.clusters.iter().map(|solana| self.watch(solana))).await;
+    }
+
+    async fn watch(&self, solana: &Solana) {
+        let mut open = solana.retries().breaker(RetryClass::Das).subscribe();
+
+        while open.changed().await.is_ok() {
+            let degraded = *open.borrow_and_update();
+
+            if let Err(e) = self.report(solana, degraded).await {
+                error!("{:?}", e.context("failed to emit DAS status event"));
+            }
+        }
+    }
+
+    async fn report(&self, solana: &Solana, degraded: bool) -> Result<()> {
+        let cluster = solana.cluster().to_string();
+
+        if degraded {
+            warn!("digital asset API of {cluster} is down, pausing compressed operations");
+        } else {
+            info!("digital asset API of {cluster} recovered, resuming compressed operations");
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let key = SolanaNftEventKey {
+            id: id.clone(),
+            correlation_id: id,
+            ..Default::default()
+        };
+        let event = SolanaNftEvents::new(SolanaNftEvent::DigitalAssetApiStatusChanged(
+            SolanaDigitalAssetApiStatus { cluster, degraded },
+        ));
+
+        Outbox::enqueue(self.db.get(), &key, &event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Utilization of the database connection pools. Each pool is sampled
+//! periodically for its open and idle connections and for how long checking
+//! out a connection takes, so exhausted pools show up before queries start
+//! timing out.
+
+use std::{collections::HashMap, time::Instant};
+
+use holaplex_hub_nfts_solana_core::db::{Connection, DatabaseConnection};
+use hub_core::{metrics::KeyValue, prelude::*, tokio};
+
+use crate::metrics::Metrics;
+
+/// Periodically reports the connections of the primary pool and of every
+/// read replica pool
+#[derive(Clone)]
+pub struct PoolMonitor {
+    db: Connection,
+    metrics: Metrics,
+    interval: Duration,
+}
+
+impl PoolMonitor {
+    #[must_use]
+    pub fn new(db: Connection, metrics: Metrics, interval: Duration) -> Self {
+        Self {
+            db,
+            metrics,
+            interval,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        let mut last = HashMap::new();
+
+        loop {
+            interval.tick().await;
+
+            let primary = ("primary".to_owned(), self.db.get());
+            let replicas = self
+                .db
+                .replicas()
+                .iter()
+                .enumerate()
+                .map(|(i, conn)| (format!("replica_{i}"), conn));
+
+            for (pool, conn) in std::iter::once(primary).chain(replicas) {
+                if let Err(e) = self.sample(&mut last, &pool, conn).await {
+                    error!("failed to sample database pool {pool}: {e:?}");
+                }
+            }
+        }
+    }
+
+    /// Report the connections of one pool, then time checking one out.
+    /// Up-down counters only take deltas, so `last` keeps the values last
+    /// reported for each pool and state.
+    async fn sample(
+        &self,
+        last: &mut HashMap<(String, &'static str), i64>,
+        pool: &str,
+        conn: &DatabaseConnection,
+    ) -> Result<()> {
+        let pg = conn.get_postgres_connection_pool();
+        let open = i64::from(pg.size());
+        let idle = i64::try_from(pg.num_idle())?;
+
+        for (state, value) in [("in_use", open - idle), ("idle", idle)] {
+            let previous = last.insert((pool.to_owned(), state), value).unwrap_or(0);
+
+            self.metrics.db_pool_connections.add(value - previous, &[
+                KeyValue::new("pool", pool.to_owned()),
+                KeyValue::new("state", state),
+            ]);
+        }
+
+        let start = Instant::now();
+        drop(pg.acquire().await?);
+        let elapsed = i64::try_from(start.elapsed().as_millis())?;
+
+        self.metrics
+            .db_pool_acquire_duration_ms_bucket
+            .record(elapsed, &[KeyValue::new("pool", pool.to_owned())]);
+
+        Ok(())
+    }
+}
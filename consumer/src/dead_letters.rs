@@ -0,0 +1,64 @@
+//! Messages whose processing fails with a permanent error are kept instead
+//! of being dropped: each is stored in the `dead_letters` table, from which
+//! operators can replay it through the admin API, and published with its
+//! error to the [`TOPIC`] topic.
+
+use std::error::Error as StdError;
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{SolanaDeadLetter, SolanaNftEventKey},
+    DeadLetter, Services,
+};
+use hub_core::{prelude::*, producer::Producer, triage};
+
+/// Topic dead letters are published to
+pub const TOPIC: &str = "hub-nfts-solana-dlq";
+
+/// Records consumed messages that failed permanently
+#[derive(Debug, Clone)]
+pub struct DeadLetters {
+    db: Connection,
+    producer: Producer<SolanaDeadLetter>,
+}
+
+impl DeadLetters {
+    #[must_use]
+    pub fn new(db: Connection, producer: Producer<SolanaDeadLetter>) -> Self {
+        Self { db, producer }
+    }
+
+    /// Store `msg` with the error chain of `err` and publish it to the
+    /// dead-letter topic
+    ///
+    /// # Errors
+    /// This function fails if the dead letter cannot be stored or published
+    pub async fn record(&self, msg: &Services, err: &triage::BoxedSync) -> Result<()> {
+        let error = std::iter::once(err.to_string())
+            .chain(std::iter::successors(err.source(), |e| e.source()).map(ToString::to_string))
+            .collect::<Vec<_>>()
+            .join(": ");
+
+        let model = DeadLetter::create(self.db.get(), msg, error).await?;
+
+        warn!("stored dead letter {} from {}", model.id, model.topic);
+
+        let mut key = match msg {
+            Services::Nfts(key, _) => SolanaNftEventKey::from(key.clone()),
+            Services::Treasury(key, _) => SolanaNftEventKey::from(key.clone()),
+        };
+        key.id = model.id.to_string();
+
+        let letter = SolanaDeadLetter {
+            id: model.id.to_string(),
+            topic: model.topic,
+            key: model.key,
+            payload: model.payload,
+            error: model.error,
+        };
+
+        self.producer.send(Some(&letter), Some(&key)).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{
+        solana_nft_events::Event as SolanaNftEvent, SolanaDropBoundary, SolanaNftEventKey,
+        SolanaNftEvents,
+    },
+    sea_orm::{ActiveModelTrait, Set},
+    Collection, Outbox,
+};
+use holaplex_hub_nfts_solana_entity::collections;
+use hub_core::{
+    anyhow::Result,
+    chrono::{NaiveDateTime, Utc},
+    prelude::*,
+    tokio,
+};
+
+/// Periodically looks for collections whose scheduled mint window has
+/// opened or closed and emits a `DropOpened` or `DropClosed` event for each,
+/// once per boundary.
+#[derive(Clone)]
+pub struct DropScheduler {
+    db: Connection,
+    interval: Duration,
+}
+
+impl DropScheduler {
+    #[must_use]
+    pub fn new(db: Connection, interval: Duration) -> Self {
+        Self { db, interval }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.announce().await {
+                error!("{:?}", e.context("failed to announce drop boundaries"));
+            }
+        }
+    }
+
+    /// Queue an event for every mint window boundary passed since the last
+    /// run. Rows are locked while they are announced so several consumers
+    /// can run this concurrently without emitting an event twice.
+    ///
+    /// # Errors
+    /// This function fails if the collections cannot be loaded or updated,
+    /// or an event cannot be queued
+    pub async fn announce(&self) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let txn = self.db.begin().await?;
+
+        for collection in Collection::find_unannounced_openings(&txn, now).await? {
+            let event = SolanaNftEvents::new(SolanaNftEvent::DropOpened(boundary(
+                &collection,
+                collection.mint_open_at,
+            )));
+
+            info!("mint window of collection {} opened", collection.id);
+            Outbox::enqueue(&txn, &key(&collection), &event).await?;
+
+            let mut am: collections::ActiveModel = collection.into();
+            am.open_announced_at = Set(Some(now));
+            am.update(&txn).await?;
+        }
+
+        for collection in Collection::find_unannounced_closings(&txn, now).await? {
+            let event = SolanaNftEvents::new(SolanaNftEvent::DropClosed(boundary(
+                &collection,
+                collection.mint_close_at,
+            )));
+
+            info!("mint window of collection {} closed", collection.id);
+            Outbox::enqueue(&txn, &key(&collection), &event).await?;
+
+            let mut am: collections::ActiveModel = collection.into();
+            am.close_announced_at = Set(Some(now));
+            am.update(&txn).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
+
+fn key(collection: &collections::Model) -> SolanaNftEventKey {
+    SolanaNftEventKey {
+        id: collection.id.to_string(),
+        correlation_id: collection.id.to_string(),
+        ..Default::default()
+    }
+}
+
+fn boundary(collection: &collections::Model, at: Option<NaiveDateTime>) -> SolanaDropBoundary {
+    SolanaDropBoundary {
+        collection_id: collection.id.to_string(),
+        timestamp: at.map_or(0, |at| at.timestamp()),
+    }
+}
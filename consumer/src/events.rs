@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::{mem, pin::Pin, time::Instant};
 
 use holaplex_hub_nfts_solana_core::{
     db,
@@ -6,45 +6,114 @@ use holaplex_hub_nfts_solana_core::{
         nft_events::Event as NftEvent,
         solana_nft_events::Event as SolanaNftEvent,
         treasury_events::{Event as TreasuryEvent, SolanaTransactionResult, TransactionStatus},
-        MetaplexMasterEditionTransaction, MintMetaplexEditionTransaction,
-        MintMetaplexMetadataTransaction, SolanaCompletedMintTransaction,
-        SolanaCompletedTransferTransaction, SolanaCompletedUpdateTransaction,
-        SolanaFailedTransaction, SolanaMintOpenDropBatchedPayload, SolanaMintPendingTransactions,
+        MetaplexMasterEditionTransaction, MintAllowlistPayload, MintMetaplexEditionTransaction,
+        MintMetaplexMetadataTransaction, MintPrice, NftEventKey, NftEvents,
+        ProjectTreasury as ProjectTreasuryPayload, SolanaCancelListingPayload, SolanaClaim,
+        SolanaCollectionAuthorityChallenge, SolanaCollectionAuthorityVerification,
+        SolanaCollectionExport, SolanaCollectionExportChunk,
+        SolanaCompletedCandyMachineTransaction, SolanaCompletedMintTransaction,
+        SolanaCompletedRoyaltySplitTransaction, SolanaCompletedTransferTransaction,
+        SolanaCompletedTreeTransaction, SolanaCompletedUpdateTransaction, SolanaCreateClaimPayload,
+        SolanaCreateRoyaltySplitPayload, SolanaDeployCandyMachinePayload,
+        SolanaDistributeRoyaltiesPayload, SolanaDropSchedulePayload, SolanaDropSupplyChanged,
+        SolanaEscrowAssetPayload, SolanaExportCollectionPayload, SolanaFailedTransaction,
+        SolanaFeePayerMode, SolanaHolderVerification, SolanaInsertCandyMachineItemsPayload,
+        SolanaListAssetPayload, SolanaLockMetadataPayload, SolanaMarkPrimarySalePayload,
+        SolanaMintOpenDropBatchedPayload, SolanaMintPayment, SolanaMintPendingTransactions,
         SolanaMintTransaction, SolanaNftEventKey, SolanaNftEvents, SolanaPendingTransaction,
-        SolanaTransactionFailureReason, SwitchCollectionPayload, TransferMetaplexAssetTransaction,
-        UpdateSolanaMintPayload,
+        SolanaRedeemClaimPayload, SolanaReleaseAssetPayload, SolanaReserveTreeCapacityPayload,
+        SolanaSignerPolicy, SolanaTransactionFailureReason, SolanaTreeCapacityReservation,
+        SolanaUpdateCollectionAssetsPayload, SolanaVerifyCollectionAuthorityPayload,
+        SolanaVerifyHolderPayload, SolanaWalletAsset, SolanaWalletContents,
+        SolanaWalletContentsRequest, SwitchCollectionPayload, TransferMetaplexAssetTransaction,
+        TransferMetaplexAssetsTransaction, UpdateSolanaMintPayload,
     },
-    sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set},
-    Collection, CollectionMint, CompressionLeaf, Services,
+    sea_orm::{ConnectionTrait, DatabaseTransaction, DbErr, EntityTrait, Set},
+    AssetEscrow, CandyMachine, Claim, Collection, CollectionAuthority, CollectionMint,
+    CompressionLeaf, EventRequest, Listing, ListingStatus, MerkleTree, Mint, MintAllowlist,
+    MintCursor, MintPayment, MultisigProposal, NonceAccount, Outbox, ProjectTreasury,
+    ProposalStatus, RevisionStatus, RoyaltySplit, Services, SignerPolicy, TransactionAudit,
+    TransactionCost, TransactionFailure, TransactionRevision, UpdateRevision, WalletMintCount,
 };
 use holaplex_hub_nfts_solana_entity::{
-    collection_mints, collections, compression_leafs, update_revisions,
+    asset_escrows,
+    candy_machines::{self, CandyMachineItem, CandyMachineItems},
+    claims, collection_mints,
+    collections::{self, CollectionCreator, CollectionCreators},
+    compression_leafs, listings, merkle_trees, mint_payments, multisig_proposals,
+    royalty_splits::{self, RoyaltySplitMember, RoyaltySplitMembers},
+    sea_orm_active_enums::{FeePayerMode, SolanaCluster},
+    transaction_audit, transaction_costs, transaction_failures, update_revisions,
 };
 use hub_core::{
-    backon::{ExponentialBuilder, Retryable},
-    chrono::Utc,
+    backon::Retryable,
+    chrono::{NaiveDateTime, Utc},
     metrics::KeyValue,
     prelude::*,
-    producer::{Producer, SendError},
+    clap,
+    producer::SendError,
     thiserror,
+    tracing::{info_span, Instrument},
     util::DebugShim,
     uuid,
     uuid::Uuid,
 };
 use solana_client::client_error::ClientError;
-use solana_program::pubkey::{ParsePubkeyError, Pubkey};
-use solana_sdk::signature::Signature;
+use solana_program::{
+    hash::{hash, hashv, Hash},
+    message::Message,
+    pubkey::{ParsePubkeyError, Pubkey},
+};
+use solana_sdk::{
+    signature::Signature,
+    signer::{keypair::Keypair, Signer},
+};
 
 use crate::{
     backend::{
-        CollectionBackend, MasterEditionAddresses, MintBackend, MintEditionAddresses,
-        MintMetaplexAddresses, TransferBackend, UpdateCollectionMintAddresses,
+        AssetStandard, Backends, MasterEditionAddresses, MintAddresses, MintEditionAddresses,
+        MintMetaplexAddresses, RoyaltySplitAddresses, TransactionResponse, TransferAssetsAddresses,
+        UpdateCollectionMintAddresses, UpdateCompressedMintAddresses, UpdateMintAddresses,
     },
+    candy_machine::{
+        self as cm, CandyMachineData, CandyMachineError, ConfigLine, ConfigLineSettings, Guards,
+    },
+    cluster::Clusters,
+    collection_authority::{self, AuthorityChallenge, AuthorityError},
+    custodial::CustodialWallets,
+    export::{self, ExportRecord, ExportSink},
+    hydra::{self, HydraError},
+    jito::Jito,
+    metadata_validation::MetadataValidator,
     metrics::Metrics,
-    solana::{CompressedRef, EditionRef, Solana, SolanaAssetIdError, UncompressedRef},
+    nonces::Nonces,
+    signer_policy::{AuthorizedSigners, SignerPolicyError},
+    solana::{self, Settled, Solana, SolanaAssetIdError, StaleRevision, SubmittedTransaction},
+    solana_pay,
+    squads::Multisig,
+    trees::{TreeError, TreeShape},
     with_retry,
 };
 
+/// Number of times a failed submit is re-signed with a fresh blockhash before
+/// the event is failed back to hub-nfts
+const MAX_RESUBMIT_ATTEMPTS: i32 = 3;
+
+/// Number of mints whose metadata is locked by each transaction when a whole
+/// collection is locked, leaving room for the collection NFT in the first
+const METADATA_LOCKS_PER_TRANSACTION: usize = 16;
+
+/// Seconds a Solana Pay transaction is handed out for after it was signed,
+/// or waited on after it was sent to be signed, leaving the wallet time to
+/// land it before its blockhash expires
+const CLAIM_TRANSACTION_TTL_SECS: i64 = 45;
+
+/// Page size of wallet contents listings that do not ask for one
+const DEFAULT_WALLET_CONTENTS_LIMIT: u32 = 50;
+
+/// Largest page of wallet contents a listing may ask for
+const MAX_WALLET_CONTENTS_LIMIT: u32 = 200;
+
 #[derive(Debug, thiserror::Error, Triage)]
 pub enum ProcessorErrorKind {
     #[error("Associated record not found in database")]
@@ -68,6 +137,121 @@ pub enum ProcessorErrorKind {
     ParseString(#[from] solana_sdk::signature::ParseSignatureError),
     #[error("Unable to extract compression nonce from signature")]
     AssetId(#[from] SolanaAssetIdError),
+    #[error("Compression nonce must be read from the landed transaction")]
+    NonceRequired,
+    #[error("Collection belongs to the {0} cluster")]
+    ClusterMismatch(SolanaCluster),
+    #[error("Compressed mints cannot switch collections")]
+    CompressedMintSwitch,
+    #[error("A claim must reserve a mint of its collection or carry a mint request, not both")]
+    InvalidClaim,
+    #[error("Invalid claim id")]
+    InvalidClaimId,
+    #[error("Mint is already reserved by an unredeemed claim")]
+    MintAlreadyClaimed,
+    #[error("Claim has already been redeemed")]
+    ClaimRedeemed,
+    #[error("Claim is already being redeemed")]
+    RedemptionPending,
+    #[error("Claim has expired")]
+    ClaimExpired,
+    #[error("Claim belongs to another wallet")]
+    ClaimantMismatch,
+    #[error("Claim cannot be redeemed through Solana Pay: {0}")]
+    SolanaPayUnsupported(&'static str),
+    #[error("Recipient {0} is not on the mint allowlist of the collection")]
+    RecipientNotAllowed(String),
+    #[error("Recipient {wallet} already holds the maximum of {max} mints of the collection")]
+    MintLimitReached { wallet: String, max: i32 },
+    #[error("Mint window of the collection has not opened yet")]
+    DropNotOpen,
+    #[error("Mint window of the collection has closed")]
+    DropClosed,
+    #[error("A drop schedule must close after it opens")]
+    InvalidDropSchedule,
+    #[error("Invalid mint payment")]
+    InvalidMintPayment(#[source] Error),
+    #[error("Batched mints cannot collect the mint price of a collection")]
+    PaidBatchMint,
+    #[error("Batched mints cannot be proposed to a multisig treasury")]
+    MultisigBatchMint,
+    #[error("Custodial recipients are not supported without a wallet resolver")]
+    CustodialWalletsDisabled,
+    #[error("Unable to resolve the custodial wallet of {0}")]
+    #[transient]
+    CustodialWallet(String, #[source] Error),
+    #[error("Mint {0} belongs to a soulbound collection and cannot be transferred")]
+    SoulboundTransfer(Uuid),
+    #[error("Edition number {0} is invalid, editions are numbered from 1")]
+    InvalidEdition(i64),
+    #[error("Edition {0} of the collection has already been minted")]
+    EditionTaken(i64),
+    #[error("Metadata of {0} is locked and can no longer be updated")]
+    MetadataLocked(Uuid),
+    #[error("Update {0} has already been submitted")]
+    UpdateSubmitted(Uuid),
+    #[error("Update {0} has already been confirmed")]
+    UpdateConfirmed(Uuid),
+    #[error("Compressed mints cannot have their metadata locked")]
+    CompressedMetadataLock,
+    #[error("Compressed mints cannot have their primary sale flag set")]
+    CompressedPrimarySale,
+    #[error("Invalid page cursor")]
+    InvalidCursor,
+    #[error("Signing request violates the signer policy of the project")]
+    SignerPolicy(#[from] SignerPolicyError),
+    #[error("Invalid royalty split")]
+    RoyaltySplit(#[from] HydraError),
+    #[error("Collection {0} already has a royalty split")]
+    RoyaltySplitExists(Uuid),
+    #[error("Collection {0} has no royalty split")]
+    NoRoyaltySplit(Uuid),
+    #[error("Mint {0} is held in escrow")]
+    AssetEscrowed(Uuid),
+    #[error("Mint {0} is not held in escrow")]
+    AssetNotEscrowed(Uuid),
+    #[error("Project {0} has no auction house to list mints on")]
+    NoAuctionHouse(String),
+    #[error("Compressed mints cannot be listed on an auction house")]
+    CompressedListing,
+    #[error("Listing price {0} is invalid")]
+    InvalidListingPrice(u64),
+    #[error("Mint {0} is listed on an auction house")]
+    AssetListed(Uuid),
+    #[error("Mint {0} has no live listing")]
+    NoActiveListing(Uuid),
+    #[error("Invalid candy machine")]
+    CandyMachine(#[from] CandyMachineError),
+    #[error("Collection {0} already has a candy machine")]
+    CandyMachineExists(Uuid),
+    #[error("Candy machine {0} has not been deployed")]
+    CandyMachineNotDeployed(Uuid),
+    #[error("Candy machine {0} is already loaded with every item")]
+    CandyMachineLoaded(Uuid),
+    #[error("Candy machines can only charge mint prices in SOL")]
+    CandyMachineTokenPayment,
+    #[error("Mints of soulbound collection {0} cannot be sold from a candy machine")]
+    SoulboundCandyMachine(Uuid),
+    #[error("Mints of soulbound collection {0} cannot be compressed")]
+    SoulboundCompressed(Uuid),
+    #[error("Invalid merkle tree")]
+    Tree(#[from] TreeError),
+    #[error("Unable to write collection export")]
+    #[transient]
+    Export(#[source] Error),
+}
+
+impl ProcessorErrorKind {
+    /// The reason reported to hub-nfts when assembling a transaction fails
+    /// with this error
+    fn failure_reason(&self) -> SolanaTransactionFailureReason {
+        match self {
+            Self::RecipientNotAllowed(_) => SolanaTransactionFailureReason::RecipientNotAllowed,
+            Self::MetadataLocked(_) => SolanaTransactionFailureReason::MetadataLocked,
+            Self::SignerPolicy(_) => SolanaTransactionFailureReason::UnauthorizedSigner,
+            _ => SolanaTransactionFailureReason::Assemble,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error, Triage)]
@@ -88,8 +272,91 @@ impl ProcessorError {
 type ProcessResult<T> = std::result::Result<T, ProcessorErrorKind>;
 type Result<T> = std::result::Result<T, ProcessorError>;
 
+type BoxedWrite<'a> = Pin<Box<dyn Future<Output = ProcessResult<()>> + Send + 'a>>;
+type Write = Box<dyn for<'a> FnOnce(&'a DatabaseTransaction) -> BoxedWrite<'a> + Send>;
+
+/// A transaction awaiting signatures along with the model writes made while
+/// assembling it. The writes are applied in the database transaction which
+/// queues the signing request, after the transaction is proposed, simulated
+/// and estimated, so no database transaction stays open across RPC calls.
+struct PendingTransaction {
+    tx: SolanaPendingTransaction,
+    ephemeral_keypairs: Vec<[u8; 64]>,
+    writes: Vec<Write>,
+}
+
+impl From<SolanaPendingTransaction> for PendingTransaction {
+    fn from(tx: SolanaPendingTransaction) -> Self {
+        Self {
+            tx,
+            ephemeral_keypairs: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+}
+
+impl<A> From<TransactionResponse<A>> for PendingTransaction {
+    fn from(mut res: TransactionResponse<A>) -> Self {
+        let ephemeral_keypairs = mem::take(&mut res.ephemeral_keypairs);
+
+        Self {
+            tx: res.into(),
+            ephemeral_keypairs,
+            writes: Vec::new(),
+        }
+    }
+}
+
+impl PendingTransaction {
+    fn new(tx: impl Into<Self>) -> Self {
+        tx.into()
+    }
+
+    /// Apply `write` along with the signing request of the transaction
+    fn with_write(
+        mut self,
+        write: impl for<'a> FnOnce(&'a DatabaseTransaction) -> BoxedWrite<'a> + Send + 'static,
+    ) -> Self {
+        self.writes.push(Box::new(write));
+        self
+    }
+
+    async fn write(writes: Vec<Write>, txn: &DatabaseTransaction) -> ProcessResult<()> {
+        for write in writes {
+            write(txn).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Box a write deferred by [`PendingTransaction::with_write`]
+fn deferred<'a>(fut: impl Future<Output = ProcessResult<()>> + Send + 'a) -> BoxedWrite<'a> {
+    Box::pin(fut)
+}
+
+/// Error details attached to failure events and persisted for later
+/// inspection
+#[derive(Debug)]
+struct FailureDetails {
+    message: String,
+    logs: Vec<String>,
+    signature: Option<String>,
+}
+
+impl FailureDetails {
+    fn from_error(err: &Error, signature: Option<String>) -> Self {
+        Self {
+            message: format!("{err:#}"),
+            logs: vec![],
+            signature,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum ErrorSource {
+    NftRequest,
     NftFailure,
     NftSignRequest,
     TreasuryStatus,
@@ -100,6 +367,7 @@ enum ErrorSource {
 impl ErrorSource {
     fn name(self) -> &'static str {
         match self {
+            Self::NftRequest => "NFT request",
             Self::NftFailure => "NFT failure response",
             Self::NftSignRequest => "NFT transaction signature request",
             Self::TreasuryStatus => "treasury status check",
@@ -109,12 +377,13 @@ impl ErrorSource {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum EventKind {
     CreateEditionDrop,
     MintEditionDrop,
     UpdateEditionDrop,
     TransferAsset,
+    TransferAssets,
     RetryCreateEditionDrop,
     RetryMintEditionDrop,
     CreateCollection,
@@ -131,15 +400,134 @@ pub enum EventKind {
     RetryCreateOpenDrop,
     RetryMintOpenDrop,
     MintOpenDropBatched,
+    RedeemClaim,
+    UpdateCollectionAssets,
+    LockMetadata,
+    MarkPrimarySale,
+    CreateRoyaltySplit,
+    DistributeRoyalties,
+    EscrowAsset,
+    ReleaseAsset,
+    ListAsset,
+    CancelListing,
+    DeployCandyMachine,
+    InsertCandyMachineItems,
+    ReserveTreeCapacity,
+    #[value(skip)]
+    AssignProjectTreasury,
+    #[value(skip)]
+    AssignSignerPolicy,
+    #[value(skip)]
+    CreateClaim,
+    #[value(skip)]
+    UpdateMintAllowlist,
+    #[value(skip)]
+    ConfigureDropSchedule,
+    #[value(skip)]
+    VerifyHolder,
+    #[value(skip)]
+    ListWalletContents,
+    #[value(skip)]
+    VerifyCollectionAuthority,
+    #[value(skip)]
+    ExportCollection,
+    #[value(skip)]
+    PrepareClaimTransaction,
 }
 
 impl EventKind {
-    fn name(self) -> &'static str {
+    pub(crate) fn from_request(event: &NftEvent) -> Option<Self> {
+        Some(match event {
+            NftEvent::SolanaCreateEditionDrop(_) => Self::CreateEditionDrop,
+            NftEvent::SolanaCreateCollection(_) => Self::CreateCollection,
+            NftEvent::SolanaMintEditionDrop(_) => Self::MintEditionDrop,
+            NftEvent::SolanaMintToCollection(_) => Self::MintToCollection,
+            NftEvent::SolanaUpdateEditionDrop(_) => Self::UpdateEditionDrop,
+            NftEvent::SolanaUpdateCollection(_) => Self::UpdateCollection,
+            NftEvent::SolanaTransferAsset(_) => Self::TransferAsset,
+            NftEvent::SolanaTransferAssets(_) => Self::TransferAssets,
+            NftEvent::SolanaRetryEditionDrop(_) => Self::RetryCreateEditionDrop,
+            NftEvent::SolanaRetryCreateCollection(_) => Self::RetryCreateCollection,
+            NftEvent::SolanaRetryMintEditionDrop(_) => Self::RetryMintEditionDrop,
+            NftEvent::SolanaRetryMintToCollection(_) => Self::RetryMintToCollection,
+            NftEvent::SolanaUpdatedCollectionMint(_) => Self::UpdateCollectionMint,
+            NftEvent::SolanaRetryUpdatedCollectionMint(_) => Self::RetryUpdateCollectionMint,
+            NftEvent::SolanaSwitchMintCollectionRequested(_) => Self::SwitchMintCollection,
+            NftEvent::SolanaCreateOpenDrop(_) => Self::CreateOpenDrop,
+            NftEvent::SolanaMintOpenDrop(_) => Self::MintOpenDrop,
+            NftEvent::SolanaUpdateOpenDrop(_) => Self::UpdateOpenDrop,
+            NftEvent::SolanaRetryOpenDrop(_) => Self::RetryCreateOpenDrop,
+            NftEvent::SolanaRetryMintOpenDrop(_) => Self::RetryMintOpenDrop,
+            NftEvent::SolanaMintOpenDropBatched(_) => Self::MintOpenDropBatched,
+            NftEvent::SolanaRedeemClaim(_) => Self::RedeemClaim,
+            NftEvent::SolanaUpdateCollectionAssets(_) => Self::UpdateCollectionAssets,
+            NftEvent::SolanaLockMetadata(_) => Self::LockMetadata,
+            NftEvent::SolanaMarkPrimarySale(_) => Self::MarkPrimarySale,
+            NftEvent::SolanaCreateRoyaltySplit(_) => Self::CreateRoyaltySplit,
+            NftEvent::SolanaDistributeRoyalties(_) => Self::DistributeRoyalties,
+            NftEvent::SolanaEscrowAsset(_) => Self::EscrowAsset,
+            NftEvent::SolanaReleaseAsset(_) => Self::ReleaseAsset,
+            NftEvent::SolanaListAsset(_) => Self::ListAsset,
+            NftEvent::SolanaCancelListing(_) => Self::CancelListing,
+            NftEvent::SolanaDeployCandyMachine(_) => Self::DeployCandyMachine,
+            NftEvent::SolanaInsertCandyMachineItems(_) => Self::InsertCandyMachineItems,
+            NftEvent::SolanaReserveTreeCapacity(_) => Self::ReserveTreeCapacity,
+            _ => return None,
+        })
+    }
+
+    /// The kind of request a signed transaction from the treasury belongs to
+    pub(crate) fn from_signed(event: &TreasuryEvent) -> Option<Self> {
+        Some(match event {
+            TreasuryEvent::SolanaCreateEditionDropSigned(_) => Self::CreateEditionDrop,
+            TreasuryEvent::SolanaMintEditionDropSigned(_) => Self::MintEditionDrop,
+            TreasuryEvent::SolanaUpdateEditionDropSigned(_) => Self::UpdateEditionDrop,
+            TreasuryEvent::SolanaTransferAssetSigned(_) => Self::TransferAsset,
+            TreasuryEvent::SolanaTransferAssetsSigned(_) => Self::TransferAssets,
+            TreasuryEvent::SolanaRetryCreateEditionDropSigned(_) => Self::RetryCreateEditionDrop,
+            TreasuryEvent::SolanaRetryMintEditionDropSigned(_) => Self::RetryMintEditionDrop,
+            TreasuryEvent::SolanaMintToCollectionSigned(_) => Self::MintToCollection,
+            TreasuryEvent::SolanaRetryMintToCollectionSigned(_) => Self::RetryMintToCollection,
+            TreasuryEvent::SolanaCreateCollectionSigned(_) => Self::CreateCollection,
+            TreasuryEvent::SolanaUpdateCollectionSigned(_) => Self::UpdateCollection,
+            TreasuryEvent::SolanaRetryCreateCollectionSigned(_) => Self::RetryCreateCollection,
+            TreasuryEvent::SolanaUpdateCollectionMintSigned(_) => Self::UpdateCollectionMint,
+            TreasuryEvent::SolanaRetryUpdateCollectionMintSigned(_) => {
+                Self::RetryUpdateCollectionMint
+            },
+            TreasuryEvent::SolanaSwitchMintCollectionSigned(_) => Self::SwitchMintCollection,
+            TreasuryEvent::SolanaCreateOpenDropSigned(_) => Self::CreateOpenDrop,
+            TreasuryEvent::SolanaMintOpenDropSigned(_) => Self::MintOpenDrop,
+            TreasuryEvent::SolanaUpdateOpenDropSigned(_) => Self::UpdateOpenDrop,
+            TreasuryEvent::SolanaRetryCreateOpenDropSigned(_) => Self::RetryCreateOpenDrop,
+            TreasuryEvent::SolanaRetryMintOpenDropSigned(_) => Self::RetryMintOpenDrop,
+            TreasuryEvent::SolanaRedeemClaimSigned(_) => Self::RedeemClaim,
+            TreasuryEvent::SolanaUpdateCollectionAssetsSigned(_) => Self::UpdateCollectionAssets,
+            TreasuryEvent::SolanaLockMetadataSigned(_) => Self::LockMetadata,
+            TreasuryEvent::SolanaMarkPrimarySaleSigned(_) => Self::MarkPrimarySale,
+            TreasuryEvent::SolanaCreateRoyaltySplitSigned(_) => Self::CreateRoyaltySplit,
+            TreasuryEvent::SolanaDistributeRoyaltiesSigned(_) => Self::DistributeRoyalties,
+            TreasuryEvent::SolanaEscrowAssetSigned(_) => Self::EscrowAsset,
+            TreasuryEvent::SolanaReleaseAssetSigned(_) => Self::ReleaseAsset,
+            TreasuryEvent::SolanaListAssetSigned(_) => Self::ListAsset,
+            TreasuryEvent::SolanaCancelListingSigned(_) => Self::CancelListing,
+            TreasuryEvent::SolanaDeployCandyMachineSigned(_) => Self::DeployCandyMachine,
+            TreasuryEvent::SolanaInsertCandyMachineItemsSigned(_) => Self::InsertCandyMachineItems,
+            TreasuryEvent::SolanaReserveTreeCapacitySigned(_) => Self::ReserveTreeCapacity,
+            TreasuryEvent::SolanaPrepareClaimTransactionSigned(_) => Self::PrepareClaimTransaction,
+            TreasuryEvent::ProjectTreasuryAssigned(_) => Self::AssignProjectTreasury,
+            TreasuryEvent::SolanaSignerPolicyAssigned(_) => Self::AssignSignerPolicy,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::CreateEditionDrop => "edition drop creation",
             Self::MintEditionDrop => "edition drop mint",
             Self::UpdateEditionDrop => "edition drop update",
             Self::TransferAsset => "asset transfer",
+            Self::TransferAssets => "multiple asset transfer",
             Self::RetryCreateEditionDrop => "editiondrop creation retry",
             Self::RetryMintEditionDrop => "edition drop mint retry",
             Self::CreateCollection => "collection creation",
@@ -156,6 +544,29 @@ impl EventKind {
             Self::RetryCreateOpenDrop => "open drop creation retry",
             Self::RetryMintOpenDrop => "open drop mint retry",
             Self::MintOpenDropBatched => "open drop mint batch",
+            Self::RedeemClaim => "claim redemption",
+            Self::UpdateCollectionAssets => "collection assets update",
+            Self::LockMetadata => "metadata lock",
+            Self::MarkPrimarySale => "primary sale flag update",
+            Self::CreateRoyaltySplit => "royalty split creation",
+            Self::DistributeRoyalties => "royalty distribution",
+            Self::EscrowAsset => "asset escrow",
+            Self::ReleaseAsset => "asset escrow release",
+            Self::ListAsset => "auction house listing",
+            Self::CancelListing => "auction house listing cancellation",
+            Self::DeployCandyMachine => "candy machine deployment",
+            Self::InsertCandyMachineItems => "candy machine item insertion",
+            Self::ReserveTreeCapacity => "tree capacity reservation",
+            Self::AssignProjectTreasury => "project treasury assignment",
+            Self::AssignSignerPolicy => "signer policy assignment",
+            Self::CreateClaim => "claim creation",
+            Self::UpdateMintAllowlist => "mint allowlist update",
+            Self::ConfigureDropSchedule => "drop schedule configuration",
+            Self::VerifyHolder => "holder verification",
+            Self::ListWalletContents => "wallet contents listing",
+            Self::VerifyCollectionAuthority => "collection authority verification",
+            Self::ExportCollection => "collection export",
+            Self::PrepareClaimTransaction => "claim transaction request",
         }
     }
 
@@ -165,6 +576,7 @@ impl EventKind {
             EventKind::MintEditionDrop => SolanaNftEvent::MintEditionDropSigningRequested(tx),
             EventKind::UpdateEditionDrop => SolanaNftEvent::UpdateEditionDropSigningRequested(tx),
             EventKind::TransferAsset => SolanaNftEvent::TransferAssetSigningRequested(tx),
+            EventKind::TransferAssets => SolanaNftEvent::TransferAssetsSigningRequested(tx),
             EventKind::RetryCreateEditionDrop => {
                 SolanaNftEvent::RetryCreateEditionDropSigningRequested(tx)
             },
@@ -196,17 +608,55 @@ impl EventKind {
                 SolanaNftEvent::RetryCreateOpenDropSigningRequested(tx)
             },
             EventKind::RetryMintOpenDrop => SolanaNftEvent::RetryMintOpenDropSigningRequested(tx),
-            EventKind::MintOpenDropBatched => unreachable!(),
+            EventKind::RedeemClaim => SolanaNftEvent::RedeemClaimSigningRequested(tx),
+            EventKind::UpdateCollectionAssets => {
+                SolanaNftEvent::UpdateCollectionAssetsSigningRequested(tx)
+            },
+            EventKind::LockMetadata => SolanaNftEvent::LockMetadataSigningRequested(tx),
+            EventKind::MarkPrimarySale => SolanaNftEvent::MarkPrimarySaleSigningRequested(tx),
+            EventKind::CreateRoyaltySplit => SolanaNftEvent::CreateRoyaltySplitSigningRequested(tx),
+            EventKind::DistributeRoyalties => {
+                SolanaNftEvent::DistributeRoyaltiesSigningRequested(tx)
+            },
+            EventKind::EscrowAsset => SolanaNftEvent::EscrowAssetSigningRequested(tx),
+            EventKind::ReleaseAsset => SolanaNftEvent::ReleaseAssetSigningRequested(tx),
+            EventKind::ListAsset => SolanaNftEvent::ListAssetSigningRequested(tx),
+            EventKind::CancelListing => SolanaNftEvent::CancelListingSigningRequested(tx),
+            EventKind::DeployCandyMachine => SolanaNftEvent::DeployCandyMachineSigningRequested(tx),
+            EventKind::InsertCandyMachineItems => {
+                SolanaNftEvent::InsertCandyMachineItemsSigningRequested(tx)
+            },
+            EventKind::ReserveTreeCapacity => {
+                SolanaNftEvent::ReserveTreeCapacitySigningRequested(tx)
+            },
+            EventKind::PrepareClaimTransaction => {
+                SolanaNftEvent::PrepareClaimTransactionSigningRequested(tx)
+            },
+            EventKind::MintOpenDropBatched
+            | EventKind::AssignProjectTreasury
+            | EventKind::AssignSignerPolicy
+            | EventKind::CreateClaim
+            | EventKind::UpdateMintAllowlist
+            | EventKind::ConfigureDropSchedule
+            | EventKind::VerifyHolder
+            | EventKind::ListWalletContents
+            | EventKind::VerifyCollectionAuthority
+            | EventKind::ExportCollection => unreachable!(),
         }
     }
 
     async fn into_success(
         self,
-        conn: &DatabaseConnection,
-        solana: &Solana,
+        conn: &impl ConnectionTrait,
+        nonce: Option<u32>,
         key: &SolanaNftEventKey,
-        signature: String,
+        submitted: SubmittedTransaction,
     ) -> ProcessResult<SolanaNftEvent> {
+        let SubmittedTransaction {
+            signature,
+            slot,
+            payer,
+        } = submitted;
         let id = || Uuid::parse_str(&key.id);
 
         Ok(match self {
@@ -218,7 +668,11 @@ impl EventKind {
 
                 SolanaNftEvent::CreateEditionDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::CreateCollection => {
@@ -230,7 +684,11 @@ impl EventKind {
 
                 SolanaNftEvent::CreateCollectionSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::RetryCreateCollection => {
@@ -242,45 +700,159 @@ impl EventKind {
 
                 SolanaNftEvent::RetryCreateCollectionSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::UpdateCollection => {
                 SolanaNftEvent::UpdateCollectionSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
-            Self::MintToCollection | Self::MintOpenDrop => {
-                let id = id()?;
-                let collection_mint = CollectionMint::find_by_id(conn, id).await?;
+            Self::UpdateCollectionAssets => {
+                Collection::increment_metadata_version(conn, id()?).await?;
 
-                let compression_leafs = CompressionLeaf::find_by_id(conn, id).await?;
+                SolanaNftEvent::UpdateCollectionAssetsSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::LockMetadata => {
+                let lock_id = id()?;
+                Collection::confirm_metadata_lock(conn, lock_id).await?;
+                CollectionMint::confirm_metadata_lock(conn, lock_id).await?;
 
-                let address = if let Some(compression_leaf) = compression_leafs {
-                    let signature = Signature::from_str(&signature)?;
-                    let nonce = solana.extract_compression_nonce(&signature).await?;
+                SolanaNftEvent::LockMetadataSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::MarkPrimarySale => {
+                SolanaNftEvent::MarkPrimarySaleSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::CreateRoyaltySplit => {
+                let split = RoyaltySplit::confirm(conn, id()?)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-                    let asset_id = mpl_bubblegum::utils::get_asset_id(
-                        &Pubkey::from_str(&compression_leaf.merkle_tree)?,
-                        nonce.into(),
-                    );
+                SolanaNftEvent::CreateRoyaltySplitSubmitted(
+                    SolanaCompletedRoyaltySplitTransaction {
+                        signature,
+                        slot,
+                        payer,
+                        fanout: split.fanout,
+                        holding_account: split.holding_account,
+                    },
+                )
+            },
+            Self::DistributeRoyalties => {
+                SolanaNftEvent::DistributeRoyaltiesSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::EscrowAsset => {
+                AssetEscrow::confirm_escrow(conn, id()?).await?;
+
+                SolanaNftEvent::EscrowAssetSubmitted(SolanaCompletedTransferTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::ReleaseAsset => {
+                AssetEscrow::confirm_release(conn, id()?).await?;
+
+                SolanaNftEvent::ReleaseAssetSubmitted(SolanaCompletedTransferTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::ListAsset => {
+                Listing::confirm_listing(conn, id()?).await?;
 
-                    let asset_id = asset_id.to_string();
+                SolanaNftEvent::ListAssetSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::CancelListing => {
+                Listing::confirm_cancel(conn, id()?).await?;
 
-                    let mut compression_leaf: compression_leafs::ActiveModel =
-                        compression_leaf.into();
+                SolanaNftEvent::CancelListingSubmitted(SolanaCompletedUpdateTransaction {
+                    signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::DeployCandyMachine => {
+                let candy_machine = CandyMachine::confirm_deploy(conn, id()?)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-                    compression_leaf.asset_id = Set(Some(asset_id.clone()));
+                SolanaNftEvent::DeployCandyMachineSubmitted(candy_machine_transaction(
+                    candy_machine,
+                    signature,
+                    slot,
+                    payer,
+                ))
+            },
+            Self::InsertCandyMachineItems => {
+                let candy_machine = CandyMachine::confirm_batch(conn, id()?)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-                    CompressionLeaf::update(conn, compression_leaf).await?;
+                SolanaNftEvent::InsertCandyMachineItemsSubmitted(candy_machine_transaction(
+                    candy_machine,
+                    signature,
+                    slot,
+                    payer,
+                ))
+            },
+            Self::ReserveTreeCapacity => {
+                let tree = MerkleTree::confirm_reservation(conn, id()?)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-                    asset_id
-                } else {
-                    collection_mint
-                        .ok_or(ProcessorErrorKind::RecordNotFound)?
-                        .mint
+                SolanaNftEvent::ReserveTreeCapacitySubmitted(SolanaCompletedTreeTransaction {
+                    signature,
+                    slot,
+                    payer,
+                    collection_id: tree
+                        .collection_id
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                    capacity: MerkleTree::remaining(&tree)
+                        .and_then(|leaves| u64::try_from(leaves).ok())
+                        .unwrap_or_default(),
+                    merkle_tree: tree.address,
+                })
+            },
+            Self::MintToCollection | Self::MintOpenDrop => {
+                let id = id()?;
+                let address = complete_mint(conn, nonce, id, &signature).await?;
+                let transaction = SolanaCompletedMintTransaction {
+                    signature,
+                    address,
+                    slot,
+                    payer,
+                    payment: mint_payment(conn, id).await?,
+                    edition: None,
                 };
-                let transaction = SolanaCompletedMintTransaction { signature, address };
 
                 match self {
                     Self::MintToCollection => {
@@ -298,17 +870,32 @@ impl EventKind {
 
                 SolanaNftEvent::MintEditionDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection_mint.mint,
+                    payment: mint_payment(conn, id).await?,
+                    edition: collection_mint.edition,
                 })
             },
             Self::UpdateEditionDrop => {
                 SolanaNftEvent::UpdateEditionDropSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
             Self::TransferAsset => {
                 SolanaNftEvent::TransferAssetSubmitted(SolanaCompletedTransferTransaction {
                     signature,
+                    slot,
+                    payer,
+                })
+            },
+            Self::TransferAssets => {
+                SolanaNftEvent::TransferAssetsSubmitted(SolanaCompletedTransferTransaction {
+                    signature,
+                    slot,
+                    payer,
                 })
             },
             Self::RetryCreateEditionDrop => {
@@ -319,7 +906,11 @@ impl EventKind {
 
                 SolanaNftEvent::RetryCreateEditionDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::RetryMintEditionDrop => {
@@ -330,7 +921,11 @@ impl EventKind {
 
                 SolanaNftEvent::RetryMintEditionDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection_mint.mint,
+                    payment: mint_payment(conn, id).await?,
+                    edition: collection_mint.edition,
                 })
             },
             Self::RetryMintToCollection => {
@@ -339,24 +934,41 @@ impl EventKind {
                     .await?
                     .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
+                WalletMintCount::increment(
+                    conn,
+                    collection_mint.collection_id,
+                    collection_mint.owner.clone(),
+                )
+                .await?;
+
                 SolanaNftEvent::RetryMintToCollectionSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection_mint.mint,
+                    payment: mint_payment(conn, id).await?,
+                    edition: None,
                 })
             },
             Self::UpdateCollectionMint => {
                 SolanaNftEvent::UpdateCollectionMintSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
             Self::RetryUpdateCollectionMint => {
                 SolanaNftEvent::RetryUpdateMintSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
             Self::SwitchMintCollection => {
                 SolanaNftEvent::SwitchMintCollectionSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
             Self::CreateOpenDrop => {
@@ -367,12 +979,18 @@ impl EventKind {
 
                 SolanaNftEvent::CreateOpenDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::UpdateOpenDrop => {
                 SolanaNftEvent::UpdateOpenDropSubmitted(SolanaCompletedUpdateTransaction {
                     signature,
+                    slot,
+                    payer,
                 })
             },
             Self::RetryCreateOpenDrop => {
@@ -383,7 +1001,11 @@ impl EventKind {
 
                 SolanaNftEvent::RetryCreateOpenDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection.mint,
+                    payment: None,
+                    edition: None,
                 })
             },
             Self::RetryMintOpenDrop => {
@@ -392,12 +1014,63 @@ impl EventKind {
                     .await?
                     .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
+                WalletMintCount::increment(
+                    conn,
+                    collection_mint.collection_id,
+                    collection_mint.owner.clone(),
+                )
+                .await?;
+
                 SolanaNftEvent::RetryMintOpenDropSubmitted(SolanaCompletedMintTransaction {
                     signature,
+                    slot,
+                    payer,
                     address: collection_mint.mint,
+                    payment: mint_payment(conn, id).await?,
+                    edition: None,
+                })
+            },
+            Self::RedeemClaim => {
+                let redemption_id = id()?;
+                let claim = Claim::find_by_redemption_id(conn, redemption_id)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+                // Claims without a reserved mint are redeemed by minting under
+                // the redemption id
+                let address = if let Some(collection_mint_id) = claim.collection_mint_id {
+                    Mint::find_by_id(conn, collection_mint_id)
+                        .await?
+                        .as_ref()
+                        .and_then(Mint::address)
+                        .ok_or(ProcessorErrorKind::RecordNotFound)?
+                        .to_owned()
+                } else {
+                    complete_mint(conn, nonce, redemption_id, &signature).await?
+                };
+
+                Claim::mark_redeemed(conn, claim).await?;
+
+                SolanaNftEvent::RedeemClaimSubmitted(SolanaCompletedMintTransaction {
+                    signature,
+                    slot,
+                    payer,
+                    address,
+                    payment: mint_payment(conn, redemption_id).await?,
+                    edition: None,
                 })
             },
-            Self::MintOpenDropBatched => unreachable!(),
+            Self::MintOpenDropBatched
+            | Self::AssignProjectTreasury
+            | Self::AssignSignerPolicy
+            | Self::CreateClaim
+            | Self::UpdateMintAllowlist
+            | Self::ConfigureDropSchedule
+            | Self::VerifyHolder
+            | Self::ListWalletContents
+            | Self::VerifyCollectionAuthority
+            | Self::ExportCollection
+            | Self::PrepareClaimTransaction => unreachable!(),
         })
     }
 
@@ -407,6 +1080,7 @@ impl EventKind {
             Self::MintEditionDrop => SolanaNftEvent::MintEditionDropFailed(tx),
             Self::UpdateEditionDrop => SolanaNftEvent::UpdateEditionDropFailed(tx),
             Self::TransferAsset => SolanaNftEvent::TransferAssetFailed(tx),
+            Self::TransferAssets => SolanaNftEvent::TransferAssetsFailed(tx),
             Self::RetryCreateEditionDrop => SolanaNftEvent::RetryCreateEditionDropFailed(tx),
             Self::RetryMintEditionDrop => SolanaNftEvent::RetryMintEditionDropFailed(tx),
             Self::CreateCollection => SolanaNftEvent::CreateCollectionFailed(tx),
@@ -422,33 +1096,473 @@ impl EventKind {
             Self::UpdateOpenDrop => SolanaNftEvent::UpdateOpenDropFailed(tx),
             Self::RetryCreateOpenDrop => SolanaNftEvent::RetryCreateOpenDropFailed(tx),
             Self::RetryMintOpenDrop => SolanaNftEvent::RetryMintOpenDropFailed(tx),
-            Self::MintOpenDropBatched => unreachable!(),
+            Self::RedeemClaim => SolanaNftEvent::RedeemClaimFailed(tx),
+            Self::UpdateCollectionAssets => SolanaNftEvent::UpdateCollectionAssetsFailed(tx),
+            Self::LockMetadata => SolanaNftEvent::LockMetadataFailed(tx),
+            Self::MarkPrimarySale => SolanaNftEvent::MarkPrimarySaleFailed(tx),
+            Self::CreateRoyaltySplit => SolanaNftEvent::CreateRoyaltySplitFailed(tx),
+            Self::DistributeRoyalties => SolanaNftEvent::DistributeRoyaltiesFailed(tx),
+            Self::EscrowAsset => SolanaNftEvent::EscrowAssetFailed(tx),
+            Self::ReleaseAsset => SolanaNftEvent::ReleaseAssetFailed(tx),
+            Self::ListAsset => SolanaNftEvent::ListAssetFailed(tx),
+            Self::CancelListing => SolanaNftEvent::CancelListingFailed(tx),
+            Self::DeployCandyMachine => SolanaNftEvent::DeployCandyMachineFailed(tx),
+            Self::InsertCandyMachineItems => SolanaNftEvent::InsertCandyMachineItemsFailed(tx),
+            Self::ReserveTreeCapacity => SolanaNftEvent::ReserveTreeCapacityFailed(tx),
+            Self::MintOpenDropBatched
+            | Self::AssignProjectTreasury
+            | Self::AssignSignerPolicy
+            | Self::CreateClaim
+            | Self::UpdateMintAllowlist
+            | Self::ConfigureDropSchedule
+            | Self::VerifyHolder
+            | Self::ListWalletContents
+            | Self::VerifyCollectionAuthority
+            | Self::ExportCollection
+            | Self::PrepareClaimTransaction => unreachable!(),
+        }
+    }
+}
+
+/// Fill in the collection's default creators and royalties when a mint
+/// payload does not specify its own creators. Collections with a royalty
+/// split name its holding account as the only creator, so that royalties are
+/// paid into the split.
+fn with_collection_defaults(
+    collection: &collections::Model,
+    split: Option<&royalty_splits::Model>,
+    mut payload: MintMetaplexMetadataTransaction,
+) -> MintMetaplexMetadataTransaction {
+    let Some(metadata) = payload.metadata.as_mut() else {
+        return payload;
+    };
+
+    let creators = match (split, collection.creators.as_ref()) {
+        (Some(split), _) => vec![CollectionCreator {
+            address: split.holding_account.clone(),
+            verified: false,
+            share: 100,
+        }],
+        (None, Some(CollectionCreators(creators))) => creators.clone(),
+        (None, None) => return payload,
+    };
+
+    if metadata.creators.is_empty() {
+        metadata.creators = creators.into_iter().map(Into::into).collect();
+
+        if let Some(bps) = collection
+            .seller_fee_basis_points
+            .and_then(|bps| u32::try_from(bps).ok())
+        {
+            metadata.seller_fee_basis_points = bps;
+        }
+    }
+
+    payload
+}
+
+/// The creators every mint of a collection's candy machine is created with,
+/// following the defaults applied to the collection's other mints. Without
+/// either, the collection's update authority takes the whole share.
+fn candy_machine_creators(
+    collection: &collections::Model,
+    split: Option<&royalty_splits::Model>,
+) -> ProcessResult<Vec<cm::Creator>> {
+    let creators = match (split, collection.creators.as_ref()) {
+        (Some(split), _) => vec![CollectionCreator {
+            address: split.holding_account.clone(),
+            verified: false,
+            share: 100,
+        }],
+        (None, Some(CollectionCreators(creators))) if !creators.is_empty() => creators.clone(),
+        (None, _) => vec![CollectionCreator {
+            address: collection.update_authority.clone(),
+            verified: false,
+            share: 100,
+        }],
+    };
+
+    creators
+        .into_iter()
+        .map(|c| {
+            Ok(cm::Creator {
+                address: c.address.parse()?,
+                verified: false,
+                percentage_share: u8::try_from(c.share)
+                    .map_err(|_| CandyMachineError::InvalidShare(c.share))?,
+            })
+        })
+        .collect()
+}
+
+/// The guards enforcing a collection's mint window, price and per wallet
+/// limit on its candy machine
+fn candy_guards(solana: &Solana, collection: &collections::Model) -> ProcessResult<Guards> {
+    let sol_payment = match solana
+        .payment_leg(collection, solana.payer())
+        .map_err(ProcessorErrorKind::Solana)?
+    {
+        Some(leg) if leg.spl_mint.is_some() => {
+            return Err(ProcessorErrorKind::CandyMachineTokenPayment);
+        },
+        Some(leg) => Some((leg.amount, leg.revenue_wallet)),
+        None => None,
+    };
+    let mint_limit = collection
+        .max_mints_per_wallet
+        .map(|max| u16::try_from(max).map_err(|_| CandyMachineError::MintLimit(max)))
+        .transpose()?;
+
+    Ok(Guards {
+        sol_payment,
+        start_date: collection.mint_open_at.map(|at| at.timestamp()),
+        end_date: collection.mint_close_at.map(|at| at.timestamp()),
+        mint_limit,
+    })
+}
+
+/// The submitted event of a candy machine transaction, reporting how many of
+/// its items are loaded
+fn candy_machine_transaction(
+    candy_machine: candy_machines::Model,
+    signature: String,
+    slot: Option<u64>,
+    payer: String,
+) -> SolanaCompletedCandyMachineTransaction {
+    SolanaCompletedCandyMachineTransaction {
+        signature,
+        slot,
+        payer,
+        candy_machine: candy_machine.address,
+        candy_guard: candy_machine.candy_guard,
+        items_loaded: candy_machine.items_loaded,
+        items_available: candy_machine.items_available,
+    }
+}
+
+/// The per-wallet mint limit requested for a collection, if the request sets
+/// one. A limit of zero removes the limit.
+fn max_mints_per_wallet(payload: &MetaplexMasterEditionTransaction) -> Option<Option<i32>> {
+    payload
+        .max_mints_per_wallet
+        .map(|max| i32::try_from(max).ok().filter(|max| *max > 0))
+}
+
+/// The mint price and payment mint requested for a collection, if the
+/// request sets a price. A price of zero makes mints free again.
+fn mint_price(
+    payload: &MetaplexMasterEditionTransaction,
+) -> ProcessResult<Option<(Option<i64>, Option<String>)>> {
+    let Some(MintPrice { amount, spl_mint }) = payload.mint_price.as_ref() else {
+        return Ok(None);
+    };
+
+    if *amount == 0 {
+        return Ok(Some((None, None)));
+    }
+
+    let amount = i64::try_from(*amount)
+        .map_err(|e| ProcessorErrorKind::InvalidMintPayment(Error::new(e)))?;
+    let spl_mint = spl_mint
+        .as_deref()
+        .map(|mint| mint.parse::<Pubkey>().map(|mint| mint.to_string()))
+        .transpose()?;
+
+    Ok(Some((Some(amount), spl_mint)))
+}
+
+/// The payment recorded for the mint `id`, as reported in its submitted
+/// event
+async fn mint_payment(
+    conn: &impl ConnectionTrait,
+    id: Uuid,
+) -> ProcessResult<Option<SolanaMintPayment>> {
+    Ok(MintPayment::find_by_id(conn, id)
+        .await?
+        .map(|payment| SolanaMintPayment {
+            payer: payment.payer,
+            revenue_wallet: payment.revenue_wallet,
+            amount: payment.amount.try_into().unwrap_or_default(),
+            spl_mint: payment.spl_mint,
+        }))
+}
+
+/// Check that mints of `collection` are requested within its scheduled mint
+/// window, if it has one
+fn ensure_mint_window(collection: &collections::Model) -> ProcessResult<()> {
+    let now = Utc::now().naive_utc();
+
+    match (collection.mint_open_at, collection.mint_close_at) {
+        (Some(open_at), _) if now < open_at => Err(ProcessorErrorKind::DropNotOpen),
+        (_, Some(close_at)) if now >= close_at => Err(ProcessorErrorKind::DropClosed),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the metadata of `collection` has not been locked
+fn ensure_collection_unlocked(collection: &collections::Model) -> ProcessResult<()> {
+    if collection.metadata_locked_at.is_some() {
+        return Err(ProcessorErrorKind::MetadataLocked(collection.id));
+    }
+
+    Ok(())
+}
+
+/// The mints locked by one transaction of a collection metadata lock, keyed
+/// by the id of the lock, and whether it also locks the collection NFT
+type MetadataLockChunk = (SolanaNftEventKey, Vec<collection_mints::Model>, bool);
+
+/// One transaction of a multiple asset transfer, keyed by the id of its
+/// chunk of the request
+type TransferChunk = (
+    SolanaNftEventKey,
+    TransactionResponse<TransferAssetsAddresses>,
+);
+
+/// The id of the `chunk`th transaction made for the request `id`, such as
+/// those locking the metadata of a collection. The first chunk keeps the id
+/// of the request.
+pub(crate) fn chunk_id(id: Uuid, chunk: usize) -> Uuid {
+    if chunk == 0 {
+        return id;
+    }
+
+    let hash = hashv(&[id.as_bytes(), &chunk.to_le_bytes()]).to_bytes();
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&hash[..16]);
+
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// The accounts required to sign a serialized message, in message order
+fn message_signers(serialized_message: &[u8]) -> Vec<String> {
+    bincode::deserialize::<Message>(serialized_message).map_or_else(
+        |_| Vec::new(),
+        |message| {
+            message
+                .account_keys
+                .iter()
+                .take(usize::from(message.header.num_required_signatures))
+                .map(ToString::to_string)
+                .collect()
+        },
+    )
+}
+
+/// Sign `serialized_message` again with the ephemeral keypairs whose
+/// signatures are listed among `signers`, the rest being public keys left to
+/// the treasury. Returns `None` if the keypair of a signature is missing.
+fn sign_ephemeral(
+    serialized_message: &[u8],
+    signers: Vec<String>,
+    keypairs: &[u8],
+) -> Option<Vec<String>> {
+    let message: Message = bincode::deserialize(serialized_message).ok()?;
+    let keypairs = keypairs
+        .chunks(64)
+        .map(Keypair::from_bytes)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .ok()?;
+
+    signers
+        .into_iter()
+        .zip(&message.account_keys)
+        .map(|(signer, key)| {
+            if Pubkey::from_str(&signer).is_ok() {
+                return Some(signer);
+            }
+
+            keypairs
+                .iter()
+                .find(|keypair| keypair.pubkey() == *key)
+                .map(|keypair| keypair.sign_message(serialized_message).to_string())
+        })
+        .collect()
+}
+
+/// Check that `claim` is neither redeemed nor being redeemed by an event
+/// other than `redemption_id`
+fn ensure_redeemable(claim: &claims::Model, redemption_id: Uuid) -> ProcessResult<()> {
+    if claim.redeemed_at.is_some() {
+        return Err(ProcessorErrorKind::ClaimRedeemed);
+    }
+
+    if claim.redemption_id.map_or(false, |id| id != redemption_id) {
+        return Err(ProcessorErrorKind::RedemptionPending);
+    }
+
+    Ok(())
+}
+
+/// Check that `mint` does not belong to a soulbound collection, whose mints
+/// stay with the wallet they were minted to, and is neither held in escrow
+/// nor listed on an auction house
+async fn ensure_transferable(conn: &impl ConnectionTrait, mint: &Mint) -> ProcessResult<()> {
+    let collection = Collection::find_by_id(conn, mint.collection_id())
+        .await?
+        .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+    if collection.soulbound {
+        return Err(ProcessorErrorKind::SoulboundTransfer(mint.id()));
+    }
+
+    if AssetEscrow::find_held(conn, mint.id()).await?.is_some() {
+        return Err(ProcessorErrorKind::AssetEscrowed(mint.id()));
+    }
+
+    if Listing::find_listed(conn, mint.id()).await?.is_some() {
+        return Err(ProcessorErrorKind::AssetListed(mint.id()));
+    }
+
+    Ok(())
+}
+
+/// Check that print edition `edition` of `collection_id` is not held by a
+/// mint other than `id`. The unique index on the edition column catches
+/// concurrent mints of the same number when the row is written, which
+/// [`edition_taken`] reports the same way.
+async fn reserve_edition(
+    conn: &impl ConnectionTrait,
+    collection_id: Uuid,
+    id: Uuid,
+    edition: i64,
+) -> ProcessResult<i64> {
+    if edition < 1 {
+        return Err(ProcessorErrorKind::InvalidEdition(edition));
+    }
+
+    match CollectionMint::find_by_edition(conn, collection_id, edition).await? {
+        Some(mint) if mint.id != id => Err(ProcessorErrorKind::EditionTaken(edition)),
+        _ => Ok(edition),
+    }
+}
+
+/// Map a unique violation raised writing print edition `edition` to
+/// [`ProcessorErrorKind::EditionTaken`]
+fn edition_taken(edition: i64) -> impl FnOnce(DbErr) -> ProcessorErrorKind {
+    move |e| {
+        if db::is_unique_violation(&e) {
+            ProcessorErrorKind::EditionTaken(edition)
+        } else {
+            e.into()
         }
     }
 }
 
+/// Count a freshly minted asset against its recipient's mint limit and
+/// return its address. The asset id of a compressed mint is only known for
+/// certain once the nonce of its leaf is read from the landed transaction,
+/// so unless the indexer has already done so it is predicted from the leaf
+/// count of its tree and confirmed later by the [`AssetIdReconciler`]. Mints
+/// into a tree whose leaf count is not tracked yet take the `nonce` read from
+/// the landed transaction and start tracking it, failing with
+/// [`ProcessorErrorKind::NonceRequired`] if it was not read.
+///
+/// [`AssetIdReconciler`]: crate::asset_ids::AssetIdReconciler
+async fn complete_mint(
+    conn: &impl ConnectionTrait,
+    nonce: Option<u32>,
+    id: Uuid,
+    signature: &str,
+) -> ProcessResult<String> {
+    let mint = Mint::find_by_id(conn, id)
+        .await?
+        .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+    WalletMintCount::increment(conn, mint.collection_id(), mint.owner().to_owned()).await?;
+
+    let Mint::Compressed(_) = mint else {
+        return Ok(mint
+            .address()
+            .ok_or(ProcessorErrorKind::RecordNotFound)?
+            .to_owned());
+    };
+
+    let compression_leaf = CompressionLeaf::find_for_update(conn, id)
+        .await?
+        .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+    if compression_leaf.asset_id_confirmed_at.is_some() {
+        return compression_leaf
+            .asset_id
+            .ok_or(ProcessorErrorKind::RecordNotFound);
+    }
+
+    let merkle_tree = Pubkey::from_str(&compression_leaf.merkle_tree)?;
+
+    let Some(leaf_count) = MerkleTree::leaf_count(conn, &compression_leaf.merkle_tree).await?
+    else {
+        let nonce = nonce.ok_or(ProcessorErrorKind::NonceRequired)?;
+        let asset_id = mpl_bubblegum::utils::get_asset_id(&merkle_tree, nonce.into()).to_string();
+
+        MerkleTree::observe_leaf(conn, compression_leaf.merkle_tree.clone(), nonce.into()).await?;
+        CompressionLeaf::confirm_asset_id(conn, compression_leaf, asset_id.clone()).await?;
+
+        return Ok(asset_id);
+    };
+
+    // Leaves predicted earlier are assumed to have landed before this one
+    let predicted = CompressionLeaf::count_predicted(conn, &compression_leaf.merkle_tree).await?;
+    let nonce = leaf_count.unsigned_abs() + predicted;
+    let asset_id = mpl_bubblegum::utils::get_asset_id(&merkle_tree, nonce).to_string();
+
+    let mut compression_leaf: compression_leafs::ActiveModel = compression_leaf.into();
+
+    compression_leaf.asset_id = Set(Some(asset_id.clone()));
+    compression_leaf.mint_signature = Set(Some(signature.to_owned()));
+
+    CompressionLeaf::update(conn, compression_leaf).await?;
+
+    Ok(asset_id)
+}
+
+/// Swap a creation or mint request for its retry counterpart, which reuses
+/// the records written by the failed attempt instead of inserting new ones
+fn into_retry(event: NftEvent) -> NftEvent {
+    match event {
+        NftEvent::SolanaCreateEditionDrop(p) => NftEvent::SolanaRetryEditionDrop(p),
+        NftEvent::SolanaCreateCollection(p) => NftEvent::SolanaRetryCreateCollection(p),
+        NftEvent::SolanaMintEditionDrop(p) => NftEvent::SolanaRetryMintEditionDrop(p),
+        NftEvent::SolanaMintToCollection(p) => NftEvent::SolanaRetryMintToCollection(p),
+        NftEvent::SolanaCreateOpenDrop(p) => NftEvent::SolanaRetryOpenDrop(p),
+        NftEvent::SolanaMintOpenDrop(p) => NftEvent::SolanaRetryMintOpenDrop(p),
+        event => event,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Processor {
+    clusters: DebugShim<Clusters>,
     solana: DebugShim<Solana>,
     db: db::Connection,
-    producer: Producer<SolanaNftEvents>,
     metrics: Metrics,
+    metadata_validator: Option<MetadataValidator>,
+    jito: Option<Jito>,
+    custodial_wallets: Option<CustodialWallets>,
+    export_sink: Option<DebugShim<Arc<dyn ExportSink>>>,
 }
 
 impl Processor {
     #[inline]
     #[must_use]
     pub fn new(
-        solana: Solana,
+        clusters: Clusters,
         db: db::Connection,
-        producer: Producer<SolanaNftEvents>,
         metrics: Metrics,
+        metadata_validator: Option<MetadataValidator>,
+        jito: Option<Jito>,
+        custodial_wallets: Option<CustodialWallets>,
+        export_sink: Option<Arc<dyn ExportSink>>,
     ) -> Self {
         Self {
-            solana: DebugShim(solana),
+            solana: DebugShim(clusters.primary().clone()),
+            clusters: DebugShim(clusters),
             db,
-            producer,
             metrics,
+            metadata_validator,
+            jito,
+            custodial_wallets,
+            export_sink: export_sink.map(DebugShim),
         }
     }
 
@@ -457,33 +1571,209 @@ impl Processor {
         &self.solana.0
     }
 
+    fn backends(&self) -> Backends<'_> {
+        Backends::new(self.solana())
+    }
+
+    /// A processor bound to the cluster serving `project_id`. A project
+    /// stays on the cluster its newest collection was created on, so only
+    /// projects without collections are placed by configuration.
+    async fn for_project(&self, project_id: &str) -> ProcessResult<Self> {
+        let cluster =
+            Collection::find_cluster_by_project(self.db.get(), project_id.to_owned()).await?;
+        let solana = cluster
+            .and_then(|cluster| self.clusters.0.get(cluster))
+            .unwrap_or_else(|| self.clusters.0.for_project(project_id));
+
+        Ok(Self {
+            solana: DebugShim(solana.clone()),
+            ..self.clone()
+        })
+    }
+
+    /// The wallet address of a mint recipient, resolving `email:` recipients
+    /// to the custodial wallet held for them
+    async fn resolve_recipient(&self, recipient: String) -> ProcessResult<String> {
+        let Some(email) = CustodialWallets::email(&recipient) else {
+            return Ok(recipient);
+        };
+
+        let custodial_wallets = self
+            .custodial_wallets
+            .as_ref()
+            .ok_or(ProcessorErrorKind::CustodialWalletsDisabled)?;
+
+        custodial_wallets
+            .resolve(email)
+            .await
+            .map_err(|e| ProcessorErrorKind::CustodialWallet(recipient, e))
+    }
+
+    /// The client compressed mints of a collection are made with, minting
+    /// into the oldest tree reserved for the collection which still has
+    /// room. Collections without one mint into the configured tree.
+    /// Compressed assets cannot be frozen, so soulbound collections only
+    /// take uncompressed mints.
+    async fn solana_for_mint(
+        &self,
+        conn: &impl ConnectionTrait,
+        collection: &collections::Model,
+        compressed: bool,
+    ) -> ProcessResult<Solana> {
+        if !compressed {
+            return Ok(self.solana().clone());
+        }
+
+        if collection.soulbound {
+            return Err(ProcessorErrorKind::SoulboundCompressed(collection.id));
+        }
+
+        let trees = MerkleTree::find_reserved(conn, collection.id).await?;
+
+        match trees
+            .iter()
+            .find(|tree| MerkleTree::remaining(tree).map_or(false, |leaves| leaves > 0))
+        {
+            Some(tree) => Ok(self.solana().with_merkle_tree(tree.address.parse()?)),
+            None => Ok(self.solana().clone()),
+        }
+    }
+
+    fn ensure_cluster(&self, collection: &collections::Model) -> ProcessResult<()> {
+        if collection.cluster == self.solana().cluster() {
+            Ok(())
+        } else {
+            Err(ProcessorErrorKind::ClusterMismatch(collection.cluster))
+        }
+    }
+
     pub async fn process(&self, msg: Services) -> Result<()> {
-        match msg {
-            Services::Nfts(key, msg) => {
-                let key = SolanaNftEventKey::from(key);
+        let project_id = match &msg {
+            Services::Nfts(key, _) => &key.project_id,
+            Services::Treasury(key, _) => &key.project_id,
+        };
 
-                match msg.event {
-                    Some(NftEvent::SolanaCreateEditionDrop(payload)) => {
-                        self.process_nft(
-                            EventKind::CreateEditionDrop,
-                            &key,
-                            self.create_collection(&UncompressedRef(self.solana()), &key, payload),
-                        )
-                        .await
-                    },
-                    Some(NftEvent::SolanaCreateCollection(payload)) => {
-                        self.process_nft(
-                            EventKind::CreateCollection,
-                            &key,
-                            self.create_collection(&UncompressedRef(self.solana()), &key, payload),
-                        )
+        let mut processor = self.for_project(project_id).await?;
+
+        if let Services::Nfts(key, req) = &msg {
+            if let Some(kind) = req.event.as_ref().and_then(EventKind::from_request) {
+                processor = processor
+                    .with_project_treasury(project_id)
+                    .await
+                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftSignRequest))?
+                    .with_bundles(kind)
+                    .with_nonces(&key.id);
+            }
+        }
+
+        processor.process_event(msg).await
+    }
+
+    /// Durable nonce accounts leased by messages assembled for `event_id`
+    fn nonces(&self, event_id: Uuid) -> Nonces {
+        Nonces::new(self.db.clone(), event_id)
+    }
+
+    /// Lease durable nonce accounts of the payer to the messages assembled
+    /// for the event `event_id`
+    fn with_nonces(mut self, event_id: &str) -> Self {
+        if let Ok(event_id) = Uuid::parse_str(event_id) {
+            self.solana = DebugShim(self.solana().with_nonces(self.nonces(event_id)));
+        }
+
+        self
+    }
+
+    /// Bind the payer to the treasury assigned to `project_id`, keeping the
+    /// cluster's default treasury for projects without one, and collect mint
+    /// payments into the project's revenue wallet. Treasuries with a multisig
+    /// pay from its vault and propose their transactions to it, while
+    /// sponsored projects keep paying from the cluster's treasury.
+    async fn with_project_treasury(mut self, project_id: &str) -> ProcessResult<Self> {
+        let Ok(project_id) = Uuid::parse_str(project_id) else {
+            return Ok(self);
+        };
+
+        if let Some(treasury) =
+            ProjectTreasury::find_by_project_id(self.db.get(), project_id).await?
+        {
+            let wallet = treasury.wallet_address.parse()?;
+            let solana = match treasury.multisig_address {
+                Some(multisig) => self
+                    .solana()
+                    .with_multisig(Multisig::new(multisig.parse()?, wallet)),
+                None => self.solana().with_treasury_wallet(wallet),
+            };
+            self.solana = DebugShim(solana.with_fee_payer_mode(treasury.fee_payer_mode));
+
+            if let Some(revenue_wallet) = treasury.revenue_wallet_address {
+                let revenue_wallet = revenue_wallet.parse()?;
+                self.solana = DebugShim(self.solana().with_revenue_wallet(revenue_wallet));
+            }
+
+            if let Some(auction_house) = treasury.auction_house_address {
+                let auction_house = auction_house.parse()?;
+                self.solana = DebugShim(self.solana().with_auction_house(auction_house));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Land transactions for `kind` through Jito bundles if it is configured
+    /// for them
+    fn with_bundles(mut self, kind: EventKind) -> Self {
+        if let Some(jito) = self.jito.as_ref().filter(|j| j.bundles(kind)) {
+            self.solana = DebugShim(self.solana().with_bundles(jito.clone()));
+        }
+
+        self
+    }
+
+    async fn process_event(&self, msg: Services) -> Result<()> {
+        match msg {
+            Services::Nfts(key, msg) => {
+                if msg
+                    .event
+                    .as_ref()
+                    .and_then(EventKind::from_request)
+                    .is_some()
+                {
+                    if let Err(e) = EventRequest::record(self.db.get(), &key, &msg).await {
+                        warn!("failed to record request for event {}: {e}", key.id);
+                    }
+                }
+
+                let key = SolanaNftEventKey::from(key);
+
+                if let Some(event) = &msg.event {
+                    if self.reject_invalid_metadata_uri(&key, event).await? {
+                        return Ok(());
+                    }
+                }
+
+                match msg.event {
+                    Some(NftEvent::SolanaCreateEditionDrop(payload)) => {
+                        self.process_nft(
+                            EventKind::CreateEditionDrop,
+                            &key,
+                            self.create_collection(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaCreateCollection(payload)) => {
+                        self.process_nft(
+                            EventKind::CreateCollection,
+                            &key,
+                            self.create_collection(&key, payload),
+                        )
                         .await
                     },
                     Some(NftEvent::SolanaMintEditionDrop(payload)) => {
                         self.process_nft(
                             EventKind::MintEditionDrop,
                             &key,
-                            self.mint_drop(&EditionRef(self.solana()), &key, payload),
+                            self.mint_drop(&key, payload),
                         )
                         .await
                     },
@@ -499,7 +1789,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::UpdateEditionDrop,
                             &key,
-                            self.update_collection(&UncompressedRef(self.solana()), &key, payload),
+                            self.update_collection(&key, payload),
                         )
                         .await
                     },
@@ -507,7 +1797,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::UpdateCollection,
                             &key,
-                            self.update_collection(&UncompressedRef(self.solana()), &key, payload),
+                            self.update_collection(&key, payload),
                         )
                         .await
                     },
@@ -519,15 +1809,14 @@ impl Processor {
                         )
                         .await
                     },
+                    Some(NftEvent::SolanaTransferAssets(payload)) => {
+                        self.transfer_assets(&key, payload).await
+                    },
                     Some(NftEvent::SolanaRetryEditionDrop(payload)) => {
                         self.process_nft(
                             EventKind::RetryCreateEditionDrop,
                             &key,
-                            self.retry_create_collection(
-                                &UncompressedRef(self.solana()),
-                                &key,
-                                payload,
-                            ),
+                            self.retry_create_collection(&key, payload),
                         )
                         .await
                     },
@@ -535,11 +1824,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::RetryCreateCollection,
                             &key,
-                            self.retry_create_collection(
-                                &UncompressedRef(self.solana()),
-                                &key,
-                                payload,
-                            ),
+                            self.retry_create_collection(&key, payload),
                         )
                         .await
                     },
@@ -547,7 +1832,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::RetryMintEditionDrop,
                             &key,
-                            self.retry_mint_drop(&EditionRef(self.solana()), &key, payload),
+                            self.retry_mint_drop(&key, payload),
                         )
                         .await
                     },
@@ -563,11 +1848,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::UpdateCollectionMint,
                             &key,
-                            self.update_collection_mint(
-                                &UncompressedRef(self.solana()),
-                                &key,
-                                payload,
-                            ),
+                            self.update_collection_mint(&key, payload),
                         )
                         .await
                     },
@@ -575,10 +1856,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::RetryUpdateCollectionMint,
                             &key,
-                            self.retry_update_collection_mint(
-                                &UncompressedRef(self.solana()),
-                                &key,
-                            ),
+                            self.retry_update_collection_mint(&key),
                         )
                         .await
                     },
@@ -586,7 +1864,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::SwitchMintCollection,
                             &key,
-                            self.switch_mint_collection(&UncompressedRef(self.solana()), payload),
+                            self.switch_mint_collection(payload),
                         )
                         .await
                     },
@@ -594,7 +1872,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::CreateOpenDrop,
                             &key,
-                            self.create_collection(&UncompressedRef(self.solana()), &key, payload),
+                            self.create_collection(&key, payload),
                         )
                         .await
                     },
@@ -610,7 +1888,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::UpdateOpenDrop,
                             &key,
-                            self.update_collection(&UncompressedRef(self.solana()), &key, payload),
+                            self.update_collection(&key, payload),
                         )
                         .await
                     },
@@ -618,11 +1896,7 @@ impl Processor {
                         self.process_nft(
                             EventKind::RetryCreateOpenDrop,
                             &key,
-                            self.retry_create_collection(
-                                &UncompressedRef(self.solana()),
-                                &key,
-                                payload,
-                            ),
+                            self.retry_create_collection(&key, payload),
                         )
                         .await
                     },
@@ -643,6 +1917,163 @@ impl Processor {
                             )
                         })
                     },
+                    Some(NftEvent::SolanaCreateClaim(payload)) => {
+                        self.create_claim(&key, payload).await.map_err(|e| {
+                            ProcessorError::new(e, EventKind::CreateClaim, ErrorSource::NftRequest)
+                        })
+                    },
+                    Some(NftEvent::SolanaAddMintAllowlistAddresses(payload)) => self
+                        .add_mint_allowlist_addresses(payload)
+                        .await
+                        .map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::UpdateMintAllowlist,
+                                ErrorSource::NftRequest,
+                            )
+                        }),
+                    Some(NftEvent::SolanaRemoveMintAllowlistAddresses(payload)) => self
+                        .remove_mint_allowlist_addresses(payload)
+                        .await
+                        .map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::UpdateMintAllowlist,
+                                ErrorSource::NftRequest,
+                            )
+                        }),
+                    Some(NftEvent::SolanaConfigureDropSchedule(payload)) => {
+                        self.configure_drop_schedule(payload).await.map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::ConfigureDropSchedule,
+                                ErrorSource::NftRequest,
+                            )
+                        })
+                    },
+                    Some(NftEvent::SolanaVerifyHolder(payload)) => {
+                        self.verify_holder(&key, payload).await.map_err(|e| {
+                            ProcessorError::new(e, EventKind::VerifyHolder, ErrorSource::NftRequest)
+                        })
+                    },
+                    Some(NftEvent::SolanaListWalletContents(payload)) => {
+                        self.list_wallet_contents(&key, payload).await.map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::ListWalletContents,
+                                ErrorSource::NftRequest,
+                            )
+                        })
+                    },
+                    Some(NftEvent::SolanaExportCollection(payload)) => {
+                        self.export_collection(&key, payload).await.map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::ExportCollection,
+                                ErrorSource::NftRequest,
+                            )
+                        })
+                    },
+                    Some(NftEvent::SolanaVerifyCollectionAuthority(payload)) => self
+                        .verify_collection_authority(&key, payload)
+                        .await
+                        .map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::VerifyCollectionAuthority,
+                                ErrorSource::NftRequest,
+                            )
+                        }),
+                    Some(NftEvent::SolanaRedeemClaim(payload)) => {
+                        self.process_nft(
+                            EventKind::RedeemClaim,
+                            &key,
+                            self.redeem_claim(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaUpdateCollectionAssets(payload)) => {
+                        self.process_nft(
+                            EventKind::UpdateCollectionAssets,
+                            &key,
+                            self.update_collection_assets(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaLockMetadata(payload)) => {
+                        self.lock_metadata(&key, payload).await
+                    },
+                    Some(NftEvent::SolanaMarkPrimarySale(payload)) => {
+                        self.process_nft(
+                            EventKind::MarkPrimarySale,
+                            &key,
+                            self.mark_primary_sale(payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaCreateRoyaltySplit(payload)) => {
+                        self.process_nft(
+                            EventKind::CreateRoyaltySplit,
+                            &key,
+                            self.create_royalty_split(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaDistributeRoyalties(payload)) => {
+                        self.process_nft(
+                            EventKind::DistributeRoyalties,
+                            &key,
+                            self.distribute_royalties(payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaEscrowAsset(payload)) => {
+                        self.process_nft(
+                            EventKind::EscrowAsset,
+                            &key,
+                            self.escrow_asset(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaReleaseAsset(payload)) => {
+                        self.process_nft(
+                            EventKind::ReleaseAsset,
+                            &key,
+                            self.release_asset(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaListAsset(payload)) => {
+                        self.process_nft(EventKind::ListAsset, &key, self.list_asset(&key, payload))
+                            .await
+                    },
+                    Some(NftEvent::SolanaCancelListing(payload)) => {
+                        self.process_nft(
+                            EventKind::CancelListing,
+                            &key,
+                            self.cancel_listing(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaDeployCandyMachine(payload)) => {
+                        self.process_nft(
+                            EventKind::DeployCandyMachine,
+                            &key,
+                            self.deploy_candy_machine(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaInsertCandyMachineItems(payload)) => {
+                        self.process_nft(
+                            EventKind::InsertCandyMachineItems,
+                            &key,
+                            self.insert_candy_machine_items(&key, payload),
+                        )
+                        .await
+                    },
+                    Some(NftEvent::SolanaReserveTreeCapacity(payload)) => {
+                        self.reserve_tree_capacity(&key, payload).await
+                    },
                     _ => Ok(()),
                 }
             },
@@ -650,6 +2081,24 @@ impl Processor {
                 let key = SolanaNftEventKey::from(key);
 
                 match msg.event {
+                    Some(TreasuryEvent::ProjectTreasuryAssigned(payload)) => {
+                        self.assign_project_treasury(payload).await.map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::AssignProjectTreasury,
+                                ErrorSource::TreasuryStatus,
+                            )
+                        })
+                    },
+                    Some(TreasuryEvent::SolanaSignerPolicyAssigned(payload)) => {
+                        self.assign_signer_policy(payload).await.map_err(|e| {
+                            ProcessorError::new(
+                                e,
+                                EventKind::AssignSignerPolicy,
+                                ErrorSource::TreasuryStatus,
+                            )
+                        })
+                    },
                     Some(TreasuryEvent::SolanaCreateEditionDropSigned(res)) => {
                         self.process_treasury(EventKind::CreateEditionDrop, key, res)
                             .await
@@ -666,6 +2115,10 @@ impl Processor {
                         self.process_treasury(EventKind::TransferAsset, key, res)
                             .await
                     },
+                    Some(TreasuryEvent::SolanaTransferAssetsSigned(res)) => {
+                        self.process_treasury(EventKind::TransferAssets, key, res)
+                            .await
+                    },
                     Some(TreasuryEvent::SolanaRetryCreateEditionDropSigned(res)) => {
                         self.process_treasury(EventKind::RetryCreateEditionDrop, key, res)
                             .await
@@ -726,182 +2179,951 @@ impl Processor {
                         self.process_treasury(EventKind::RetryMintOpenDrop, key, res)
                             .await
                     },
+                    Some(TreasuryEvent::SolanaRedeemClaimSigned(res)) => {
+                        self.process_treasury(EventKind::RedeemClaim, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaUpdateCollectionAssetsSigned(res)) => {
+                        self.process_treasury(EventKind::UpdateCollectionAssets, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaLockMetadataSigned(res)) => {
+                        self.process_treasury(EventKind::LockMetadata, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaMarkPrimarySaleSigned(res)) => {
+                        self.process_treasury(EventKind::MarkPrimarySale, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaCreateRoyaltySplitSigned(res)) => {
+                        self.process_treasury(EventKind::CreateRoyaltySplit, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaDistributeRoyaltiesSigned(res)) => {
+                        self.process_treasury(EventKind::DistributeRoyalties, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaEscrowAssetSigned(res)) => {
+                        self.process_treasury(EventKind::EscrowAsset, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaReleaseAssetSigned(res)) => {
+                        self.process_treasury(EventKind::ReleaseAsset, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaListAssetSigned(res)) => {
+                        self.process_treasury(EventKind::ListAsset, key, res).await
+                    },
+                    Some(TreasuryEvent::SolanaCancelListingSigned(res)) => {
+                        self.process_treasury(EventKind::CancelListing, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaDeployCandyMachineSigned(res)) => {
+                        self.process_treasury(EventKind::DeployCandyMachine, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaInsertCandyMachineItemsSigned(res)) => {
+                        self.process_treasury(EventKind::InsertCandyMachineItems, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaReserveTreeCapacitySigned(res)) => {
+                        self.process_treasury(EventKind::ReserveTreeCapacity, key, res)
+                            .await
+                    },
+                    Some(TreasuryEvent::SolanaPrepareClaimTransactionSigned(res)) => {
+                        self.store_claim_transaction(&key, res).await.map_err(|k| {
+                            ProcessorError::new(
+                                k,
+                                EventKind::PrepareClaimTransaction,
+                                ErrorSource::TreasuryStatus,
+                            )
+                        })
+                    },
                     _ => Ok(()),
                 }
             },
         }
     }
 
-    async fn process_mint_batch(
-        &self,
-        key: &SolanaNftEventKey,
-        payload: SolanaMintOpenDropBatchedPayload,
-    ) -> ProcessResult<()> {
-        let conn = self.db.get();
-        let producer = self.producer.clone();
-
-        let collection_id = Uuid::parse_str(&payload.collection_id)?;
-        let collection = Collection::find_by_id(conn, collection_id)
-            .await?
-            .ok_or(ProcessorErrorKind::RecordNotFound)?;
-
-        let signers_pubkeys = vec![
-            self.solana().treasury_wallet().to_string(),
-            collection.owner.clone(),
-        ];
-        let blockhash = with_retry!(self.solana().rpc().get_latest_blockhash())
-            .await
-            .context("blockhash not found")
-            .map_err(ProcessorErrorKind::Solana)?;
-
-        let send_event = |mint_transactions: Vec<SolanaMintTransaction>,
-                          signers_pubkeys: Vec<String>| async {
-            producer
-                .send(
-                    Some(&SolanaNftEvents {
-                        event: Some(SolanaNftEvent::MintOpenDropBatchedSigningRequested(
-                            SolanaMintPendingTransactions {
-                                signers_pubkeys,
-                                mint_transactions,
-                            },
-                        )),
-                    }),
-                    Some(key),
-                )
-                .await
+    async fn assign_project_treasury(&self, payload: ProjectTreasuryPayload) -> ProcessResult<()> {
+        let project_id = Uuid::parse_str(&payload.project_id)?;
+        let wallet: Pubkey = payload.wallet_address.parse()?;
+        let revenue_wallet = payload
+            .revenue_wallet_address
+            .as_deref()
+            .map(str::parse::<Pubkey>)
+            .transpose()?;
+        let multisig = payload
+            .multisig_address
+            .as_deref()
+            .map(str::parse::<Pubkey>)
+            .transpose()?;
+        let auction_house = payload
+            .auction_house_address
+            .as_deref()
+            .map(str::parse::<Pubkey>)
+            .transpose()?;
+        let fee_payer_mode = match SolanaFeePayerMode::from_i32(payload.fee_payer_mode) {
+            Some(SolanaFeePayerMode::Holaplex) => FeePayerMode::Holaplex,
+            Some(SolanaFeePayerMode::Project) | None => FeePayerMode::Project,
         };
 
-        if payload.compressed {
-            let backend = &CompressedRef(self.solana());
-            let mut leafs: Vec<compression_leafs::ActiveModel> = Vec::new();
-            let mut mint_transactions = Vec::new();
-
-            for mint_tx in payload.mint_open_drop_transactions.clone() {
-                let id = Uuid::from_str(&mint_tx.mint_id)?;
-
-                let tx = backend
-                    .mint(
-                        &collection,
-                        Some(blockhash),
-                        MintMetaplexMetadataTransaction {
-                            recipient_address: mint_tx.recipient_address,
-                            metadata: mint_tx.metadata,
-                            collection_id: payload.collection_id.clone(),
-                            compressed: payload.compressed,
-                        },
-                    )
-                    .await
-                    .map_err(ProcessorErrorKind::Solana)?;
+        ProjectTreasury::upsert(
+            self.db.get(),
+            project_id,
+            wallet.to_string(),
+            revenue_wallet.map(|w| w.to_string()),
+            multisig.map(|m| m.to_string()),
+            fee_payer_mode,
+            auction_house.map(|a| a.to_string()),
+        )
+        .await?;
+
+        match multisig {
+            Some(multisig) => info!(
+                "assigned treasury {wallet} proposing to multisig {multisig} to project \
+                 {project_id}"
+            ),
+            None => info!("assigned treasury {wallet} to project {project_id}"),
+        }
 
-                mint_transactions.push(SolanaMintTransaction {
-                    serialized_message: tx.serialized_message,
-                    mint_id: mint_tx.mint_id,
-                    signer_signature: None,
-                });
+        Ok(())
+    }
 
-                let compression_leaf = compression_leafs::Model {
-                    id,
-                    collection_id: collection.id,
-                    merkle_tree: tx.addresses.merkle_tree.to_string(),
-                    tree_authority: tx.addresses.tree_authority.to_string(),
-                    tree_delegate: tx.addresses.tree_delegate.to_string(),
-                    leaf_owner: tx.addresses.leaf_owner.to_string(),
-                    created_at: Utc::now().naive_utc(),
-                    ..Default::default()
-                };
+    async fn assign_signer_policy(
+        &self,
+        SolanaSignerPolicy {
+            project_id,
+            signer_addresses,
+        }: SolanaSignerPolicy,
+    ) -> ProcessResult<()> {
+        let project_id = Uuid::parse_str(&project_id)?;
+        let signer_addresses = signer_addresses
+            .iter()
+            .map(|a| a.parse::<Pubkey>().map(|a| a.to_string()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-                leafs.push(compression_leaf.into());
-            }
+        SignerPolicy::upsert(self.db.get(), project_id, &signer_addresses).await?;
 
-            compression_leafs::Entity::insert_many(leafs)
-                .exec(conn)
-                .await?;
+        info!(
+            "assigned a signer policy of {} wallets to project {project_id}",
+            signer_addresses.len()
+        );
 
-            send_event(mint_transactions, signers_pubkeys).await?;
+        Ok(())
+    }
 
+    /// Check the signers of an assembled transaction against the signer
+    /// policy of the project it was requested for. Projects without a policy
+    /// are not restricted.
+    async fn check_signers(
+        &self,
+        key: &SolanaNftEventKey,
+        signers: &[String],
+    ) -> ProcessResult<()> {
+        let Ok(project_id) = Uuid::parse_str(&key.project_id) else {
             return Ok(());
-        }
+        };
+        let Some(policy) = SignerPolicy::find_by_project_id(self.db.get(), project_id).await?
+        else {
+            return Ok(());
+        };
 
-        let backend = &UncompressedRef(self.solana());
-        let mut mints: Vec<collection_mints::ActiveModel> = Vec::new();
-        let mut mint_transactions = Vec::new();
+        let wallets = SignerPolicy::signer_addresses(&policy)?
+            .iter()
+            .map(|a| a.parse())
+            .collect::<std::result::Result<Vec<Pubkey>, _>>()?;
+        let solana = self.solana();
 
-        for mint_tx in payload.mint_open_drop_transactions.clone() {
-            let id = Uuid::from_str(&mint_tx.mint_id)?;
-            let tx = backend
-                .mint(
-                    &collection,
-                    Some(blockhash),
-                    MintMetaplexMetadataTransaction {
-                        recipient_address: mint_tx.recipient_address,
-                        metadata: mint_tx.metadata,
-                        collection_id: payload.collection_id.clone(),
-                        compressed: payload.compressed,
-                    },
-                )
-                .await
-                .map_err(ProcessorErrorKind::Solana)?;
+        AuthorizedSigners::new(
+            solana.payer(),
+            wallets
+                .into_iter()
+                .chain([solana.treasury_wallet(), solana.tree_delegate()]),
+        )
+        .check(signers)?;
 
-            mint_transactions.push(SolanaMintTransaction {
-                serialized_message: tx.serialized_message,
-                mint_id: mint_tx.mint_id,
-                signer_signature: tx.signatures_or_signers_public_keys.get(1).cloned(),
-            });
+        Ok(())
+    }
 
-            let collection_mint = collection_mints::Model {
-                id,
-                collection_id: collection.id,
-                owner: tx.addresses.recipient.to_string(),
-                mint: tx.addresses.mint.to_string(),
-                created_at: Utc::now().naive_utc(),
-                associated_token_account: tx.addresses.associated_token_account.to_string(),
-            };
+    async fn add_mint_allowlist_addresses(
+        &self,
+        MintAllowlistPayload {
+            collection_id,
+            addresses,
+        }: MintAllowlistPayload,
+    ) -> ProcessResult<()> {
+        let collection_id = Uuid::parse_str(&collection_id)?;
+        let addresses = addresses
+            .iter()
+            .map(|a| a.parse::<Pubkey>().map(|a| a.to_string()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-            mints.push(collection_mint.into());
-        }
+        MintAllowlist::add(self.db.get(), collection_id, addresses).await?;
 
-        collection_mints::Entity::insert_many(mints)
-            .exec(conn)
-            .await?;
+        Ok(())
+    }
+
+    async fn remove_mint_allowlist_addresses(
+        &self,
+        MintAllowlistPayload {
+            collection_id,
+            addresses,
+        }: MintAllowlistPayload,
+    ) -> ProcessResult<()> {
+        let collection_id = Uuid::parse_str(&collection_id)?;
+
+        MintAllowlist::remove(self.db.get(), collection_id, addresses).await?;
 
-        send_event(mint_transactions, signers_pubkeys).await?;
         Ok(())
     }
 
-    async fn process_nft(
+    async fn configure_drop_schedule(
         &self,
-        kind: EventKind,
-        key: &SolanaNftEventKey,
-        fut: impl Future<Output = ProcessResult<SolanaPendingTransaction>>,
-    ) -> Result<()> {
-        match fut.await {
-            Ok(tx) => self
-                .producer
-                .send(
-                    Some(&SolanaNftEvents {
-                        event: Some(kind.into_sign_request(tx)),
-                    }),
-                    Some(key),
+        SolanaDropSchedulePayload {
+            collection_id,
+            mint_open_at,
+            mint_close_at,
+        }: SolanaDropSchedulePayload,
+    ) -> ProcessResult<()> {
+        let parse = |secs: Option<i64>| {
+            secs.map(|secs| {
+                NaiveDateTime::from_timestamp_opt(secs, 0)
+                    .ok_or(ProcessorErrorKind::InvalidDropSchedule)
+            })
+            .transpose()
+        };
+        let mint_open_at = parse(mint_open_at)?;
+        let mint_close_at = parse(mint_close_at)?;
+
+        if let Some((open_at, close_at)) = mint_open_at.zip(mint_close_at) {
+            if close_at <= open_at {
+                return Err(ProcessorErrorKind::InvalidDropSchedule);
+            }
+        }
+
+        let collection_id = Uuid::parse_str(&collection_id)?;
+        let collection = Collection::find_by_id(self.db.get(), collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        // A moved boundary is announced again once it is reached
+        let mut am: collections::ActiveModel = collection.clone().into();
+
+        if collection.mint_open_at != mint_open_at {
+            am.mint_open_at = Set(mint_open_at);
+            am.open_announced_at = Set(None);
+        }
+
+        if collection.mint_close_at != mint_close_at {
+            am.mint_close_at = Set(mint_close_at);
+            am.close_announced_at = Set(None);
+        }
+
+        Collection::update(self.db.get(), am).await?;
+
+        info!("configured mint window of collection {collection_id}");
+
+        Ok(())
+    }
+
+    /// Answer whether a wallet holds at least the requested number of mints
+    /// of a collection, from indexed ownership rather than the chain
+    async fn verify_holder(
+        &self,
+        key: &SolanaNftEventKey,
+        SolanaVerifyHolderPayload {
+            collection_id,
+            wallet,
+            min_count,
+        }: SolanaVerifyHolderPayload,
+    ) -> ProcessResult<()> {
+        let mints =
+            CollectionMint::holders_of(self.db.read(), Uuid::parse_str(&collection_id)?, &wallet)
+                .await?;
+        let count = u32::try_from(mints.len()).unwrap_or(u32::MAX);
+
+        let event =
+            SolanaNftEvents::new(SolanaNftEvent::HolderVerified(SolanaHolderVerification {
+                collection_id,
+                wallet,
+                min_count,
+                count,
+                verified: count >= min_count.max(1),
+                mint_addresses: mints.into_iter().map(|mint| mint.mint).collect(),
+            }));
+
+        Self::enqueue(self.db.begin().await?, key, &event).await
+    }
+
+    /// Issue a challenge for the update authority of a collection to sign,
+    /// or check the signed challenge so the project may import the
+    /// collection. Rejected signatures are answered rather than retried.
+    async fn verify_collection_authority(
+        &self,
+        key: &SolanaNftEventKey,
+        SolanaVerifyCollectionAuthorityPayload {
+            mint_address,
+            update_authority,
+            signature,
+        }: SolanaVerifyCollectionAuthorityPayload,
+    ) -> ProcessResult<()> {
+        let project_id = Uuid::parse_str(&key.project_id)?;
+        let txn = self.db.begin().await?;
+
+        if signature.is_empty() {
+            let challenge = AuthorityChallenge::new(project_id, &mint_address, &update_authority);
+
+            CollectionAuthority::issue(
+                &txn,
+                project_id,
+                mint_address.clone(),
+                update_authority.clone(),
+                challenge.message.clone(),
+                challenge.expires_at,
+            )
+            .await?;
+
+            let event = SolanaNftEvents::new(SolanaNftEvent::CollectionAuthorityChallengeIssued(
+                SolanaCollectionAuthorityChallenge {
+                    mint_address,
+                    update_authority,
+                    message: challenge.message,
+                    expires_at: challenge.expires_at.timestamp(),
+                },
+            ));
+
+            return Self::enqueue(txn, key, &event).await;
+        }
+
+        let checked = match CollectionAuthority::find(&txn, project_id, &mint_address).await? {
+            Some(issued) => collection_authority::verify(&issued, &update_authority, &signature)
+                .map(|()| issued),
+            None => Err(AuthorityError::NoChallenge(mint_address.clone())),
+        };
+
+        let reason = match checked {
+            Ok(issued) => {
+                CollectionAuthority::verify(&txn, issued).await?;
+                String::new()
+            },
+            Err(e) => {
+                warn!("rejected authority of collection {mint_address}: {e}");
+                e.to_string()
+            },
+        };
+
+        let event = SolanaNftEvents::new(SolanaNftEvent::CollectionAuthorityVerified(
+            SolanaCollectionAuthorityVerification {
+                mint_address,
+                update_authority,
+                verified: reason.is_empty(),
+                reason,
+            },
+        ));
+
+        Self::enqueue(txn, key, &event).await
+    }
+
+    /// Write every mint of a collection as newline-delimited JSON, one chunk
+    /// of [`export::CHUNK_SIZE`] mints at a time, reporting each chunk as it
+    /// is written. Chunks are uploaded to the export sink if one is
+    /// configured and sent inline otherwise. A retried export writes its
+    /// chunks again under the same indexes.
+    async fn export_collection(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaExportCollectionPayload,
+    ) -> ProcessResult<()> {
+        let export_id = Uuid::parse_str(&key.id)?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let conn = self.db.read();
+        let das = self.solana().asset_rpc();
+
+        Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        let mut cursor = None;
+        let mut chunks = 0_u32;
+        let mut mints = 0_u64;
+
+        loop {
+            let page = CollectionMint::find_all_by_collection(
+                conn,
+                collection_id,
+                cursor,
+                export::CHUNK_SIZE,
+            )
+            .await?;
+            let Some((last, _)) = page.last() else {
+                break;
+            };
+            cursor = Some(MintCursor::from(last));
+
+            let count = u32::try_from(page.len()).unwrap_or(u32::MAX);
+            let ids = page.iter().map(|(mint, _)| mint.id).collect();
+            let addresses: Vec<_> = page.iter().map(|(mint, _)| mint.mint.clone()).collect();
+            let mut signatures = TransactionAudit::latest_signatures(conn, ids).await?;
+            let mut uris = export::metadata_uris(das.as_ref(), &addresses).await;
+
+            let records: Vec<_> = page
+                .into_iter()
+                .map(|(mint, leaf)| {
+                    let uri = uris.remove(&mint.mint);
+                    let signature = signatures.remove(&mint.id);
+
+                    ExportRecord::new(mint, leaf, uri, signature)
+                })
+                .collect();
+            let chunk = export::to_ndjson(&records).map_err(ProcessorErrorKind::Export)?;
+
+            let (url, data) = match &self.export_sink {
+                Some(sink) => {
+                    let url = sink
+                        .0
+                        .put(export_id, chunks, chunk)
+                        .await
+                        .map_err(ProcessorErrorKind::Export)?;
+
+                    (url, Vec::new())
+                },
+                None => (String::new(), chunk),
+            };
+
+            let event = SolanaNftEvents::new(SolanaNftEvent::CollectionExportChunk(
+                SolanaCollectionExportChunk {
+                    collection_id: payload.collection_id.clone(),
+                    index: chunks,
+                    mints: count,
+                    url,
+                    data,
+                },
+            ));
+
+            Self::enqueue(self.db.begin().await?, key, &event).await?;
+
+            chunks += 1;
+            mints += u64::from(count);
+
+            if u64::from(count) < export::CHUNK_SIZE {
+                break;
+            }
+        }
+
+        info!("Exported {mints} mints of collection {collection_id} in {chunks} chunks");
+
+        let event =
+            SolanaNftEvents::new(SolanaNftEvent::CollectionExported(SolanaCollectionExport {
+                collection_id: payload.collection_id,
+                mints,
+                chunks,
+            }));
+
+        Self::enqueue(self.db.begin().await?, key, &event).await
+    }
+
+    /// Reply with a page of the Hub-managed assets held by a wallet, both
+    /// uncompressed mints and compressed leaves
+    async fn list_wallet_contents(
+        &self,
+        key: &SolanaNftEventKey,
+        SolanaWalletContentsRequest {
+            wallet,
+            cursor,
+            limit,
+        }: SolanaWalletContentsRequest,
+    ) -> ProcessResult<()> {
+        let cursor = if cursor.is_empty() {
+            None
+        } else {
+            Some(MintCursor::decode(&cursor).ok_or(ProcessorErrorKind::InvalidCursor)?)
+        };
+        let limit = match limit {
+            0 => DEFAULT_WALLET_CONTENTS_LIMIT,
+            limit => limit.min(MAX_WALLET_CONTENTS_LIMIT),
+        };
+
+        let page = CollectionMint::find_all_by_owner(self.db.read(), &wallet, cursor, limit.into())
+            .await?;
+
+        // A full page may be followed by more assets
+        let next_cursor = (page.len() == limit as usize)
+            .then(|| page.last().map(|(mint, _)| MintCursor::from(mint).encode()))
+            .flatten();
+
+        let assets = page
+            .into_iter()
+            .map(|(mint, leaf)| SolanaWalletAsset {
+                mint_id: mint.id.to_string(),
+                collection_id: mint.collection_id.to_string(),
+                address: mint.mint,
+                compressed: mint.compressed,
+                merkle_tree: leaf.map(|leaf| leaf.merkle_tree).unwrap_or_default(),
+                edition: mint.edition,
+            })
+            .collect();
+
+        let event = SolanaNftEvents::new(SolanaNftEvent::WalletContents(SolanaWalletContents {
+            wallet,
+            assets,
+            next_cursor,
+        }));
+
+        Self::enqueue(self.db.begin().await?, key, &event).await
+    }
+
+    /// Assemble a batch of open drop mints, returning the nonce accounts
+    /// leased to its mints to their pools if the batch is not sent to be
+    /// signed
+    async fn process_mint_batch(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaMintOpenDropBatchedPayload,
+    ) -> ProcessResult<()> {
+        let mint_ids: Vec<_> = payload
+            .mint_open_drop_transactions
+            .iter()
+            .filter_map(|mint_tx| Uuid::from_str(&mint_tx.mint_id).ok())
+            .collect();
+        let res = self.assemble_mint_batch(key, payload).await;
+
+        if res.is_err() {
+            NonceAccount::release(self.db.get(), mint_ids).await?;
+        }
+
+        res
+    }
+
+    async fn assemble_mint_batch(
+        &self,
+        key: &SolanaNftEventKey,
+        mut payload: SolanaMintOpenDropBatchedPayload,
+    ) -> ProcessResult<()> {
+        for mint_tx in &mut payload.mint_open_drop_transactions {
+            mint_tx.recipient_address = self
+                .resolve_recipient(mem::take(&mut mint_tx.recipient_address))
+                .await?;
+        }
+
+        let conn = self.db.get();
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        ensure_mint_window(&collection)?;
+
+        // Signers of batched mints are fixed and cannot include recipients
+        if collection.mint_price.is_some() {
+            return Err(ProcessorErrorKind::PaidBatchMint);
+        }
+
+        // Each proposal takes the next transaction index of the multisig, so
+        // a batch cannot share one blockhash across independent messages
+        if self.solana().multisig().is_some() {
+            return Err(ProcessorErrorKind::MultisigBatchMint);
+        }
+
+        let payer = self.solana().payer();
+        let tree_delegate = self.solana().tree_delegate();
+        let signers_pubkeys = if payload.compressed && tree_delegate != payer {
+            vec![
+                payer.to_string(),
+                tree_delegate.to_string(),
+                collection.owner.clone(),
+            ]
+        } else {
+            vec![payer.to_string(), collection.owner.clone()]
+        };
+        self.check_signers(key, &signers_pubkeys).await?;
+
+        let blockhash = with_retry!(
+            self.solana().retries(),
+            self.solana().solana_rpc().get_latest_blockhash()
+        )
+        .await
+        .context("blockhash not found")
+        .map_err(ProcessorErrorKind::Solana)?;
+
+        let signing_requested = |mint_transactions: Vec<SolanaMintTransaction>,
+                                 signers_pubkeys: Vec<String>| {
+            SolanaNftEvents::new(SolanaNftEvent::MintOpenDropBatchedSigningRequested(
+                SolanaMintPendingTransactions {
+                    signers_pubkeys,
+                    mint_transactions,
+                },
+            ))
+        };
+
+        let solana = self
+            .solana_for_mint(conn, &collection, payload.compressed)
+            .await?;
+        let standard = AssetStandard::new(payload.compressed);
+        let mut leafs: Vec<compression_leafs::Model> = Vec::new();
+        let mut mints: Vec<collection_mints::ActiveModel> = Vec::new();
+        let mut mint_transactions = Vec::new();
+        let mut audits = Vec::new();
+
+        for mint_tx in payload.mint_open_drop_transactions.clone() {
+            let id = Uuid::from_str(&mint_tx.mint_id)?;
+            // Each mint is signed and submitted on its own, so it leases its
+            // own nonce account and only falls back to the shared blockhash
+            let solana = solana.with_nonces(self.nonces(id));
+            let tx = Backends::new(&solana)
+                .mint(
+                    standard,
+                    &collection,
+                    Some(blockhash),
+                    MintMetaplexMetadataTransaction {
+                        recipient_address: mint_tx.recipient_address,
+                        metadata: mint_tx.metadata,
+                        collection_id: payload.collection_id.clone(),
+                        compressed: payload.compressed,
+                    },
                 )
                 .await
-                .map_err(|e| ProcessorError::new(e.into(), kind, ErrorSource::NftSignRequest)),
-            Err(e) => {
-                warn!(
-                    "{:?}",
-                    Error::new(e).context(format!("Error processing {}", kind.name()))
-                );
-                self.event_failed(kind, key, SolanaTransactionFailureReason::Assemble)
+                .map_err(ProcessorErrorKind::Solana)?;
+
+            let signer_signature = match &tx.addresses {
+                MintAddresses::Compressed(addresses) => {
+                    leafs.push(compression_leafs::Model {
+                        id,
+                        collection_id: collection.id,
+                        merkle_tree: addresses.merkle_tree.to_string(),
+                        tree_authority: addresses.tree_authority.to_string(),
+                        tree_delegate: addresses.tree_delegate.to_string(),
+                        leaf_owner: addresses.leaf_owner.to_string(),
+                        created_at: Utc::now().naive_utc(),
+                        ..Default::default()
+                    });
+
+                    None
+                },
+                MintAddresses::NonFungible(addresses) => {
+                    let collection_mint = collection_mints::Model {
+                        id,
+                        collection_id: collection.id,
+                        owner: addresses.recipient.to_string(),
+                        mint: addresses.mint.to_string(),
+                        created_at: Utc::now().naive_utc(),
+                        associated_token_account: addresses.associated_token_account.to_string(),
+                        compressed: false,
+                        edition: None,
+                        metadata_lock_id: None,
+                        metadata_locked_at: None,
+                        primary_sale_at: None,
+                        deleted_at: None,
+                    };
+
+                    mints.push(collection_mint.into());
+
+                    // The mint keypair signs when the message is assembled
+                    tx.signatures_or_signers_public_keys.get(1).cloned()
+                },
+            };
+
+            audits.push((
+                SolanaNftEventKey {
+                    id: mint_tx.mint_id.clone(),
+                    ..key.clone()
+                },
+                tx.serialized_message.clone(),
+            ));
+
+            mint_transactions.push(SolanaMintTransaction {
+                serialized_message: tx.serialized_message,
+                mint_id: mint_tx.mint_id,
+                signer_signature,
+            });
+        }
+
+        // Every message is assembled before the database transaction begins
+        let txn = self.db.begin().await?;
+
+        for (key, serialized_message) in audits {
+            Self::audit(
+                &txn,
+                EventKind::MintOpenDropBatched,
+                &key,
+                "signing_requested",
+                Some(serialized_message.as_slice()),
+                signers_pubkeys.clone(),
+                None,
+            )
+            .await?;
+        }
+
+        if !leafs.is_empty() {
+            CompressionLeaf::create_many(&txn, leafs).await?;
+        }
+
+        if !mints.is_empty() {
+            collection_mints::Entity::insert_many(mints)
+                .exec(&txn)
+                .await?;
+        }
+
+        Self::enqueue(
+            txn,
+            key,
+            &signing_requested(mint_transactions, signers_pubkeys),
+        )
+        .await
+    }
+
+    /// Process a previously received request again, as its retry
+    /// counterpart where one exists.
+    ///
+    /// # Errors
+    /// This function fails if the replayed event fails to process
+    pub async fn replay(&self, key: NftEventKey, msg: NftEvents) -> Result<()> {
+        let msg = NftEvents {
+            event: msg.event.map(into_retry),
+        };
+
+        self.process(Services::Nfts(key, msg)).await
+    }
+
+    /// Mark a request as failed without processing it, notifying hub-nfts
+    /// with a manual failure reason. Returns `false` if the request is not an
+    /// event this service can fail.
+    ///
+    /// # Errors
+    /// This function fails if the failure cannot be recorded
+    pub async fn force_fail(
+        &self,
+        key: NftEventKey,
+        msg: &NftEvents,
+        message: String,
+    ) -> Result<bool> {
+        let Some(kind) = msg.event.as_ref().and_then(EventKind::from_request) else {
+            return Ok(false);
+        };
+
+        if kind == EventKind::MintOpenDropBatched {
+            return Ok(false);
+        }
+
+        self.event_failed(
+            kind,
+            &SolanaNftEventKey::from(key),
+            SolanaTransactionFailureReason::Manual,
+            FailureDetails {
+                message,
+                logs: vec![],
+                signature: None,
+            },
+        )
+        .await
+        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure))?;
+
+        Ok(true)
+    }
+
+    /// Add the nonce account at `address` on `cluster` to the pool of its
+    /// nonce authority, returning the authority. Returns `None` if this
+    /// consumer does not serve `cluster`.
+    ///
+    /// # Errors
+    /// This function fails if the account is not a nonce account or cannot
+    /// be recorded
+    pub async fn register_nonce_account(
+        &self,
+        cluster: SolanaCluster,
+        address: Pubkey,
+    ) -> Result<Option<Pubkey>> {
+        let Some(solana) = self.clusters.0.get(cluster) else {
+            return Ok(None);
+        };
+
+        let authority = solana.nonce_account(address).await?.authority;
+
+        NonceAccount::register(
+            self.db.get(),
+            address.to_string(),
+            cluster,
+            authority.to_string(),
+        )
+        .await?;
+
+        Ok(Some(authority))
+    }
+
+    /// Fail a request up front if its metadata URI or image does not resolve,
+    /// rather than minting an NFT pointing at a missing document. Returns
+    /// `true` if the request was failed.
+    async fn reject_invalid_metadata_uri(
+        &self,
+        key: &SolanaNftEventKey,
+        event: &NftEvent,
+    ) -> Result<bool> {
+        let (Some(validator), Some(kind), Some(uri)) = (
+            &self.metadata_validator,
+            EventKind::from_request(event),
+            MetadataValidator::metadata_uri(event),
+        ) else {
+            return Ok(false);
+        };
+
+        let Err(e) = validator.validate(uri).await else {
+            return Ok(false);
+        };
+
+        let e = Error::new(e).context(format!("Invalid metadata URI for {}", kind.name()));
+        warn!("{e:?}");
+
+        self.event_failed(
+            kind,
+            key,
+            SolanaTransactionFailureReason::InvalidMetadataUri,
+            FailureDetails::from_error(&e, None),
+        )
+        .await
+        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure))?;
+
+        Ok(true)
+    }
+
+    async fn process_nft(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        fut: impl Future<Output = ProcessResult<PendingTransaction>>,
+    ) -> Result<()> {
+        let span = info_span!(
+            "process_nft",
+            event = kind.name(),
+            id = %key.id,
+            project_id = %key.project_id,
+            correlation_id = %key.correlation_id,
+        );
+
+        self.request_signature(kind, key, fut)
+            .instrument(span)
+            .await
+    }
+
+    async fn request_signature(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        fut: impl Future<Output = ProcessResult<PendingTransaction>>,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let res = fut.await;
+        let elapsed = i64::try_from(start.elapsed().as_millis()).unwrap_or(0);
+
+        self.metrics
+            .rpc_tx_assembly_duration_ms_bucket
+            .record(elapsed, &[
+                KeyValue::new("blockchain", "Solana"),
+                KeyValue::new("event", kind.name()),
+                KeyValue::new("success", res.is_ok()),
+            ]);
+
+        let res = match res {
+            Ok(pending) => self
+                .check_signers(key, &pending.tx.signatures_or_signers_public_keys)
+                .await
+                .map(|()| pending),
+            Err(e) => Err(e),
+        };
+        let res = match res {
+            Ok(pending) => self.propose(kind, key, pending).await,
+            Err(e) => Err(e),
+        };
+
+        match res {
+            Ok(PendingTransaction {
+                tx,
+                ephemeral_keypairs,
+                writes,
+            }) => {
+                if let Some(logs) = self.simulate(kind, &tx).await {
+                    // Keep the assembled records so the event can be retried,
+                    // releasing the transaction index of any proposal
+                    self.keep_unsimulated(key, writes)
+                        .await
+                        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure))?;
+
+                    return self
+                        .event_failed(
+                            kind,
+                            key,
+                            SolanaTransactionFailureReason::SimulationFailed,
+                            FailureDetails {
+                                message: format!("simulation of {} failed", kind.name()),
+                                logs,
+                                signature: None,
+                            },
+                        )
+                        .await
+                        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure));
+                }
+
+                let tx = self.estimate_cost(kind, tx).await;
+
+                // Writes are part of assembling the transaction, so their
+                // failures are reported like any other assembly failure
+                let txn = match self.apply_writes(writes).await {
+                    Ok(txn) => txn,
+                    Err(e) => return self.assembly_failed(kind, key, e).await,
+                };
+
+                Self::queue_signing_request(txn, kind, key, tx, ephemeral_keypairs)
                     .await
-                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure))
+                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftSignRequest))?;
+
+                self.record_processed(kind, "signing_requested");
+
+                Ok(())
             },
+            Err(e) => self.assembly_failed(kind, key, e).await,
         }
     }
 
+    async fn assembly_failed(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        e: ProcessorErrorKind,
+    ) -> Result<()> {
+        let reason = e.failure_reason();
+        let e = Error::new(e).context(format!("Error processing {}", kind.name()));
+        warn!("{e:?}");
+
+        self.event_failed(kind, key, reason, FailureDetails::from_error(&e, None))
+            .await
+            .map_err(|k| ProcessorError::new(k, kind, ErrorSource::NftFailure))
+    }
+
     async fn process_treasury(
         &self,
         kind: EventKind,
         key: SolanaNftEventKey,
         res: SolanaTransactionResult,
+    ) -> Result<()> {
+        let span = info_span!(
+            "process_treasury",
+            event = kind.name(),
+            id = %key.id,
+            project_id = %key.project_id,
+            correlation_id = %key.correlation_id,
+            signature = res
+                .signed_message_signatures
+                .first()
+                .map_or("", String::as_str),
+        );
+
+        self.submit_signed(kind, key, res).instrument(span).await
+    }
+
+    async fn submit_signed(
+        &self,
+        kind: EventKind,
+        key: SolanaNftEventKey,
+        res: SolanaTransactionResult,
     ) -> Result<()> {
         let start = Instant::now();
         let status = TransactionStatus::from_i32(res.status).ok_or_else(|| {
@@ -912,337 +3134,2421 @@ impl Processor {
             )
         })?;
 
+        let signature = res.signed_message_signatures.first().cloned();
+
+        Self::audit(
+            self.db.get(),
+            kind,
+            &key,
+            if status == TransactionStatus::Failed {
+                "sign_failed"
+            } else {
+                "signed"
+            },
+            res.serialized_message.as_deref(),
+            res.serialized_message
+                .as_deref()
+                .map(message_signers)
+                .unwrap_or_default(),
+            signature.clone(),
+        )
+        .await
+        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryStatus))?;
+
         if status == TransactionStatus::Failed {
+            Self::settle_proposal(self.db.get(), &key, ProposalStatus::Failed, None)
+                .await
+                .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryStatus))?;
+            self.record_update_status(kind, &key, RevisionStatus::Failed, signature.clone())
+                .await
+                .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryStatus))?;
+
             return self
-                .event_failed(kind, &key, SolanaTransactionFailureReason::Sign)
+                .event_failed(
+                    kind,
+                    &key,
+                    SolanaTransactionFailureReason::Sign,
+                    FailureDetails {
+                        message: format!("treasury failed to sign {}", kind.name()),
+                        logs: vec![],
+                        signature,
+                    },
+                )
                 .await
                 .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryStatus));
         }
 
-        let res = match self.solana().submit_transaction(&res).await {
-            Ok(sig) => self
-                .event_submitted(kind, &key, sig)
+        if let Some(signature) = &signature {
+            self.record_mint_signature(kind, &key, signature)
                 .await
-                .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasurySuccess)),
-            Err(e) => {
-                warn!(
-                    "{:?}",
-                    e.context(format!("Error submitting {}", kind.name()))
-                );
-                self.event_failed(kind, &key, SolanaTransactionFailureReason::Submit)
-                    .await
-                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryFailure))
+                .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryStatus))?;
+        }
+
+        let nonce = solana::durable_nonce(&res);
+        let bundled = self.clone().with_bundles(kind);
+        let submitted = match bundled.solana().submit_transaction(&res).await {
+            Ok(submitted) => Ok(submitted),
+            Err(e) => match bundled.solana().settle(&res).await {
+                Ok(Settled::Landed(submitted)) => {
+                    warn!("{} {} landed despite: {e}", kind.name(), key.id);
+
+                    Ok(submitted)
+                },
+                Ok(settled) => Err((e, Some(settled))),
+                Err(settle_err) => {
+                    warn!(
+                        "{:?}",
+                        settle_err.context(format!("Error settling {}", kind.name()))
+                    );
+
+                    Err((e, None))
+                },
             },
         };
-        let elapsed = i64::try_from(start.elapsed().as_millis()).unwrap_or(0);
+        let res = match submitted {
+            Ok(submitted) => {
+                let sig = submitted.signature.clone();
+                Self::settle_proposal(
+                    self.db.get(),
+                    &key,
+                    ProposalStatus::Created,
+                    Some(sig.clone()),
+                )
+                .await
+                .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasurySuccess))?;
+                Self::release_nonces(self.db.get(), &key)
+                    .await
+                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasurySuccess))?;
+
+                let update_status = if submitted.slot.is_some() {
+                    RevisionStatus::Confirmed
+                } else {
+                    RevisionStatus::Submitted
+                };
+                self.record_update_status(kind, &key, update_status, Some(sig))
+                    .await
+                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasurySuccess))?;
+
+                let fee = self.message_fee(kind, &res).await;
+
+                self.event_submitted(kind, &key, submitted, fee)
+                    .await
+                    .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasurySuccess))
+            },
+            Err((e, settled)) => {
+                let e = match &settled {
+                    Some(Settled::Failed(err)) => e.context(format!("transaction failed: {err}")),
+                    _ => e,
+                };
+                let e = e.context(format!("Error submitting {}", kind.name()));
+                warn!("{e:?}");
+
+                // The nonce of a message which may still land must be advanced
+                // before its account is leased again
+                if let Some(nonce) = nonce
+                    .filter(|_| !matches!(settled, Some(Settled::Failed(_) | Settled::Expired)))
+                {
+                    Self::hold_stale_nonces(self.db.get(), &key, nonce)
+                        .await
+                        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryFailure))?;
+                }
+
+                // A message which landed with an error would fail again, and
+                // one which has not expired or uses a durable nonce may still
+                // land, so only expired messages are signed again
+                let resubmitted = match settled {
+                    Some(Settled::Expired) => self.resubmit(kind, &key).await,
+                    _ => Ok(false),
+                };
+
+                match resubmitted {
+                    Ok(true) => Ok(()),
+                    Ok(false) => {
+                        Self::settle_proposal(self.db.get(), &key, ProposalStatus::Failed, None)
+                            .await
+                            .map_err(|k| {
+                                ProcessorError::new(k, kind, ErrorSource::TreasuryFailure)
+                            })?;
+                        self.record_update_status(kind, &key, RevisionStatus::Failed, None)
+                            .await
+                            .map_err(|k| {
+                                ProcessorError::new(k, kind, ErrorSource::TreasuryFailure)
+                            })?;
+
+                        self.event_failed(
+                            kind,
+                            &key,
+                            SolanaTransactionFailureReason::Submit,
+                            FailureDetails::from_error(&e, signature),
+                        )
+                        .await
+                        .map_err(|k| ProcessorError::new(k, kind, ErrorSource::TreasuryFailure))
+                    },
+                    Err(k) => Err(ProcessorError::new(k, kind, ErrorSource::TreasuryFailure)),
+                }
+            },
+        };
+        let elapsed = i64::try_from(start.elapsed().as_millis()).unwrap_or(0);
+
+        self.metrics
+            .rpc_tx_submission_duration_ms_bucket
+            .record(elapsed, &[
+                KeyValue::new("blockchain", "Solana"),
+                KeyValue::new("event", kind.name()),
+            ]);
+        res
+    }
+
+    /// Return the nonce accounts leased to an event whose message landed
+    async fn release_nonces(
+        conn: &impl ConnectionTrait,
+        key: &SolanaNftEventKey,
+    ) -> ProcessResult<()> {
+        NonceAccount::release(conn, [key.id.parse()?]).await?;
+
+        Ok(())
+    }
+
+    /// Return the nonce accounts leased to an event whose message signed
+    /// with `nonce` may still land, holding them back until it is advanced
+    async fn hold_stale_nonces(
+        conn: &impl ConnectionTrait,
+        key: &SolanaNftEventKey,
+        nonce: Hash,
+    ) -> ProcessResult<()> {
+        NonceAccount::release_stale(conn, key.id.parse()?, nonce.to_string()).await?;
+
+        Ok(())
+    }
+
+    /// Store the signature of a compressed mint before it is submitted, so the
+    /// indexer can confirm its asset id should it see the mint land before
+    /// it is reported as submitted
+    async fn record_mint_signature(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        signature: &str,
+    ) -> ProcessResult<()> {
+        if !matches!(kind, EventKind::MintToCollection | EventKind::MintOpenDrop) {
+            return Ok(());
+        }
+
+        CompressionLeaf::record_signature(self.db.get(), key.id.parse()?, signature.to_owned())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record how far the transaction of a mint update got, so a redelivered
+    /// or retried update knows whether it already landed
+    async fn record_update_status(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        status: RevisionStatus,
+        signature: Option<String>,
+    ) -> ProcessResult<()> {
+        if !matches!(
+            kind,
+            EventKind::UpdateCollectionMint | EventKind::RetryUpdateCollectionMint
+        ) {
+            return Ok(());
+        }
+
+        UpdateRevision::set_status(self.db.get(), key.id.parse()?, status, signature).await?;
+
+        Ok(())
+    }
+
+    /// Store the assembled message of an event alongside its signing request
+    async fn record_revision(
+        txn: &DatabaseTransaction,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        tx: &SolanaPendingTransaction,
+        ephemeral_keypairs: Vec<[u8; 64]>,
+    ) -> ProcessResult<()> {
+        TransactionRevision::record(
+            txn,
+            Uuid::parse_str(&key.id)?,
+            kind.name(),
+            tx.serialized_message.clone(),
+            &tx.signatures_or_signers_public_keys,
+            (!ephemeral_keypairs.is_empty()).then(|| ephemeral_keypairs.concat()),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a signing request or a signed result received from the
+    /// treasury in the transaction audit log
+    async fn audit(
+        conn: &impl ConnectionTrait,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        status: &str,
+        serialized_message: Option<&[u8]>,
+        signers: Vec<String>,
+        signature: Option<String>,
+    ) -> ProcessResult<()> {
+        let entry = transaction_audit::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            event_id: Set(key.id.parse()?),
+            event_kind: Set(kind.name().to_owned()),
+            user_id: Set(key.user_id.clone()),
+            project_id: Set(key.project_id.clone()),
+            status: Set(status.to_owned()),
+            message_hash: Set(serialized_message.map(|m| hash(m).to_string())),
+            signers: Set(signers.into()),
+            signature: Set(signature),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        TransactionAudit::create(conn, entry).await?;
+
+        Ok(())
+    }
+
+    /// Wrap an assembled transaction into a proposal to the project's
+    /// multisig treasury, recording the proposal with the writes made while
+    /// assembling it. Transactions of other projects are returned as they
+    /// are.
+    async fn propose(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        pending: PendingTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        let backends = self.backends();
+        let Some(proposals) = backends.proposal() else {
+            return Ok(pending);
+        };
+        let Some(multisig) = self.solana().multisig() else {
+            return Ok(pending);
+        };
+        let PendingTransaction { tx, writes, .. } = pending;
+
+        let reserved =
+            MultisigProposal::highest_requested_index(self.db.get(), &multisig.address.to_string())
+                .await?
+                .and_then(|i| u64::try_from(i).ok());
+
+        let proposal = proposals
+            .propose(tx, reserved)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+        let addresses = &proposal.addresses;
+
+        let am = multisig_proposals::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            event_id: Set(key.id.parse()?),
+            event_kind: Set(kind.name().to_owned()),
+            project_id: Set(key.project_id.clone()),
+            multisig: Set(addresses.multisig.to_string()),
+            transaction_index: Set(addresses
+                .transaction_index
+                .try_into()
+                .context("transaction index out of range")
+                .map_err(ProcessorErrorKind::Solana)?),
+            proposal: Set(addresses.proposal.to_string()),
+            status: Set(ProposalStatus::Requested.as_str().to_owned()),
+            signature: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        info!(
+            "proposing {} as transaction {} of multisig {}",
+            kind.name(),
+            addresses.transaction_index,
+            addresses.multisig
+        );
+
+        let pending = PendingTransaction {
+            writes,
+            ..proposal.into()
+        };
+
+        Ok(pending.with_write(move |txn| {
+            deferred(async move {
+                MultisigProposal::create(txn, am).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Move the proposal requested for an event, if any, to `status`
+    async fn settle_proposal(
+        conn: &impl ConnectionTrait,
+        key: &SolanaNftEventKey,
+        status: ProposalStatus,
+        signature: Option<String>,
+    ) -> ProcessResult<()> {
+        MultisigProposal::settle(conn, key.id.parse()?, status, signature).await?;
+
+        Ok(())
+    }
+
+    /// Begin the database transaction queueing the signing request of a
+    /// transaction with the writes made while assembling it
+    async fn apply_writes(&self, writes: Vec<Write>) -> ProcessResult<DatabaseTransaction> {
+        let txn = self.db.begin().await?;
+
+        PendingTransaction::write(writes, &txn).await?;
+
+        Ok(txn)
+    }
+
+    /// Queue the signing request of a transaction, committing it along with
+    /// the writes already applied in `txn`
+    async fn queue_signing_request(
+        txn: DatabaseTransaction,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        tx: SolanaPendingTransaction,
+        ephemeral_keypairs: Vec<[u8; 64]>,
+    ) -> ProcessResult<()> {
+        Self::record_revision(&txn, kind, key, &tx, ephemeral_keypairs).await?;
+        Self::audit(
+            &txn,
+            kind,
+            key,
+            "signing_requested",
+            Some(tx.serialized_message.as_slice()),
+            tx.signatures_or_signers_public_keys.clone(),
+            None,
+        )
+        .await?;
+
+        let event = SolanaNftEvents::new(kind.into_sign_request(tx));
+
+        Self::enqueue(txn, key, &event).await
+    }
+
+    /// Apply the writes of a transaction which failed simulation, failing
+    /// its proposal if it had one
+    async fn keep_unsimulated(
+        &self,
+        key: &SolanaNftEventKey,
+        writes: Vec<Write>,
+    ) -> ProcessResult<()> {
+        let txn = self.apply_writes(writes).await?;
+
+        Self::settle_proposal(&txn, key, ProposalStatus::Failed, None).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Request signatures again for the stored message of an event whose
+    /// blockhash expired before it landed, with a fresh blockhash, rather
+    /// than failing it back to hub-nfts. Ephemeral keypairs stored with the
+    /// revision sign the new message. Returns `false` if the event has no
+    /// revision, has used up its attempts, or was signed by an ephemeral
+    /// keypair which was not stored.
+    async fn resubmit(&self, kind: EventKind, key: &SolanaNftEventKey) -> ProcessResult<bool> {
+        let conn = self.db.get();
+
+        let Some(revision) = TransactionRevision::find_by_event_id(conn, key.id.parse()?).await?
+        else {
+            return Ok(false);
+        };
+
+        if revision.attempts >= MAX_RESUBMIT_ATTEMPTS {
+            return Ok(false);
+        }
+
+        let serialized_message = self
+            .solana()
+            .refresh_blockhash(&revision.serialized_message)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let Some(signers) = sign_ephemeral(
+            &serialized_message,
+            TransactionRevision::signers(&revision)?,
+            revision.ephemeral_keypairs.as_deref().unwrap_or_default(),
+        ) else {
+            return Ok(false);
+        };
+
+        let txn = self.db.begin().await?;
+        let revision =
+            TransactionRevision::resubmitted(&txn, revision, serialized_message.clone(), &signers)
+                .await?;
+
+        info!(
+            "resubmitting {} {} (attempt {})",
+            kind.name(),
+            key.id,
+            revision.attempts
+        );
+
+        Self::audit(
+            &txn,
+            kind,
+            key,
+            "resubmission_requested",
+            Some(serialized_message.as_slice()),
+            signers.clone(),
+            None,
+        )
+        .await?;
+
+        let event = SolanaNftEvents::new(kind.into_sign_request(SolanaPendingTransaction {
+            serialized_message,
+            signatures_or_signers_public_keys: signers,
+            estimated_cost: None,
+        }));
+
+        Self::enqueue(txn, key, &event).await?;
+
+        self.record_processed(kind, "resubmitted");
+
+        Ok(true)
+    }
+
+    fn record_processed(&self, kind: EventKind, status: &'static str) {
+        self.metrics.events_processed_counter.add(1, &[
+            KeyValue::new("blockchain", "Solana"),
+            KeyValue::new("event", kind.name()),
+            KeyValue::new("status", status),
+        ]);
+    }
+
+    async fn event_submitted(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        submitted: SubmittedTransaction,
+        fee: Option<u64>,
+    ) -> ProcessResult<()> {
+        let res = self
+            .complete_submitted(kind, key, submitted.clone(), None, fee)
+            .await;
+
+        match res {
+            // The nonce is read outside the database transaction, which is
+            // rolled back while the landed transaction is fetched
+            Err(ProcessorErrorKind::NonceRequired) => {
+                let signature = Signature::from_str(&submitted.signature)?;
+                let nonce = self.solana().extract_compression_nonce(&signature).await?;
+
+                self.complete_submitted(kind, key, submitted, Some(nonce), fee)
+                    .await?;
+            },
+            res => res?,
+        }
+
+        self.record_processed(kind, "submitted");
+
+        Ok(())
+    }
+
+    async fn complete_submitted(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        submitted: SubmittedTransaction,
+        nonce: Option<u32>,
+        fee: Option<u64>,
+    ) -> ProcessResult<()> {
+        let txn = self.db.begin().await?;
+
+        if let Some(fee) = fee {
+            Self::record_cost(&txn, kind, key, &submitted.signature, fee).await?;
+        }
+
+        let success = kind.into_success(&txn, nonce, key, submitted).await?;
+        let event = SolanaNftEvents::new(success);
+
+        Outbox::enqueue(&txn, key, &event).await?;
+
+        if matches!(
+            kind,
+            EventKind::MintEditionDrop | EventKind::RetryMintEditionDrop
+        ) {
+            Self::edition_minted(&txn, key).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Count a landed edition mint against the supply of its drop and queue
+    /// the updated supply, followed by `DropSoldOut` once no editions remain
+    async fn edition_minted(
+        conn: &impl ConnectionTrait,
+        key: &SolanaNftEventKey,
+    ) -> ProcessResult<()> {
+        let id = Uuid::parse_str(&key.id)?;
+        let collection_mint = CollectionMint::find_by_id(conn, id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let collection = Collection::increment_editions_minted(conn, collection_mint.collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        let minted = u64::try_from(collection.editions_minted).unwrap_or_default();
+        let max_supply = collection
+            .max_supply
+            .and_then(|max_supply| u64::try_from(max_supply).ok());
+        let supply = SolanaDropSupplyChanged {
+            collection_id: collection.id.to_string(),
+            max_supply,
+            minted,
+            remaining: max_supply.map(|max_supply| max_supply.saturating_sub(minted)),
+        };
+        let key = SolanaNftEventKey {
+            id: collection.id.to_string(),
+            ..key.clone()
+        };
+
+        let sold_out = supply.remaining == Some(0);
+
+        Outbox::enqueue(
+            conn,
+            &key,
+            &SolanaNftEvents::new(SolanaNftEvent::DropSupplyChanged(supply.clone())),
+        )
+        .await?;
+
+        if sold_out {
+            Outbox::enqueue(
+                conn,
+                &key,
+                &SolanaNftEvents::new(SolanaNftEvent::DropSoldOut(supply)),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Queue `event` for the outbox relay and commit it along with the model
+    /// writes already made in `txn`
+    async fn enqueue(
+        txn: DatabaseTransaction,
+        key: &SolanaNftEventKey,
+        event: &SolanaNftEvents,
+    ) -> ProcessResult<()> {
+        Outbox::enqueue(&txn, key, event).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Attach the estimated lamport cost to a signing request. Estimation is
+    /// informational, so failures are logged and the request is sent without
+    /// an estimate.
+    async fn estimate_cost(
+        &self,
+        kind: EventKind,
+        mut tx: SolanaPendingTransaction,
+    ) -> SolanaPendingTransaction {
+        match self.solana().estimate_cost(&tx.serialized_message).await {
+            Ok(cost) => tx.estimated_cost = Some(cost),
+            Err(e) => warn!(
+                "{:?}",
+                e.context(format!("Error estimating cost of {}", kind.name()))
+            ),
+        }
+
+        tx
+    }
+
+    /// The fee charged for the message signed in `res`. Recording it is
+    /// informational, so failures are logged and no cost is recorded.
+    async fn message_fee(&self, kind: EventKind, res: &SolanaTransactionResult) -> Option<u64> {
+        let message = res.serialized_message.as_deref()?;
+
+        match self.solana().message_fee(message).await {
+            Ok(fee) => Some(fee),
+            Err(e) => {
+                warn!(
+                    "{:?}",
+                    e.context(format!("Error fetching the fee of {}", kind.name()))
+                );
+                None
+            },
+        }
+    }
+
+    /// Record the fee charged for a landed transaction
+    async fn record_cost(
+        conn: &impl ConnectionTrait,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        signature: &str,
+        fee: u64,
+    ) -> ProcessResult<()> {
+        TransactionCost::create(conn, transaction_costs::Model {
+            id: Uuid::new_v4(),
+            event_id: key.id.parse()?,
+            event_kind: kind.name().to_string(),
+            signature: signature.to_string(),
+            fee_lamports: i64::try_from(fee).unwrap_or(i64::MAX),
+            created_at: Utc::now().naive_utc(),
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Simulate the assembled transaction if simulation is enabled, returning
+    /// the simulation logs if it would fail on chain. Errors reaching the RPC
+    /// are logged and otherwise ignored so the transaction still proceeds to
+    /// signing.
+    async fn simulate(
+        &self,
+        kind: EventKind,
+        tx: &SolanaPendingTransaction,
+    ) -> Option<Vec<String>> {
+        if !self.solana().simulate_transactions() {
+            return None;
+        }
+
+        match self.solana().simulate_transaction(tx).await {
+            Ok(logs) => logs,
+            Err(e) => {
+                warn!(
+                    "{:?}",
+                    e.context(format!("Error simulating {}", kind.name()))
+                );
+                None
+            },
+        }
+    }
+
+    async fn event_failed(
+        &self,
+        kind: EventKind,
+        key: &SolanaNftEventKey,
+        reason: SolanaTransactionFailureReason,
+        details: FailureDetails,
+    ) -> ProcessResult<()> {
+        let txn = self.db.begin().await?;
+        let FailureDetails {
+            message,
+            logs,
+            signature,
+        } = details;
+
+        let failure = transaction_failures::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            event_id: Set(key.id.parse()?),
+            event_kind: Set(kind.name().to_string()),
+            reason: Set(reason.as_str_name().to_string()),
+            message: Set(message.clone()),
+            logs: Set(logs.clone().into()),
+            signature: Set(signature.clone()),
+            ..Default::default()
+        };
+
+        TransactionFailure::create(&txn, failure).await?;
+        NonceAccount::release(&txn, [key.id.parse()?]).await?;
+
+        if kind == EventKind::RedeemClaim {
+            Claim::fail_redemption(&txn, key.id.parse()?).await?;
+        }
+
+        let event = SolanaNftEvents::new(kind.into_failure(SolanaFailedTransaction {
+            reason: reason as i32,
+            message,
+            logs,
+            signature,
+        }));
+
+        Self::enqueue(txn, key, &event).await?;
+
+        self.record_processed(kind, "failed");
+        self.metrics.event_failures_counter.add(1, &[
+            KeyValue::new("blockchain", "Solana"),
+            KeyValue::new("event", kind.name()),
+            KeyValue::new("reason", reason.as_str_name()),
+        ]);
+
+        Ok(())
+    }
+
+    async fn create_collection(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: MetaplexMasterEditionTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        let tx = self
+            .backends()
+            .collection()
+            .create(payload.clone())
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let MasterEditionAddresses {
+            metadata,
+            associated_token_account,
+            mint,
+            master_edition,
+            update_authority,
+            owner,
+        } = tx.addresses;
+        let id = key.id.parse()?;
+        let max_mints_per_wallet = max_mints_per_wallet(&payload);
+        let (mint_price, payment_mint) = mint_price(&payload)?.unwrap_or_default();
+        let max_supply = payload
+            .master_edition
+            .as_ref()
+            .and_then(|master_edition| master_edition.supply)
+            .and_then(|supply| i64::try_from(supply).ok());
+        let (seller_fee_basis_points, creators) =
+            payload
+                .master_edition
+                .map_or((None, None), |master_edition| {
+                    (
+                        i32::try_from(master_edition.seller_fee_basis_points).ok(),
+                        Some(CollectionCreators(
+                            master_edition
+                                .creators
+                                .into_iter()
+                                .map(Into::into)
+                                .collect(),
+                        )),
+                    )
+                });
+
+        let collection = collections::Model {
+            id,
+            master_edition: master_edition.to_string(),
+            owner: owner.to_string(),
+            metadata: metadata.to_string(),
+            associated_token_account: associated_token_account.to_string(),
+            mint: mint.to_string(),
+            update_authority: update_authority.to_string(),
+            created_at: Utc::now().naive_utc(),
+            cluster: self.solana().cluster(),
+            seller_fee_basis_points,
+            creators,
+            max_mints_per_wallet: max_mints_per_wallet.flatten(),
+            mint_price,
+            payment_mint,
+            soulbound: payload.soulbound,
+            max_supply,
+            project_id: Some(key.project_id.clone()),
+            ..Default::default()
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                Collection::create(txn, collection.into()).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// The payment assembled into the mint `id` of `collection`, if the
+    /// collection has a mint price
+    fn payment_for(
+        &self,
+        id: Uuid,
+        collection: &collections::Model,
+        recipient: &str,
+    ) -> ProcessResult<Option<mint_payments::Model>> {
+        let Some(payment) = self
+            .solana()
+            .payment_leg(collection, recipient.parse()?)
+            .map_err(ProcessorErrorKind::InvalidMintPayment)?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(mint_payments::Model {
+            id,
+            collection_id: collection.id,
+            payer: payment.payer.to_string(),
+            revenue_wallet: payment.revenue_wallet.to_string(),
+            amount: payment
+                .amount
+                .try_into()
+                .map_err(|e| ProcessorErrorKind::InvalidMintPayment(Error::new(e)))?,
+            spl_mint: payment.spl_mint.map(|mint| mint.to_string()),
+            ..Default::default()
+        }))
+    }
+
+    async fn mint_to_collection(
+        &self,
+        key: &SolanaNftEventKey,
+        mut payload: MintMetaplexMetadataTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        payload.recipient_address = self.resolve_recipient(payload.recipient_address).await?;
+
+        let conn = self.db.get();
+        let id = Uuid::parse_str(&key.id.clone())?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        ensure_mint_window(&collection)?;
+
+        if !MintAllowlist::is_allowed(conn, collection.id, &payload.recipient_address).await? {
+            return Err(ProcessorErrorKind::RecipientNotAllowed(
+                payload.recipient_address,
+            ));
+        }
+
+        if let Some(max) = collection.max_mints_per_wallet {
+            let minted =
+                WalletMintCount::find(conn, collection.id, &payload.recipient_address).await?;
+
+            if minted >= max {
+                return Err(ProcessorErrorKind::MintLimitReached {
+                    wallet: payload.recipient_address,
+                    max,
+                });
+            }
+        }
+
+        let payment = self.payment_for(id, &collection, &payload.recipient_address)?;
+
+        let split = RoyaltySplit::find_confirmed(conn, collection.id).await?;
+        let payload = with_collection_defaults(&collection, split.as_ref(), payload);
+        let solana = self
+            .solana_for_mint(conn, &collection, payload.compressed)
+            .await?;
+
+        let tx = Backends::new(&solana)
+            .mint(
+                AssetStandard::new(payload.compressed),
+                &collection,
+                None,
+                payload,
+            )
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let mint = match &tx.addresses {
+            MintAddresses::Compressed(addresses) => Mint::Compressed(compression_leafs::Model {
+                id,
+                collection_id: collection.id,
+                merkle_tree: addresses.merkle_tree.to_string(),
+                tree_authority: addresses.tree_authority.to_string(),
+                tree_delegate: addresses.tree_delegate.to_string(),
+                leaf_owner: addresses.leaf_owner.to_string(),
+                created_at: Utc::now().naive_utc(),
+                ..Default::default()
+            }),
+            MintAddresses::NonFungible(addresses) => Mint::Uncompressed(collection_mints::Model {
+                id,
+                collection_id: collection.id,
+                owner: addresses.recipient.to_string(),
+                mint: addresses.mint.to_string(),
+                created_at: Utc::now().naive_utc(),
+                associated_token_account: addresses.associated_token_account.to_string(),
+                compressed: false,
+                edition: None,
+                metadata_lock_id: None,
+                metadata_locked_at: None,
+                primary_sale_at: None,
+                deleted_at: None,
+            }),
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                if let Some(payment) = payment {
+                    MintPayment::upsert(txn, payment).await?;
+                }
+
+                match mint {
+                    Mint::Compressed(compression_leaf) => {
+                        CompressionLeaf::create(txn, compression_leaf).await?;
+                    },
+                    Mint::Uncompressed(collection_mint) => {
+                        CollectionMint::create(txn, collection_mint).await?;
+                    },
+                }
+
+                Ok(())
+            })
+        }))
+    }
+
+    async fn mint_drop(
+        &self,
+        key: &SolanaNftEventKey,
+        mut payload: MintMetaplexEditionTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        payload.recipient_address = self.resolve_recipient(payload.recipient_address).await?;
+
+        let conn = self.db.get();
+        let id = Uuid::parse_str(&key.id.clone())?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        ensure_mint_window(&collection)?;
+        let edition = reserve_edition(conn, collection.id, id, payload.edition).await?;
+        let payment = self.payment_for(id, &collection, &payload.recipient_address)?;
+
+        let tx = self
+            .backends()
+            .edition()
+            .mint(&collection, None, payload)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let collection_mint = collection_mints::Model {
+            id,
+            collection_id: collection.id,
+            mint: tx.addresses.mint.to_string(),
+            owner: tx.addresses.recipient.to_string(),
+            associated_token_account: tx.addresses.associated_token_account.to_string(),
+            created_at: Utc::now().naive_utc(),
+            compressed: false,
+            edition: Some(edition),
+            metadata_lock_id: None,
+            metadata_locked_at: None,
+            primary_sale_at: None,
+            deleted_at: None,
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                if let Some(payment) = payment {
+                    MintPayment::upsert(txn, payment).await?;
+                }
+
+                CollectionMint::create(txn, collection_mint)
+                    .await
+                    .map_err(edition_taken(edition))?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    async fn update_collection(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: MetaplexMasterEditionTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        let collection_id = Uuid::parse_str(&key.id.clone())?;
+        let mut collection = Collection::find_by_id(self.db.get(), collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        ensure_collection_unlocked(&collection)?;
+
+        let max_mints_per_wallet = max_mints_per_wallet(&payload);
+        let mint_price = mint_price(&payload)?;
+        let changed = max_mints_per_wallet.is_some() || mint_price.is_some();
+
+        if let Some(max) = max_mints_per_wallet {
+            collection.max_mints_per_wallet = max;
+        }
+
+        if let Some((price, payment_mint)) = mint_price {
+            collection.mint_price = price;
+            collection.payment_mint = payment_mint;
+        }
+
+        let tx = self
+            .backends()
+            .collection()
+            .update(&collection, payload)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        if !changed {
+            return Ok(PendingTransaction::new(tx));
+        }
+
+        let mut am = collections::ActiveModel::from(collection.clone());
+        am.max_mints_per_wallet = Set(collection.max_mints_per_wallet);
+        am.mint_price = Set(collection.mint_price);
+        am.payment_mint = Set(collection.payment_mint);
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                Collection::update(txn, am).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Refresh the art of a collection by pointing its NFT at a new metadata
+    /// URI, without resending the rest of the master edition
+    async fn update_collection_assets(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaUpdateCollectionAssetsPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let collection_id = Uuid::parse_str(&key.id)?;
+        let collection = Collection::find_by_id(self.db.get(), collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        ensure_collection_unlocked(&collection)?;
+
+        let tx = self
+            .backends()
+            .collection()
+            .update_assets(&collection, payload.metadata_uri)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx))
+    }
+
+    /// Make the metadata of a mint, or of a whole collection when no mint is
+    /// given, immutable. Mints of a collection are locked in chunks, each
+    /// requesting its own signature under a key derived from the request.
+    async fn lock_metadata(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaLockMetadataPayload,
+    ) -> Result<()> {
+        if !payload.mint_id.is_empty() {
+            return self
+                .process_nft(
+                    EventKind::LockMetadata,
+                    key,
+                    self.lock_mint_metadata(key, payload),
+                )
+                .await;
+        }
+
+        let (collection, chunks) = match self.plan_metadata_locks(key, &payload).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                return self
+                    .process_nft(EventKind::LockMetadata, key, async { Err(e) })
+                    .await;
+            },
+        };
+
+        for (key, mints, lock_collection) in chunks {
+            self.process_nft(
+                EventKind::LockMetadata,
+                &key,
+                self.lock_metadata_chunk(&key, &collection, mints, lock_collection),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn lock_mint_metadata(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaLockMetadataPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let mint = match Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?
+        {
+            Mint::Uncompressed(mint) => mint,
+            Mint::Compressed(_) => return Err(ProcessorErrorKind::CompressedMetadataLock),
+        };
+
+        if mint.metadata_locked_at.is_some() {
+            return Err(ProcessorErrorKind::MetadataLocked(mint.id));
+        }
+
+        let collection = Collection::find_by_id(conn, mint.collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let lock_id = Uuid::parse_str(&key.id)?;
+        let tx = self
+            .backends()
+            .collection()
+            .lock_metadata(&collection, std::slice::from_ref(&mint), false)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                CollectionMint::start_metadata_lock(txn, vec![mint.id], lock_id).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Split the unlocked mints of a collection into the chunks locked by
+    /// each transaction, the first of which also locks the collection NFT
+    async fn plan_metadata_locks(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: &SolanaLockMetadataPayload,
+    ) -> ProcessResult<(collections::Model, Vec<MetadataLockChunk>)> {
+        let conn = self.db.get();
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let lock_collection = collection.metadata_locked_at.is_none();
+        let mut mints = CollectionMint::find_unlocked_by_collection(conn, collection.id).await?;
+
+        if !lock_collection && mints.is_empty() {
+            return Err(ProcessorErrorKind::MetadataLocked(collection.id));
+        }
+
+        let id = Uuid::parse_str(&key.id)?;
+        let mut chunks = Vec::new();
+
+        while (lock_collection && chunks.is_empty()) || !mints.is_empty() {
+            let rest = mints.split_off(METADATA_LOCKS_PER_TRANSACTION.min(mints.len()));
+            let first = chunks.is_empty();
+            let key = SolanaNftEventKey {
+                id: chunk_id(id, chunks.len()).to_string(),
+                ..key.clone()
+            };
+
+            chunks.push((key, mints, lock_collection && first));
+            mints = rest;
+        }
+
+        Ok((collection, chunks))
+    }
+
+    async fn lock_metadata_chunk(
+        &self,
+        key: &SolanaNftEventKey,
+        collection: &collections::Model,
+        mints: Vec<collection_mints::Model>,
+        lock_collection: bool,
+    ) -> ProcessResult<PendingTransaction> {
+        let lock_id = Uuid::parse_str(&key.id)?;
+
+        let tx = self
+            .backends()
+            .collection()
+            .lock_metadata(collection, &mints, lock_collection)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let collection_id = lock_collection.then_some(collection.id);
+        let ids: Vec<_> = mints.into_iter().map(|mint| mint.id).collect();
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                if let Some(collection_id) = collection_id {
+                    Collection::start_metadata_lock(txn, collection_id, lock_id).await?;
+                }
+
+                if !ids.is_empty() {
+                    CollectionMint::start_metadata_lock(txn, ids, lock_id).await?;
+                }
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Set the `primary_sale_happened` flag of a mint, recording its primary
+    /// sale if the indexer has not already
+    async fn mark_primary_sale(
+        &self,
+        payload: SolanaMarkPrimarySalePayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let mint = match Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?
+        {
+            Mint::Uncompressed(mint) => mint,
+            Mint::Compressed(_) => return Err(ProcessorErrorKind::CompressedPrimarySale),
+        };
+
+        let collection = Collection::find_by_id(conn, mint.collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let tx = self
+            .backends()
+            .collection()
+            .mark_primary_sale(&collection, &mint)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                CollectionMint::mark_primary_sale(txn, mint.id).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Create the Hydra fanout receiving the royalties of a collection. Once
+    /// it lands, mints of the collection which do not list their own
+    /// creators name the fanout as their only creator.
+    async fn create_royalty_split(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaCreateRoyaltySplitPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        if RoyaltySplit::find_confirmed(conn, collection_id)
+            .await?
+            .is_some()
+        {
+            return Err(ProcessorErrorKind::RoyaltySplitExists(collection_id));
+        }
+
+        let members = payload
+            .members
+            .into_iter()
+            .map(|m| Ok((m.address.parse()?, m.shares)))
+            .collect::<ProcessResult<Vec<(Pubkey, u64)>>>()?;
+        hydra::check_members(&members)?;
+
+        let members: Vec<_> = members
+            .into_iter()
+            .map(|(address, shares)| RoyaltySplitMember {
+                address: address.to_string(),
+                shares,
+            })
+            .collect();
+        let name = collection_id.simple().to_string();
+
+        let tx = self
+            .backends()
+            .royalty_split()
+            .create_split(name.clone(), &members)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let RoyaltySplitAddresses {
+            payer,
+            fanout,
+            holding_account,
+        } = tx.addresses.clone();
+
+        let split = royalty_splits::ActiveModel {
+            collection_id: Set(collection_id),
+            event_id: Set(event_id),
+            name: Set(name),
+            authority: Set(payer.to_string()),
+            fanout: Set(fanout.to_string()),
+            holding_account: Set(holding_account.to_string()),
+            members: Set(RoyaltySplitMembers(members)),
+            created_at: Set(Utc::now().naive_utc()),
+            confirmed_at: Set(None),
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                RoyaltySplit::upsert(txn, split).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Pay out the royalties held by the fanout of a collection to its
+    /// members
+    async fn distribute_royalties(
+        &self,
+        payload: SolanaDistributeRoyaltiesPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let split = RoyaltySplit::find_confirmed(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::NoRoyaltySplit(collection_id))?;
+
+        let tx = self
+            .backends()
+            .royalty_split()
+            .distribute(&split)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx))
+    }
+
+    async fn update_collection_mint(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: UpdateSolanaMintPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        let mint_id = payload.mint_id.parse()?;
+        let event_id = key.id.parse()?;
+
+        // A redelivered update must not land twice
+        if let Some(revision) = UpdateRevision::find_by_id(conn, event_id).await? {
+            match RevisionStatus::of(&revision) {
+                RevisionStatus::Submitted => {
+                    return Err(ProcessorErrorKind::UpdateSubmitted(event_id));
+                },
+                RevisionStatus::Confirmed => {
+                    return Err(ProcessorErrorKind::UpdateConfirmed(event_id));
+                },
+                RevisionStatus::Pending | RevisionStatus::Failed => (),
+            }
+        }
+
+        let mint = Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        if let Mint::Uncompressed(collection_mint) = &mint {
+            if collection_mint.metadata_locked_at.is_some() {
+                return Err(ProcessorErrorKind::MetadataLocked(mint_id));
+            }
+        }
+
+        let tx = self
+            .backends()
+            .update_mint(&collection, &mint, payload.clone())
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let revision = Self::update_revision(event_id, mint_id, &tx);
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                UpdateRevision::upsert(txn, revision, &payload).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// The revision storing the message of an update so it can be retried.
+    /// Compressed mints keep their merkle tree in place of a metadata
+    /// account.
+    fn update_revision(
+        event_id: Uuid,
+        mint_id: Uuid,
+        tx: &TransactionResponse<UpdateMintAddresses>,
+    ) -> update_revisions::ActiveModel {
+        let (payer, metadata, update_authority) = match &tx.addresses {
+            UpdateMintAddresses::NonFungible(UpdateCollectionMintAddresses {
+                payer,
+                metadata,
+                update_authority,
+            }) => (payer, metadata, update_authority),
+            UpdateMintAddresses::Compressed(UpdateCompressedMintAddresses {
+                payer,
+                merkle_tree,
+                update_authority,
+                ..
+            }) => (payer, merkle_tree, update_authority),
+        };
+
+        update_revisions::ActiveModel {
+            id: Set(event_id),
+            mint_id: Set(mint_id),
+            serialized_message: Set(tx.serialized_message.clone()),
+            payer: Set(payer.to_string()),
+            metadata: Set(metadata.to_string()),
+            update_authority: Set(update_authority.to_string()),
+            status: Set(RevisionStatus::Pending.as_str().to_owned()),
+            signature: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+            payload: Set(None),
+        }
+    }
+
+    async fn retry_update_collection_mint(
+        &self,
+        key: &SolanaNftEventKey,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let event_id = key.id.parse()?;
+        let revision = UpdateRevision::find_by_id(conn, event_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        if RevisionStatus::of(&revision) == RevisionStatus::Confirmed {
+            return Err(ProcessorErrorKind::UpdateConfirmed(event_id));
+        }
+
+        let mint = Mint::find_by_id(conn, revision.mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let collection = Collection::find_by_id(conn, mint.collection_id())
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let backends = self.backends();
+        let res = match &mint {
+            Mint::Uncompressed(collection_mint) => {
+                if collection_mint.metadata_locked_at.is_some() {
+                    return Err(ProcessorErrorKind::MetadataLocked(collection_mint.id));
+                }
+
+                backends
+                    .collection()
+                    .retry_update_mint(&collection, collection_mint, &revision)
+                    .await
+            },
+            Mint::Compressed(_) => Err(StaleRevision::Proof.into()),
+        };
+
+        let (tx, reassembled): (SolanaPendingTransaction, _) = match res {
+            Ok(tx) => (tx.into(), None),
+            // The stored message would be signed for accounts which no
+            // longer apply, so it is assembled again from its payload
+            Err(e) if e.is::<StaleRevision>() => {
+                let payload = UpdateRevision::payload(&revision)?.ok_or_else(|| {
+                    ProcessorErrorKind::Solana(e.context("revision has no payload"))
+                })?;
+                info!("assembling update {event_id} again from its payload");
+
+                let tx = backends
+                    .update_mint(&collection, &mint, payload.clone())
+                    .await
+                    .map_err(ProcessorErrorKind::Solana)?;
+
+                let reassembled = (
+                    Self::update_revision(event_id, revision.mint_id, &tx),
+                    payload,
+                );
+
+                (tx.into(), Some(reassembled))
+            },
+            Err(e) => return Err(ProcessorErrorKind::Solana(e)),
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                UpdateRevision::set_status(txn, event_id, RevisionStatus::Pending, None).await?;
+
+                if let Some((revision, payload)) = reassembled {
+                    UpdateRevision::upsert(txn, revision, &payload).await?;
+                }
+
+                Ok(())
+            })
+        }))
+    }
+
+    async fn transfer_asset(
+        &self,
+        _key: &SolanaNftEventKey,
+        payload: TransferMetaplexAssetTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let collection_mint_id = Uuid::parse_str(&payload.collection_mint_id.clone())?;
+        let mint = Mint::find_by_id(conn, collection_mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        ensure_transferable(conn, &mint).await?;
+
+        let tx = self
+            .backends()
+            .transfer(&mint, payload)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx))
+    }
+
+    /// Transfer a mint from its owner into the associated token account of
+    /// the project treasury, which holds it until it is released
+    async fn escrow_asset(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaEscrowAssetPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let mint = Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        ensure_transferable(conn, &mint).await?;
+
+        let depositor: Pubkey = payload.owner_address.parse()?;
+        let escrow_wallet = self.solana().treasury_wallet();
+
+        let tx = self
+            .backends()
+            .transfer(&mint, TransferMetaplexAssetTransaction {
+                owner_address: depositor.to_string(),
+                recipient_address: escrow_wallet.to_string(),
+                collection_mint_id: mint_id.to_string(),
+            })
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let escrow = asset_escrows::ActiveModel {
+            mint_id: Set(mint_id),
+            depositor: Set(depositor.to_string()),
+            escrow_wallet: Set(escrow_wallet.to_string()),
+            escrow_event_id: Set(event_id),
+            release_event_id: Set(None),
+            release_recipient: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+            escrowed_at: Set(None),
+            released_at: Set(None),
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                AssetEscrow::request_escrow(txn, escrow).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Transfer an escrowed mint back to its depositor, or to another
+    /// recipient if the request names one
+    async fn release_asset(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaReleaseAssetPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let mint = Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let escrow = AssetEscrow::find_held(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::AssetNotEscrowed(mint_id))?;
+
+        let recipient: Pubkey = if payload.recipient_address.is_empty() {
+            escrow.depositor.parse()?
+        } else {
+            payload.recipient_address.parse()?
+        };
+
+        let tx = self
+            .backends()
+            .transfer(&mint, TransferMetaplexAssetTransaction {
+                owner_address: escrow.escrow_wallet,
+                recipient_address: recipient.to_string(),
+                collection_mint_id: mint_id.to_string(),
+            })
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                AssetEscrow::request_release(txn, mint_id, event_id, recipient.to_string()).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// List a mint on the auction house of its project, at a price in base
+    /// units of the auction house's treasury mint
+    async fn list_asset(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaListAssetPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        if self.solana().auction_house().is_none() {
+            return Err(ProcessorErrorKind::NoAuctionHouse(key.project_id.clone()));
+        }
+
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let mint = Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        ensure_transferable(conn, &mint).await?;
+
+        let Mint::Uncompressed(collection_mint) = mint else {
+            return Err(ProcessorErrorKind::CompressedListing);
+        };
+        let seller: Pubkey = payload.owner_address.parse()?;
+        // A zero price is reserved for the auction house's free trade state
+        let price = i64::try_from(payload.price)
+            .ok()
+            .filter(|p| *p > 0)
+            .ok_or(ProcessorErrorKind::InvalidListingPrice(payload.price))?;
+
+        let tx = self
+            .backends()
+            .listing()
+            .list(&collection_mint, seller, payload.price)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let listing = listings::ActiveModel {
+            id: Set(event_id),
+            mint_id: Set(mint_id),
+            auction_house: Set(tx.addresses.auction_house.to_string()),
+            seller: Set(seller.to_string()),
+            token_account: Set(tx.addresses.token_account.to_string()),
+            price: Set(price),
+            trade_state: Set(tx.addresses.trade_state.to_string()),
+            status: Set(ListingStatus::Requested.as_str().to_owned()),
+            cancel_event_id: Set(None),
+            buyer: Set(None),
+            sale_signature: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                Listing::request(txn, listing).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Cancel the live listing of a mint, returning it to its seller's
+    /// control
+    async fn cancel_listing(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaCancelListingPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        if self.solana().auction_house().is_none() {
+            return Err(ProcessorErrorKind::NoAuctionHouse(key.project_id.clone()));
+        }
+
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let mint_id = Uuid::parse_str(&payload.mint_id)?;
+        let Mint::Uncompressed(collection_mint) = Mint::find_by_id(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?
+        else {
+            return Err(ProcessorErrorKind::CompressedListing);
+        };
+        let listing = Listing::find_listed(conn, mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::NoActiveListing(mint_id))?;
+
+        let tx = self
+            .backends()
+            .listing()
+            .cancel(&collection_mint, &listing)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                Listing::request_cancel(txn, listing.id, event_id).await?;
+
+                Ok(())
+            })
+        }))
+    }
+
+    /// Create a candy machine selling the items of a manifest into a
+    /// collection, guarded by the collection's mint window, price and per
+    /// wallet limit. Its config lines are inserted by later requests.
+    async fn deploy_candy_machine(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: SolanaDeployCandyMachinePayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let conn = self.db.get();
+        let event_id = Uuid::parse_str(&key.id)?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(conn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+        if collection.soulbound {
+            return Err(ProcessorErrorKind::SoulboundCandyMachine(collection_id));
+        }
+
+        if CandyMachine::find_deployed(conn, collection_id)
+            .await?
+            .is_some()
+        {
+            return Err(ProcessorErrorKind::CandyMachineExists(collection_id));
+        }
+
+        let items: Vec<ConfigLine> = payload
+            .items
+            .into_iter()
+            .map(|i| ConfigLine {
+                name: i.name,
+                uri: i.uri,
+            })
+            .collect();
+        let settings = ConfigLineSettings::from_manifest(&items, payload.is_sequential)?;
+        let items_available =
+            u32::try_from(items.len()).map_err(|_| CandyMachineError::TooManyItems(items.len()))?;
+        let split = RoyaltySplit::find_confirmed(conn, collection_id).await?;
+
+        let data = CandyMachineData {
+            items_available: items_available.into(),
+            symbol: payload.symbol,
+            seller_fee_basis_points: collection
+                .seller_fee_basis_points
+                .and_then(|bps| u16::try_from(bps).ok())
+                .unwrap_or_default(),
+            max_supply: 0,
+            is_mutable: collection.metadata_locked_at.is_none(),
+            creators: candy_machine_creators(&collection, split.as_ref())?,
+            config_line_settings: Some(settings),
+            hidden_settings: None,
+        };
+        data.check()?;
+        let guards = candy_guards(self.solana(), &collection)?;
+
+        let tx = self
+            .backends()
+            .candy_machine()
+            .deploy(&collection, data, &guards)
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
+
+        let candy_machine = candy_machines::ActiveModel {
+            id: Set(event_id),
+            collection_id: Set(collection_id),
+            address: Set(tx.addresses.candy_machine.to_string()),
+            candy_guard: Set(tx.addresses.candy_guard.to_string()),
+            authority: Set(tx.addresses.authority.to_string()),
+            items_available: Set(items_available.into()),
+            items: Set(CandyMachineItems(
+                items
+                    .into_iter()
+                    .map(|ConfigLine { name, uri }| CandyMachineItem { name, uri })
+                    .collect(),
+            )),
+            is_sequential: Set(payload.is_sequential),
+            items_loaded: Set(0),
+            created_at: Set(Utc::now().naive_utc()),
+            deployed_at: Set(None),
+        };
 
-        self.metrics
-            .rpc_tx_submission_duration_ms_bucket
-            .record(elapsed, &[KeyValue::new("blockchain", "Solana")]);
-        res
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                CandyMachine::request_deploy(txn, candy_machine).await?;
+
+                Ok(())
+            })
+        }))
     }
 
-    async fn event_submitted(
+    /// Make sure the trees reserved for a collection have room for the mints
+    /// expected of an upcoming drop, creating a tree for the shortfall.
+    /// Collections with enough room left are answered right away.
+    async fn reserve_tree_capacity(
         &self,
-        kind: EventKind,
         key: &SolanaNftEventKey,
-        sig: String,
-    ) -> ProcessResult<()> {
-        let conn = self.db.get();
-        self.producer
-            .send(
-                Some(&SolanaNftEvents {
-                    event: Some(kind.into_success(conn, self.solana(), key, sig).await?),
-                }),
-                Some(key),
-            )
+        payload: SolanaReserveTreeCapacityPayload,
+    ) -> Result<()> {
+        let kind = EventKind::ReserveTreeCapacity;
+        let collection_id = Uuid::parse_str(&payload.collection_id)
+            .map_err(|e| ProcessorError::new(e.into(), kind, ErrorSource::NftRequest))?;
+        let shortfall = self
+            .reserved_capacity(key, collection_id, payload.expected_mints)
             .await
-            .map_err(Into::into)
+            .map_err(|e| ProcessorError::new(e, kind, ErrorSource::NftRequest))?;
+
+        let Some(shortfall) = shortfall else {
+            return Ok(());
+        };
+
+        self.process_nft(
+            kind,
+            key,
+            self.create_reserved_tree(key, collection_id, shortfall),
+        )
+        .await
     }
 
-    async fn event_failed(
+    /// The leaves missing from the trees reserved for a collection to fit
+    /// `expected_mints`, or none after reporting that the trees already fit
+    /// them
+    async fn reserved_capacity(
         &self,
-        kind: EventKind,
         key: &SolanaNftEventKey,
-        reason: SolanaTransactionFailureReason,
-    ) -> ProcessResult<()> {
-        self.producer
-            .send(
-                Some(&SolanaNftEvents {
-                    event: Some(kind.into_failure(SolanaFailedTransaction {
-                        reason: reason as i32,
-                    })),
-                }),
-                Some(key),
-            )
-            .await
-            .map_err(Into::into)
+        collection_id: Uuid,
+        expected_mints: u64,
+    ) -> ProcessResult<Option<u64>> {
+        let txn = self.db.begin().await?;
+        let collection = Collection::find_by_id(&txn, collection_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+
+        let trees = MerkleTree::find_reserved(&txn, collection_id).await?;
+        let available = trees
+            .iter()
+            .filter_map(MerkleTree::remaining)
+            .filter_map(|leaves| u64::try_from(leaves).ok())
+            .sum::<u64>();
+
+        if available < expected_mints {
+            return Ok(Some(expected_mints - available));
+        }
+
+        let event = SolanaNftEvents::new(SolanaNftEvent::TreeCapacityReserved(
+            SolanaTreeCapacityReservation {
+                collection_id: collection_id.to_string(),
+                expected_mints,
+                available,
+                merkle_trees: trees.into_iter().map(|tree| tree.address).collect(),
+            },
+        ));
+
+        Self::enqueue(txn, key, &event).await?;
+
+        Ok(None)
     }
 
-    async fn create_collection<B: CollectionBackend>(
+    /// Create a tree holding at least `leaves` leaves and pin it to a
+    /// collection once it lands
+    async fn create_reserved_tree(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
-        payload: MetaplexMasterEditionTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let conn = self.db.get();
-        let tx = backend
-            .create(payload.clone())
+        collection_id: Uuid,
+        leaves: u64,
+    ) -> ProcessResult<PendingTransaction> {
+        let event_id = Uuid::parse_str(&key.id)?;
+        let shape = TreeShape::for_capacity(leaves)?;
+        let dimension = |value: u32| Set(Some(i32::try_from(value).unwrap_or(i32::MAX)));
+
+        let tx = self
+            .backends()
+            .tree()
+            .create_tree(shape)
             .await
             .map_err(ProcessorErrorKind::Solana)?;
 
-        let MasterEditionAddresses {
-            metadata,
-            associated_token_account,
-            mint,
-            master_edition,
-            update_authority,
-            owner,
-        } = tx.addresses;
-        let id = key.id.parse()?;
-
-        let collection = collections::Model {
-            id,
-            master_edition: master_edition.to_string(),
-            owner: owner.to_string(),
-            metadata: metadata.to_string(),
-            associated_token_account: associated_token_account.to_string(),
-            mint: mint.to_string(),
-            update_authority: update_authority.to_string(),
-            created_at: Utc::now().naive_utc(),
+        let tree = merkle_trees::ActiveModel {
+            address: Set(tx.addresses.merkle_tree.to_string()),
+            leaf_count: Set(0),
+            updated_at: Set(Utc::now().naive_utc()),
+            max_depth: dimension(shape.max_depth),
+            max_buffer_size: dimension(shape.max_buffer_size),
+            canopy_depth: dimension(shape.canopy_depth),
+            last_leaf_slot: Set(None),
+            collection_id: Set(Some(collection_id)),
+            reserve_event_id: Set(Some(event_id)),
+            reserved_at: Set(None),
         };
 
-        Collection::create(conn, collection.into()).await?;
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                MerkleTree::request_reservation(txn, event_id, tree).await?;
 
-        Ok(tx.into())
+                Ok(())
+            })
+        }))
     }
 
-    async fn mint_to_collection(
+    /// Insert the next batch of config lines into a deployed candy machine,
+    /// continuing from the lines already loaded
+    async fn insert_candy_machine_items(
         &self,
         key: &SolanaNftEventKey,
-        payload: MintMetaplexMetadataTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let start = Instant::now();
-        let conn = self.db.get();
-        let id = Uuid::parse_str(&key.id.clone())?;
-        let collection_id = Uuid::parse_str(&payload.collection_id)?;
-        let collection = Collection::find_by_id(conn, collection_id)
+        payload: SolanaInsertCandyMachineItemsPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let event_id = Uuid::parse_str(&key.id)?;
+        let candy_machine_id = Uuid::parse_str(&payload.candy_machine_id)?;
+        let candy_machine = CandyMachine::find_by_id(self.db.get(), candy_machine_id)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-        if payload.compressed {
-            let backend = &CompressedRef(self.solana());
-
-            let tx = backend
-                .mint(&collection, None, payload)
-                .await
-                .map_err(ProcessorErrorKind::Solana)?;
-
-            let compression_leaf = compression_leafs::Model {
-                id,
-                collection_id: collection.id,
-                merkle_tree: tx.addresses.merkle_tree.to_string(),
-                tree_authority: tx.addresses.tree_authority.to_string(),
-                tree_delegate: tx.addresses.tree_delegate.to_string(),
-                leaf_owner: tx.addresses.leaf_owner.to_string(),
-                created_at: Utc::now().naive_utc(),
-                ..Default::default()
-            };
-
-            CompressionLeaf::create(conn, compression_leaf).await?;
-            let elapsed = i64::try_from(start.elapsed().as_millis()).unwrap_or(0);
-
-            self.metrics
-                .rpc_tx_assembly_duration_ms_bucket
-                .record(elapsed, &[
-                    KeyValue::new("blockchain", "Solana"),
-                    KeyValue::new("compressed", "true"),
-                ]);
+        if candy_machine.deployed_at.is_none() {
+            return Err(ProcessorErrorKind::CandyMachineNotDeployed(
+                candy_machine_id,
+            ));
+        }
 
-            return Ok(tx.into());
+        let items: Vec<ConfigLine> = candy_machine
+            .items
+            .0
+            .iter()
+            .map(|i| ConfigLine {
+                name: i.name.clone(),
+                uri: i.uri.clone(),
+            })
+            .collect();
+        let settings = ConfigLineSettings::from_manifest(&items, candy_machine.is_sequential)?;
+        let start = u32::try_from(candy_machine.items_loaded).unwrap_or(u32::MAX);
+        let mut lines: Vec<ConfigLine> = items
+            .iter()
+            .skip(start as usize)
+            .map(|line| settings.strip(line))
+            .collect();
+
+        if lines.is_empty() {
+            return Err(ProcessorErrorKind::CandyMachineLoaded(candy_machine_id));
         }
 
-        let backend = &UncompressedRef(self.solana());
+        lines.truncate(settings.batch_len(&lines));
+        let end = start.saturating_add(u32::try_from(lines.len()).unwrap_or(u32::MAX));
 
-        let tx = backend
-            .mint(&collection, None, payload)
+        let tx = self
+            .backends()
+            .candy_machine()
+            .insert_items(&candy_machine, start, lines)
             .await
             .map_err(ProcessorErrorKind::Solana)?;
 
-        let collection_mint = collection_mints::Model {
-            id,
-            collection_id: collection.id,
-            owner: tx.addresses.recipient.to_string(),
-            mint: tx.addresses.mint.to_string(),
-            created_at: Utc::now().naive_utc(),
-            associated_token_account: tx.addresses.associated_token_account.to_string(),
-        };
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                CandyMachine::request_batch(
+                    txn,
+                    event_id,
+                    candy_machine_id,
+                    start.into(),
+                    end.into(),
+                )
+                .await?;
 
-        CollectionMint::create(conn, collection_mint).await?;
-        let elapsed = i64::try_from(start.elapsed().as_millis()).unwrap_or(0);
+                Ok(())
+            })
+        }))
+    }
 
-        self.metrics
-            .rpc_tx_assembly_duration_ms_bucket
-            .record(elapsed, &[
-                KeyValue::new("blockchain", "Solana"),
-                KeyValue::new("compressed", "false"),
-            ]);
+    /// Transfer assets in as many transactions as they take, each keyed by
+    /// the id of its chunk of the request
+    async fn transfer_assets(
+        &self,
+        key: &SolanaNftEventKey,
+        payload: TransferMetaplexAssetsTransaction,
+    ) -> Result<()> {
+        let chunks = match self.assemble_transfers(key, payload).await {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                return self
+                    .process_nft(EventKind::TransferAssets, key, async { Err(e) })
+                    .await;
+            },
+        };
+
+        for (key, tx) in chunks {
+            self.process_nft(EventKind::TransferAssets, &key, async {
+                Ok(PendingTransaction::new(tx))
+            })
+            .await?;
+        }
 
-        Ok(tx.into())
+        Ok(())
     }
 
-    async fn mint_drop<B: MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses>>(
+    async fn assemble_transfers(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
-        payload: MintMetaplexEditionTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
+        payload: TransferMetaplexAssetsTransaction,
+    ) -> ProcessResult<Vec<TransferChunk>> {
         let conn = self.db.get();
-        let id = Uuid::parse_str(&key.id.clone())?;
-        let collection_id = Uuid::parse_str(&payload.collection_id)?;
-        let collection = Collection::find_by_id(conn, collection_id)
-            .await?
-            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let id = Uuid::parse_str(&key.id)?;
+        let mut mints = Vec::new();
+        let mut leafs = Vec::new();
 
-        let tx = backend
-            .mint(&collection, None, payload)
+        for id in &payload.collection_mint_ids {
+            let id = Uuid::parse_str(id)?;
+            let mint = Mint::find_by_id(conn, id)
+                .await?
+                .ok_or(ProcessorErrorKind::RecordNotFound)?;
+            ensure_transferable(conn, &mint).await?;
+
+            match mint {
+                Mint::Uncompressed(collection_mint) => mints.push(collection_mint),
+                Mint::Compressed(compression_leaf) => leafs.push(compression_leaf),
+            }
+        }
+
+        let txs = self
+            .backends()
+            .batch_transfer()
+            .transfer_many(&mints, &leafs, payload)
             .await
             .map_err(ProcessorErrorKind::Solana)?;
 
-        let collection_mint = collection_mints::Model {
-            id,
-            collection_id: collection.id,
-            mint: tx.addresses.mint.to_string(),
-            owner: tx.addresses.recipient.to_string(),
-            associated_token_account: tx.addresses.associated_token_account.to_string(),
-            created_at: Utc::now().naive_utc(),
-        };
-
-        CollectionMint::create(conn, collection_mint).await?;
+        Ok(txs
+            .into_iter()
+            .enumerate()
+            .map(|(chunk, tx)| {
+                let key = SolanaNftEventKey {
+                    id: chunk_id(id, chunk).to_string(),
+                    ..key.clone()
+                };
 
-        Ok(tx.into())
+                (key, tx)
+            })
+            .collect())
     }
 
-    async fn update_collection<B: CollectionBackend>(
+    /// Reserve a mint for a wallet, either an existing mint of the collection
+    /// or one minted from the request stored with the claim when it is
+    /// redeemed
+    async fn create_claim(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
-        payload: MetaplexMasterEditionTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let conn = self.db.get();
-        let collection_id = Uuid::parse_str(&key.id.clone())?;
-        let collection = Collection::find_by_id(conn, collection_id)
+        payload: SolanaCreateClaimPayload,
+    ) -> ProcessResult<()> {
+        let txn = self.db.begin().await?;
+        let id = Uuid::parse_str(&key.id)?;
+        let collection_id = Uuid::parse_str(&payload.collection_id)?;
+        let collection = Collection::find_by_id(&txn, collection_id)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
+        let claimant: Pubkey = payload.claimant_address.parse()?;
+
+        let split = RoyaltySplit::find_confirmed(&txn, collection_id).await?;
+
+        let (collection_mint_id, mint_request) =
+            match (payload.collection_mint_id.as_str(), payload.mint) {
+                ("", Some(mint)) => (
+                    None,
+                    Some(with_collection_defaults(&collection, split.as_ref(), mint)),
+                ),
+                (collection_mint_id, None) if !collection_mint_id.is_empty() => {
+                    let collection_mint_id = Uuid::parse_str(collection_mint_id)?;
+                    let mint = Mint::find_by_id(&txn, collection_mint_id)
+                        .await?
+                        .ok_or(ProcessorErrorKind::RecordNotFound)?;
+
+                    if mint.collection_id() != collection.id {
+                        return Err(ProcessorErrorKind::InvalidClaim);
+                    }
+
+                    if collection.soulbound {
+                        return Err(ProcessorErrorKind::SoulboundTransfer(collection_mint_id));
+                    }
+
+                    if !Claim::find_unredeemed_by_collection_mint(&txn, collection_mint_id)
+                        .await?
+                        .is_empty()
+                    {
+                        return Err(ProcessorErrorKind::MintAlreadyClaimed);
+                    }
+
+                    (Some(collection_mint_id), None)
+                },
+                _ => return Err(ProcessorErrorKind::InvalidClaim),
+            };
 
-        let tx = backend
-            .update(&collection, payload)
-            .await
-            .map_err(ProcessorErrorKind::Solana)?;
+        let expires_at = payload
+            .expires_at
+            .map(|secs| {
+                NaiveDateTime::from_timestamp_opt(secs, 0).ok_or(ProcessorErrorKind::InvalidClaim)
+            })
+            .transpose()?;
+
+        // Claims created concurrently for the same mint both pass the check
+        // above, leaving the unique index to turn one of them away
+        Claim::create(
+            &txn,
+            claims::Model {
+                id,
+                collection_id,
+                collection_mint_id,
+                claimant: claimant.to_string(),
+                expires_at,
+                project_id: Uuid::parse_str(&key.project_id).ok(),
+                ..Default::default()
+            },
+            mint_request.as_ref(),
+        )
+        .await
+        .map_err(|e| {
+            if db::is_unique_violation(&e) {
+                ProcessorErrorKind::MintAlreadyClaimed
+            } else {
+                e.into()
+            }
+        })?;
 
-        Ok(tx.into())
+        let event = SolanaNftEvents::new(SolanaNftEvent::ClaimCreated(SolanaClaim {
+            claim_id: Claim::encode_id(id),
+            claimant_address: claimant.to_string(),
+        }));
+
+        Self::enqueue(txn, key, &event).await
     }
 
-    async fn update_collection_mint<B: CollectionBackend>(
+    /// Assemble the mint or transfer of a claimed asset to its claimant
+    async fn redeem_claim(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
-        payload: UpdateSolanaMintPayload,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let collection_id = Uuid::parse_str(&payload.collection_id)?;
-        let collection = Collection::find_by_id(self.db.get(), collection_id)
-            .await?
-            .ok_or(ProcessorErrorKind::RecordNotFound)?;
-        let mint = CollectionMint::find_by_id(self.db.get(), payload.mint_id.parse()?)
+        payload: SolanaRedeemClaimPayload,
+    ) -> ProcessResult<PendingTransaction> {
+        let redemption_id = Uuid::parse_str(&key.id)?;
+        let claim_id =
+            Claim::decode_id(&payload.claim_id).ok_or(ProcessorErrorKind::InvalidClaimId)?;
+        let claim = Claim::find_by_id(self.db.get(), claim_id)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-        let tx = backend
-            .update_mint(&collection, &mint, payload)
-            .await
-            .map_err(ProcessorErrorKind::Solana)?;
+        ensure_redeemable(&claim, redemption_id)?;
 
-        let UpdateCollectionMintAddresses {
-            payer,
-            metadata,
-            update_authority,
-        } = tx.addresses.clone();
-        let msg_bytes = tx.serialized_message.clone();
+        if claim
+            .expires_at
+            .map_or(false, |expires_at| expires_at <= Utc::now().naive_utc())
+        {
+            return Err(ProcessorErrorKind::ClaimExpired);
+        }
 
-        let revision = update_revisions::ActiveModel {
-            id: Set(key.id.parse()?),
-            mint_id: Set(mint.id),
-            serialized_message: Set(msg_bytes),
-            payer: Set(payer.to_string()),
-            metadata: Set(metadata.to_string()),
-            update_authority: Set(update_authority.to_string()),
+        let pending = if let Some(collection_mint_id) = claim.collection_mint_id {
+            let conn = self.db.get();
+            let mint = Mint::find_by_id(conn, collection_mint_id)
+                .await?
+                .ok_or(ProcessorErrorKind::RecordNotFound)?;
+            ensure_transferable(conn, &mint).await?;
+
+            let tx = self
+                .backends()
+                .transfer(&mint, TransferMetaplexAssetTransaction {
+                    owner_address: mint.owner().to_owned(),
+                    recipient_address: claim.claimant.clone(),
+                    collection_mint_id: collection_mint_id.to_string(),
+                })
+                .await
+                .map_err(ProcessorErrorKind::Solana)?;
+
+            PendingTransaction::new(tx)
+        } else {
+            let mint = Claim::mint_request(&claim)?.ok_or(ProcessorErrorKind::InvalidClaim)?;
+
+            self.mint_to_collection(key, MintMetaplexMetadataTransaction {
+                recipient_address: claim.claimant.clone(),
+                collection_id: claim.collection_id.to_string(),
+                ..mint
+            })
+            .await?
         };
 
-        revision.insert(self.db.get()).await?;
+        // Another redemption may have started while this one was assembled
+        Ok(pending.with_write(move |txn| {
+            deferred(async move {
+                let claim = Claim::find_by_id_for_update(txn, claim.id)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
+                ensure_redeemable(&claim, redemption_id)?;
+
+                Claim::start_redemption(txn, claim, redemption_id).await?;
 
-        Ok(tx.into())
+                Ok(())
+            })
+        }))
     }
 
-    async fn retry_update_collection_mint<B: CollectionBackend>(
+    /// The Solana Pay transaction redeeming a claim, transferring the mint it
+    /// reserves to `account`, which pays its fees. A transaction the treasury
+    /// signed within [`CLAIM_TRANSACTION_TTL_SECS`] is returned as is.
+    /// Otherwise one is assembled and sent to be signed, and `None` is
+    /// returned until the treasury has signed it.
+    ///
+    /// # Errors
+    /// This function fails if `account` cannot redeem the claim through
+    /// Solana Pay or if the transaction cannot be assembled
+    pub async fn claim_transaction(
         &self,
-        backend: &B,
-        key: &SolanaNftEventKey,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let revision = update_revisions::Entity::find_by_id(Uuid::from_str(&key.id)?)
-            .one(self.db.get())
+        claim_id: &str,
+        account: &str,
+    ) -> ProcessResult<Option<Vec<u8>>> {
+        let id = Claim::decode_id(claim_id).ok_or(ProcessorErrorKind::InvalidClaimId)?;
+        let claim = Claim::find_by_id(self.db.get(), id)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let account: Pubkey = account.parse()?;
+        let now = Utc::now().naive_utc();
 
-        let tx = backend
-            .retry_update_mint(&revision)
-            .await
-            .map_err(ProcessorErrorKind::Solana)?;
-
-        Ok(tx.into())
-    }
+        // The transfer would race a redemption started through an event
+        ensure_redeemable(&claim, id)?;
 
-    async fn transfer_asset(
-        &self,
-        _key: &SolanaNftEventKey,
-        payload: TransferMetaplexAssetTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let conn = self.db.get();
-        let collection_mint_id = Uuid::parse_str(&payload.collection_mint_id.clone())?;
-        let collection_mint = CollectionMint::find_by_id(conn, collection_mint_id).await?;
+        if claim
+            .expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+        {
+            return Err(ProcessorErrorKind::ClaimExpired);
+        }
 
-        if let Some(collection_mint) = collection_mint {
-            let backend = &UncompressedRef(self.solana());
+        if claim.claimant != account.to_string() {
+            return Err(ProcessorErrorKind::ClaimantMismatch);
+        }
 
-            let tx = backend
-                .transfer(&collection_mint, payload)
-                .await
-                .map_err(ProcessorErrorKind::Solana)?;
+        let fresh = |at: NaiveDateTime| (now - at).num_seconds() < CLAIM_TRANSACTION_TTL_SECS;
 
-            return Ok(tx.into());
+        match (&claim.pay_transaction, claim.pay_signed_at) {
+            (Some(tx), Some(signed_at)) if fresh(signed_at) => return Ok(Some(tx.clone())),
+            (None, _) if claim.pay_requested_at.map_or(false, fresh) => return Ok(None),
+            _ => (),
         }
 
-        let compression_leaf = CompressionLeaf::find_by_id(conn, collection_mint_id)
+        // Assembling a mint for every request could mint the asset twice, so
+        // only claims reserving an existing mint are served
+        let Some(collection_mint_id) = claim.collection_mint_id else {
+            return Err(ProcessorErrorKind::SolanaPayUnsupported(
+                "it mints a new asset",
+            ));
+        };
+        let Some(project_id) = claim.project_id else {
+            return Err(ProcessorErrorKind::SolanaPayUnsupported(
+                "it was created without a project",
+            ));
+        };
+
+        let kind = EventKind::PrepareClaimTransaction;
+        let key = SolanaNftEventKey {
+            id: id.to_string(),
+            project_id: project_id.to_string(),
+            correlation_id: id.to_string(),
+            ..Default::default()
+        };
+
+        let processor = self
+            .for_project(&key.project_id)
             .await?
-            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+            .with_project_treasury(&key.project_id)
+            .await?;
+
+        if processor.solana().multisig().is_some() {
+            return Err(ProcessorErrorKind::SolanaPayUnsupported(
+                "the project treasury is a multisig",
+            ));
+        }
 
-        let backend = &CompressedRef(self.solana());
+        let processor = Self {
+            solana: DebugShim(processor.solana().with_fee_payer(account)),
+            ..processor
+        };
 
-        let tx = backend
-            .transfer(&compression_leaf, payload)
+        let conn = self.db.get();
+        let mint = Mint::find_by_id(conn, collection_mint_id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        ensure_transferable(conn, &mint).await?;
+
+        let tx = processor
+            .backends()
+            .transfer(&mint, TransferMetaplexAssetTransaction {
+                owner_address: mint.owner().to_owned(),
+                recipient_address: claim.claimant.clone(),
+                collection_mint_id: collection_mint_id.to_string(),
+            })
             .await
             .map_err(ProcessorErrorKind::Solana)?;
+        let tx = SolanaPendingTransaction::from(tx);
+
+        processor
+            .check_signers(&key, &tx.signatures_or_signers_public_keys)
+            .await?;
+
+        // Another request or redemption may have started while this one
+        // was assembled, in which case its transaction is awaited instead
+        let txn = self.db.begin().await?;
+        let claim = Claim::find_by_id_for_update(&txn, id)
+            .await?
+            .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        ensure_redeemable(&claim, id)?;
+
+        if claim.pay_requested_at.map_or(false, fresh) {
+            return Ok(None);
+        }
+
+        Self::audit(
+            &txn,
+            kind,
+            &key,
+            "signing_requested",
+            Some(tx.serialized_message.as_slice()),
+            tx.signatures_or_signers_public_keys.clone(),
+            None,
+        )
+        .await?;
+        Claim::request_transaction(&txn, claim).await?;
+
+        let event = SolanaNftEvents::new(kind.into_sign_request(tx));
+        Self::enqueue(txn, &key, &event).await?;
+
+        info!("requested the Solana Pay transaction of claim {id} for {account}");
+
+        Ok(None)
+    }
+
+    /// Store the Solana Pay transaction of a claim once the treasury has
+    /// signed it, leaving the claimant's signature to their wallet
+    async fn store_claim_transaction(
+        &self,
+        key: &SolanaNftEventKey,
+        res: SolanaTransactionResult,
+    ) -> ProcessResult<()> {
+        let id = Uuid::parse_str(&key.id)?;
+        let status = TransactionStatus::from_i32(res.status)
+            .ok_or(ProcessorErrorKind::TransactionStatusNotFound)?;
+        let failed = status == TransactionStatus::Failed;
+
+        Self::audit(
+            self.db.get(),
+            EventKind::PrepareClaimTransaction,
+            key,
+            if failed { "sign_failed" } else { "signed" },
+            res.serialized_message.as_deref(),
+            res.serialized_message
+                .as_deref()
+                .map(message_signers)
+                .unwrap_or_default(),
+            None,
+        )
+        .await?;
+
+        if failed {
+            warn!("treasury failed to sign the Solana Pay transaction of claim {id}");
+            Claim::store_transaction(self.db.get(), id, None).await?;
+
+            return Ok(());
+        }
+
+        let tx = solana_pay::partially_signed(&res).map_err(ProcessorErrorKind::Solana)?;
+        let tx = bincode::serialize(&tx).map_err(|e| ProcessorErrorKind::Solana(e.into()))?;
 
-        Ok(tx.into())
+        Claim::store_transaction(self.db.get(), id, Some(tx)).await?;
+
+        Ok(())
     }
 
-    async fn retry_create_collection<B: CollectionBackend>(
+    async fn retry_create_collection(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
         payload: MetaplexMasterEditionTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
-        let conn = self.db.get();
-        let tx = backend
+    ) -> ProcessResult<PendingTransaction> {
+        let tx = self
+            .backends()
+            .collection()
             .create(payload.clone())
             .await
             .map_err(ProcessorErrorKind::Solana)?;
@@ -1257,9 +5563,10 @@ impl Processor {
         } = tx.addresses;
 
         let collection_id = Uuid::parse_str(&key.id.clone())?;
-        let collection = Collection::find_by_id(conn, collection_id)
+        let collection = Collection::find_by_id(self.db.get(), collection_id)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&collection)?;
 
         let mut collection: collections::ActiveModel = collection.into();
 
@@ -1270,45 +5577,53 @@ impl Processor {
         collection.update_authority = Set(update_authority.to_string());
         collection.owner = Set(owner.to_string());
 
-        Collection::update(conn, collection).await?;
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                Collection::update(txn, collection).await?;
 
-        Ok(tx.into())
+                Ok(())
+            })
+        }))
     }
 
-    async fn switch_mint_collection<B: CollectionBackend>(
+    async fn switch_mint_collection(
         &self,
-        backend: &B,
         payload: SwitchCollectionPayload,
-    ) -> ProcessResult<SolanaPendingTransaction> {
+    ) -> ProcessResult<PendingTransaction> {
         let conn = self.db.get();
-
         let (mint, collection) =
             CollectionMint::find_by_id_with_collection(conn, payload.mint_id.parse()?)
                 .await?
                 .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
+        if mint.compressed {
+            return Err(ProcessorErrorKind::CompressedMintSwitch);
+        }
+
         let collection = collection.ok_or(ProcessorErrorKind::RecordNotFound)?;
 
         let new_collection = Collection::find_by_id(conn, payload.collection_id.parse()?)
             .await?
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
+        self.ensure_cluster(&new_collection)?;
 
-        let tx = backend
+        let tx = self
+            .backends()
+            .collection()
             .switch(&mint, &collection, &new_collection)
             .await
             .map_err(ProcessorErrorKind::Solana)?;
 
-        Ok(tx.into())
+        Ok(PendingTransaction::new(tx))
     }
 
-    async fn retry_mint_drop<
-        B: MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses>,
-    >(
+    async fn retry_mint_drop(
         &self,
-        backend: &B,
         key: &SolanaNftEventKey,
-        payload: MintMetaplexEditionTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
+        mut payload: MintMetaplexEditionTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        payload.recipient_address = self.resolve_recipient(payload.recipient_address).await?;
+
         let conn = self.db.get();
         let id = Uuid::parse_str(&key.id.clone())?;
 
@@ -1317,8 +5632,12 @@ impl Processor {
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
         let collection = collection.ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let edition = reserve_edition(conn, collection.id, id, payload.edition).await?;
+        let payment = self.payment_for(id, &collection, &payload.recipient_address)?;
 
-        let tx = backend
+        let tx = self
+            .backends()
+            .edition()
             .mint(&collection, None, payload)
             .await
             .map_err(ProcessorErrorKind::Solana)?;
@@ -1335,17 +5654,30 @@ impl Processor {
         collection_mint.mint = Set(mint.to_string());
         collection_mint.owner = Set(recipient.to_string());
         collection_mint.associated_token_account = Set(associated_token_account.to_string());
+        collection_mint.edition = Set(Some(edition));
+
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                if let Some(payment) = payment {
+                    MintPayment::upsert(txn, payment).await?;
+                }
 
-        CollectionMint::update(conn, collection_mint).await?;
+                CollectionMint::update(txn, collection_mint)
+                    .await
+                    .map_err(edition_taken(edition))?;
 
-        Ok(tx.into())
+                Ok(())
+            })
+        }))
     }
 
     async fn retry_mint_to_collection(
         &self,
         key: &SolanaNftEventKey,
-        payload: MintMetaplexMetadataTransaction,
-    ) -> ProcessResult<SolanaPendingTransaction> {
+        mut payload: MintMetaplexMetadataTransaction,
+    ) -> ProcessResult<PendingTransaction> {
+        payload.recipient_address = self.resolve_recipient(payload.recipient_address).await?;
+
         let conn = self.db.get();
         let id = Uuid::parse_str(&key.id.clone())?;
 
@@ -1354,53 +5686,72 @@ impl Processor {
             .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
         let collection = collection.ok_or(ProcessorErrorKind::RecordNotFound)?;
+        let payment = self.payment_for(id, &collection, &payload.recipient_address)?;
 
-        if payload.compressed {
-            let backend = &CompressedRef(self.solana());
-
-            let tx = backend
-                .mint(&collection, None, payload)
-                .await
-                .map_err(ProcessorErrorKind::Solana)?;
-
-            let leaf_model = CompressionLeaf::find_by_id(conn, id)
-                .await?
-                .ok_or(ProcessorErrorKind::RecordNotFound)?;
-
-            let mut compression_leaf: compression_leafs::ActiveModel = leaf_model.into();
-
-            compression_leaf.merkle_tree = Set(tx.addresses.merkle_tree.to_string());
-            compression_leaf.tree_authority = Set(tx.addresses.tree_authority.to_string());
-            compression_leaf.tree_delegate = Set(tx.addresses.tree_delegate.to_string());
-            compression_leaf.leaf_owner = Set(tx.addresses.leaf_owner.to_string());
+        let split = RoyaltySplit::find_confirmed(conn, collection.id).await?;
+        let payload = with_collection_defaults(&collection, split.as_ref(), payload);
+        let solana = self
+            .solana_for_mint(conn, &collection, payload.compressed)
+            .await?;
 
-            compression_leaf.update(conn).await?;
+        let tx = Backends::new(&solana)
+            .mint(
+                AssetStandard::new(payload.compressed),
+                &collection,
+                None,
+                payload,
+            )
+            .await
+            .map_err(ProcessorErrorKind::Solana)?;
 
-            return Ok(tx.into());
-        }
+        let (compression_leaf, collection_mint) = match &tx.addresses {
+            MintAddresses::Compressed(addresses) => {
+                let leaf_model = CompressionLeaf::find_by_id(conn, id)
+                    .await?
+                    .ok_or(ProcessorErrorKind::RecordNotFound)?;
 
-        let backend = &UncompressedRef(self.solana());
+                let mut compression_leaf: compression_leafs::ActiveModel = leaf_model.into();
 
-        let tx = backend
-            .mint(&collection, None, payload)
-            .await
-            .map_err(ProcessorErrorKind::Solana)?;
+                compression_leaf.merkle_tree = Set(addresses.merkle_tree.to_string());
+                compression_leaf.tree_authority = Set(addresses.tree_authority.to_string());
+                compression_leaf.tree_delegate = Set(addresses.tree_delegate.to_string());
+                compression_leaf.leaf_owner = Set(addresses.leaf_owner.to_string());
 
-        let MintMetaplexAddresses {
-            mint,
-            recipient,
-            associated_token_account,
-            ..
-        } = tx.addresses;
+                (Some(compression_leaf), None)
+            },
+            MintAddresses::NonFungible(MintMetaplexAddresses {
+                mint,
+                recipient,
+                associated_token_account,
+                ..
+            }) => {
+                let mut collection_mint: collection_mints::ActiveModel = collection_mint.into();
+
+                collection_mint.mint = Set(mint.to_string());
+                collection_mint.owner = Set(recipient.to_string());
+                collection_mint.associated_token_account =
+                    Set(associated_token_account.to_string());
+
+                (None, Some(collection_mint))
+            },
+        };
 
-        let mut collection_mint: collection_mints::ActiveModel = collection_mint.into();
+        Ok(PendingTransaction::new(tx).with_write(move |txn| {
+            deferred(async move {
+                if let Some(payment) = payment {
+                    MintPayment::upsert(txn, payment).await?;
+                }
 
-        collection_mint.mint = Set(mint.to_string());
-        collection_mint.owner = Set(recipient.to_string());
-        collection_mint.associated_token_account = Set(associated_token_account.to_string());
+                if let Some(compression_leaf) = compression_leaf {
+                    CompressionLeaf::update(txn, compression_leaf).await?;
+                }
 
-        CollectionMint::update(conn, collection_mint).await?;
+                if let Some(collection_mint) = collection_mint {
+                    CollectionMint::update(txn, collection_mint).await?;
+                }
 
-        Ok(tx.into())
+                Ok(())
+            })
+        }))
     }
 }
@@ -0,0 +1,210 @@
+//! Exports of every mint of a collection as newline-delimited JSON, for
+//! customers leaving Hub or keeping their own backup. Each chunk of an
+//! export is written to an [`ExportSink`] when one is configured and is
+//! otherwise sent back inline in the event reporting it.
+
+use std::collections::HashMap;
+
+use holaplex_hub_nfts_solana_entity::{collection_mints, compression_leafs};
+use hub_core::{chrono::NaiveDateTime, futures_util::stream, prelude::*, reqwest, uuid::Uuid};
+use serde::Serialize;
+
+use crate::asset_api::DasProvider;
+
+/// Mints written to each chunk of an export
+pub const CHUNK_SIZE: u64 = 1000;
+
+/// Digital asset API requests made at once while looking up metadata URIs
+const CONCURRENT_REQUESTS: usize = 32;
+
+/// One line of an export
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportRecord {
+    pub id: Uuid,
+    pub mint: String,
+    pub owner: String,
+    pub compressed: bool,
+    pub merkle_tree: Option<String>,
+    pub edition: Option<i64>,
+    /// The metadata JSON URI reported by the digital asset API, if it knows
+    /// the asset
+    pub metadata_uri: Option<String>,
+    /// The signature of the transaction which minted the asset, if it was
+    /// minted through Hub
+    pub signature: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ExportRecord {
+    /// The line for `mint`, taking the signature of a compressed mint from
+    /// the leaf backing it
+    #[must_use]
+    pub fn new(
+        mint: collection_mints::Model,
+        leaf: Option<compression_leafs::Model>,
+        metadata_uri: Option<String>,
+        signature: Option<String>,
+    ) -> Self {
+        let (merkle_tree, leaf_signature) = leaf
+            .map(|leaf| (Some(leaf.merkle_tree), leaf.mint_signature))
+            .unwrap_or_default();
+
+        Self {
+            id: mint.id,
+            mint: mint.mint,
+            owner: mint.owner,
+            compressed: mint.compressed,
+            merkle_tree,
+            edition: mint.edition,
+            metadata_uri,
+            signature: leaf_signature.or(signature),
+            created_at: mint.created_at,
+        }
+    }
+}
+
+/// The metadata JSON URIs of `mints` known to the digital asset API. Assets
+/// it fails to return are left out rather than failing the export.
+pub(crate) async fn metadata_uris(
+    das: &dyn DasProvider,
+    mints: &[String],
+) -> HashMap<String, String> {
+    stream::iter(mints)
+        .map(|mint| async move {
+            match das.get_asset(mint).await {
+                Ok(asset) => Some((mint.clone(), asset.content.json_uri)),
+                Err(e) => {
+                    warn!("failed to look up the metadata URI of {mint}: {e:?}");
+                    None
+                },
+            }
+        })
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .filter_map(|uri| async move { uri })
+        .collect()
+        .await
+}
+
+/// Serialize `records` as newline-delimited JSON
+///
+/// # Errors
+/// This function fails if a record cannot be serialized
+pub fn to_ndjson(records: &[ExportRecord]) -> Result<Vec<u8>> {
+    let mut chunk = Vec::new();
+
+    for record in records {
+        serde_json::to_writer(&mut chunk, record)?;
+        chunk.push(b'\n');
+    }
+
+    Ok(chunk)
+}
+
+/// Storage for the chunks of collection exports
+#[async_trait]
+pub trait ExportSink: Send + Sync {
+    /// Store chunk `index` of export `export_id`, returning where it can be
+    /// read from. Storing a chunk again replaces it.
+    async fn put(&self, export_id: Uuid, index: u32, chunk: Vec<u8>) -> Result<String>;
+}
+
+/// An [`ExportSink`] uploading each chunk with
+/// `PUT <endpoint>/<export id>/<index>.ndjson`, as accepted by most object
+/// stores and their gateways
+#[derive(Debug, Clone)]
+pub struct HttpExportSink {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpExportSink {
+    /// Build a sink whose uploads time out after `timeout`, sending
+    /// `api_key` as a bearer token if given.
+    ///
+    /// # Errors
+    /// This function fails if the HTTP client cannot be built
+    pub fn new(endpoint: String, api_key: Option<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("failed to build export sink client")?;
+
+        Ok(Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            api_key,
+        })
+    }
+}
+
+#[async_trait]
+impl ExportSink for HttpExportSink {
+    async fn put(&self, export_id: Uuid, index: u32, chunk: Vec<u8>) -> Result<String> {
+        let url = format!("{}/{export_id}/{index:05}.ndjson", self.endpoint);
+        let mut req = self
+            .client
+            .put(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(chunk);
+
+        if let Some(api_key) = &self.api_key {
+            req = req.bearer_auth(api_key);
+        }
+
+        req.send()
+            .await
+            .context("export upload failed")?
+            .error_for_status()
+            .context("export upload was rejected")?;
+
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hub_core::chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn writes_one_line_per_mint() {
+        let mint = collection_mints::Model {
+            id: Uuid::new_v4(),
+            mint: "mint".to_owned(),
+            owner: "owner".to_owned(),
+            compressed: true,
+            created_at: Utc::now().naive_utc(),
+            ..Default::default()
+        };
+        let leaf = compression_leafs::Model {
+            merkle_tree: "tree".to_owned(),
+            mint_signature: Some("leaf signature".to_owned()),
+            ..Default::default()
+        };
+
+        let compressed = ExportRecord::new(
+            mint.clone(),
+            Some(leaf),
+            Some("https://example.com/0.json".to_owned()),
+            Some("audit signature".to_owned()),
+        );
+        let uncompressed = ExportRecord::new(mint, None, None, Some("audit signature".to_owned()));
+
+        assert_eq!(compressed.merkle_tree.as_deref(), Some("tree"));
+        assert_eq!(compressed.signature.as_deref(), Some("leaf signature"));
+        assert_eq!(uncompressed.signature.as_deref(), Some("audit signature"));
+
+        let chunk = to_ndjson(&[compressed, uncompressed]).unwrap();
+        let lines: Vec<serde_json::Value> = chunk
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["metadata_uri"], "https://example.com/0.json");
+        assert_eq!(lines[1]["metadata_uri"], serde_json::Value::Null);
+    }
+}
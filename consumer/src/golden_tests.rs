@@ -0,0 +1,393 @@
+//! Snapshots of the messages assembled by each backend method for fixed
+//! inputs and a fixed blockhash. Keys derived from freshly generated mint
+//! keypairs are replaced with their role so the snapshots are stable; every
+//! other key is printed as is.
+//!
+//! Review snapshot changes with `cargo insta review`.
+
+use std::fmt::Write;
+
+use holaplex_hub_nfts_solana_core::proto::{
+    Creator, MasterEdition, MetaplexMasterEditionTransaction, MetaplexMetadata,
+    MintMetaplexEditionTransaction, MintMetaplexMetadataTransaction,
+    TransferMetaplexAssetTransaction, TransferMetaplexAssetsTransaction, UpdateSolanaMintPayload,
+};
+use holaplex_hub_nfts_solana_entity::{
+    collection_mints, collections, sea_orm_active_enums::SolanaCluster,
+};
+use hub_core::{prelude::*, uuid::Uuid};
+use solana_program::{hash::Hash, message::Message, pubkey::Pubkey, system_program, sysvar};
+
+use crate::{
+    asset_api::DasProviderKind,
+    backend::{
+        BatchTransferBackend, CollectionBackend, MintBackend, TransactionResponse, TransferBackend,
+        UpdateMintBackend,
+    },
+    metrics::Metrics,
+    solana::{CompressedRef, EditionRef, Solana, SolanaArgs, UncompressedRef},
+    solana_rpc::MockSolanaRpc,
+};
+
+const RENT_LAMPORTS: u64 = 1_461_600;
+
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+fn treasury() -> Pubkey {
+    key(1)
+}
+
+fn owner() -> Pubkey {
+    key(4)
+}
+
+fn recipient() -> Pubkey {
+    key(5)
+}
+
+fn solana() -> Solana {
+    let solana = Solana::new(
+        SolanaArgs {
+            solana_endpoint: "http://127.0.0.1:8899".into(),
+            solana_ws_endpoint: None,
+            solana_cluster: SolanaCluster::Devnet,
+            solana_fallback_endpoints: vec![],
+            solana_health_check_interval: 5,
+            solana_rpc_requests_per_second: None,
+            solana_simulate_transactions: false,
+            solana_treasury_wallet_address: treasury(),
+            digital_asset_api_endpoint: "http://127.0.0.1:8080".into(),
+            digital_asset_api_provider: DasProviderKind::Helius,
+            digital_asset_api_hedge_delay: None,
+            tree_authority: key(2),
+            merkle_tree: key(3),
+            tree_delegate: None,
+            submit_commitment: None,
+        },
+        Metrics::new().unwrap(),
+    )
+    .unwrap();
+
+    solana.with_rpc(Arc::new(MockSolanaRpc::new(
+        Hash::new_from_array([7; 32]),
+        RENT_LAMPORTS,
+    )))
+}
+
+fn collection() -> collections::Model {
+    collections::Model {
+        id: Uuid::nil(),
+        master_edition: key(10).to_string(),
+        update_authority: owner().to_string(),
+        associated_token_account: key(11).to_string(),
+        owner: owner().to_string(),
+        mint: key(12).to_string(),
+        metadata: key(13).to_string(),
+        cluster: SolanaCluster::Devnet,
+        ..Default::default()
+    }
+}
+
+fn collection_mint(mint: Pubkey) -> collection_mints::Model {
+    collection_mints::Model {
+        id: Uuid::nil(),
+        collection_id: Uuid::nil(),
+        mint: mint.to_string(),
+        owner: owner().to_string(),
+        associated_token_account: spl_associated_token_account::get_associated_token_address(
+            &owner(),
+            &mint,
+        )
+        .to_string(),
+        ..Default::default()
+    }
+}
+
+fn creators() -> Vec<Creator> {
+    vec![
+        Creator {
+            address: owner().to_string(),
+            verified: true,
+            share: 90,
+        },
+        Creator {
+            address: key(6).to_string(),
+            verified: false,
+            share: 10,
+        },
+    ]
+}
+
+fn metadata() -> MetaplexMetadata {
+    MetaplexMetadata {
+        name: "Golden #1".into(),
+        symbol: "GOLD".into(),
+        seller_fee_basis_points: 250,
+        metadata_uri: "https://example.com/golden/1.json".into(),
+        creators: creators(),
+        owner_address: owner().to_string(),
+    }
+}
+
+fn master_edition() -> MetaplexMasterEditionTransaction {
+    MetaplexMasterEditionTransaction {
+        master_edition: Some(MasterEdition {
+            name: "Golden".into(),
+            symbol: "GOLD".into(),
+            seller_fee_basis_points: 250,
+            metadata_uri: "https://example.com/golden.json".into(),
+            creators: creators(),
+            owner_address: owner().to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Render an assembled message as one line per instruction account, naming
+/// well-known programs and the keys listed in `names`
+fn render<A>(res: &TransactionResponse<A>, names: &[(Pubkey, &str)]) -> String {
+    let programs = [
+        (system_program::ID, "system_program"),
+        (sysvar::rent::ID, "rent_sysvar"),
+        (spl_token::ID, "token_program"),
+        (spl_associated_token_account::ID, "associated_token_program"),
+        (mpl_token_metadata::ID, "token_metadata_program"),
+        (mpl_bubblegum::ID, "bubblegum_program"),
+        (spl_account_compression::ID, "account_compression_program"),
+        (spl_noop::ID, "noop_program"),
+    ];
+    let name = |key: &Pubkey| {
+        names
+            .iter()
+            .chain(&programs)
+            .find(|(k, _)| k == key)
+            .map_or_else(|| key.to_string(), |(_, n)| (*n).to_owned())
+    };
+
+    let message: Message = bincode::deserialize(&res.serialized_message).unwrap();
+    let mut out = String::new();
+
+    writeln!(out, "payer: {}", name(&message.account_keys[0])).unwrap();
+    writeln!(out, "blockhash: {}", message.recent_blockhash).unwrap();
+    writeln!(out, "signers:").unwrap();
+
+    for signer in &res.signatures_or_signers_public_keys {
+        let signer = signer
+            .parse()
+            .map_or_else(|_| "<signature>".to_owned(), |k| name(&k));
+
+        writeln!(out, "  {signer}").unwrap();
+    }
+
+    for (i, ix) in message.instructions.iter().enumerate() {
+        let program = name(&message.account_keys[usize::from(ix.program_id_index)]);
+
+        writeln!(out, "instruction {i}: {program}").unwrap();
+
+        for account in ix.accounts.iter().map(|a| usize::from(*a)) {
+            let flags = match (message.is_signer(account), message.is_writable(account)) {
+                (true, true) => " [signer, writable]",
+                (true, false) => " [signer]",
+                (false, true) => " [writable]",
+                (false, false) => "",
+            };
+
+            writeln!(out, "  {}{flags}", name(&message.account_keys[account])).unwrap();
+        }
+
+        let data = ix.data.iter().fold(String::new(), |mut s, b| {
+            write!(s, "{b:02x}").unwrap();
+            s
+        });
+
+        writeln!(out, "  data: {data}").unwrap();
+    }
+
+    out
+}
+
+#[tokio::test]
+async fn create_collection() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .create(master_edition())
+        .await
+        .unwrap();
+    let a = &res.addresses;
+
+    insta::assert_snapshot!(render(&res, &[
+        (a.mint, "mint"),
+        (a.metadata, "metadata"),
+        (a.master_edition, "master_edition"),
+        (a.associated_token_account, "associated_token_account"),
+    ]));
+}
+
+#[tokio::test]
+async fn update_collection() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .update(&collection(), master_edition())
+        .await
+        .unwrap();
+
+    insta::assert_snapshot!(render(&res, &[]));
+}
+
+#[tokio::test]
+async fn switch_collection() {
+    let solana = solana();
+    let new_collection = collections::Model {
+        master_edition: key(20).to_string(),
+        update_authority: key(21).to_string(),
+        owner: key(21).to_string(),
+        mint: key(22).to_string(),
+        metadata: key(23).to_string(),
+        ..collection()
+    };
+    let res = UncompressedRef(&solana)
+        .switch(&collection_mint(key(30)), &collection(), &new_collection)
+        .await
+        .unwrap();
+
+    insta::assert_snapshot!(render(&res, &[]));
+}
+
+#[tokio::test]
+async fn mint_to_collection() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .mint(&collection(), None, MintMetaplexMetadataTransaction {
+            recipient_address: recipient().to_string(),
+            metadata: Some(metadata()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let a = &res.addresses;
+
+    insta::assert_snapshot!(render(&res, &[
+        (a.mint, "mint"),
+        (a.metadata, "metadata"),
+        (a.associated_token_account, "associated_token_account"),
+    ]));
+}
+
+#[tokio::test]
+async fn mint_compressed_to_collection() {
+    let solana = solana();
+    let res = CompressedRef(&solana)
+        .mint(&collection(), None, MintMetaplexMetadataTransaction {
+            recipient_address: recipient().to_string(),
+            metadata: Some(metadata()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    insta::assert_snapshot!(render(&res, &[]));
+}
+
+#[tokio::test]
+async fn mint_edition() {
+    let solana = solana();
+    let res = EditionRef(&solana)
+        .mint(&collection(), None, MintMetaplexEditionTransaction {
+            recipient_address: recipient().to_string(),
+            owner_address: owner().to_string(),
+            edition: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let a = &res.addresses;
+
+    insta::assert_snapshot!(render(&res, &[
+        (a.mint, "mint"),
+        (a.metadata, "metadata"),
+        (a.edition, "edition"),
+        (a.associated_token_account, "associated_token_account"),
+    ]));
+}
+
+#[tokio::test]
+async fn update_collection_mint() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .update_mint(
+            &collection(),
+            &collection_mint(key(30)),
+            UpdateSolanaMintPayload {
+                metadata: Some(metadata()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    insta::assert_snapshot!(render(&res, &[]));
+}
+
+#[tokio::test]
+async fn transfer_asset() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .transfer(
+            &collection_mint(key(30)),
+            TransferMetaplexAssetTransaction {
+                owner_address: owner().to_string(),
+                recipient_address: recipient().to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    insta::assert_snapshot!(render(&res, &[]));
+}
+
+#[tokio::test]
+async fn transfer_assets() {
+    let solana = solana();
+    let res = UncompressedRef(&solana)
+        .transfer_many(
+            &[collection_mint(key(30)), collection_mint(key(31))],
+            &[],
+            TransferMetaplexAssetsTransaction {
+                owner_address: owner().to_string(),
+                recipient_address: recipient().to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.len(), 1);
+    insta::assert_snapshot!(render(&res[0], &[]));
+}
+
+#[tokio::test]
+async fn transfer_assets_across_transactions() {
+    let solana = solana();
+    let mints: Vec<_> = (40..70).map(|n| collection_mint(key(n))).collect();
+    let res = UncompressedRef(&solana)
+        .transfer_many(&mints, &[], TransferMetaplexAssetsTransaction {
+            owner_address: owner().to_string(),
+            recipient_address: recipient().to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let transferred: Vec<_> = res
+        .iter()
+        .flat_map(|tx| tx.addresses.mints.iter().map(ToString::to_string))
+        .collect();
+    let requested: Vec<_> = mints.iter().map(|mint| mint.mint.clone()).collect();
+
+    assert!(res.len() > 1);
+    assert_eq!(transferred, requested);
+}
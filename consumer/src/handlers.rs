@@ -2,13 +2,27 @@ use hub_core::{
     anyhow::Result,
     metrics::{Encoder, TextEncoder},
 };
-use poem::{handler, http::StatusCode, web::Data};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json},
+    IntoResponse, Response,
+};
 
-use crate::Metrics;
+use crate::{
+    health::{HealthCheck, Status},
+    Metrics,
+};
 
 #[handler]
-pub fn health() -> StatusCode {
-    StatusCode::OK
+pub async fn health(Data(check): Data<&HealthCheck>) -> Response {
+    let report = check.check().await;
+    let status = match report.status {
+        Status::Ok => StatusCode::OK,
+        Status::Degraded => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (status, Json(report)).into_response()
 }
 
 #[handler]
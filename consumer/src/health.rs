@@ -0,0 +1,160 @@
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    sea_orm::{ConnectionTrait, Statement},
+    Outbox,
+};
+use hub_core::{anyhow::Result, chrono::Utc, prelude::*, tokio};
+use solana_program::pubkey::Pubkey;
+
+use crate::{asset_api::DasProvider, solana::Solana};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Degraded,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DependencyHealth {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<Result<()>> for DependencyHealth {
+    fn from(res: Result<()>) -> Self {
+        match res {
+            Ok(()) => Self {
+                status: Status::Ok,
+                error: None,
+            },
+            Err(e) => Self {
+                status: Status::Degraded,
+                error: Some(format!("{e:#}")),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HealthReport {
+    pub status: Status,
+    pub database: DependencyHealth,
+    pub kafka: DependencyHealth,
+    pub solana_rpc: DependencyHealth,
+    pub asset_api: DependencyHealth,
+}
+
+/// Checks the consumer's external dependencies for the health endpoint.
+#[derive(Clone)]
+pub struct HealthCheck {
+    db: Connection,
+    solana: Solana,
+    max_outbox_lag: Duration,
+}
+
+impl HealthCheck {
+    #[must_use]
+    pub fn new(db: Connection, solana: Solana, max_outbox_lag: Duration) -> Self {
+        Self {
+            db,
+            solana,
+            max_outbox_lag,
+        }
+    }
+
+    pub async fn check(&self) -> HealthReport {
+        let (database, kafka, solana_rpc, asset_api) = tokio::join!(
+            with_timeout(self.check_database()),
+            with_timeout(self.check_kafka()),
+            with_timeout(self.check_solana_rpc()),
+            with_timeout(self.check_asset_api()),
+        );
+
+        let (database, kafka, solana_rpc, asset_api) = (
+            DependencyHealth::from(database),
+            DependencyHealth::from(kafka),
+            DependencyHealth::from(solana_rpc),
+            DependencyHealth::from(asset_api),
+        );
+
+        let status = if [&database, &kafka, &solana_rpc, &asset_api]
+            .iter()
+            .all(|d| d.status == Status::Ok)
+        {
+            Status::Ok
+        } else {
+            Status::Degraded
+        };
+
+        HealthReport {
+            status,
+            database,
+            kafka,
+            solana_rpc,
+            asset_api,
+        }
+    }
+
+    async fn check_database(&self) -> Result<()> {
+        let conn = self.db.get();
+
+        conn.execute(Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT 1".to_owned(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    /// The producer is only exercised by the outbox relay, so an event left
+    /// unsent for longer than the allowed lag means Kafka is unreachable.
+    async fn check_kafka(&self) -> Result<()> {
+        let Some(oldest) = Outbox::oldest_pending(self.db.get()).await? else {
+            return Ok(());
+        };
+
+        let lag = Utc::now().naive_utc() - oldest;
+
+        ensure!(
+            lag.to_std().unwrap_or_default() <= self.max_outbox_lag,
+            "oldest pending outbox event is {}s old",
+            lag.num_seconds()
+        );
+
+        Ok(())
+    }
+
+    async fn check_solana_rpc(&self) -> Result<()> {
+        self.solana
+            .rpc_pool()
+            .call(|rpc| async move { rpc.get_health().await })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Any JSON-RPC response, including an error for the unknown asset,
+    /// means the API is reachable.
+    async fn check_asset_api(&self) -> Result<()> {
+        match self
+            .solana
+            .asset_rpc()
+            .get_asset(&Pubkey::default().to_string())
+            .await
+        {
+            Ok(_) | Err(jsonrpsee::core::Error::Call(_)) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+async fn with_timeout(fut: impl Future<Output = Result<()>>) -> Result<()> {
+    tokio::time::timeout(CHECK_TIMEOUT, fut)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", CHECK_TIMEOUT.as_secs()))?
+}
@@ -0,0 +1,273 @@
+//! Royalty splits held by Hydra fanout wallets.
+//!
+//! The royalties of a collection can be paid to the native account of a
+//! Hydra fanout rather than to its creators. The fanout records the shares
+//! of its member wallets, and anyone may ask it to pay out what its account
+//! holds to them.
+
+use anchor_lang::{prelude::AccountMeta, AnchorSerialize};
+use hub_core::thiserror;
+use solana_program::{instruction::Instruction, pubkey, pubkey::Pubkey, system_program, sysvar};
+
+use crate::squads::instruction_data;
+
+/// The Hydra program
+pub const PROGRAM_ID: Pubkey = pubkey!("hyDQ4Nz1eYyegS6JfenyKwKzYxRsCWCriYSAjtzP4Vg");
+
+/// Most members a split can be created with, so that the fanout and all of
+/// its memberships fit in a single transaction
+pub const MAX_MEMBERS: usize = 5;
+
+const SEED_FANOUT: &[u8] = b"fanout-config";
+const SEED_NATIVE_ACCOUNT: &[u8] = b"fanout-native-account";
+const SEED_MEMBERSHIP: &[u8] = b"fanout-membership";
+
+/// Why a royalty split could not be created
+#[derive(Debug, thiserror::Error)]
+pub enum HydraError {
+    #[error("Royalty split has no members")]
+    NoMembers,
+    #[error("Royalty split has {0} members, at most {MAX_MEMBERS} are supported")]
+    TooManyMembers(usize),
+    #[error("Royalty split member {0} has no shares")]
+    NoShares(Pubkey),
+    #[error("Royalty split member {0} is listed more than once")]
+    DuplicateMember(Pubkey),
+}
+
+/// A fanout wallet, identified by its unique name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fanout {
+    pub name: String,
+    pub authority: Pubkey,
+}
+
+#[derive(AnchorSerialize)]
+struct InitializeFanoutArgs {
+    bump_seed: u8,
+    native_account_bump_seed: u8,
+    name: String,
+    total_shares: u64,
+}
+
+/// How membership of a fanout is tracked. Splits always list member wallets.
+#[derive(AnchorSerialize)]
+enum MembershipModel {
+    Wallet,
+}
+
+#[derive(AnchorSerialize)]
+struct InitArgs {
+    args: InitializeFanoutArgs,
+    model: MembershipModel,
+}
+
+#[derive(AnchorSerialize)]
+struct AddMemberArgs {
+    shares: u64,
+}
+
+#[derive(AnchorSerialize)]
+struct DistributeArgs {
+    distribute_for_mint: bool,
+}
+
+impl Fanout {
+    /// The fanout named `name`, administered by `authority`
+    #[must_use]
+    pub fn new(name: String, authority: Pubkey) -> Self {
+        Self { name, authority }
+    }
+
+    fn find_address(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[SEED_FANOUT, self.name.as_bytes()], &PROGRAM_ID)
+    }
+
+    fn find_holding_account(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[SEED_NATIVE_ACCOUNT, self.address().as_ref()], &PROGRAM_ID)
+    }
+
+    /// The fanout account recording the members and their shares
+    #[must_use]
+    pub fn address(&self) -> Pubkey {
+        self.find_address().0
+    }
+
+    /// The account receiving royalties, which is set as the creator of the
+    /// collection's mints
+    #[must_use]
+    pub fn holding_account(&self) -> Pubkey {
+        self.find_holding_account().0
+    }
+
+    /// The voucher recording the shares of `member`
+    #[must_use]
+    pub fn membership(&self, member: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[SEED_MEMBERSHIP, self.address().as_ref(), member.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Instructions creating the fanout and adding each of `members` with
+    /// its shares. The authority pays the rent of every account.
+    ///
+    /// # Errors
+    /// This function fails if [`check_members`] rejects `members`
+    pub fn create(&self, members: &[(Pubkey, u64)]) -> Result<Vec<Instruction>, HydraError> {
+        check_members(members)?;
+
+        let (fanout, bump_seed) = self.find_address();
+        let (holding_account, native_account_bump_seed) = self.find_holding_account();
+
+        let init = InitArgs {
+            args: InitializeFanoutArgs {
+                bump_seed,
+                native_account_bump_seed,
+                name: self.name.clone(),
+                total_shares: members.iter().map(|(_, shares)| shares).sum(),
+            },
+            model: MembershipModel::Wallet,
+        };
+
+        let mut instructions = vec![Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(self.authority, true),
+                AccountMeta::new(fanout, false),
+                AccountMeta::new(holding_account, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(spl_token::native_mint::ID, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data: instruction_data("process_init", &init),
+        }];
+
+        instructions.extend(members.iter().map(|(member, shares)| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(self.authority, true),
+                AccountMeta::new_readonly(*member, false),
+                AccountMeta::new(fanout, false),
+                AccountMeta::new(self.membership(member), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data: instruction_data("process_add_member_wallet", &AddMemberArgs {
+                shares: *shares,
+            }),
+        }));
+
+        Ok(instructions)
+    }
+
+    /// Instructions paying out the native balance of the fanout to each of
+    /// `members`
+    #[must_use]
+    pub fn distribute(&self, payer: Pubkey, members: &[Pubkey]) -> Vec<Instruction> {
+        let fanout = self.address();
+        let holding_account = self.holding_account();
+
+        members
+            .iter()
+            .map(|member| {
+                let membership = self.membership(member);
+
+                Instruction {
+                    program_id: PROGRAM_ID,
+                    accounts: vec![
+                        AccountMeta::new(payer, true),
+                        AccountMeta::new(*member, false),
+                        AccountMeta::new(membership, false),
+                        AccountMeta::new(fanout, false),
+                        AccountMeta::new(holding_account, false),
+                        // Unused by native distributions
+                        AccountMeta::new(fanout, false),
+                        AccountMeta::new(membership, false),
+                        AccountMeta::new_readonly(spl_token::native_mint::ID, false),
+                        AccountMeta::new(*member, false),
+                        AccountMeta::new_readonly(system_program::ID, false),
+                        AccountMeta::new_readonly(sysvar::rent::ID, false),
+                        AccountMeta::new_readonly(spl_token::ID, false),
+                    ],
+                    data: instruction_data("process_distribute_wallet", &DistributeArgs {
+                        distribute_for_mint: false,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Check that a fanout can be created with `members`
+///
+/// # Errors
+/// This function fails if `members` is empty, too long, lists a wallet twice
+/// or gives a wallet no shares
+pub fn check_members(members: &[(Pubkey, u64)]) -> Result<(), HydraError> {
+    if members.is_empty() {
+        return Err(HydraError::NoMembers);
+    }
+
+    if members.len() > MAX_MEMBERS {
+        return Err(HydraError::TooManyMembers(members.len()));
+    }
+
+    for (i, (member, shares)) in members.iter().enumerate() {
+        if *shares == 0 {
+            return Err(HydraError::NoShares(*member));
+        }
+
+        if members[..i].iter().any(|(m, _)| m == member) {
+            return Err(HydraError::DuplicateMember(*member));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    #[test]
+    fn creates_fanout_with_members() {
+        let fanout = Fanout::new("a".repeat(32), key(1));
+
+        let ixs = fanout.create(&[(key(2), 70), (key(3), 30)]).unwrap();
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0].accounts[2].pubkey, fanout.holding_account());
+        assert_eq!(ixs[2].accounts[3].pubkey, fanout.membership(&key(3)));
+
+        // Discriminator, two bumps, the name and its length prefix, then the
+        // total shares and the membership model
+        assert_eq!(ixs[0].data.len(), 8 + 2 + 4 + 32 + 8 + 1);
+        assert_eq!(&ixs[0].data[46..54], &100u64.to_le_bytes());
+    }
+
+    #[test]
+    fn rejects_invalid_members() {
+        let fanout = Fanout::new("a".repeat(32), key(1));
+
+        assert!(matches!(fanout.create(&[]), Err(HydraError::NoMembers)));
+        assert!(matches!(
+            fanout.create(&[(key(2), 0)]),
+            Err(HydraError::NoShares(m)) if m == key(2)
+        ));
+        assert!(matches!(
+            fanout.create(&[(key(2), 1), (key(2), 1)]),
+            Err(HydraError::DuplicateMember(m)) if m == key(2)
+        ));
+        assert!(matches!(
+            fanout.create(&[(key(2), 1); MAX_MEMBERS + 1]),
+            Err(HydraError::TooManyMembers(_))
+        ));
+    }
+}
@@ -1,14 +1,17 @@
+use std::collections::HashMap;
+
 use holaplex_hub_nfts_solana_core::{
     db,
     proto::{
         nft_events::Event as NftEvent, solana_nft_events::Event as SolanaNftEvent, Attribute,
-        CollectionImport, File, Metadata, SolanaCollectionPayload, SolanaCreator,
-        SolanaMintPayload, SolanaNftEventKey, SolanaNftEvents,
+        CollectionImport, File, Metadata, MintImport, SolanaCollectionPayload, SolanaCreator,
+        SolanaImportProgress, SolanaMintPayload, SolanaNftEventKey, SolanaNftEvents,
     },
-    sea_orm::{DbErr, EntityTrait, ModelTrait, Set},
-    Collection, Services,
+    sea_orm::{ConnectionTrait, DbErr, Set, TransactionTrait},
+    Collection, CollectionAuthority, CollectionMint, ImportStatus, Outbox, Services,
+    TransferBackfill,
 };
-use holaplex_hub_nfts_solana_entity::{collection_mints, collections, prelude::CollectionMints};
+use holaplex_hub_nfts_solana_entity::{collection_mints, collections};
 use hub_core::{
     backon::{ExponentialBuilder, Retryable},
     chrono::Utc,
@@ -20,20 +23,61 @@ use hub_core::{
     uuid::{self, Uuid},
 };
 use mpl_token_metadata::pda::{find_master_edition_account, find_metadata_account};
+use solana_program::pubkey::{ParsePubkeyError, Pubkey};
 use spl_associated_token_account::get_associated_token_address;
 
 use crate::{
-    asset_api::{self, Asset, RpcClient},
+    asset_api::{self, Asset, DasProvider},
+    cluster::Clusters,
     solana::Solana,
 };
 
 const CONCURRENT_REQUESTS: usize = 64;
 
+/// A name-based ID for `mint` imported by `project` into the collection at
+/// address `collection`, so importing the same asset again updates its row
+/// rather than adding another. Collection rows are recreated by each import,
+/// so the namespace is derived from the collection address instead.
+fn imported_mint_id(project: Uuid, collection: &Pubkey, mint: &Pubkey) -> Uuid {
+    let namespace = Uuid::new_v5(&project, collection.as_ref());
+
+    Uuid::new_v5(&namespace, mint.as_ref())
+}
+
+/// The ids and addresses of the uncompressed `mints`, whose transfer history
+/// can be backfilled from the signatures of their mint account
+fn backfills(mints: &[collection_mints::Model]) -> Vec<(Uuid, String)> {
+    mints
+        .iter()
+        .filter(|mint| !mint.compressed)
+        .map(|mint| (mint.id, mint.mint.clone()))
+        .collect()
+}
+
+/// An asset read for import, and the event announcing it once stored
+struct ImportedMint {
+    model: collection_mints::ActiveModel,
+    key: SolanaNftEventKey,
+    event: SolanaNftEvents,
+}
+
 #[derive(Debug, thiserror::Error, Triage)]
 pub enum ProcessorError {
     #[error("Missing update authority (index 0) on asset")]
     #[transient]
     MissingUpdateAuthority,
+    #[error("Collection to import into not found")]
+    #[transient]
+    CollectionNotFound,
+    #[error("Asset has been burned")]
+    #[permanent]
+    BurnedAsset,
+    #[error("Fungible assets cannot be imported")]
+    #[permanent]
+    FungibleAsset,
+    #[error("Update authority {0} of the collection has not been verified for the project")]
+    #[permanent]
+    UnverifiedAuthority(String),
 
     #[error("Error fetching metadata JSON")]
     JsonFetch(#[source] reqwest::Error),
@@ -44,6 +88,9 @@ pub enum ProcessorError {
     #[error("Invalid conversion from byte slice to public key")]
     #[permanent]
     InvalidPubkey(#[source] std::array::TryFromSliceError),
+    #[error("Invalid public key")]
+    #[permanent]
+    ParsePubkey(#[from] ParsePubkeyError),
     #[error("Database error")]
     DbError(#[from] DbErr),
     #[error("Error sending message")]
@@ -56,16 +103,27 @@ type Result<T> = std::result::Result<T, ProcessorError>;
 #[derive(Debug, Clone)]
 pub struct Processor {
     solana: DebugShim<Solana>,
+    clusters: DebugShim<Clusters>,
     db: db::Connection,
     producer: Producer<SolanaNftEvents>,
+    batch_size: usize,
 }
 
 impl Processor {
-    pub fn new(solana: Solana, db: db::Connection, producer: Producer<SolanaNftEvents>) -> Self {
+    /// `batch_size` caps the mints written by each insert of a collection
+    /// import
+    pub fn new(
+        clusters: Clusters,
+        db: db::Connection,
+        producer: Producer<SolanaNftEvents>,
+        batch_size: usize,
+    ) -> Self {
         Self {
-            solana: DebugShim(solana),
+            solana: DebugShim(clusters.primary().clone()),
+            clusters: DebugShim(clusters),
             db,
             producer,
+            batch_size: batch_size.max(1),
         }
     }
 
@@ -75,9 +133,18 @@ impl Processor {
                 let key = SolanaNftEventKey::from(key.clone());
 
                 match msg.event {
-                    Some(NftEvent::StartedImportingSolanaCollection(ref c)) => {
-                        self.process_import(key, c.clone()).await.map(Some)
-                    },
+                    Some(NftEvent::StartedImportingSolanaCollection(ref c)) => self
+                        .for_project(&key.project_id)
+                        .await?
+                        .process_import(key, c.clone())
+                        .await
+                        .map(Some),
+                    Some(NftEvent::SolanaImportMint(ref m)) => self
+                        .for_project(&key.project_id)
+                        .await?
+                        .process_mint_import(key, m.clone())
+                        .await
+                        .map(Some),
                     _ => Ok(None),
                 }
             },
@@ -85,54 +152,107 @@ impl Processor {
         }
     }
 
+    /// A processor bound to the cluster serving `project_id`. A project
+    /// stays on the cluster its newest collection was created on, so only
+    /// projects without collections are placed by configuration.
+    async fn for_project(&self, project_id: &str) -> Result<Self> {
+        let cluster =
+            Collection::find_cluster_by_project(self.db.get(), project_id.to_owned()).await?;
+        let solana = cluster
+            .and_then(|cluster| self.clusters.0.get(cluster))
+            .unwrap_or_else(|| self.clusters.0.for_project(project_id));
+
+        Ok(Self {
+            solana: DebugShim(solana.clone()),
+            ..self.clone()
+        })
+    }
+
     async fn process_import(
         &self,
         SolanaNftEventKey {
             id,
             project_id,
             user_id,
+            correlation_id,
         }: SolanaNftEventKey,
         CollectionImport { mint_address }: CollectionImport,
     ) -> Result<()> {
         const MAX_LIMIT: u64 = 1000;
 
+        let collection_address: Pubkey = mint_address.parse()?;
+
         let rpc = &self.solana.0.asset_rpc();
         let conn = self.db.get();
 
         let mut page = 1;
+        let mut progress = SolanaImportProgress::default();
 
         let collection = rpc.get_asset(&mint_address).await?;
 
+        // Only the project whose challenge was signed by the current update
+        // authority of the collection may import it
+        let update_authority = collection
+            .authorities
+            .get(0)
+            .ok_or(ProcessorError::MissingUpdateAuthority)?
+            .address
+            .to_string();
+
+        CollectionAuthority::find_verified(conn, project_id.parse()?, &mint_address)
+            .await?
+            .filter(|authority| authority.update_authority == update_authority)
+            .ok_or(ProcessorError::UnverifiedAuthority(update_authority))?;
+
         let collection_model = Collection::find_by_id(conn, id.parse()?).await?;
 
         if let Some(collection_model) = collection_model {
             info!(
-                "Deleting already indexed collection: {:?}",
+                "Archiving already indexed collection: {:?}",
                 collection_model.id
             );
-            collection_model.delete(conn).await?;
+            let txn = conn.begin().await?;
+            Collection::archive(&txn, collection_model.id).await?;
+            txn.commit().await?;
         }
 
         info!("Importing collection: {:?}", collection.id.to_string());
 
         let collection_model = self
-            .index_collection(project_id.clone(), user_id.clone(), collection)
+            .index_collection(
+                project_id.clone(),
+                user_id.clone(),
+                correlation_id.clone(),
+                collection,
+            )
             .await?;
 
+        ImportStatus::start(conn, collection_model.id, mint_address.clone()).await?;
+
         loop {
-            let result = rpc
-                .search_assets(vec!["collection", &mint_address], page)
-                .await?;
+            let result = rpc.collection_assets(&mint_address, page).await?;
 
-            let mut mints: Vec<collection_mints::ActiveModel> = Vec::new();
+            let mut mints: Vec<ImportedMint> = Vec::new();
             let mut futures = Vec::new();
 
             for asset in result.items {
                 let project_id = project_id.clone();
                 let user_id = user_id.clone();
+                let correlation_id = correlation_id.clone();
+
+                if asset.is_burned() {
+                    progress.skipped_burned += 1;
+                    continue;
+                }
+
+                if asset.is_fungible() {
+                    progress.skipped_fungible += 1;
+                    continue;
+                }
 
-                // Check whether NFT is burned
-                if asset.ownership.owner.0.is_empty() {
+                // Skip assets which merely reference the collection
+                if !asset.in_verified_collection(&mint_address) {
+                    progress.skipped_unverified += 1;
                     continue;
                 }
 
@@ -141,7 +261,9 @@ impl Processor {
                 futures.push(self.collection_mint_event(
                     project_id,
                     user_id,
+                    correlation_id,
                     collection_model.id,
+                    collection_address,
                     asset,
                 ));
             }
@@ -151,14 +273,191 @@ impl Processor {
                 mints.push(model?);
             }
 
-            CollectionMints::insert_many(mints).exec(conn).await?;
+            let stored = self.store_mints(conn, collection_model.id, &mints).await?;
+            progress.imported += u64::try_from(stored).unwrap_or_default();
 
-            if result.total < MAX_LIMIT {
+            let done = result.total < MAX_LIMIT;
+            ImportStatus::update(conn, collection_model.id, &progress, done).await?;
+
+            self.producer
+                .send(
+                    Some(&SolanaNftEvents::new(
+                        SolanaNftEvent::ImportCollectionProgress(progress.clone()),
+                    )),
+                    Some(&SolanaNftEventKey {
+                        id: collection_model.id.to_string(),
+                        project_id: project_id.clone(),
+                        user_id: user_id.clone(),
+                        correlation_id: correlation_id.clone(),
+                    }),
+                )
+                .await?;
+
+            if done {
                 break;
             }
             page += 1;
         }
 
+        info!(
+            "Imported {} mints into {:?}, skipping {} burned, {} fungible and {} unverified",
+            progress.imported,
+            collection_model.id,
+            progress.skipped_burned,
+            progress.skipped_fungible,
+            progress.skipped_unverified
+        );
+
+        Ok(())
+    }
+
+    /// Store a page of imported mints in batches, returning how many were
+    /// stored. A batch which fails is retried one mint at a time so a bad row
+    /// only skips itself.
+    async fn store_mints(
+        &self,
+        conn: &(impl ConnectionTrait + TransactionTrait),
+        collection: Uuid,
+        mints: &[ImportedMint],
+    ) -> Result<usize> {
+        let mut stored = 0;
+
+        for batch in mints.chunks(self.batch_size) {
+            match Self::store(conn, collection, batch).await {
+                Ok(count) => stored += count,
+                Err(e) => {
+                    warn!("failed to store a batch of imported mints, retrying one by one: {e:?}");
+                    stored += Self::store_each(conn, collection, batch).await?;
+                },
+            }
+        }
+
+        Ok(stored)
+    }
+
+    /// Store `mints` one at a time, skipping those which fail. Fails if none
+    /// can be stored, as the database itself is then likely at fault.
+    async fn store_each(
+        conn: &(impl ConnectionTrait + TransactionTrait),
+        collection: Uuid,
+        mints: &[ImportedMint],
+    ) -> Result<usize> {
+        let mut stored = 0;
+        let mut last_err = None;
+
+        for mint in mints {
+            match Self::store(conn, collection, std::slice::from_ref(mint)).await {
+                Ok(count) => stored += count,
+                Err(e) => {
+                    error!(
+                        "failed to store imported mint {}: {e:?}",
+                        mint.model.mint.as_ref()
+                    );
+                    last_err = Some(e);
+                },
+            }
+        }
+
+        match last_err {
+            Some(e) if stored == 0 => Err(e.into()),
+            _ => Ok(stored),
+        }
+    }
+
+    /// Upsert `mints` into `collection`, queueing their transfer backfills
+    /// and the events announcing them in the same transaction so only mints
+    /// which were stored are announced. Events are keyed by the ID of the
+    /// stored row, which is kept when a mint is imported again.
+    async fn store(
+        conn: &(impl ConnectionTrait + TransactionTrait),
+        collection: Uuid,
+        mints: &[ImportedMint],
+    ) -> Result<usize> {
+        let events = mints
+            .iter()
+            .map(|mint| (mint.model.mint.as_ref(), mint))
+            .collect::<HashMap<_, _>>();
+
+        let txn = conn.begin().await?;
+
+        let stored = CollectionMint::upsert_many(
+            &txn,
+            mints.iter().map(|mint| mint.model.clone()).collect(),
+        )
+        .await?;
+
+        TransferBackfill::enqueue(&txn, collection, backfills(&stored)).await?;
+
+        for model in &stored {
+            let Some(ImportedMint { key, event, .. }) = events.get(&model.mint) else {
+                continue;
+            };
+
+            let key = SolanaNftEventKey {
+                id: model.id.to_string(),
+                ..key.clone()
+            };
+
+            Outbox::enqueue(&txn, &key, event).await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(stored.len())
+    }
+
+    /// Index a single asset into an existing collection. A previous import
+    /// of the same mint is updated in place rather than deleted, so rows
+    /// referencing it are kept.
+    async fn process_mint_import(
+        &self,
+        SolanaNftEventKey {
+            project_id,
+            user_id,
+            correlation_id,
+            ..
+        }: SolanaNftEventKey,
+        MintImport {
+            mint_address,
+            collection_id,
+        }: MintImport,
+    ) -> Result<()> {
+        let rpc = &self.solana.0.asset_rpc();
+        let conn = self.db.get();
+
+        // Collections are written once when their import starts, well before
+        // their mints are imported
+        let collection = Collection::find_by_id(self.db.read(), collection_id.parse()?)
+            .await?
+            .ok_or(ProcessorError::CollectionNotFound)?;
+
+        let asset = rpc.get_asset(&mint_address).await?;
+
+        if asset.is_burned() {
+            return Err(ProcessorError::BurnedAsset);
+        }
+
+        if asset.is_fungible() {
+            return Err(ProcessorError::FungibleAsset);
+        }
+
+        // The upsert moves a mint already imported elsewhere (e.g. into a
+        // different collection) into this one
+        info!("Importing mint: {:?}", asset.id.to_string());
+
+        let mint = self
+            .collection_mint_event(
+                project_id,
+                user_id,
+                correlation_id,
+                collection.id,
+                collection.mint.parse()?,
+                asset,
+            )
+            .await?;
+
+        Self::store(conn, collection.id, std::slice::from_ref(&mint)).await?;
+
         Ok(())
     }
 
@@ -166,6 +465,7 @@ impl Processor {
         &self,
         project_id: String,
         user_id: String,
+        correlation_id: String,
         collection: Asset,
     ) -> Result<collections::Model> {
         let conn = self.db.get();
@@ -225,36 +525,40 @@ impl Processor {
             owner: Set(owner.to_string()),
             mint: Set(mint.to_string()),
             metadata: Set(metadata_pubkey.to_string()),
+            cluster: Set(self.solana.0.cluster()),
+            project_id: Set(Some(project_id.clone())),
             ..Default::default()
         })
         .await?;
 
         producer
             .send(
-                Some(&SolanaNftEvents {
-                    event: Some(SolanaNftEvent::ImportedExternalCollection(
-                        SolanaCollectionPayload {
-                            supply: collection.supply.map(|s| s.print_max_supply),
-                            mint_address: mint.to_string(),
-                            seller_fee_basis_points,
-                            creators,
-                            metadata: Some(Metadata {
-                                name: json_metadata.name,
-                                description: json_metadata.description,
-                                symbol: json_metadata.symbol.unwrap_or_default(),
-                                attributes,
-                                uri: collection.content.json_uri,
-                                image,
-                            }),
-                            files,
-                            update_authority: update_authority.to_string(),
-                        },
-                    )),
-                }),
+                Some(&SolanaNftEvents::new(
+                    SolanaNftEvent::ImportedExternalCollection(SolanaCollectionPayload {
+                        supply: collection.supply.map(|s| s.print_max_supply),
+                        mint_address: mint.to_string(),
+                        seller_fee_basis_points,
+                        creators,
+                        metadata: Some(Metadata {
+                            name: json_metadata
+                                .name
+                                .or_else(|| collection.content.metadata.name.clone())
+                                .unwrap_or_default(),
+                            description: json_metadata.description,
+                            symbol: json_metadata.symbol.unwrap_or_default(),
+                            attributes,
+                            uri: collection.content.json_uri,
+                            image,
+                        }),
+                        files,
+                        update_authority: update_authority.to_string(),
+                    }),
+                )),
                 Some(&SolanaNftEventKey {
                     id: collection_model.id.to_string(),
                     project_id,
                     user_id,
+                    correlation_id,
                 }),
             )
             .await?;
@@ -285,10 +589,11 @@ impl Processor {
         &self,
         project_id: String,
         user_id: String,
+        correlation_id: String,
         collection: Uuid,
+        collection_address: Pubkey,
         asset: Asset,
-    ) -> Result<collection_mints::ActiveModel> {
-        let producer = self.producer.clone();
+    ) -> Result<ImportedMint> {
         let owner = asset
             .ownership
             .owner
@@ -297,6 +602,10 @@ impl Processor {
         let mint = asset.id.try_into().map_err(ProcessorError::InvalidPubkey)?;
         let ata = get_associated_token_address(&owner, &mint);
         let seller_fee_basis_points = asset.royalty.basis_points;
+        let primary_sale_at = asset
+            .royalty
+            .primary_sale_happened
+            .then(|| Utc::now().naive_utc());
 
         let update_authority = asset
             .authorities
@@ -331,8 +640,7 @@ impl Processor {
             })
             .collect::<Vec<_>>();
 
-        #[allow(clippy::cast_sign_loss)]
-        let uuid = Uuid::from_u64_pair(Utc::now().timestamp_nanos() as u64, rand::random::<u64>());
+        let uuid = imported_mint_id(project_id.parse()?, &collection_address, &mint);
 
         let mint_model = collection_mints::Model {
             id: uuid,
@@ -341,39 +649,46 @@ impl Processor {
             owner: owner.to_string(),
             associated_token_account: ata.to_string(),
             created_at: Utc::now().naive_utc(),
+            compressed: asset.compression.compressed,
+            edition: None,
+            metadata_lock_id: None,
+            metadata_locked_at: None,
+            primary_sale_at,
+            deleted_at: None,
         };
 
-        producer
-            .send(
-                Some(&SolanaNftEvents {
-                    event: Some(SolanaNftEvent::ImportedExternalMint(SolanaMintPayload {
-                        collection_id: collection.to_string(),
-                        mint_address: mint.to_string(),
-                        owner: owner.to_string(),
-                        seller_fee_basis_points,
-                        compressed: asset.compression.compressed,
-                        creators,
-                        metadata: Some(Metadata {
-                            name: json_metadata.name,
-                            description: json_metadata.description,
-                            symbol: json_metadata.symbol.unwrap_or_default(),
-                            attributes,
-                            uri: asset.content.json_uri,
-                            image,
-                        }),
-                        files,
-                        update_authority: update_authority.to_string(),
-                    })),
-                }),
-                Some(&SolanaNftEventKey {
-                    id: uuid.to_string(),
-                    user_id,
-                    project_id,
-                }),
-            )
-            .await?;
-
-        Ok(mint_model.into())
+        let event = SolanaNftEvents::new(SolanaNftEvent::ImportedExternalMint(SolanaMintPayload {
+            collection_id: collection.to_string(),
+            mint_address: mint.to_string(),
+            owner: owner.to_string(),
+            seller_fee_basis_points,
+            compressed: asset.compression.compressed,
+            creators,
+            metadata: Some(Metadata {
+                name: json_metadata
+                    .name
+                    .or_else(|| asset.content.metadata.name.clone())
+                    .unwrap_or_default(),
+                description: json_metadata.description,
+                symbol: json_metadata.symbol.unwrap_or_default(),
+                attributes,
+                uri: asset.content.json_uri,
+                image,
+            }),
+            files,
+            update_authority: update_authority.to_string(),
+        }));
+
+        Ok(ImportedMint {
+            model: mint_model.into(),
+            key: SolanaNftEventKey {
+                id: uuid.to_string(),
+                user_id,
+                project_id,
+                correlation_id,
+            },
+            event,
+        })
     }
 }
 
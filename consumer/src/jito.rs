@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use hub_core::{bs58, prelude::*, reqwest};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+use solana_sdk::transaction::Transaction;
+
+use crate::events::EventKind;
+
+#[derive(Debug, serde::Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<RpcError>,
+}
+
+/// Submits transactions as Jito bundles for the events that need a higher
+/// chance of landing during congestion. Bundled transactions pay a tip to the
+/// configured tip account.
+#[derive(Debug, Clone)]
+pub struct Jito {
+    client: reqwest::Client,
+    endpoint: String,
+    tip_account: Pubkey,
+    tip_lamports: u64,
+    events: Arc<HashSet<EventKind>>,
+}
+
+impl Jito {
+    /// Build a client sending bundles for `events` to the block engine at
+    /// `endpoint`.
+    ///
+    /// # Errors
+    /// This function fails if the HTTP client cannot be built
+    pub fn new(
+        endpoint: String,
+        tip_account: Pubkey,
+        tip_lamports: u64,
+        events: Vec<EventKind>,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .context("failed to build Jito bundle client")?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            tip_account,
+            tip_lamports,
+            events: Arc::new(events.into_iter().collect()),
+        })
+    }
+
+    /// Whether transactions for `kind` are submitted as bundles
+    #[must_use]
+    pub fn bundles(&self, kind: EventKind) -> bool {
+        self.events.contains(&kind)
+    }
+
+    /// The tip paid by `payer` for a bundled transaction
+    #[must_use]
+    pub fn tip(&self, payer: &Pubkey) -> Instruction {
+        system_instruction::transfer(payer, &self.tip_account, self.tip_lamports)
+    }
+
+    /// Send `transaction` as a single-transaction bundle, returning the
+    /// bundle ID
+    ///
+    /// # Errors
+    /// This function fails if the transaction cannot be encoded or the block
+    /// engine rejects the bundle
+    pub async fn send_bundle(&self, transaction: &Transaction) -> Result<String> {
+        let encoded = bs58::encode(bincode::serialize(transaction)?).into_string();
+
+        let res = self
+            .client
+            .post(&self.endpoint)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendBundle",
+                "params": [[encoded]],
+            }))
+            .send()
+            .await
+            .context("failed to send bundle")?
+            .error_for_status()
+            .context("block engine rejected bundle")?
+            .json::<RpcResponse>()
+            .await
+            .context("invalid sendBundle response")?;
+
+        match res {
+            RpcResponse {
+                result: Some(bundle_id),
+                ..
+            } => Ok(bundle_id),
+            RpcResponse {
+                error: Some(RpcError { message }),
+                ..
+            } => bail!("block engine rejected bundle: {message}"),
+            RpcResponse { .. } => bail!("sendBundle returned neither a result nor an error"),
+        }
+    }
+}
@@ -2,17 +2,56 @@
 #![warn(clippy::pedantic, clippy::cargo)]
 #![allow(clippy::module_name_repetitions)]
 
+pub mod admin;
 pub(crate) mod asset_api;
+pub mod asset_ids;
+mod auth;
 mod backend;
+pub mod candy_machine;
+pub mod cluster;
+pub mod collection_authority;
+pub mod consumed;
+pub mod custodial;
+pub mod das_status;
+pub mod db_pool;
+pub mod dead_letters;
+pub mod drop_schedule;
 pub mod events;
+pub mod export;
+#[cfg(test)]
+mod golden_tests;
 pub mod handlers;
+pub mod hydra;
+pub mod health;
 pub mod import;
+pub mod jito;
+pub mod metadata_validation;
 pub mod metrics;
+pub mod nonces;
+pub mod outbox;
+pub mod read_api;
+pub mod reconcile;
+pub mod retry;
+pub mod rpc_pool;
+pub mod scheduler;
+pub mod signer_policy;
 pub mod solana;
+pub mod solana_pay;
+pub mod solana_rpc;
+pub mod squads;
+pub mod transfer_backfill;
+pub mod trees;
+pub mod update_revisions;
+pub mod webhooks;
+use cluster::TestClusterArgs;
+use events::EventKind;
 use holaplex_hub_nfts_solana_core::db::DbArgs;
 use hub_core::{clap, prelude::*};
 use metrics::Metrics;
+use retry::RetryPolicySetting;
+use scheduler::LaneSetting;
 use solana::SolanaArgs;
+use solana_program::pubkey::Pubkey;
 
 #[derive(Debug, clap::Args)]
 #[command(version, author, about)]
@@ -25,4 +64,211 @@ pub struct Args {
 
     #[command(flatten)]
     pub solana: SolanaArgs,
+
+    #[command(flatten)]
+    pub test_cluster: TestClusterArgs,
+
+    /// Interval in milliseconds between polls of the event outbox
+    #[arg(long, env, default_value_t = 250)]
+    pub outbox_relay_interval: u64,
+
+    #[arg(long, env, default_value_t = 100)]
+    pub outbox_relay_batch_size: u64,
+
+    /// Skip relaying the event outbox from this replica. Events sharing a
+    /// key are only produced in order by a single relay, so it should be
+    /// disabled on all but one replica.
+    #[arg(long, env, default_value_t = false)]
+    pub disable_outbox_relay: bool,
+
+    /// Seconds a sent event is kept in the outbox before it is deleted
+    #[arg(long, env, default_value_t = 604_800)]
+    pub outbox_retention: u64,
+
+    /// Interval in seconds between deletions of sent outbox events
+    #[arg(long, env, default_value_t = 3600)]
+    pub outbox_cleanup_interval: u64,
+
+    /// Oldest `solana_nfts` schema version still run by consumers of the
+    /// events relayed from the outbox. Events renamed since then are
+    /// produced under their legacy names. Defaults to the current version.
+    #[arg(long, env)]
+    pub event_schema_version: Option<u32>,
+
+    /// Check that metadata and image URIs resolve before assembling create,
+    /// mint and update transactions
+    #[arg(long, env, default_value_t = false)]
+    pub validate_metadata_uris: bool,
+
+    /// Timeout in seconds for each metadata URI check
+    #[arg(long, env, default_value_t = 10)]
+    pub metadata_validation_timeout: u64,
+
+    /// Interval in seconds between on-chain metadata reconciliation runs.
+    /// Reconciliation is disabled unless this is set.
+    #[arg(long, env)]
+    pub metadata_reconcile_interval: Option<u64>,
+
+    /// Number of mints sampled by each reconciliation run
+    #[arg(long, env, default_value_t = 100)]
+    pub metadata_reconcile_sample_size: u64,
+
+    /// Interval in seconds between confirmations of compressed asset ids
+    /// which were predicted and have not been confirmed by the indexer
+    #[arg(long, env, default_value_t = 30)]
+    pub asset_id_reconcile_interval: u64,
+
+    /// Number of predicted asset ids confirmed by each run
+    #[arg(long, env, default_value_t = 100)]
+    pub asset_id_reconcile_batch_size: u64,
+
+    /// Interval in seconds between checks for collections whose scheduled
+    /// mint window has opened or closed
+    #[arg(long, env, default_value_t = 30)]
+    pub drop_schedule_interval: u64,
+
+    /// Seconds a mint update revision is kept for retries before it is
+    /// deleted
+    #[arg(long, env, default_value_t = 604_800)]
+    pub update_revision_ttl: u64,
+
+    /// Interval in seconds between deletions of expired mint update
+    /// revisions
+    #[arg(long, env, default_value_t = 3600)]
+    pub update_revision_cleanup_interval: u64,
+
+    /// Mints written by each insert while importing a collection
+    #[arg(long, env, default_value_t = 250)]
+    pub import_batch_size: usize,
+
+    /// Interval in seconds between runs backfilling the transfer history of
+    /// imported mints
+    #[arg(long, env, default_value_t = 30)]
+    pub transfer_backfill_interval: u64,
+
+    /// Number of imported mints whose transfer history is backfilled by
+    /// each run
+    #[arg(long, env, default_value_t = 10)]
+    pub transfer_backfill_batch_size: u64,
+
+    /// Most signatures walked back for each imported mint while backfilling
+    /// its transfer history
+    #[arg(long, env, default_value_t = 1000)]
+    pub transfer_backfill_depth: u64,
+
+    /// Interval in seconds between samples of the database connection pools
+    #[arg(long, env, default_value_t = 15)]
+    pub db_pool_metrics_interval: u64,
+
+    /// Bearer token required by the admin API. The admin routes are not
+    /// served unless this is set.
+    #[arg(long, env)]
+    pub admin_api_key: Option<String>,
+
+    /// Bearer token required by the read API serving collections, mints,
+    /// transfer history and import progress. The read routes are not served
+    /// unless this is set.
+    #[arg(long, env)]
+    pub read_api_key: Option<String>,
+
+    /// Name shown by wallets answering Solana Pay transaction requests for
+    /// claims. The Solana Pay routes are not served unless this is set.
+    #[arg(long, env)]
+    pub solana_pay_label: Option<String>,
+
+    /// URL of the icon shown by wallets answering Solana Pay transaction
+    /// requests
+    #[arg(long, env)]
+    pub solana_pay_icon: Option<String>,
+
+    /// Seconds a Solana Pay transaction request waits for the treasury to
+    /// sign the claim transaction before asking the wallet to retry
+    #[arg(long, env, default_value_t = 5)]
+    pub solana_pay_wait: u64,
+
+    /// Seconds an event may wait in the outbox before the health check
+    /// reports Kafka as degraded
+    #[arg(long, env, default_value_t = 60)]
+    pub outbox_max_lag: u64,
+
+    /// Jito block engine bundle endpoint. Transactions for the events listed
+    /// in `--jito-bundle-events` are tipped and submitted through it.
+    #[arg(long, env, requires = "jito_tip_account")]
+    pub jito_bundle_endpoint: Option<String>,
+
+    /// Account receiving the tip paid by each bundled transaction
+    #[arg(long, env)]
+    pub jito_tip_account: Option<Pubkey>,
+
+    #[arg(long, env, default_value_t = 10_000)]
+    pub jito_tip_lamports: u64,
+
+    /// Events whose transactions are submitted as Jito bundles
+    #[arg(long, env, value_delimiter = ',', value_enum)]
+    pub jito_bundle_events: Vec<EventKind>,
+
+    /// Shared secret Helius sends as the `Authorization` header of webhook
+    /// deliveries. The webhook routes are not served unless this is set.
+    #[arg(long, env)]
+    pub helius_webhook_secret: Option<String>,
+
+    /// Maximum number of consumed messages processed at once
+    #[arg(long, env, default_value_t = 64)]
+    pub event_concurrency: usize,
+
+    /// Per-kind caps on concurrently processed messages as `kind=limit`,
+    /// e.g. `import=2,mint-open-drop-batched=8`. Kinds are the event kinds
+    /// accepted by `--jito-bundle-events`, `import`, `export` or `other`;
+    /// kinds without a cap may use all of `--event-concurrency`.
+    #[arg(long, env, value_delimiter = ',')]
+    pub event_concurrency_limits: Vec<LaneSetting>,
+
+    /// Scheduling weights as `kind=weight`. While messages of several kinds
+    /// are waiting, each kind is given free slots in proportion to its
+    /// weight, which defaults to 1.
+    #[arg(long, env, value_delimiter = ',')]
+    pub event_weights: Vec<LaneSetting>,
+
+    /// Custodial wallet API used to resolve `email:` mint recipients. Mints
+    /// to such recipients are rejected unless this is set.
+    #[arg(long, env)]
+    pub custodial_wallet_resolver_endpoint: Option<String>,
+
+    /// Bearer token sent with custodial wallet requests
+    #[arg(long, env)]
+    pub custodial_wallet_resolver_api_key: Option<String>,
+
+    /// Timeout in seconds for custodial wallet requests
+    #[arg(long, env, default_value_t = 10)]
+    pub custodial_wallet_resolver_timeout: u64,
+
+    /// Base URL collection exports are uploaded to, one
+    /// `PUT <endpoint>/<export id>/<index>.ndjson` per chunk. Chunks are
+    /// sent inline in `CollectionExportChunk` events unless this is set.
+    #[arg(long, env)]
+    pub export_sink_endpoint: Option<String>,
+
+    /// Bearer token sent with export uploads
+    #[arg(long, env)]
+    pub export_sink_api_key: Option<String>,
+
+    /// Timeout in seconds for each export upload
+    #[arg(long, env, default_value_t = 60)]
+    pub export_sink_timeout: u64,
+
+    /// Retry policies as `class=max_times:min_delay_ms:max_delay_ms`, e.g.
+    /// `das=3:500:10000`. Classes are `rpc`, `das`, `db` and `kafka`;
+    /// classes without a policy keep their defaults.
+    #[arg(long, env, value_delimiter = ',')]
+    pub retry_policies: Vec<RetryPolicySetting>,
+
+    /// Consecutive transport failures after which Solana RPC or DAS calls
+    /// fail fast instead of being retried
+    #[arg(long, env, default_value_t = retry::Retries::DEFAULT_THRESHOLD)]
+    pub circuit_breaker_threshold: u32,
+
+    /// Seconds a tripped circuit breaker fails calls for before letting them
+    /// through again
+    #[arg(long, env, default_value_t = retry::Retries::DEFAULT_COOLDOWN.as_secs())]
+    pub circuit_breaker_cooldown: u64,
 }
@@ -1,13 +1,47 @@
 use holaplex_hub_nfts_solana::{
+    admin,
+    asset_ids::AssetIdReconciler,
+    cluster::Clusters,
+    consumed::Consumed,
+    custodial::{CustodialWallets, HttpWalletResolver},
+    das_status::DasStatusMonitor,
+    db_pool::PoolMonitor,
+    dead_letters::{self, DeadLetters},
+    drop_schedule::DropScheduler,
     events,
+    export::{ExportSink, HttpExportSink},
     handlers::{health, metrics_handler},
+    health::HealthCheck,
     import,
+    jito::Jito,
+    metadata_validation::MetadataValidator,
     metrics::Metrics,
-    solana::Solana,
-    Args,
+    outbox, read_api,
+    reconcile::Reconciler,
+    retry::{Retries, RetryClass},
+    scheduler::{Lane, Scheduler},
+    solana_pay,
+    transfer_backfill::TransferBackfiller,
+    update_revisions::RevisionCleanup,
+    webhooks, Args,
+};
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{compat, SolanaDeadLetter, SolanaNftEvents},
+};
+use hub_core::{
+    backon::Retryable,
+    prelude::*,
+    tokio::{
+        self,
+        signal::{
+            self,
+            unix::{signal as unix_signal, SignalKind},
+        },
+        sync::RwLock,
+    },
+    triage::{self, Severity, Triage},
 };
-use holaplex_hub_nfts_solana_core::{db::Connection, proto::SolanaNftEvents, Services};
-use hub_core::{prelude::*, tokio, triage};
 use poem::{get, listener::TcpListener, middleware::AddData, EndpointExt, Route, Server};
 
 pub fn main() {
@@ -16,58 +50,336 @@ pub fn main() {
     };
 
     hub_core::run(opts, |common, args| {
-        let Args { db, solana, port } = args;
+        let Args {
+            db,
+            solana,
+            test_cluster,
+            port,
+            outbox_relay_interval,
+            outbox_relay_batch_size,
+            disable_outbox_relay,
+            outbox_retention,
+            outbox_cleanup_interval,
+            event_schema_version,
+            validate_metadata_uris,
+            metadata_validation_timeout,
+            metadata_reconcile_interval,
+            metadata_reconcile_sample_size,
+            asset_id_reconcile_interval,
+            asset_id_reconcile_batch_size,
+            drop_schedule_interval,
+            update_revision_ttl,
+            update_revision_cleanup_interval,
+            import_batch_size,
+            transfer_backfill_interval,
+            transfer_backfill_batch_size,
+            transfer_backfill_depth,
+            db_pool_metrics_interval,
+            admin_api_key,
+            read_api_key,
+            solana_pay_label,
+            solana_pay_icon,
+            solana_pay_wait,
+            outbox_max_lag,
+            jito_bundle_endpoint,
+            jito_tip_account,
+            jito_tip_lamports,
+            jito_bundle_events,
+            helius_webhook_secret,
+            event_concurrency,
+            event_concurrency_limits,
+            event_weights,
+            custodial_wallet_resolver_endpoint,
+            custodial_wallet_resolver_api_key,
+            custodial_wallet_resolver_timeout,
+            export_sink_endpoint,
+            export_sink_api_key,
+            export_sink_timeout,
+            retry_policies,
+            circuit_breaker_threshold,
+            circuit_breaker_cooldown,
+        } = args;
 
         common.rt.block_on(async move {
-            let connection = Connection::new(db)
+            let metrics = Metrics::new()?;
+            let retries = Retries::new(
+                retry_policies,
+                circuit_breaker_threshold,
+                Duration::from_secs(circuit_breaker_cooldown),
+                metrics.clone(),
+            );
+
+            let connection = (|| Connection::new(db.clone()))
+                .retry(&retries.policy(RetryClass::Db).backoff())
+                .notify(|err: &Error, dur: Duration| {
+                    warn!("retrying database connection in {dur:?}: {err:?}");
+                })
                 .await
                 .context("failed to get database connection")?;
 
+            let dead_letter_producer = common
+                .producer_cfg
+                .clone()
+                .topic(dead_letters::TOPIC)
+                .build::<SolanaDeadLetter>()
+                .await?;
             let producer = common.producer_cfg.build::<SolanaNftEvents>().await?;
+            let dead_letters = DeadLetters::new(connection.clone(), dead_letter_producer);
+
+            let clusters = Clusters::new(solana, test_cluster, &metrics)?.with_retries(&retries);
+
+            for solana in clusters.iter() {
+                tokio::spawn(solana.rpc_pool().run_health_checks());
+            }
+
+            tokio::spawn(DasStatusMonitor::new(connection.clone(), clusters.clone()).run());
+
+            if let Some(interval) = metadata_reconcile_interval {
+                let reconciler = Reconciler::new(
+                    connection.clone(),
+                    clusters.clone(),
+                    metrics.clone(),
+                    Duration::from_secs(interval),
+                    metadata_reconcile_sample_size,
+                );
+                tokio::spawn(reconciler.run());
+            }
+
+            let asset_id_reconciler = AssetIdReconciler::new(
+                connection.clone(),
+                clusters.clone(),
+                Duration::from_secs(asset_id_reconcile_interval),
+                asset_id_reconcile_batch_size,
+            );
+            tokio::spawn(asset_id_reconciler.run());
+
+            let transfer_backfiller = TransferBackfiller::new(
+                connection.clone(),
+                clusters.clone(),
+                Duration::from_secs(transfer_backfill_interval),
+                transfer_backfill_batch_size,
+                transfer_backfill_depth,
+            );
+            tokio::spawn(transfer_backfiller.run());
+
+            let drop_scheduler = DropScheduler::new(
+                connection.clone(),
+                Duration::from_secs(drop_schedule_interval),
+            );
+            tokio::spawn(drop_scheduler.run());
 
-            let solana = Solana::new(solana)?;
+            let pool_monitor = PoolMonitor::new(
+                connection.clone(),
+                metrics.clone(),
+                Duration::from_secs(db_pool_metrics_interval),
+            );
+            tokio::spawn(pool_monitor.run());
 
-            let cons = common.consumer_cfg.build::<Services>().await?;
+            let revision_cleanup = RevisionCleanup::new(
+                connection.clone(),
+                Duration::from_secs(update_revision_ttl),
+                Duration::from_secs(update_revision_cleanup_interval),
+            );
+            tokio::spawn(revision_cleanup.run());
+
+            let solana = clusters.primary().clone();
+
+            let cons = common.consumer_cfg.build::<Consumed>().await?;
             // TODO: change these names once there are fewer in-flight feature branches
-            let import_processor =
-                import::Processor::new(solana.clone(), connection.clone(), producer.clone());
+            let import_processor = import::Processor::new(
+                clusters.clone(),
+                connection.clone(),
+                producer.clone(),
+                import_batch_size,
+            );
 
-            let metrics = Metrics::new()?;
-            let event_processor =
-                events::Processor::new(solana, connection, producer, metrics.clone());
-
-            tokio::spawn(async move {
-                cons.consume::<_, _, _, triage::BoxedSync>(
-                    |b| {
-                        b.with_jitter()
-                            .with_min_delay(Duration::from_millis(500))
-                            .with_max_delay(Duration::from_secs(90))
-                    },
-                    move |e| async move {
-                        if let Some(()) = import_processor
-                            .process(&e)
-                            .await
-                            .map_err(|e| Box::new(e) as triage::BoxedSync)?
-                        {
-                            return Ok(());
-                        }
-
-                        event_processor
-                            .process(e)
-                            .await
-                            .map_err(|e| Box::new(e) as triage::BoxedSync)
-                    },
-                )
-                .await;
+            let relay = outbox::Relay::new(
+                connection.clone(),
+                producer,
+                Duration::from_millis(outbox_relay_interval),
+                outbox_relay_batch_size,
+                event_schema_version.unwrap_or(compat::SCHEMA_VERSION),
+            );
+            if !disable_outbox_relay {
+                tokio::spawn(relay.clone().run());
+            }
+
+            let outbox_cleanup = outbox::Cleanup::new(
+                connection.clone(),
+                Duration::from_secs(outbox_retention),
+                Duration::from_secs(outbox_cleanup_interval),
+            );
+            tokio::spawn(outbox_cleanup.run());
+
+            let health_check = HealthCheck::new(
+                connection.clone(),
+                solana,
+                Duration::from_secs(outbox_max_lag),
+            );
+
+            let metadata_validator = validate_metadata_uris
+                .then(|| MetadataValidator::new(Duration::from_secs(metadata_validation_timeout)))
+                .transpose()?;
+
+            let jito = jito_bundle_endpoint
+                .zip(jito_tip_account)
+                .map(|(endpoint, tip_account)| {
+                    Jito::new(endpoint, tip_account, jito_tip_lamports, jito_bundle_events)
+                })
+                .transpose()?;
+
+            let custodial_wallets = custodial_wallet_resolver_endpoint
+                .map(|endpoint| {
+                    HttpWalletResolver::new(
+                        endpoint,
+                        custodial_wallet_resolver_api_key,
+                        Duration::from_secs(custodial_wallet_resolver_timeout),
+                    )
+                })
+                .transpose()?
+                .map(|resolver| CustodialWallets::new(connection.clone(), Arc::new(resolver)));
+
+            let export_sink = export_sink_endpoint
+                .map(|endpoint| {
+                    HttpExportSink::new(
+                        endpoint,
+                        export_sink_api_key,
+                        Duration::from_secs(export_sink_timeout),
+                    )
+                })
+                .transpose()?
+                .map(|sink| Arc::new(sink) as Arc<dyn ExportSink>);
+
+            let event_processor = events::Processor::new(
+                clusters,
+                connection.clone(),
+                metrics.clone(),
+                metadata_validator,
+                jito,
+                custodial_wallets,
+                export_sink,
+            );
+
+            let scheduler = Scheduler::new(
+                event_concurrency,
+                event_concurrency_limits,
+                event_weights,
+                metrics.clone(),
+            );
+
+            let mut routes = Route::new()
+                .at("/health", get(health).with(AddData::new(health_check)))
+                .at(
+                    "/metrics",
+                    get(metrics_handler).with(AddData::new(metrics.clone())),
+                );
+
+            if let Some(secret) = helius_webhook_secret {
+                routes = routes.nest("/webhooks", webhooks::routes(connection.clone(), secret));
+            }
+
+            if let Some(api_key) = read_api_key {
+                routes = routes.nest("/read", read_api::routes(connection.clone(), api_key));
+            }
+
+            if let Some(label) = solana_pay_label {
+                routes = routes.nest(
+                    "/solana-pay",
+                    solana_pay::routes(
+                        event_processor.clone(),
+                        label,
+                        solana_pay_icon,
+                        Duration::from_secs(solana_pay_wait),
+                    ),
+                );
+            }
+
+            if let Some(api_key) = admin_api_key {
+                routes = routes.nest(
+                    "/admin",
+                    admin::routes(
+                        connection,
+                        event_processor.clone(),
+                        import_processor.clone(),
+                        api_key,
+                    ),
+                );
+            }
+
+            // Every message holds a read guard while it is processed. Taking the
+            // write guard on shutdown waits for in-flight messages and, since
+            // the lock is fair, keeps new messages from starting.
+            let in_flight = Arc::new(RwLock::new(()));
+
+            let consumer = tokio::spawn({
+                let in_flight = in_flight.clone();
+                let kafka_policy = retries.policy(RetryClass::Kafka);
+
+                async move {
+                    cons.consume::<_, _, _, triage::BoxedSync>(
+                        |b| kafka_policy.apply(b),
+                        move |consumed: Consumed| async move {
+                            consumed.observe(&metrics);
+                            let e = consumed.msg;
+
+                            let _guard = in_flight.read().await;
+                            let _permit = scheduler.acquire(Lane::of(&e)).await;
+
+                            let res = match import_processor.process(&e).await {
+                                Ok(Some(())) => Ok(()),
+                                Ok(None) => event_processor
+                                    .process(e.clone())
+                                    .await
+                                    .map_err(|e| Box::new(e) as triage::BoxedSync),
+                                Err(e) => Err(Box::new(e) as triage::BoxedSync),
+                            };
+
+                            if let Err(err) = &res {
+                                if err.severity() == Severity::Permanent {
+                                    if let Err(dl) = dead_letters.record(&e, err).await {
+                                        error!("{:?}", dl.context("failed to store dead letter"));
+                                    }
+                                }
+                            }
+
+                            res
+                        },
+                    )
+                    .await;
+                }
             });
-            Server::new(TcpListener::bind(format!("0.0.0.0:{port}")))
-                .run(
-                    Route::new()
-                        .at("/health", get(health))
-                        .at("/metrics", get(metrics_handler).with(AddData::new(metrics))),
-                )
-                .await
-                .context("failed to build graphql server")
+
+            let server = Server::new(TcpListener::bind(format!("0.0.0.0:{port}"))).run(routes);
+
+            tokio::select! {
+                res = server => res.context("failed to build graphql server"),
+                res = shutdown_signal() => {
+                    res.context("failed to listen for shutdown signals")?;
+
+                    info!("shutdown requested, draining in-flight messages");
+                    let _drained = in_flight.write().await;
+                    consumer.abort();
+
+                    if !disable_outbox_relay {
+                        relay.drain().await.context("failed to flush outbox on shutdown")?;
+                    }
+                    info!("shutdown complete");
+
+                    Ok(())
+                },
+            }
         })
     });
 }
+
+/// Resolves once the process receives SIGINT or SIGTERM.
+async fn shutdown_signal() -> Result<()> {
+    let mut terminate = unix_signal(SignalKind::terminate())?;
+
+    tokio::select! {
+        res = signal::ctrl_c() => res?,
+        _ = terminate.recv() => (),
+    }
+
+    Ok(())
+}
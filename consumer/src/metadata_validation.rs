@@ -0,0 +1,274 @@
+use std::{net::IpAddr, sync::Arc};
+
+use holaplex_hub_nfts_solana_core::proto::{nft_events::Event as NftEvent, MasterEdition};
+use hub_core::{
+    prelude::*,
+    reqwest::{
+        self,
+        dns::{Addrs, Name, Resolve, Resolving},
+        redirect, StatusCode, Url,
+    },
+    thiserror, tokio,
+};
+
+use crate::asset_api;
+
+/// Largest metadata document read before giving up on it
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MetadataUriError {
+    #[error("{0} is not an HTTP URL")]
+    Invalid(String),
+    #[error("{0} points at a private address")]
+    PrivateAddress(String),
+    #[error("Request to {0} failed")]
+    Request(String, #[source] reqwest::Error),
+    #[error("{0} responded with {1}")]
+    Status(String, StatusCode),
+    #[error("Metadata JSON at {0} is larger than {MAX_METADATA_BYTES} bytes")]
+    TooLarge(String),
+    #[error("Unable to parse metadata JSON at {0}")]
+    Json(String, #[source] serde_json::Error),
+}
+
+/// Whether `ip` may be reached from the open internet. Requests for
+/// metadata URIs must not reach the network the consumer runs in.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            // 100.64.0.0/10 is shared by carrier-grade NATs
+            let shared = ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64;
+
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || shared)
+        },
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(ip.into());
+            }
+
+            // fc00::/7 is unique local and fe80::/10 link-local
+            let unique_local = ip.segments()[0] & 0xfe00 == 0xfc00;
+            let link_local = ip.segments()[0] & 0xffc0 == 0xfe80;
+
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        },
+    }
+}
+
+/// The address `url` names directly rather than by host name, if any
+fn literal_ip(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+
+    host.parse().ok()
+}
+
+/// Resolves host names with the system resolver, leaving out private
+/// addresses so neither redirects nor rebound DNS records can reach them
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            let addrs: Addrs = Box::new(addrs.into_iter());
+
+            Ok(addrs)
+        })
+    }
+}
+
+/// Checks that the metadata URI of a request, and the image it refers to,
+/// resolve before a transaction pointing at them is assembled.
+#[derive(Debug, Clone)]
+pub struct MetadataValidator {
+    client: reqwest::Client,
+}
+
+impl MetadataValidator {
+    /// Build a validator whose requests time out after `timeout`.
+    ///
+    /// # Errors
+    /// This function fails if the HTTP client cannot be built
+    pub fn new(timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if literal_ip(attempt.url()).map_or(false, |ip| !is_public(ip)) {
+                    attempt.error("redirected to a private address")
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()
+            .context("failed to build metadata validation client")?;
+
+        Ok(Self { client })
+    }
+
+    /// The metadata URI referenced by a create, mint or update request
+    #[must_use]
+    pub fn metadata_uri(event: &NftEvent) -> Option<&str> {
+        let master_edition =
+            |m: &Option<MasterEdition>| m.as_ref().map(|m| m.metadata_uri.as_str());
+
+        match event {
+            NftEvent::SolanaCreateEditionDrop(p)
+            | NftEvent::SolanaCreateCollection(p)
+            | NftEvent::SolanaUpdateEditionDrop(p)
+            | NftEvent::SolanaUpdateCollection(p)
+            | NftEvent::SolanaCreateOpenDrop(p)
+            | NftEvent::SolanaUpdateOpenDrop(p)
+            | NftEvent::SolanaRetryEditionDrop(p)
+            | NftEvent::SolanaRetryCreateCollection(p)
+            | NftEvent::SolanaRetryOpenDrop(p) => master_edition(&p.master_edition),
+            NftEvent::SolanaMintToCollection(p)
+            | NftEvent::SolanaMintOpenDrop(p)
+            | NftEvent::SolanaRetryMintToCollection(p)
+            | NftEvent::SolanaRetryMintOpenDrop(p) => {
+                p.metadata.as_ref().map(|m| m.metadata_uri.as_str())
+            },
+            NftEvent::SolanaUpdatedCollectionMint(p) => p
+                .metadata
+                .as_ref()
+                .map(|m| m.metadata_uri.as_str())
+                .or_else(|| p.metadata_patch.as_ref()?.metadata_uri.as_deref()),
+            NftEvent::SolanaUpdateCollectionAssets(p) => Some(p.metadata_uri.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Check that `uri` resolves and, if its metadata names an image, that
+    /// the image resolves too.
+    ///
+    /// # Errors
+    /// This function fails if either URI is not a public HTTP URL, cannot be
+    /// reached or responds with an error status, or the metadata is not
+    /// valid JSON of at most [`MAX_METADATA_BYTES`]
+    pub async fn validate(&self, uri: &str) -> Result<(), MetadataUriError> {
+        self.head(uri).await?;
+
+        let mut res = self
+            .client
+            .get(uri)
+            .send()
+            .await
+            .map_err(|e| MetadataUriError::Request(uri.to_owned(), e))?;
+
+        if !res.status().is_success() {
+            return Err(MetadataUriError::Status(uri.to_owned(), res.status()));
+        }
+
+        let mut body = Vec::new();
+
+        while let Some(chunk) = res
+            .chunk()
+            .await
+            .map_err(|e| MetadataUriError::Request(uri.to_owned(), e))?
+        {
+            if body.len() + chunk.len() > MAX_METADATA_BYTES {
+                return Err(MetadataUriError::TooLarge(uri.to_owned()));
+            }
+
+            body.extend_from_slice(&chunk);
+        }
+
+        let metadata: asset_api::Metadata =
+            serde_json::from_slice(&body).map_err(|e| MetadataUriError::Json(uri.to_owned(), e))?;
+
+        match metadata.image {
+            Some(image) if !image.is_empty() => self.head(&image).await,
+            _ => Ok(()),
+        }
+    }
+
+    async fn head(&self, uri: &str) -> Result<(), MetadataUriError> {
+        let url = Url::parse(uri).map_err(|_| MetadataUriError::Invalid(uri.to_owned()))?;
+
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(MetadataUriError::Invalid(uri.to_owned()));
+        }
+
+        if literal_ip(&url).map_or(false, |ip| !is_public(ip)) {
+            return Err(MetadataUriError::PrivateAddress(uri.to_owned()));
+        }
+
+        let res = self
+            .client
+            .head(uri)
+            .send()
+            .await
+            .map_err(|e| MetadataUriError::Request(uri.to_owned(), e))?;
+
+        // Some gateways only serve GET, which is checked separately
+        if res.status().is_success() || res.status() == StatusCode::METHOD_NOT_ALLOWED {
+            Ok(())
+        } else {
+            Err(MetadataUriError::Status(uri.to_owned(), res.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    #[test]
+    fn rejects_private_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} should be private");
+        }
+
+        for ip in ["1.1.1.1", "104.18.0.1", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} should be public");
+        }
+    }
+
+    #[test]
+    fn reads_literal_addresses_from_urls() {
+        let ip = |url: &str| literal_ip(&Url::parse(url).unwrap());
+
+        assert_eq!(
+            ip("http://169.254.169.254/latest"),
+            Some(Ipv4Addr::new(169, 254, 169, 254).into())
+        );
+        assert_eq!(ip("http://[::1]:8080/"), Some(Ipv6Addr::LOCALHOST.into()));
+        assert_eq!(ip("https://arweave.net/abc"), None);
+    }
+}
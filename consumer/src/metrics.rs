@@ -10,6 +10,20 @@ pub struct Metrics {
     pub provider: MeterProvider,
     pub rpc_tx_submission_duration_ms_bucket: Histogram<i64>,
     pub rpc_tx_assembly_duration_ms_bucket: Histogram<i64>,
+    pub rpc_health_check_duration_ms_bucket: Histogram<i64>,
+    pub rpc_rate_limit_wait_duration_ms_bucket: Histogram<i64>,
+    pub rpc_healthy_endpoints: UpDownCounter<i64>,
+    pub events_processed_counter: Counter<u64>,
+    pub event_failures_counter: Counter<u64>,
+    pub metadata_drift_counter: Counter<u64>,
+    pub event_queue_depth: UpDownCounter<i64>,
+    pub events_in_flight: UpDownCounter<i64>,
+    pub messages_consumed_counter: Counter<u64>,
+    pub consumer_lag_ms_bucket: Histogram<i64>,
+    pub event_processing_duration_ms_bucket: Histogram<i64>,
+    pub circuit_breakers_open: UpDownCounter<i64>,
+    pub db_pool_connections: UpDownCounter<i64>,
+    pub db_pool_acquire_duration_ms_bucket: Histogram<i64>,
 }
 
 impl Metrics {
@@ -45,11 +59,102 @@ impl Metrics {
             .with_description("Transaction assembly duration time in milliseconds.")
             .init();
 
+        let rpc_health_check_duration_ms_bucket = meter
+            .i64_histogram("rpc_health_check.time")
+            .with_unit(Unit::new("ms"))
+            .with_description("RPC endpoint health check duration time in milliseconds.")
+            .init();
+
+        let rpc_rate_limit_wait_duration_ms_bucket = meter
+            .i64_histogram("rpc_rate_limit_wait.time")
+            .with_unit(Unit::new("ms"))
+            .with_description("Time RPC requests spent queued by the rate limiter in milliseconds.")
+            .init();
+
+        let rpc_healthy_endpoints = meter
+            .i64_up_down_counter("rpc_healthy_endpoints")
+            .with_description("Number of RPC endpoints currently passing health checks.")
+            .init();
+
+        let events_processed_counter = meter
+            .u64_counter("events_processed")
+            .with_description("Number of events processed by event kind and outcome.")
+            .init();
+
+        let event_failures_counter = meter
+            .u64_counter("event_failures")
+            .with_description("Number of failed events by event kind and failure reason.")
+            .init();
+
+        let metadata_drift_counter = meter
+            .u64_counter("metadata_drift")
+            .with_description("Number of mints whose on-chain metadata differs from the database.")
+            .init();
+
+        let event_queue_depth = meter
+            .i64_up_down_counter("event_queue_depth")
+            .with_description("Number of events waiting for a processing slot by event kind.")
+            .init();
+
+        let events_in_flight = meter
+            .i64_up_down_counter("events_in_flight")
+            .with_description("Number of events currently being processed by event kind.")
+            .init();
+
+        let messages_consumed_counter = meter
+            .u64_counter("messages_consumed")
+            .with_description("Number of messages consumed by topic.")
+            .init();
+
+        let consumer_lag_ms_bucket = meter
+            .i64_histogram("consumer_lag.time")
+            .with_unit(Unit::new("ms"))
+            .with_description(
+                "Time between a message being produced and consumed in milliseconds, by topic.",
+            )
+            .init();
+
+        let event_processing_duration_ms_bucket = meter
+            .i64_histogram("event_processing.time")
+            .with_unit(Unit::new("ms"))
+            .with_description("Time spent processing a message in milliseconds by event kind.")
+            .init();
+
+        let circuit_breakers_open = meter
+            .i64_up_down_counter("circuit_breaker_open")
+            .with_description("Number of open circuit breakers by upstream class.")
+            .init();
+
+        let db_pool_connections = meter
+            .i64_up_down_counter("db_pool_connections")
+            .with_description("Number of open database connections by pool and state.")
+            .init();
+
+        let db_pool_acquire_duration_ms_bucket = meter
+            .i64_histogram("db_pool_acquire.time")
+            .with_unit(Unit::new("ms"))
+            .with_description("Time taken to acquire a database connection in milliseconds.")
+            .init();
+
         Ok(Self {
             registry,
             provider,
             rpc_tx_submission_duration_ms_bucket,
             rpc_tx_assembly_duration_ms_bucket,
+            rpc_health_check_duration_ms_bucket,
+            rpc_rate_limit_wait_duration_ms_bucket,
+            rpc_healthy_endpoints,
+            events_processed_counter,
+            event_failures_counter,
+            metadata_drift_counter,
+            event_queue_depth,
+            events_in_flight,
+            messages_consumed_counter,
+            consumer_lag_ms_bucket,
+            event_processing_duration_ms_bucket,
+            circuit_breakers_open,
+            db_pool_connections,
+            db_pool_acquire_duration_ms_bucket,
         })
     }
 }
@@ -0,0 +1,89 @@
+//! Durable nonce accounts leased to events from the pools of their
+//! treasuries. Messages paid by a treasury with a free nonce account use its
+//! stored nonce in place of a recent blockhash, so they never expire while
+//! awaiting signatures.
+
+use holaplex_hub_nfts_solana_core::{db::Connection, NonceAccount};
+use holaplex_hub_nfts_solana_entity::sea_orm_active_enums::SolanaCluster;
+use hub_core::{anyhow::Result, chrono, prelude::*, uuid::Uuid};
+use solana_program::pubkey::Pubkey;
+
+use crate::events::chunk_id;
+
+/// Leases older than this are taken back, since their events never settled
+const LEASE_HOURS: i64 = 24;
+
+/// The nonce account pools messages assembled for an event lease from
+#[derive(Clone)]
+pub struct Nonces {
+    db: Connection,
+    event_id: Uuid,
+}
+
+impl Nonces {
+    #[must_use]
+    pub fn new(db: Connection, event_id: Uuid) -> Self {
+        Self { db, event_id }
+    }
+
+    /// The pools leased from by the `chunk`th of several transactions made
+    /// for the event, keyed by the id of that transaction
+    #[must_use]
+    pub fn for_chunk(&self, chunk: usize) -> Self {
+        Self {
+            db: self.db.clone(),
+            event_id: chunk_id(self.event_id, chunk),
+        }
+    }
+
+    /// Lease a nonce account of `authority` to the event, if one is free
+    ///
+    /// # Errors
+    /// This function fails if the pool cannot be read or the lease written
+    pub async fn lease(
+        &self,
+        cluster: SolanaCluster,
+        authority: &Pubkey,
+    ) -> Result<Option<Pubkey>> {
+        NonceAccount::lease(
+            self.db.get(),
+            cluster,
+            authority.to_string(),
+            self.event_id,
+            chrono::Duration::hours(LEASE_HOURS),
+        )
+        .await?
+        .map(|account| account.address.parse())
+        .transpose()
+        .map_err(Into::into)
+    }
+
+    /// Nonce accounts of `authority` held back until their nonces are
+    /// advanced, with the stale nonce of each
+    ///
+    /// # Errors
+    /// This function fails if the pool cannot be read
+    pub async fn stale(
+        &self,
+        cluster: SolanaCluster,
+        authority: &Pubkey,
+    ) -> Result<Vec<(Pubkey, String)>> {
+        let stale = NonceAccount::find_stale(self.db.get(), cluster, authority.to_string()).await?;
+
+        Ok(stale
+            .into_iter()
+            .filter_map(|account| Some((account.address.parse().ok()?, account.stale_nonce?)))
+            .collect())
+    }
+
+    /// Return the nonce account at `address` to its pool once its stale
+    /// nonce has been advanced
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn advanced(&self, address: &Pubkey) -> Result<()> {
+        NonceAccount::advanced(self.db.get(), address.to_string())
+            .await
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,169 @@
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{compat, SolanaNftEvents},
+    Outbox,
+};
+use holaplex_hub_nfts_solana_entity::outbox;
+use hub_core::{
+    chrono::{self, Utc},
+    prelude::*,
+    producer::Producer,
+    tokio,
+};
+
+/// How long a relay may hold claimed events before another relay takes them
+/// over, in case it exited before producing them
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+/// Publishes events queued in the outbox table and marks them as sent.
+/// Events are queued in the same database transaction as the models they
+/// refer to, so an event is never lost if the process exits between writing
+/// a model and producing its event.
+///
+/// Events sharing a key are only produced in order while a single relay
+/// runs, so all but one replica should be started with
+/// `--disable-outbox-relay`.
+#[derive(Debug, Clone)]
+pub struct Relay {
+    db: Connection,
+    producer: Producer<SolanaNftEvents>,
+    interval: Duration,
+    batch_size: u64,
+    schema_version: u32,
+}
+
+impl Relay {
+    #[must_use]
+    pub fn new(
+        db: Connection,
+        producer: Producer<SolanaNftEvents>,
+        interval: Duration,
+        batch_size: u64,
+        schema_version: u32,
+    ) -> Self {
+        Self {
+            db,
+            producer,
+            interval,
+            batch_size,
+            schema_version,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.relay_pending().await {
+                error!("{:?}", e.context("failed to relay outbox events"));
+            }
+        }
+    }
+
+    /// Publish pending events until the outbox is empty. Used on shutdown so
+    /// events queued by in-flight handlers are not left for the next deploy.
+    ///
+    /// # Errors
+    /// This function fails if a batch of pending events cannot be relayed
+    pub async fn drain(&self) -> Result<()> {
+        while self.relay_pending().await? > 0 {}
+
+        Ok(())
+    }
+
+    /// Publish a batch of pending events, returning the number of events
+    /// sent or set aside as failed. The batch is claimed first, skipping
+    /// rows claimed by concurrent relays, so no database transaction is held
+    /// open while events are produced.
+    ///
+    /// # Errors
+    /// This function fails if the outbox cannot be read or updated, or an
+    /// event cannot be produced
+    pub async fn relay_pending(&self) -> Result<usize> {
+        let lease = chrono::Duration::from_std(CLAIM_LEASE)?;
+        let pending = Outbox::claim_pending(self.db.get(), self.batch_size, lease).await?;
+
+        self.publish(pending).await
+    }
+
+    async fn publish(&self, pending: Vec<outbox::Model>) -> Result<usize> {
+        let mut relayed = 0;
+        let mut pending = pending.into_iter();
+
+        while let Some(row) = pending.next() {
+            let (key, event) = match Outbox::decode(&row) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    error!("failed to decode outbox event {}: {e}", row.id);
+                    Outbox::mark_failed(self.db.get(), row, e.to_string()).await?;
+                    relayed += 1;
+                    continue;
+                },
+            };
+
+            let event = compat::downgrade(event, self.schema_version);
+
+            if let Err(e) = self.producer.send(Some(&event), Some(&key)).await {
+                let unsent = std::iter::once(row.id).chain(pending.map(|row| row.id));
+                Outbox::release(self.db.get(), unsent).await?;
+
+                return Err(e.into());
+            }
+
+            Outbox::mark_sent(self.db.get(), row).await?;
+            relayed += 1;
+        }
+
+        Ok(relayed)
+    }
+}
+
+/// Periodically deletes events sent longer ago than the retention period, so
+/// the outbox only grows with the events still pending
+#[derive(Debug, Clone)]
+pub struct Cleanup {
+    db: Connection,
+    retention: Duration,
+    interval: Duration,
+}
+
+impl Cleanup {
+    #[must_use]
+    pub fn new(db: Connection, retention: Duration, interval: Duration) -> Self {
+        Self {
+            db,
+            retention,
+            interval,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.clean().await {
+                error!("{:?}", e.context("failed to delete sent outbox events"));
+            }
+        }
+    }
+
+    /// Delete the events sent more than the retention period ago
+    ///
+    /// # Errors
+    /// This function fails if the retention period is out of range or the
+    /// rows cannot be deleted
+    pub async fn clean(&self) -> Result<()> {
+        let cutoff = Utc::now().naive_utc() - chrono::Duration::from_std(self.retention)?;
+        let deleted = Outbox::delete_sent(self.db.get(), cutoff).await?;
+
+        if deleted > 0 {
+            info!("deleted {deleted} sent outbox events");
+        }
+
+        Ok(())
+    }
+}
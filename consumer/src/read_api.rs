@@ -0,0 +1,271 @@
+use std::fmt;
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection, Collection, CollectionMint, ImportStatus, MintCursor,
+};
+use holaplex_hub_nfts_solana_entity::{
+    collection_imports, collection_mints, collections, mint_transfer_histories,
+};
+use hub_core::{prelude::*, uuid::Uuid};
+use poem::{
+    get, handler,
+    http::StatusCode,
+    middleware::AddData,
+    web::{Data, Json, Path, Query},
+    Endpoint, EndpointExt, Error, Route,
+};
+
+use crate::auth;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Clone)]
+struct ReadState {
+    db: Connection,
+}
+
+/// Read-only routes serving indexed collections, mints, transfers and import
+/// progress from the database. Every request must carry
+/// `Authorization: Bearer <api_key>`.
+pub fn routes(db: Connection, api_key: String) -> impl Endpoint {
+    Route::new()
+        .at("/collections/:id", get(get_collection))
+        .at("/collections/:id/mints", get(list_collection_mints))
+        .at("/collections/:id/import", get(get_import_status))
+        .at("/mints/:id", get(get_mint))
+        .at("/mints/:id/transfers", get(list_transfers))
+        .with(AddData::new(ReadState { db }))
+        .with(auth::RequireSecret::bearer(api_key))
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Page {
+    cursor: Option<String>,
+    limit: Option<u64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct CollectionInfo {
+    id: Uuid,
+    mint: String,
+    metadata: String,
+    master_edition: String,
+    update_authority: String,
+    owner: String,
+    seller_fee_basis_points: Option<i32>,
+    max_supply: Option<i64>,
+    editions_minted: i64,
+    soulbound: bool,
+    metadata_locked: bool,
+    created_at: String,
+}
+
+impl From<collections::Model> for CollectionInfo {
+    fn from(model: collections::Model) -> Self {
+        Self {
+            id: model.id,
+            mint: model.mint,
+            metadata: model.metadata,
+            master_edition: model.master_edition,
+            update_authority: model.update_authority,
+            owner: model.owner,
+            seller_fee_basis_points: model.seller_fee_basis_points,
+            max_supply: model.max_supply,
+            editions_minted: model.editions_minted,
+            soulbound: model.soulbound,
+            metadata_locked: model.metadata_locked_at.is_some(),
+            created_at: model.created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct MintInfo {
+    id: Uuid,
+    collection_id: Uuid,
+    mint: String,
+    owner: String,
+    associated_token_account: String,
+    compressed: bool,
+    edition: Option<i64>,
+    metadata_locked: bool,
+    primary_sale_at: Option<String>,
+    created_at: String,
+}
+
+impl From<collection_mints::Model> for MintInfo {
+    fn from(model: collection_mints::Model) -> Self {
+        Self {
+            id: model.id,
+            collection_id: model.collection_id,
+            mint: model.mint,
+            owner: model.owner,
+            associated_token_account: model.associated_token_account,
+            compressed: model.compressed,
+            edition: model.edition,
+            metadata_locked: model.metadata_locked_at.is_some(),
+            primary_sale_at: model.primary_sale_at.map(|at| at.to_string()),
+            created_at: model.created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct MintPage {
+    mints: Vec<MintInfo>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Transfer {
+    sender: String,
+    recipient: String,
+    signature: String,
+    slot: i64,
+    created_at: String,
+}
+
+impl From<mint_transfer_histories::Model> for Transfer {
+    fn from(model: mint_transfer_histories::Model) -> Self {
+        let mint_transfer_histories::Model {
+            sender,
+            recipient,
+            signature,
+            slot,
+            created_at,
+            ..
+        } = model;
+
+        Self {
+            sender,
+            recipient,
+            signature,
+            slot,
+            created_at: created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ImportProgress {
+    mint_address: String,
+    imported: i64,
+    skipped_burned: i64,
+    skipped_fungible: i64,
+    skipped_unverified: i64,
+    completed: bool,
+    started_at: String,
+    updated_at: String,
+    completed_at: Option<String>,
+}
+
+impl From<collection_imports::Model> for ImportProgress {
+    fn from(model: collection_imports::Model) -> Self {
+        Self {
+            mint_address: model.mint_address,
+            imported: model.imported,
+            skipped_burned: model.skipped_burned,
+            skipped_fungible: model.skipped_fungible,
+            skipped_unverified: model.skipped_unverified,
+            completed: model.completed_at.is_some(),
+            started_at: model.started_at.to_string(),
+            updated_at: model.updated_at.to_string(),
+            completed_at: model.completed_at.map(|at| at.to_string()),
+        }
+    }
+}
+
+fn internal(e: impl fmt::Debug) -> Error {
+    error!("read API request failed: {e:?}");
+    Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn not_found() -> Error {
+    Error::from_status(StatusCode::NOT_FOUND)
+}
+
+#[handler]
+async fn get_collection(
+    Data(state): Data<&ReadState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<Json<CollectionInfo>> {
+    let collection = Collection::find_by_id(state.db.read(), id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(collection.into()))
+}
+
+#[handler]
+async fn list_collection_mints(
+    Data(state): Data<&ReadState>,
+    Path(id): Path<Uuid>,
+    Query(Page { cursor, limit }): Query<Page>,
+) -> poem::Result<Json<MintPage>> {
+    let cursor = cursor
+        .map(|c| {
+            MintCursor::decode(&c)
+                .ok_or_else(|| Error::from_string("invalid cursor", StatusCode::BAD_REQUEST))
+        })
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let mints = CollectionMint::find_by_collection(state.db.read(), id, cursor, limit)
+        .await
+        .map_err(internal)?;
+
+    // A full page may be followed by more mints
+    let next_cursor = (mints.len() as u64 == limit)
+        .then(|| mints.last().map(|mint| MintCursor::from(mint).encode()))
+        .flatten();
+
+    Ok(Json(MintPage {
+        mints: mints.into_iter().map(Into::into).collect(),
+        next_cursor,
+    }))
+}
+
+#[handler]
+async fn get_import_status(
+    Data(state): Data<&ReadState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<Json<ImportProgress>> {
+    let status = ImportStatus::find_by_collection(state.db.read(), id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(status.into()))
+}
+
+#[handler]
+async fn get_mint(
+    Data(state): Data<&ReadState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<Json<MintInfo>> {
+    let mint = CollectionMint::find_by_id(state.db.read(), id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(mint.into()))
+}
+
+#[handler]
+async fn list_transfers(
+    Data(state): Data<&ReadState>,
+    Path(id): Path<Uuid>,
+) -> poem::Result<Json<Vec<Transfer>>> {
+    let conn = state.db.read();
+
+    CollectionMint::find_by_id(conn, id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+
+    let history = CollectionMint::history(conn, id).await.map_err(internal)?;
+
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{
+        solana_nft_events::Event as SolanaNftEvent, SolanaMetadataDrift, SolanaNftEventKey,
+        SolanaNftEvents,
+    },
+    CollectionMint, Outbox, UpdateRevision,
+};
+use holaplex_hub_nfts_solana_entity::{
+    collection_mints, collections, sea_orm_active_enums::SolanaCluster,
+};
+use hub_core::{anyhow::Result, metrics::KeyValue, prelude::*, tokio};
+use mpl_token_metadata::{
+    pda::find_metadata_account,
+    state::{Metadata, TokenMetadataAccount},
+};
+use solana_program::pubkey::Pubkey;
+
+use crate::{cluster::Clusters, metrics::Metrics, solana::Solana};
+
+/// `getMultipleAccounts` accepts at most 100 addresses per request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+type MintWithCollection = (collection_mints::Model, collections::Model);
+
+/// Periodically compares the on-chain metadata of a sample of mints with
+/// what the database expects, emitting a drift event for each mismatch.
+#[derive(Clone)]
+pub struct Reconciler {
+    db: Connection,
+    clusters: Clusters,
+    metrics: Metrics,
+    interval: Duration,
+    sample_size: u64,
+}
+
+impl Reconciler {
+    #[must_use]
+    pub fn new(
+        db: Connection,
+        clusters: Clusters,
+        metrics: Metrics,
+        interval: Duration,
+        sample_size: u64,
+    ) -> Self {
+        Self {
+            db,
+            clusters,
+            metrics,
+            interval,
+            sample_size,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.reconcile().await {
+                error!("{:?}", e.context("failed to reconcile on-chain metadata"));
+            }
+        }
+    }
+
+    /// Check a random sample of mints against their on-chain metadata.
+    /// Mints with an update in flight are left out, since their metadata is
+    /// about to change.
+    ///
+    /// # Errors
+    /// This function fails if the sample cannot be loaded, metadata accounts
+    /// cannot be fetched, or a drift event cannot be queued
+    pub async fn reconcile(&self) -> Result<()> {
+        let conn = self.db.get();
+        let sample = CollectionMint::sample_with_collection(conn, self.sample_size).await?;
+        let updating =
+            UpdateRevision::find_in_flight(conn, sample.iter().map(|(mint, _)| mint.id)).await?;
+
+        let mut by_cluster: HashMap<SolanaCluster, Vec<MintWithCollection>> = HashMap::new();
+
+        for (mint, collection) in sample {
+            if updating.contains(&mint.id) {
+                continue;
+            }
+
+            if let Some(collection) = collection {
+                by_cluster
+                    .entry(collection.cluster)
+                    .or_default()
+                    .push((mint, collection));
+            }
+        }
+
+        for (cluster, mints) in by_cluster {
+            let Some(solana) = self.clusters.get(cluster) else {
+                continue;
+            };
+
+            for chunk in mints.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+                self.reconcile_chunk(solana, chunk).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reconcile_chunk(&self, solana: &Solana, mints: &[MintWithCollection]) -> Result<()> {
+        let addresses = mints
+            .iter()
+            .map(|(mint, _)| Ok(find_metadata_account(&mint.mint.parse()?).0))
+            .collect::<Result<Vec<Pubkey>>>()?;
+        let addresses = &addresses;
+
+        let accounts = solana
+            .rpc_pool()
+            .call(|rpc| async move { rpc.get_multiple_accounts(addresses).await })
+            .await?;
+
+        for ((mint, collection), account) in mints.iter().zip(accounts) {
+            let metadata = match account.map(|a| Metadata::safe_deserialize(&a.data)) {
+                Some(Ok(metadata)) => Some(metadata),
+                Some(Err(e)) => {
+                    warn!("failed to deserialize metadata of mint {}: {e}", mint.id);
+                    continue;
+                },
+                None => None,
+            };
+
+            if let Some(drift) = drift(mint, collection, metadata.as_ref()) {
+                self.report(mint, collection, drift).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn report(
+        &self,
+        mint: &collection_mints::Model,
+        collection: &collections::Model,
+        drift: SolanaMetadataDrift,
+    ) -> Result<()> {
+        warn!(
+            "on-chain metadata of mint {} drifted: update authority {:?}, collection {:?} \
+             (verified: {})",
+            mint.id,
+            drift.actual_update_authority,
+            drift.actual_collection,
+            drift.collection_verified
+        );
+
+        let key = SolanaNftEventKey {
+            id: mint.id.to_string(),
+            correlation_id: mint.id.to_string(),
+            project_id: collection.project_id.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        let event = SolanaNftEvents::new(SolanaNftEvent::MetadataDrift(drift));
+
+        Outbox::enqueue(self.db.get(), &key, &event).await?;
+
+        self.metrics.metadata_drift_counter.add(1, &[KeyValue::new(
+            "cluster",
+            collection.cluster.to_string(),
+        )]);
+
+        Ok(())
+    }
+}
+
+/// Compare a mint's metadata with the update authority and verified
+/// collection expected from its collection. A missing metadata account is
+/// always reported.
+fn drift(
+    mint: &collection_mints::Model,
+    collection: &collections::Model,
+    metadata: Option<&Metadata>,
+) -> Option<SolanaMetadataDrift> {
+    let actual_update_authority = metadata.map(|m| m.update_authority.to_string());
+    let actual_collection = metadata
+        .and_then(|m| m.collection.as_ref())
+        .map(|c| (c.key.to_string(), c.verified));
+
+    let matches = actual_update_authority.as_deref() == Some(collection.update_authority.as_str())
+        && actual_collection.as_ref().map_or(false, |(key, verified)| {
+            *verified && *key == collection.mint
+        });
+
+    if matches {
+        return None;
+    }
+
+    let (actual_collection, collection_verified) = actual_collection.unwrap_or_default();
+
+    Some(SolanaMetadataDrift {
+        collection_mint_id: mint.id.to_string(),
+        collection_id: collection.id.to_string(),
+        mint: mint.mint.clone(),
+        expected_update_authority: collection.update_authority.clone(),
+        actual_update_authority: actual_update_authority.unwrap_or_default(),
+        expected_collection: collection.mint.clone(),
+        actual_collection,
+        collection_verified,
+    })
+}
@@ -0,0 +1,408 @@
+//! Retry budgets and circuit breakers for calls to upstream services.
+//!
+//! Every class of upstream has its own [`RetryPolicy`], which can be
+//! overridden with `--retry-policies`. Solana RPC and DAS calls also go
+//! through a [`CircuitBreaker`] that trips after a run of consecutive
+//! transport failures, failing calls immediately instead of retrying them
+//! until the cooldown has passed.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+use hub_core::{
+    anyhow::Error, backon::ExponentialBuilder, clap, metrics::KeyValue, prelude::*,
+    tokio::sync::watch,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+
+use crate::metrics::Metrics;
+
+/// Prefix of the message of errors returned while a breaker is open
+const CIRCUIT_OPEN: &str = "circuit breaker open";
+
+/// A class of upstream whose calls share a retry policy and circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum RetryClass {
+    Rpc,
+    Das,
+    Db,
+    Kafka,
+}
+
+impl RetryClass {
+    const ALL: [Self; 4] = [Self::Rpc, Self::Das, Self::Db, Self::Kafka];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rpc => "rpc",
+            Self::Das => "das",
+            Self::Db => "db",
+            Self::Kafka => "kafka",
+        }
+    }
+}
+
+/// How often and how long to retry a failed call. Delays grow
+/// exponentially from `min_delay` to `max_delay` with jitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first, or `None` to retry indefinitely
+    pub max_times: Option<usize>,
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    #[must_use]
+    pub fn default_for(class: RetryClass) -> Self {
+        let (max_times, min_delay, max_delay) = match class {
+            RetryClass::Rpc => (Some(25), Duration::from_millis(30), Duration::from_secs(1)),
+            RetryClass::Das => (Some(5), Duration::from_millis(200), Duration::from_secs(5)),
+            RetryClass::Db => (Some(5), Duration::from_millis(500), Duration::from_secs(10)),
+            RetryClass::Kafka => (None, Duration::from_millis(500), Duration::from_secs(90)),
+        };
+
+        Self {
+            max_times,
+            min_delay,
+            max_delay,
+        }
+    }
+
+    #[must_use]
+    pub fn backoff(&self) -> ExponentialBuilder {
+        self.apply(ExponentialBuilder::default())
+    }
+
+    /// Configure `builder` with this policy
+    #[must_use]
+    pub fn apply(&self, builder: ExponentialBuilder) -> ExponentialBuilder {
+        let builder = builder
+            .with_jitter()
+            .with_min_delay(self.min_delay)
+            .with_max_delay(self.max_delay);
+
+        match self.max_times {
+            Some(max_times) => builder.with_max_times(max_times),
+            None => builder,
+        }
+    }
+}
+
+/// A `class=max_times:min_delay_ms:max_delay_ms` policy given on the command
+/// line
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicySetting {
+    pub class: RetryClass,
+    pub policy: RetryPolicy,
+}
+
+impl FromStr for RetryPolicySetting {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("expected class=max_times:min_delay_ms:max_delay_ms, got {s:?}");
+
+        let (class, policy) = s.split_once('=').ok_or_else(invalid)?;
+        let class = <RetryClass as clap::ValueEnum>::from_str(class, true)
+            .map_err(|e| anyhow!("invalid retry class {class:?}: {e}"))?;
+
+        let mut parts = policy.split(':').map(str::parse::<u64>);
+        let (Some(Ok(max_times)), Some(Ok(min_delay)), Some(Ok(max_delay)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        ensure!(
+            min_delay <= max_delay,
+            "minimum delay exceeds maximum delay in {s:?}"
+        );
+
+        Ok(Self {
+            class,
+            policy: RetryPolicy {
+                max_times: Some(usize::try_from(max_times)?),
+                min_delay: Duration::from_millis(min_delay),
+                max_delay: Duration::from_millis(max_delay),
+            },
+        })
+    }
+}
+
+/// Errors of an upstream guarded by a [`CircuitBreaker`]
+pub trait Upstream {
+    /// The error returned without calling the upstream while the breaker of
+    /// `class` is open
+    fn circuit_open(class: RetryClass) -> Self;
+
+    fn is_circuit_open(&self) -> bool;
+
+    /// Whether the error suggests the upstream is unreachable, as opposed to
+    /// rejecting this particular call
+    fn is_upstream_failure(&self) -> bool;
+}
+
+impl Upstream for ClientError {
+    fn circuit_open(class: RetryClass) -> Self {
+        ClientErrorKind::Custom(format!("{CIRCUIT_OPEN}: {}", class.name())).into()
+    }
+
+    fn is_circuit_open(&self) -> bool {
+        matches!(&self.kind, ClientErrorKind::Custom(msg) if msg.starts_with(CIRCUIT_OPEN))
+    }
+
+    fn is_upstream_failure(&self) -> bool {
+        matches!(
+            self.kind,
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_)
+        )
+    }
+}
+
+impl Upstream for jsonrpsee::core::Error {
+    fn circuit_open(class: RetryClass) -> Self {
+        Self::Custom(format!("{CIRCUIT_OPEN}: {}", class.name()))
+    }
+
+    fn is_circuit_open(&self) -> bool {
+        matches!(self, Self::Custom(msg) if msg.starts_with(CIRCUIT_OPEN))
+    }
+
+    fn is_upstream_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::Transport(_) | Self::RequestTimeout | Self::RestartNeeded(_)
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Trips after `threshold` consecutive upstream failures and fails calls
+/// immediately for `cooldown`. Calls are let through again once the
+/// cooldown has passed; the first success closes the breaker while another
+/// failure trips it again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    class: RetryClass,
+    threshold: u32,
+    cooldown: Duration,
+    metrics: Metrics,
+    state: Mutex<BreakerState>,
+    open: watch::Sender<bool>,
+}
+
+impl CircuitBreaker {
+    fn new(class: RetryClass, threshold: u32, cooldown: Duration, metrics: Metrics) -> Self {
+        Self {
+            class,
+            threshold: threshold.max(1),
+            cooldown,
+            metrics,
+            state: Mutex::new(BreakerState::default()),
+            open: watch::channel(false).0,
+        }
+    }
+
+    /// Watch whether the breaker is open. The value changes when the breaker
+    /// trips and when it closes again.
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.open.subscribe()
+    }
+
+    /// Run `fut` unless the breaker is open, recording its outcome
+    ///
+    /// # Errors
+    /// This function fails if the breaker is open or `fut` fails
+    pub async fn call<T, E: Upstream>(
+        &self,
+        fut: impl Future<Output = std::result::Result<T, E>>,
+    ) -> std::result::Result<T, E> {
+        if self.is_open() {
+            return Err(E::circuit_open(self.class));
+        }
+
+        let res = fut.await;
+        self.record(
+            res.as_ref()
+                .err()
+                .map_or(false, Upstream::is_upstream_failure),
+        );
+
+        res
+    }
+
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.lock()
+            .open_until
+            .map_or(false, |until| Instant::now() < until)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<BreakerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, failed: bool) {
+        let mut state = self.lock();
+        let class = [KeyValue::new("class", self.class.name())];
+
+        if !failed {
+            state.failures = 0;
+
+            if state.open_until.take().is_some() {
+                info!("{} circuit breaker closed", self.class.name());
+                self.metrics.circuit_breakers_open.add(-1, &class);
+                self.open.send_replace(false);
+            }
+
+            return;
+        }
+
+        state.failures = state.failures.saturating_add(1);
+
+        if state.failures >= self.threshold {
+            if state.open_until.is_none() {
+                warn!(
+                    "{} circuit breaker opened after {} consecutive failures",
+                    self.class.name(),
+                    state.failures
+                );
+                self.metrics.circuit_breakers_open.add(1, &class);
+                self.open.send_replace(true);
+            }
+
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+/// The retry policy and circuit breaker of every [`RetryClass`]
+#[derive(Debug, Clone)]
+pub struct Retries {
+    policies: Arc<HashMap<RetryClass, RetryPolicy>>,
+    breakers: Arc<HashMap<RetryClass, CircuitBreaker>>,
+    threshold: u32,
+    cooldown: Duration,
+    metrics: Metrics,
+}
+
+impl Retries {
+    /// Default threshold of consecutive failures tripping a breaker
+    pub const DEFAULT_THRESHOLD: u32 = 20;
+
+    /// Default time a tripped breaker fails calls for
+    pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+    /// Policies for every class, taken from `settings` or the defaults
+    #[must_use]
+    pub fn new(
+        settings: Vec<RetryPolicySetting>,
+        threshold: u32,
+        cooldown: Duration,
+        metrics: Metrics,
+    ) -> Self {
+        let mut policies: HashMap<_, _> = RetryClass::ALL
+            .into_iter()
+            .map(|class| (class, RetryPolicy::default_for(class)))
+            .collect();
+        policies.extend(settings.into_iter().map(|s| (s.class, s.policy)));
+
+        let retries = Self {
+            policies: Arc::new(policies),
+            breakers: Arc::default(),
+            threshold,
+            cooldown,
+            metrics,
+        };
+
+        retries.separate()
+    }
+
+    /// A copy with the same policies and breakers of its own, for a
+    /// different set of upstreams
+    #[must_use]
+    pub fn separate(&self) -> Self {
+        let breakers = RetryClass::ALL
+            .into_iter()
+            .map(|class| {
+                let breaker =
+                    CircuitBreaker::new(class, self.threshold, self.cooldown, self.metrics.clone());
+
+                (class, breaker)
+            })
+            .collect();
+
+        Self {
+            breakers: Arc::new(breakers),
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn policy(&self, class: RetryClass) -> RetryPolicy {
+        self.policies
+            .get(&class)
+            .copied()
+            .unwrap_or_else(|| RetryPolicy::default_for(class))
+    }
+
+    #[must_use]
+    pub fn breaker(&self, class: RetryClass) -> &CircuitBreaker {
+        self.breakers
+            .get(&class)
+            .unwrap_or_else(|| unreachable!("every retry class has a breaker"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_policy_settings() {
+        let setting: RetryPolicySetting = "das=3:100:2000".parse().unwrap();
+
+        assert_eq!(setting.class, RetryClass::Das);
+        assert_eq!(setting.policy, RetryPolicy {
+            max_times: Some(3),
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        });
+
+        assert!("das=3:100".parse::<RetryPolicySetting>().is_err());
+        assert!("das=3:2000:100".parse::<RetryPolicySetting>().is_err());
+        assert!("s3=3:100:2000".parse::<RetryPolicySetting>().is_err());
+    }
+
+    #[test]
+    fn breaker_trips_and_closes() {
+        let breaker = CircuitBreaker::new(
+            RetryClass::Rpc,
+            2,
+            Duration::from_secs(60),
+            Metrics::new().unwrap(),
+        );
+
+        breaker.record(true);
+        assert!(!breaker.is_open());
+
+        breaker.record(true);
+        assert!(breaker.is_open());
+
+        breaker.record(false);
+        assert!(!breaker.is_open());
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Instant,
+};
+
+use hub_core::{anyhow::Result, metrics::KeyValue, prelude::*, tokio};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    http_sender::HttpSender,
+    nonblocking::rpc_client::RpcClient as SolanaRpcClient,
+    rpc_client::RpcClientConfig,
+    rpc_request::RpcRequest,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::metrics::Metrics;
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A token bucket allowing bursts of up to one second's worth of requests
+struct RateLimiter {
+    requests_per_second: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: NonZeroU32) -> Self {
+        let requests_per_second = f64::from(requests_per_second.get());
+
+        Self {
+            requests_per_second,
+            bucket: Mutex::new(Bucket {
+                tokens: requests_per_second,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait for a token, returning how long the caller was queued
+    async fn acquire(&self) -> Duration {
+        let start = Instant::now();
+
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+                let now = Instant::now();
+                let refill =
+                    now.duration_since(bucket.refilled_at).as_secs_f64() * self.requests_per_second;
+
+                bucket.tokens = (bucket.tokens + refill).min(self.requests_per_second);
+                bucket.refilled_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+
+                    return start.elapsed();
+                }
+
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second)
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// An HTTP transport which holds requests back to stay under the rate limit
+/// of its endpoint
+struct RateLimitedSender {
+    index: usize,
+    inner: HttpSender,
+    limiter: RateLimiter,
+    metrics: Metrics,
+}
+
+#[async_trait]
+impl RpcSender for RateLimitedSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let waited = self.limiter.acquire().await;
+
+        self.metrics.rpc_rate_limit_wait_duration_ms_bucket.record(
+            i64::try_from(waited.as_millis()).unwrap_or(i64::MAX),
+            &[KeyValue::new(
+                "endpoint",
+                i64::try_from(self.index).unwrap_or_default(),
+            )],
+        );
+
+        self.inner.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+struct Endpoint {
+    index: usize,
+    client: Arc<SolanaRpcClient>,
+    healthy: AtomicBool,
+    latency_ms: AtomicU64,
+}
+
+impl Endpoint {
+    fn latency(&self) -> u64 {
+        self.latency_ms.load(Ordering::Relaxed)
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn set_healthy(&self, healthy: bool, metrics: &Metrics) {
+        let was_healthy = self.healthy.swap(healthy, Ordering::Relaxed);
+
+        if was_healthy == healthy {
+            return;
+        }
+
+        if healthy {
+            info!("RPC endpoint {} recovered", self.index);
+        } else {
+            warn!("RPC endpoint {} marked unhealthy", self.index);
+        }
+
+        metrics
+            .rpc_healthy_endpoints
+            .add(if healthy { 1 } else { -1 }, &[]);
+    }
+
+    async fn check(&self, metrics: &Metrics) {
+        let start = Instant::now();
+        let res = self.client.get_health().await;
+        let elapsed = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        if let Err(e) = &res {
+            warn!("health check failed for RPC endpoint {}: {e}", self.index);
+        }
+
+        // Exponentially weighted so a single slow response doesn't reroute traffic
+        let latency = self.latency();
+        let latency = if latency == 0 {
+            elapsed
+        } else {
+            (latency * 3 + elapsed) / 4
+        };
+        self.latency_ms.store(latency, Ordering::Relaxed);
+
+        self.set_healthy(res.is_ok(), metrics);
+
+        metrics.rpc_health_check_duration_ms_bucket.record(
+            i64::try_from(elapsed).unwrap_or(i64::MAX),
+            &[
+                KeyValue::new("endpoint", i64::try_from(self.index).unwrap_or_default()),
+                KeyValue::new("healthy", res.is_ok().to_string()),
+            ],
+        );
+    }
+}
+
+/// A set of Solana RPC endpoints. Requests are routed to the healthy endpoint
+/// with the lowest observed latency, falling back to the primary endpoint
+/// when none are healthy.
+#[derive(Clone)]
+pub struct RpcPool {
+    endpoints: Arc<Vec<Endpoint>>,
+    health_check_interval: Duration,
+    metrics: Metrics,
+}
+
+impl RpcPool {
+    /// Build a pool from `urls`, treating the first URL as the primary endpoint.
+    /// If `requests_per_second` is set, requests to each endpoint are queued
+    /// to stay within it.
+    ///
+    /// # Errors
+    /// This function fails if no endpoints are provided
+    pub fn new(
+        urls: impl IntoIterator<Item = String>,
+        health_check_interval: Duration,
+        requests_per_second: Option<NonZeroU32>,
+        metrics: Metrics,
+    ) -> Result<Self> {
+        let endpoints = urls
+            .into_iter()
+            .enumerate()
+            .map(|(index, url)| Endpoint {
+                index,
+                client: Arc::new(match requests_per_second {
+                    Some(requests_per_second) => SolanaRpcClient::new_sender(
+                        RateLimitedSender {
+                            index,
+                            inner: HttpSender::new(url),
+                            limiter: RateLimiter::new(requests_per_second),
+                            metrics: metrics.clone(),
+                        },
+                        RpcClientConfig::with_commitment(CommitmentConfig::default()),
+                    ),
+                    None => SolanaRpcClient::new(url),
+                }),
+                healthy: AtomicBool::new(true),
+                latency_ms: AtomicU64::new(0),
+            })
+            .collect::<Vec<_>>();
+
+        ensure!(
+            !endpoints.is_empty(),
+            "at least one Solana RPC endpoint is required"
+        );
+
+        metrics
+            .rpc_healthy_endpoints
+            .add(i64::try_from(endpoints.len()).unwrap_or_default(), &[]);
+
+        Ok(Self {
+            endpoints: Arc::new(endpoints),
+            health_check_interval,
+            metrics,
+        })
+    }
+
+    fn primary(&self) -> &Endpoint {
+        &self.endpoints[0]
+    }
+
+    /// Endpoints ordered by preference: healthy endpoints by latency, then
+    /// unhealthy endpoints as a last resort.
+    fn ordered(&self) -> Vec<&Endpoint> {
+        let mut endpoints = self.endpoints.iter().collect::<Vec<_>>();
+        endpoints.sort_by_key(|e| (!e.is_healthy(), e.latency()));
+        endpoints
+    }
+
+    #[must_use]
+    pub fn get(&self) -> Arc<SolanaRpcClient> {
+        self.endpoints
+            .iter()
+            .filter(|e| e.is_healthy())
+            .min_by_key(|e| e.latency())
+            .unwrap_or_else(|| self.primary())
+            .client
+            .clone()
+    }
+
+    /// Run `f` against each endpoint in order of preference until one of them
+    /// responds. Endpoints failing with a transport error are marked unhealthy
+    /// until the next successful health check.
+    ///
+    /// # Errors
+    /// This function fails with the last error returned if no endpoint could
+    /// be reached, or immediately if an endpoint returns a non-transport error
+    pub async fn call<T, F, Fut>(&self, f: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<SolanaRpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut last_err = None;
+
+        for endpoint in self.ordered() {
+            match f(endpoint.client.clone()).await {
+                Ok(res) => return Ok(res),
+                Err(e)
+                    if matches!(e.kind, ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_)) =>
+                {
+                    endpoint.set_healthy(false, &self.metrics);
+                    last_err = Some(e);
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| unreachable!("RPC pool is never empty")))
+    }
+
+    /// Periodically check every endpoint with `getHealth`, updating its
+    /// health status and latency estimate.
+    pub async fn run_health_checks(self) {
+        let mut interval = tokio::time::interval(self.health_check_interval);
+
+        loop {
+            interval.tick().await;
+
+            for endpoint in self.endpoints.iter() {
+                endpoint.check(&self.metrics).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
+
+use holaplex_hub_nfts_solana_core::{
+    proto::{nft_events::Event as NftEvent, treasury_events::Event as TreasuryEvent},
+    Services,
+};
+use hub_core::{
+    anyhow::Error,
+    clap::ValueEnum,
+    metrics::KeyValue,
+    prelude::*,
+    tokio::sync::oneshot,
+};
+
+use crate::{events::EventKind, metrics::Metrics};
+
+/// Each grant advances a lane's pass by `STRIDE / weight`, so lanes with a
+/// higher weight fall behind more slowly and are picked more often
+const STRIDE: u64 = 1 << 20;
+
+/// The class a consumed message is scheduled under. Requests and the signed
+/// transactions returned for them share the lane of their event kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lane {
+    Event(EventKind),
+    Import,
+    Export,
+    Other,
+}
+
+impl Lane {
+    #[must_use]
+    pub fn of(msg: &Services) -> Self {
+        let kind = match msg {
+            Services::Nfts(_, msg) => match msg.event.as_ref() {
+                Some(
+                    NftEvent::StartedImportingSolanaCollection(_) | NftEvent::SolanaImportMint(_),
+                ) => return Self::Import,
+                Some(NftEvent::SolanaExportCollection(_)) => return Self::Export,
+                Some(event) => EventKind::from_request(event),
+                None => None,
+            },
+            Services::Treasury(_, msg) => msg.event.as_ref().and_then(EventKind::from_signed),
+        };
+
+        kind.map_or(Self::Other, Self::Event)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Event(kind) => kind.name(),
+            Self::Import => "collection import",
+            Self::Export => "collection export",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl FromStr for Lane {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "import" => Ok(Self::Import),
+            "export" => Ok(Self::Export),
+            "other" => Ok(Self::Other),
+            s => EventKind::from_str(s, true)
+                .map(Self::Event)
+                .map_err(|e| anyhow!("invalid event kind {s:?}: {e}")),
+        }
+    }
+}
+
+/// A `kind=value` pair given on the command line, where `kind` is an event
+/// kind, `import`, `export` or `other`
+#[derive(Debug, Clone, Copy)]
+pub struct LaneSetting {
+    pub lane: Lane,
+    pub value: usize,
+}
+
+impl FromStr for LaneSetting {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (lane, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected kind=value, got {s:?}"))?;
+        let value = value
+            .parse()
+            .ok()
+            .filter(|v| *v > 0)
+            .ok_or_else(|| anyhow!("invalid value in {s:?}, expected a positive integer"))?;
+
+        Ok(Self {
+            lane: lane.parse()?,
+            value,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct LaneState {
+    limit: usize,
+    weight: u64,
+    running: usize,
+    pass: u64,
+    waiting: VecDeque<oneshot::Sender<Permit>>,
+}
+
+#[derive(Debug)]
+struct State {
+    running: usize,
+    pass: u64,
+    lanes: HashMap<Lane, LaneState>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    capacity: usize,
+    limits: HashMap<Lane, usize>,
+    weights: HashMap<Lane, u64>,
+    metrics: Metrics,
+    state: Mutex<State>,
+}
+
+/// Bounds how many consumed messages are processed at once, overall and per
+/// [`Lane`]. When messages of several lanes are waiting, free slots are
+/// handed out by stride scheduling so every lane progresses in proportion to
+/// its weight and a burst of one kind (e.g. a large import or airdrop) cannot
+/// starve the others.
+#[derive(Debug, Clone)]
+pub struct Scheduler(Arc<Shared>);
+
+/// A processing slot, released when dropped. The time it was held is
+/// recorded as the processing time of the message.
+#[derive(Debug)]
+pub struct Permit {
+    shared: Arc<Shared>,
+    lane: Lane,
+    armed: bool,
+    granted_at: Instant,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.armed {
+            self.shared.processed(self.lane, self.granted_at);
+            self.shared.release(self.lane);
+        }
+    }
+}
+
+impl Scheduler {
+    #[must_use]
+    pub fn new(
+        capacity: usize,
+        limits: Vec<LaneSetting>,
+        weights: Vec<LaneSetting>,
+        metrics: Metrics,
+    ) -> Self {
+        let limits = limits.into_iter().map(|s| (s.lane, s.value)).collect();
+        let weights = weights
+            .into_iter()
+            .map(|s| (s.lane, u64::try_from(s.value).unwrap_or(u64::MAX)))
+            .collect();
+
+        Self(Arc::new(Shared {
+            capacity: capacity.max(1),
+            limits,
+            weights,
+            metrics,
+            state: Mutex::new(State {
+                running: 0,
+                pass: 0,
+                lanes: HashMap::new(),
+            }),
+        }))
+    }
+
+    /// Wait for a slot to process a message in `lane`
+    pub async fn acquire(&self, lane: Lane) -> Permit {
+        let (tx, rx) = oneshot::channel();
+
+        {
+            let mut state = self.0.lock();
+            let State { pass, lanes, .. } = &mut *state;
+            let lane_state = lanes.entry(lane).or_insert_with(|| LaneState {
+                limit: self.0.limits.get(&lane).copied().unwrap_or(self.0.capacity),
+                weight: self.0.weights.get(&lane).copied().unwrap_or(1),
+                running: 0,
+                pass: 0,
+                waiting: VecDeque::new(),
+            });
+
+            // A lane that has been idle must not bank its unused share
+            if lane_state.waiting.is_empty() && lane_state.running == 0 {
+                lane_state.pass = lane_state.pass.max(*pass);
+            }
+
+            lane_state.waiting.push_back(tx);
+            self.0.queued(lane, 1);
+            self.0.dispatch(&mut state);
+        }
+
+        // Queued senders are only dropped after a permit is sent, and the
+        // scheduler outlives this call since `self` holds a reference to it
+        rx.await
+            .unwrap_or_else(|_| unreachable!("queued message was dropped"))
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn queued(&self, lane: Lane, delta: i64) {
+        self.metrics
+            .event_queue_depth
+            .add(delta, &[KeyValue::new("event", lane.name())]);
+    }
+
+    fn in_flight(&self, lane: Lane, delta: i64) {
+        self.metrics
+            .events_in_flight
+            .add(delta, &[KeyValue::new("event", lane.name())]);
+    }
+
+    fn processed(&self, lane: Lane, granted_at: Instant) {
+        let elapsed = i64::try_from(granted_at.elapsed().as_millis()).unwrap_or(i64::MAX);
+
+        self.metrics
+            .event_processing_duration_ms_bucket
+            .record(elapsed, &[KeyValue::new("event", lane.name())]);
+    }
+
+    /// Hand out free slots to the waiting lane with the lowest pass that is
+    /// under its own limit
+    fn dispatch(self: &Arc<Self>, state: &mut State) {
+        let State {
+            running,
+            pass,
+            lanes,
+        } = state;
+
+        while *running < self.capacity {
+            let Some((&lane, lane_state)) = lanes
+                .iter_mut()
+                .filter(|(_, l)| !l.waiting.is_empty() && l.running < l.limit)
+                .min_by_key(|(_, l)| l.pass)
+            else {
+                break;
+            };
+
+            let Some(tx) = lane_state.waiting.pop_front() else {
+                break;
+            };
+            self.queued(lane, -1);
+
+            let permit = Permit {
+                shared: Arc::clone(self),
+                lane,
+                armed: true,
+                granted_at: Instant::now(),
+            };
+
+            match tx.send(permit) {
+                Ok(()) => {
+                    *running += 1;
+                    *pass = lane_state.pass;
+                    lane_state.running += 1;
+                    lane_state.pass += STRIDE / lane_state.weight.max(1);
+                    self.in_flight(lane, 1);
+                },
+                // The waiting message was cancelled; the slot is still free
+                Err(mut permit) => permit.armed = false,
+            }
+        }
+    }
+
+    fn release(self: &Arc<Self>, lane: Lane) {
+        let mut state = self.lock();
+
+        state.running -= 1;
+
+        if let Some(lane_state) = state.lanes.get_mut(&lane) {
+            lane_state.running -= 1;
+        }
+
+        self.in_flight(lane, -1);
+        self.dispatch(&mut state);
+    }
+}
@@ -0,0 +1,114 @@
+//! Checks of the wallets a signing request is addressed to against the
+//! signer policy the treasury service assigned to a project.
+
+use std::{collections::HashSet, str::FromStr};
+
+use hub_core::thiserror;
+use solana_program::pubkey::Pubkey;
+
+/// Why the signers of an assembled transaction were rejected
+#[derive(Debug, thiserror::Error)]
+pub enum SignerPolicyError {
+    #[error("Transaction is paid for by {found} rather than the project treasury {expected}")]
+    Payer { expected: Pubkey, found: String },
+    #[error("{0} is not an authorized signer of the project")]
+    UnknownSigner(String),
+}
+
+/// The wallets allowed to sign transactions of a project
+#[derive(Debug, Clone)]
+pub struct AuthorizedSigners {
+    treasury: Pubkey,
+    signers: HashSet<Pubkey>,
+}
+
+impl AuthorizedSigners {
+    /// Signers paid for by `treasury`, which may sign alongside `signers`
+    #[must_use]
+    pub fn new(treasury: Pubkey, signers: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self {
+            treasury,
+            signers: signers.into_iter().collect(),
+        }
+    }
+
+    /// Check the signers of an assembled message, in message order. The
+    /// treasury must pay for the transaction, and every other signature
+    /// must be requested from an authorized wallet. Entries that are not
+    /// public keys are signatures made while assembling the message and are
+    /// not requested from anyone.
+    ///
+    /// # Errors
+    /// This function fails if the payer is not the treasury or a signature
+    /// is requested from an unknown wallet
+    pub fn check(&self, signers: &[String]) -> Result<(), SignerPolicyError> {
+        let mut signers = signers.iter();
+
+        match signers.next() {
+            Some(payer) if Pubkey::from_str(payer).map_or(false, |p| p == self.treasury) => (),
+            found => {
+                return Err(SignerPolicyError::Payer {
+                    expected: self.treasury,
+                    found: found.cloned().unwrap_or_default(),
+                });
+            },
+        }
+
+        for signer in signers {
+            let Ok(wallet) = Pubkey::from_str(signer) else {
+                continue;
+            };
+
+            if wallet != self.treasury && !self.signers.contains(&wallet) {
+                return Err(SignerPolicyError::UnknownSigner(signer.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::Signature;
+
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    #[test]
+    fn allows_authorized_signers() {
+        let signers = AuthorizedSigners::new(key(1), [key(2)]);
+        let presigned = Signature::new(&[3; 64]).to_string();
+
+        signers
+            .check(&[key(1).to_string(), presigned, key(2).to_string()])
+            .unwrap();
+    }
+
+    #[test]
+    fn rejects_other_payers() {
+        let signers = AuthorizedSigners::new(key(1), [key(2)]);
+
+        assert!(matches!(
+            signers.check(&[key(2).to_string(), key(1).to_string()]),
+            Err(SignerPolicyError::Payer { .. })
+        ));
+        assert!(matches!(
+            signers.check(&[]),
+            Err(SignerPolicyError::Payer { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_signers() {
+        let signers = AuthorizedSigners::new(key(1), [key(2)]);
+
+        assert!(matches!(
+            signers.check(&[key(1).to_string(), key(4).to_string()]),
+            Err(SignerPolicyError::UnknownSigner(s)) if s == key(4).to_string()
+        ));
+    }
+}
@@ -1,11 +1,21 @@
+use std::num::NonZeroU32;
+
 use anchor_lang::{prelude::AccountMeta, AnchorDeserialize, InstructionData};
-use holaplex_hub_nfts_solana_core::proto::{
-    treasury_events::SolanaTransactionResult, MasterEdition, MetaplexMasterEditionTransaction,
-    MetaplexMetadata, MintMetaplexEditionTransaction, MintMetaplexMetadataTransaction,
-    TransferMetaplexAssetTransaction, UpdateSolanaMintPayload,
+use holaplex_hub_nfts_solana_core::{
+    auction_house::AuctionHouse,
+    metaplex,
+    proto::{
+        treasury_events::SolanaTransactionResult, MasterEdition, MetaplexCreators,
+        MetaplexMasterEditionTransaction, MetaplexMetadata, MetaplexMetadataPatch,
+        MintMetaplexEditionTransaction, MintMetaplexMetadataTransaction, SolanaPendingTransaction,
+        SolanaTransactionCost, TransferMetaplexAssetTransaction, TransferMetaplexAssetsTransaction,
+        UpdateSolanaMintPayload,
+    },
 };
 use holaplex_hub_nfts_solana_entity::{
-    collection_mints, collections, compression_leafs, update_revisions,
+    candy_machines, collection_mints, collections, compression_leafs, listings, royalty_splits,
+    sea_orm_active_enums::{FeePayerMode, SolanaCluster},
+    update_revisions,
 };
 use hub_core::{
     anyhow::Result,
@@ -16,66 +26,98 @@ use hub_core::{
     uuid::Uuid,
 };
 use mpl_bubblegum::state::metaplex_adapter::{
-    Collection, Creator as BubblegumCreator, TokenProgramVersion,
+    Collection, MetadataArgs, TokenProgramVersion, UpdateArgs,
 };
 use mpl_token_metadata::{
     instruction::{
         mint_new_edition_from_master_edition_via_token, set_and_verify_sized_collection_item,
         unverify_sized_collection_item, update_metadata_accounts_v2,
     },
-    state::{Creator, DataV2, EDITION, PREFIX},
+    state::{Creator, DataV2, Metadata, TokenMetadataAccount, EDITION, PREFIX},
 };
 use solana_client::{
     client_error::{ClientError, ClientErrorKind},
-    nonblocking::rpc_client::RpcClient as SolanaRpcClient,
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient as SolanaRpcClient},
+    nonce_utils,
     rpc_client::SerializableTransaction,
-    rpc_config::RpcSendTransactionConfig,
+    rpc_config::{
+        RpcSendTransactionConfig, RpcSignatureSubscribeConfig, RpcSimulateTransactionConfig,
+    },
     rpc_request::RpcError,
+    rpc_response::{
+        ProcessedSignatureResult, Response, RpcSignatureResult, RpcSimulateTransactionResult,
+    },
 };
 use solana_program::{
-    instruction::Instruction, program_pack::Pack, pubkey::Pubkey,
-    system_instruction::create_account, system_program,
+    borsh::try_from_slice_unchecked,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    nonce::state::Data as NonceData,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction::{self, create_account, SystemInstruction},
+    system_program,
 };
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    compute_budget::{self, ComputeBudgetInstruction},
+    packet::PACKET_DATA_SIZE,
     signature::Signature,
     signer::{keypair::Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionStatus, UiInnerInstructions,
+    UiInstruction, UiLoadedAddresses, UiMessage, UiTransaction,
 };
-use solana_transaction_status::{UiInnerInstructions, UiInstruction, UiTransactionEncoding};
 use spl_account_compression::{
     events::{AccountCompressionEvent, ChangeLogEventV1},
     ChangeLogEvent,
 };
 use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account,
+    get_associated_token_address,
+    instruction::{create_associated_token_account, create_associated_token_account_idempotent},
 };
 use spl_token::{
-    instruction::{initialize_mint, mint_to},
+    instruction::{freeze_account, initialize_mint, mint_to},
     state,
 };
 
 use crate::{
-    asset_api::RpcClient,
+    asset_api::{self, DasProvider, DasProviderKind},
     backend::{
-        CollectionBackend, MasterEditionAddresses, MintBackend, MintCompressedMintV1Addresses,
-        MintEditionAddresses, MintMetaplexAddresses, SwitchCollectionAddresses,
-        TransactionResponse, TransferAssetAddresses, TransferBackend,
-        TransferCompressedMintV1Addresses, UpdateCollectionMintAddresses,
-        UpdateMasterEditionAddresses,
+        BatchTransferBackend, CandyMachineAddresses, CandyMachineBackend, CollectionBackend,
+        CreateTreeAddresses, ListingAddresses, ListingBackend, LockMetadataAddresses,
+        MasterEditionAddresses, MintBackend, MintCompressedMintV1Addresses, MintEditionAddresses,
+        MintMetaplexAddresses, MultisigProposalAddresses, ProposalBackend, RoyaltySplitAddresses,
+        RoyaltySplitBackend, SwitchCollectionAddresses, TransactionResponse,
+        TransferAssetAddresses, TransferAssetsAddresses, TransferBackend,
+        TransferCompressedMintV1Addresses, TreeBackend, UpdateCollectionMintAddresses,
+        UpdateCompressedMintAddresses, UpdateMasterEditionAddresses, UpdateMintBackend,
+    },
+    candy_machine::{
+        self, CandyMachineAccounts, CandyMachineData, CollectionAccounts, ConfigLine, Guards,
     },
+    hydra::Fanout,
+    jito::Jito,
+    metrics::Metrics,
+    nonces::Nonces,
+    retry::{Retries, Upstream},
+    rpc_pool::RpcPool,
+    solana_rpc::SolanaRpc,
+    squads::Multisig,
+    trees::{self, TreeShape},
 };
 #[macro_export]
 macro_rules! with_retry {
-    ($expr:expr) => {{
-        (|| async { $expr.await })
-            .retry(
-                &ExponentialBuilder::default()
-                    .with_jitter()
-                    .with_min_delay(Duration::from_millis(30))
-                    .with_max_delay(Duration::from_secs(1))
-                    .with_max_times(25),
-            )
+    ($retries:expr, $expr:expr) => {{
+        let retries: &$crate::retry::Retries = $retries;
+        let breaker = retries.breaker($crate::retry::RetryClass::Rpc);
+
+        (|| breaker.call(async { $expr.await }))
+            .retry(&retries.policy($crate::retry::RetryClass::Rpc).backoff())
+            .when(|err: &ClientError| !$crate::retry::Upstream::is_circuit_open(err))
             .notify(|err: &ClientError, dur: Duration| {
                 error!("retrying error {:?} in {:?}", err, dur);
             })
@@ -83,22 +125,77 @@ macro_rules! with_retry {
 }
 pub use with_retry;
 const TOKEN_PROGRAM_PUBKEY: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+/// Roughly the lifetime of a blockhash, after which a signature subscription
+/// gives way to polling so an expired blockhash is noticed
+const SIGNATURE_SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(90);
 
 #[derive(Debug, clap::Args)]
 pub struct SolanaArgs {
     #[arg(long, env)]
     pub solana_endpoint: String,
 
+    /// WebSocket endpoint used to track submitted transactions with
+    /// `signatureSubscribe`. Signature statuses are polled over HTTP if this
+    /// is unset or the connection drops.
+    #[arg(long, env)]
+    pub solana_ws_endpoint: Option<String>,
+
+    /// Cluster served by the configured endpoints. Collections created
+    /// through this configuration are recorded against it.
+    #[arg(long, env, default_value = "mainnet")]
+    pub solana_cluster: SolanaCluster,
+
+    /// Additional RPC endpoints to fail over to when the primary endpoint is
+    /// unhealthy or slower than its peers
+    #[arg(long, env, value_delimiter = ',')]
+    pub solana_fallback_endpoints: Vec<String>,
+
+    #[arg(long, env, default_value_t = 5)]
+    pub solana_health_check_interval: u64,
+
+    /// Maximum requests per second sent to each RPC endpoint. Requests over
+    /// the limit are queued rather than rejected.
+    #[arg(long, env)]
+    pub solana_rpc_requests_per_second: Option<NonZeroU32>,
+
+    /// Simulate assembled transactions before requesting signatures
+    #[arg(long, env, default_value_t = false)]
+    pub solana_simulate_transactions: bool,
+
     #[arg(long, env)]
     pub solana_treasury_wallet_address: Pubkey,
 
     #[arg(long, env)]
     pub digital_asset_api_endpoint: String,
 
+    /// Vendor of the digital asset API, which decides the params sent to it
+    #[arg(long, env, value_enum, default_value = "helius")]
+    pub digital_asset_api_provider: DasProviderKind,
+
+    /// Milliseconds to wait for a digital asset API response before sending
+    /// the same request again and taking whichever answers first. Requests
+    /// are not hedged unless this is set.
+    #[arg(long, env)]
+    pub digital_asset_api_hedge_delay: Option<u64>,
+
     #[arg(long, env)]
     pub tree_authority: Pubkey,
     #[arg(long, env)]
     pub merkle_tree: Pubkey,
+    /// Signer delegated to mint into the merkle tree, if it is not the
+    /// treasury wallet. Its key is held by the treasury service, which signs
+    /// for it alongside the payer.
+    #[arg(long, env)]
+    pub tree_delegate: Option<Pubkey>,
+
+    /// Wait for submitted transactions to reach this commitment (processed,
+    /// confirmed or finalized) and report the slot they landed in. If unset,
+    /// transactions are only checked to have been processed.
+    #[arg(long, env)]
+    pub submit_commitment: Option<CommitmentLevel>,
 }
 
 #[derive(Clone)]
@@ -127,6 +224,30 @@ pub struct TransferAssetRequest {
     pub mint_address: String,
 }
 
+/// A transaction accepted by the cluster
+#[derive(Debug, Clone)]
+pub struct SubmittedTransaction {
+    pub signature: String,
+    /// Slot the transaction was confirmed in, if submitted with a commitment
+    pub slot: Option<u64>,
+    /// The wallet which paid the fees of the transaction
+    pub payer: String,
+}
+
+/// What became of a signed transaction whose submission failed
+#[derive(Debug)]
+pub enum Settled {
+    /// The transaction landed despite the error
+    Landed(SubmittedTransaction),
+    /// The transaction landed with an error it would fail with again
+    Failed(TransactionError),
+    /// The blockhash of the transaction expired before it landed
+    Expired,
+    /// The transaction did not land and uses a durable nonce, so it does not
+    /// expire
+    Durable,
+}
+
 #[derive(Debug, thiserror::Error)]
 enum SolanaErrorNotFoundMessage {
     #[error("master edition message not found")]
@@ -137,12 +258,29 @@ enum SolanaErrorNotFoundMessage {
     Metadata,
 }
 
+/// A stored mint update whose accounts no longer match the mint, its
+/// collection or the wallet paying for transactions, so its message has to
+/// be assembled again
+#[derive(Debug, thiserror::Error)]
+pub enum StaleRevision {
+    #[error("metadata account of the mint has changed")]
+    Metadata,
+    #[error("update authority of the collection has changed")]
+    UpdateAuthority,
+    #[error("fee payer has changed")]
+    Payer,
+    #[error("merkle proof of the compressed mint may have changed")]
+    Proof,
+}
+
 #[derive(Debug, thiserror::Error, Triage)]
 pub enum SolanaAssetIdError {
     #[error("The transaction has no meta field")]
     NoTransactionMeta,
     #[error("No inner instruction found")]
     NoInnerInstruction,
+    #[error("The transaction has no account keys")]
+    NoAccountKeys,
     #[error("Solana RPC error")]
     Rpc(#[from] solana_client::client_error::ClientError),
     #[error("No nonce found in log messages")]
@@ -157,24 +295,233 @@ pub enum SolanaAssetIdError {
 
 #[derive(Clone)]
 pub struct Solana {
-    rpc_client: Arc<SolanaRpcClient>,
+    cluster: SolanaCluster,
+    rpc_pool: RpcPool,
+    rpc_api: Arc<dyn SolanaRpc>,
+    pubsub: Option<Pubsub>,
     treasury_wallet_address: Pubkey,
+    /// The configured treasury, which keeps paying for projects sponsored by
+    /// the service after their own treasury is bound
+    sponsor_wallet_address: Pubkey,
+    fee_payer_mode: FeePayerMode,
+    /// A wallet paying for assembled transactions in place of the one chosen
+    /// by `fee_payer_mode`, such as a Solana Pay claimant
+    fee_payer: Option<Pubkey>,
     bubblegum_tree_authority: Pubkey,
+    bubblegum_tree_delegate: Pubkey,
     bubblegum_merkle_tree: Pubkey,
     bubblegum_cpi_address: Pubkey,
-    asset_rpc_client: jsonrpsee::http_client::HttpClient,
+    /// Pools of durable nonce accounts assembled messages lease from
+    nonces: Option<Nonces>,
+    asset_rpc_client: Arc<dyn DasProvider>,
+    asset_rpc_hedge_delay: Option<Duration>,
+    simulate_transactions: bool,
+    submit_commitment: Option<CommitmentLevel>,
+    bundles: Option<Jito>,
+    revenue_wallet: Option<Pubkey>,
+    auction_house: Option<Pubkey>,
+    multisig: Option<Multisig>,
+    retries: Retries,
+}
+
+/// A payment from the recipient of a mint to the project's revenue wallet,
+/// assembled into the same message as the mint so that neither lands
+/// without the other. The recipient signs the message, so it must be a
+/// wallet the treasury service can sign for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaymentLeg {
+    pub payer: Pubkey,
+    pub revenue_wallet: Pubkey,
+    /// Lamports, or base units of `spl_mint`
+    pub amount: u64,
+    /// The SPL token the payment is made in, or `None` for SOL
+    pub spl_mint: Option<Pubkey>,
+}
+
+impl PaymentLeg {
+    /// Instructions moving the payment. For SPL payments, `fee_payer` funds
+    /// the revenue wallet's token account if it does not exist yet.
+    ///
+    /// # Errors
+    /// This function fails if the token transfer cannot be built
+    pub fn instructions(&self, fee_payer: &Pubkey) -> Result<Vec<Instruction>> {
+        let Some(spl_mint) = self.spl_mint else {
+            return Ok(vec![system_instruction::transfer(
+                &self.payer,
+                &self.revenue_wallet,
+                self.amount,
+            )]);
+        };
+
+        Ok(vec![
+            create_associated_token_account_idempotent(
+                fee_payer,
+                &self.revenue_wallet,
+                &spl_mint,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &get_associated_token_address(&self.payer, &spl_mint),
+                &get_associated_token_address(&self.revenue_wallet, &spl_mint),
+                &self.payer,
+                &[],
+                self.amount,
+            )?,
+        ])
+    }
+}
+
+/// A WebSocket connection to the cluster shared by every signature watched
+/// on it. The connection is opened on first use and reopened after a
+/// subscription on it fails.
+#[derive(Clone)]
+struct Pubsub {
+    url: String,
+    client: Arc<tokio::sync::Mutex<Option<Arc<PubsubClient>>>>,
+}
+
+impl Pubsub {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            client: Arc::default(),
+        }
+    }
+
+    async fn client(&self) -> Result<Arc<PubsubClient>> {
+        let mut current = self.client.lock().await;
+
+        if let Some(client) = &*current {
+            return Ok(Arc::clone(client));
+        }
+
+        let connected = Arc::new(PubsubClient::new(&self.url).await?);
+        *current = Some(Arc::clone(&connected));
+
+        Ok(connected)
+    }
+
+    /// Drop the connection `client` so the next watch reconnects, unless it
+    /// has been replaced already
+    async fn reset(&self, client: &Arc<PubsubClient>) {
+        let mut current = self.client.lock().await;
+
+        if current.as_ref().map_or(false, |c| Arc::ptr_eq(c, client)) {
+            *current = None;
+        }
+    }
+}
+
+/// Token Metadata stores names and symbols padded with NUL bytes to their
+/// maximum length
+fn unpad(s: &str) -> String {
+    s.trim_end_matches('\0').to_owned()
+}
+
+/// The signers of `message` in the order they appear in it, with the
+/// signatures made while assembling the message in place of their keys
+fn signers(message: &Message, signed: &[(Pubkey, Signature)]) -> Vec<String> {
+    message
+        .account_keys
+        .iter()
+        .take(usize::from(message.header.num_required_signatures))
+        .map(|key| {
+            signed
+                .iter()
+                .find(|(k, _)| k == key)
+                .map_or_else(|| key.to_string(), |(_, sig)| sig.to_string())
+        })
+        .collect()
+}
+
+/// The leaf index in the first changelog emitted through the noop program
+/// by a compressed mint. Every inner instruction is scanned, so compute
+/// budget instructions and other CPIs do not shift where it is found.
+fn compression_nonce(
+    transaction: &EncodedTransactionWithStatusMeta,
+) -> Result<u32, SolanaAssetIdError> {
+    let meta = transaction
+        .meta
+        .as_ref()
+        .ok_or(SolanaAssetIdError::NoTransactionMeta)?;
+
+    let EncodedTransaction::Json(UiTransaction {
+        message: UiMessage::Raw(message),
+        ..
+    }) = &transaction.transaction
+    else {
+        return Err(SolanaAssetIdError::NoAccountKeys);
+    };
+
+    // Program ids index into the static keys followed by the writable and
+    // readonly keys loaded from address lookup tables
+    let loaded: Option<&UiLoadedAddresses> = meta.loaded_addresses.as_ref().into();
+    let keys: Vec<&String> = message
+        .account_keys
+        .iter()
+        .chain(
+            loaded
+                .into_iter()
+                .flat_map(|l| l.writable.iter().chain(&l.readonly)),
+        )
+        .collect();
+    let noop = spl_noop::id().to_string();
+
+    let inner_instructions: Option<&Vec<UiInnerInstructions>> =
+        meta.inner_instructions.as_ref().into();
+    let inner_instructions = inner_instructions.ok_or(SolanaAssetIdError::NoInnerInstruction)?;
+
+    inner_instructions
+        .iter()
+        .flat_map(|set| &set.instructions)
+        .filter_map(|instruction| match instruction {
+            UiInstruction::Compiled(instruction) => Some(instruction),
+            UiInstruction::Parsed(_) => None,
+        })
+        .filter(|instruction| {
+            keys.get(usize::from(instruction.program_id_index))
+                .map_or(false, |key| **key == noop)
+        })
+        .find_map(|instruction| {
+            let data = bs58::decode(&instruction.data).into_vec().ok()?;
+
+            match AccountCompressionEvent::try_from_slice(&data).ok()? {
+                AccountCompressionEvent::ChangeLog(ChangeLogEvent::V1(ChangeLogEventV1 {
+                    index,
+                    ..
+                })) => Some(index),
+                AccountCompressionEvent::ApplicationData(_) => None,
+            }
+        })
+        .ok_or(SolanaAssetIdError::NoNonce)
 }
 
 impl Solana {
-    pub fn new(args: SolanaArgs) -> Result<Self> {
+    pub fn new(args: SolanaArgs, metrics: Metrics) -> Result<Self> {
         let SolanaArgs {
             solana_endpoint,
+            solana_ws_endpoint,
+            solana_cluster,
+            solana_fallback_endpoints,
+            solana_health_check_interval,
+            solana_rpc_requests_per_second,
+            solana_simulate_transactions,
             solana_treasury_wallet_address,
             digital_asset_api_endpoint,
+            digital_asset_api_provider,
+            digital_asset_api_hedge_delay,
             tree_authority,
             merkle_tree,
+            tree_delegate,
+            submit_commitment,
         } = args;
-        let rpc_client = Arc::new(SolanaRpcClient::new(solana_endpoint));
+        let rpc_pool = RpcPool::new(
+            std::iter::once(solana_endpoint).chain(solana_fallback_endpoints),
+            Duration::from_secs(solana_health_check_interval),
+            solana_rpc_requests_per_second,
+            metrics.clone(),
+        )?;
 
         let (bubblegum_cpi_address, _) = Pubkey::find_program_address(
             &[mpl_bubblegum::state::COLLECTION_CPI_PREFIX.as_bytes()],
@@ -182,31 +529,611 @@ impl Solana {
         );
 
         Ok(Self {
-            rpc_client,
+            cluster: solana_cluster,
+            rpc_api: Arc::new(rpc_pool.clone()),
+            rpc_pool,
+            pubsub: solana_ws_endpoint.map(Pubsub::new),
             treasury_wallet_address: solana_treasury_wallet_address,
+            sponsor_wallet_address: solana_treasury_wallet_address,
+            fee_payer_mode: FeePayerMode::default(),
+            fee_payer: None,
             bubblegum_tree_authority: tree_authority,
+            bubblegum_tree_delegate: tree_delegate.unwrap_or(solana_treasury_wallet_address),
             bubblegum_merkle_tree: merkle_tree,
             bubblegum_cpi_address,
-            asset_rpc_client: jsonrpsee::http_client::HttpClientBuilder::default()
-                .request_timeout(std::time::Duration::from_secs(15))
-                .build(digital_asset_api_endpoint)
-                .context("Failed to initialize asset API client")?,
+            nonces: None,
+            asset_rpc_client: asset_api::provider(
+                digital_asset_api_provider,
+                &digital_asset_api_endpoint,
+            )?,
+            asset_rpc_hedge_delay: digital_asset_api_hedge_delay.map(Duration::from_millis),
+            simulate_transactions: solana_simulate_transactions,
+            submit_commitment,
+            bundles: None,
+            revenue_wallet: None,
+            auction_house: None,
+            multisig: None,
+            retries: Retries::new(
+                vec![],
+                Retries::DEFAULT_THRESHOLD,
+                Retries::DEFAULT_COOLDOWN,
+                metrics,
+            ),
         })
     }
 
-    pub fn asset_rpc(&self) -> jsonrpsee::http_client::HttpClient {
-        self.asset_rpc_client.clone()
+    /// Retry RPC and DAS calls under the policies and circuit breakers of
+    /// `retries`
+    #[must_use]
+    pub fn with_retries(&self, retries: Retries) -> Self {
+        Self {
+            retries,
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn retries(&self) -> &Retries {
+        &self.retries
+    }
+
+    pub fn asset_rpc(&self) -> Arc<dyn DasProvider> {
+        Arc::new(asset_api::Guarded::new(
+            self.asset_rpc_client.clone(),
+            self.retries.clone(),
+            self.asset_rpc_hedge_delay,
+        ))
+    }
+
+    #[must_use]
+    pub fn cluster(&self) -> SolanaCluster {
+        self.cluster
     }
 
     #[must_use]
     pub fn rpc(&self) -> Arc<SolanaRpcClient> {
-        self.rpc_client.clone()
+        self.rpc_pool.get()
+    }
+
+    #[must_use]
+    pub fn rpc_pool(&self) -> RpcPool {
+        self.rpc_pool.clone()
+    }
+
+    /// The cluster calls used to assemble and submit transactions
+    #[must_use]
+    pub fn solana_rpc(&self) -> Arc<dyn SolanaRpc> {
+        self.rpc_api.clone()
+    }
+
+    /// A copy of this client assembling and submitting transactions through
+    /// `rpc`, e.g. a [`MockSolanaRpc`](crate::solana_rpc::MockSolanaRpc)
+    #[must_use]
+    pub fn with_rpc(&self, rpc: Arc<dyn SolanaRpc>) -> Self {
+        Self {
+            rpc_api: rpc,
+            ..self.clone()
+        }
     }
 
     pub fn treasury_wallet(&self) -> Pubkey {
         self.treasury_wallet_address
     }
 
+    /// A copy of this client that pays for assembled transactions from
+    /// `treasury_wallet` instead of the configured treasury
+    #[must_use]
+    pub fn with_treasury_wallet(&self, treasury_wallet: Pubkey) -> Self {
+        Self {
+            treasury_wallet_address: treasury_wallet,
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client that pays for assembled transactions from the
+    /// wallet chosen by `fee_payer_mode`
+    #[must_use]
+    pub fn with_fee_payer_mode(&self, fee_payer_mode: FeePayerMode) -> Self {
+        Self {
+            fee_payer_mode,
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client that pays for assembled transactions from
+    /// `fee_payer`, whatever the fee payer mode
+    #[must_use]
+    pub fn with_fee_payer(&self, fee_payer: Pubkey) -> Self {
+        Self {
+            fee_payer: Some(fee_payer),
+            ..self.clone()
+        }
+    }
+
+    /// The wallet paying the fees and rent of assembled transactions. Vaults
+    /// of multisig treasuries always pay for the transactions proposed to
+    /// them.
+    #[must_use]
+    pub fn payer(&self) -> Pubkey {
+        if let Some(fee_payer) = self.fee_payer {
+            return fee_payer;
+        }
+
+        match self.fee_payer_mode {
+            FeePayerMode::Holaplex if self.multisig.is_none() => self.sponsor_wallet_address,
+            FeePayerMode::Holaplex | FeePayerMode::Project => self.treasury_wallet_address,
+        }
+    }
+
+    /// A copy of this client that pays for assembled transactions from the
+    /// vault of `multisig`, proposing them to the multisig rather than
+    /// having them signed directly
+    #[must_use]
+    pub fn with_multisig(&self, multisig: Multisig) -> Self {
+        Self {
+            treasury_wallet_address: multisig.vault(),
+            multisig: Some(multisig),
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn multisig(&self) -> Option<Multisig> {
+        self.multisig
+    }
+
+    /// A copy of this client that adds the mint price of a collection to its
+    /// mints as a payment to `revenue_wallet`
+    #[must_use]
+    pub fn with_revenue_wallet(&self, revenue_wallet: Pubkey) -> Self {
+        Self {
+            revenue_wallet: Some(revenue_wallet),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client that lists mints on the auction house at
+    /// `auction_house`
+    #[must_use]
+    pub fn with_auction_house(&self, auction_house: Pubkey) -> Self {
+        Self {
+            auction_house: Some(auction_house),
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn auction_house(&self) -> Option<Pubkey> {
+        self.auction_house
+    }
+
+    /// The payment `payer` makes for a mint of `collection`, if the
+    /// collection has a mint price
+    ///
+    /// # Errors
+    /// This function fails if the collection has a price but no revenue
+    /// wallet is configured, or its price or payment mint is invalid
+    pub fn payment_leg(
+        &self,
+        collection: &collections::Model,
+        payer: Pubkey,
+    ) -> Result<Option<PaymentLeg>> {
+        let Some(amount) = collection.mint_price else {
+            return Ok(None);
+        };
+
+        let revenue_wallet = self.revenue_wallet.with_context(|| {
+            format!(
+                "collection {} has a mint price but its project has no revenue wallet",
+                collection.id
+            )
+        })?;
+        let spl_mint = collection
+            .payment_mint
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .context("invalid payment mint")?;
+
+        Ok(Some(PaymentLeg {
+            payer,
+            revenue_wallet,
+            amount: amount.try_into().context("invalid mint price")?,
+            spl_mint,
+        }))
+    }
+
+    /// A copy of this client whose assembled messages lease durable nonce
+    /// accounts of their payer from `nonces`
+    #[must_use]
+    pub fn with_nonces(&self, nonces: Nonces) -> Self {
+        Self {
+            nonces: Some(nonces),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client that tips assembled transactions and submits
+    /// them as Jito bundles
+    #[must_use]
+    pub fn with_bundles(&self, jito: Jito) -> Self {
+        Self {
+            bundles: Some(jito),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this client assembling the `chunk`th of several
+    /// transactions made for one event, which lease nonce accounts for that
+    /// transaction alone
+    #[must_use]
+    pub fn for_chunk(&self, chunk: usize) -> Self {
+        Self {
+            nonces: self.nonces.as_ref().map(|nonces| nonces.for_chunk(chunk)),
+            ..self.clone()
+        }
+    }
+
+    #[must_use]
+    pub fn tree_delegate(&self) -> Pubkey {
+        self.bubblegum_tree_delegate
+    }
+
+    /// A copy of this client that mints compressed assets into the tree at
+    /// `merkle_tree` instead of the configured tree
+    #[must_use]
+    pub fn with_merkle_tree(&self, merkle_tree: Pubkey) -> Self {
+        Self {
+            bubblegum_merkle_tree: merkle_tree,
+            bubblegum_tree_authority: trees::tree_authority(merkle_tree),
+            ..self.clone()
+        }
+    }
+
+    /// Fetch and decode the Token Metadata account at `address`
+    ///
+    /// # Errors
+    /// This function fails if the account cannot be fetched or is not a
+    /// metadata account
+    pub async fn metadata_account(&self, address: Pubkey) -> Result<Metadata> {
+        let account = self
+            .rpc_pool
+            .call(|rpc| async move { rpc.get_account_data(&address).await })
+            .await
+            .with_context(|| format!("failed to fetch metadata account {address}"))?;
+
+        Metadata::safe_deserialize(&account)
+            .with_context(|| format!("failed to deserialize metadata account {address}"))
+    }
+
+    /// Fetch and decode the auction house account mints are listed on
+    ///
+    /// # Errors
+    /// This function fails if no auction house is configured, or its account
+    /// cannot be fetched or does not accept listings without sign off
+    pub async fn auction_house_account(&self) -> Result<AuctionHouse> {
+        let address = self.auction_house.context("project has no auction house")?;
+        let data = self
+            .rpc_pool
+            .call(|rpc| async move { rpc.get_account_data(&address).await })
+            .await
+            .with_context(|| format!("failed to fetch auction house account {address}"))?;
+
+        Ok(AuctionHouse::from_account(address, &data)?)
+    }
+
+    /// Build a message paid by `payer`. If a nonce account of `payer` can be
+    /// leased, the message advances it first and uses the stored nonce in
+    /// place of a recent blockhash, or else `blockhash` or the latest one.
+    /// Nonce accounts of `payer` held back with a stale nonce are advanced
+    /// by the message as well. Messages landed through Jito bundles end with
+    /// a tip to the block engine.
+    ///
+    /// # Errors
+    /// This function fails if the nonce pool cannot be read, or the
+    /// blockhash or nonce account cannot be fetched
+    pub async fn message(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        blockhash: Option<Hash>,
+    ) -> Result<Message> {
+        let mut instructions = match &self.bundles {
+            Some(jito) => [instructions, &[jito.tip(payer)]].concat(),
+            None => instructions.to_vec(),
+        };
+
+        let nonce_account = match &self.nonces {
+            Some(nonces) => {
+                if let Some(stale) = self.stale_nonce_account(nonces, payer).await? {
+                    instructions.push(system_instruction::advance_nonce_account(&stale, payer));
+                }
+
+                nonces.lease(self.cluster, payer).await?
+            },
+            None => None,
+        };
+
+        let Some(nonce_account) = nonce_account else {
+            let blockhash = match blockhash {
+                Some(blockhash) => blockhash,
+                None => with_retry!(self.retries(), self.rpc_api.get_latest_blockhash()).await?,
+            };
+
+            return Ok(Message::new_with_blockhash(
+                &instructions,
+                Some(payer),
+                &blockhash,
+            ));
+        };
+
+        let nonce = self.nonce_account(nonce_account).await?.blockhash();
+        let mut message = Message::new_with_nonce(instructions, Some(payer), &nonce_account, payer);
+        message.recent_blockhash = nonce;
+
+        Ok(message)
+    }
+
+    /// Whether a message paid by `payer` with `instructions` fits in a
+    /// transaction, leaving room for the instructions [`Self::message`]
+    /// adds to it
+    ///
+    /// # Errors
+    /// This function fails if the transaction size cannot be computed
+    pub fn fits(&self, instructions: &[Instruction], payer: &Pubkey) -> Result<bool> {
+        let mut instructions = instructions.to_vec();
+
+        if let Some(jito) = &self.bundles {
+            instructions.push(jito.tip(payer));
+        }
+
+        // A leased nonce account is advanced first, and a stale one after
+        if self.nonces.is_some() {
+            for _ in 0..2 {
+                let account = Pubkey::new_unique();
+                instructions.push(system_instruction::advance_nonce_account(&account, payer));
+            }
+        }
+
+        let message = Message::new(&instructions, Some(payer));
+        let size = bincode::serialized_size(&Transaction::new_unsigned(message))?;
+
+        Ok(size <= PACKET_DATA_SIZE as u64)
+    }
+
+    /// A nonce account of `payer` whose stale nonce is still stored, to be
+    /// advanced by the next message `payer` signs. Accounts whose nonce has
+    /// moved on are returned to the pool.
+    async fn stale_nonce_account(&self, nonces: &Nonces, payer: &Pubkey) -> Result<Option<Pubkey>> {
+        for (address, stale_nonce) in nonces.stale(self.cluster, payer).await? {
+            let nonce = match self.nonce_account(address).await {
+                Ok(data) => data.blockhash(),
+                Err(e) => {
+                    warn!("{e:?}");
+                    continue;
+                },
+            };
+
+            if nonce.to_string() == stale_nonce {
+                return Ok(Some(address));
+            }
+
+            nonces.advanced(&address).await?;
+        }
+
+        Ok(None)
+    }
+
+    /// The state of the initialized nonce account at `address`
+    ///
+    /// # Errors
+    /// This function fails if the account cannot be fetched or is not a
+    /// nonce account
+    pub async fn nonce_account(&self, address: Pubkey) -> Result<NonceData> {
+        let account = self
+            .rpc_pool
+            .call(|rpc| async move {
+                rpc.get_account_with_commitment(&address, rpc.commitment())
+                    .await
+            })
+            .await?
+            .value
+            .with_context(|| format!("nonce account {address} not found"))?;
+        nonce_utils::data_from_account(&account)
+            .with_context(|| format!("invalid nonce account {address}"))
+    }
+
+    /// Replace the blockhash of a previously assembled message so it can be
+    /// signed and submitted again
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be decoded or a blockhash
+    /// cannot be fetched
+    pub async fn refresh_blockhash(&self, serialized_message: &[u8]) -> Result<Vec<u8>> {
+        let mut message: Message = bincode::deserialize(serialized_message)?;
+
+        // The blockhash of a durable nonce message is the nonce itself
+        if uses_durable_nonce(&message) {
+            bail!("message uses a durable nonce");
+        }
+
+        message.recent_blockhash =
+            with_retry!(self.retries(), self.rpc_api.get_latest_blockhash()).await?;
+
+        Ok(message.serialize())
+    }
+
+    #[must_use]
+    pub fn simulate_transactions(&self) -> bool {
+        self.simulate_transactions
+    }
+
+    /// Run an assembled, unsigned transaction against the RPC without
+    /// verifying signatures. Returns the simulation logs if the transaction
+    /// would fail on chain.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be deserialized or the
+    /// simulation request cannot be completed
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &SolanaPendingTransaction,
+    ) -> Result<Option<Vec<String>>> {
+        let message: solana_program::message::Message =
+            bincode::deserialize(&transaction.serialized_message)?;
+        let transaction = Transaction::new_unsigned(message);
+
+        let response = with_retry!(
+            self.retries(),
+            self.rpc_pool.call(|rpc| {
+                let transaction = &transaction;
+
+                async move {
+                    rpc.simulate_transaction_with_config(
+                        transaction,
+                        RpcSimulateTransactionConfig {
+                            sig_verify: false,
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                }
+            })
+        )
+        .await?;
+
+        let RpcSimulateTransactionResult { err, logs, .. } = response.value;
+
+        Ok(err.map(|err| {
+            let mut logs = logs.unwrap_or_default();
+            logs.push(format!("simulation failed: {err}"));
+            logs
+        }))
+    }
+
+    /// Estimate the lamports a transaction will cost the fee payer: rent for
+    /// any accounts it creates, the base signature fee, and the priority fee
+    /// requested through compute budget instructions.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be deserialized or the fee
+    /// or rent cannot be fetched from the RPC
+    pub async fn estimate_cost(&self, serialized_message: &[u8]) -> Result<SolanaTransactionCost> {
+        let message: solana_program::message::Message = bincode::deserialize(serialized_message)?;
+
+        let base_fee_lamports = {
+            let message = &message;
+
+            with_retry!(
+                self.retries(),
+                self.rpc_pool
+                    .call(|rpc| async move { rpc.get_fee_for_message(message).await })
+            )
+            .await?
+        };
+
+        let mut rent_lamports = 0;
+        let mut compute_unit_price = 0;
+        let mut compute_unit_limit = None;
+        let mut instruction_count = 0;
+
+        for ins in &message.instructions {
+            let program_id = message.account_keys[usize::from(ins.program_id_index)];
+
+            if program_id == compute_budget::ID {
+                match try_from_slice_unchecked::<ComputeBudgetInstruction>(&ins.data) {
+                    Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => {
+                        compute_unit_price = price;
+                    },
+                    Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => {
+                        compute_unit_limit = Some(limit);
+                    },
+                    _ => (),
+                }
+
+                continue;
+            }
+
+            instruction_count += 1;
+
+            if program_id == system_program::ID {
+                if let Ok(SystemInstruction::CreateAccount { lamports, .. }) =
+                    bincode::deserialize(&ins.data)
+                {
+                    rent_lamports += lamports;
+                }
+            } else if program_id == spl_associated_token_account::ID {
+                // Idempotent creates of an account which already exists
+                // fund nothing
+                let Some(&ata) = ins
+                    .accounts
+                    .get(1)
+                    .and_then(|i| message.account_keys.get(usize::from(*i)))
+                else {
+                    continue;
+                };
+
+                let exists = with_retry!(
+                    self.retries(),
+                    self.rpc_pool.call(|rpc| async move {
+                        rpc.get_account_with_commitment(&ata, rpc.commitment())
+                            .await
+                    })
+                )
+                .await?
+                .value
+                .is_some();
+
+                if !exists {
+                    rent_lamports += with_retry!(
+                        self.retries(),
+                        self.rpc_api
+                            .get_minimum_balance_for_rent_exemption(spl_token::state::Account::LEN)
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        let compute_unit_limit = compute_unit_limit.map_or_else(
+            || {
+                (DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT * instruction_count)
+                    .min(MAX_COMPUTE_UNIT_LIMIT)
+            },
+            u64::from,
+        );
+        let priority_fee_lamports = compute_unit_price
+            .saturating_mul(compute_unit_limit)
+            .saturating_add(MICRO_LAMPORTS_PER_LAMPORT - 1)
+            / MICRO_LAMPORTS_PER_LAMPORT;
+
+        Ok(SolanaTransactionCost {
+            rent_lamports,
+            base_fee_lamports,
+            priority_fee_lamports,
+            total_lamports: rent_lamports + base_fee_lamports + priority_fee_lamports,
+        })
+    }
+
+    /// The fee charged for the signed message `serialized_message`: the
+    /// signature fee along with any priority fee it requests
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be deserialized or the fee
+    /// cannot be fetched from the RPC
+    pub async fn message_fee(&self, serialized_message: &[u8]) -> Result<u64> {
+        let message: Message = bincode::deserialize(serialized_message)?;
+        let message = &message;
+
+        with_retry!(
+            self.retries(),
+            self.rpc_pool
+                .call(|rpc| async move { rpc.get_fee_for_message(message).await })
+        )
+        .await
+        .map_err(Into::into)
+    }
+
     /// Res
     ///
     /// # Errors
@@ -215,11 +1142,7 @@ impl Solana {
         &self,
         signature: &Signature,
     ) -> Result<u32, SolanaAssetIdError> {
-        let response = (|| async {
-            self.rpc()
-                .get_transaction(signature, UiTransactionEncoding::Json)
-                .await
-        })
+        let response = (|| async { self.rpc_api.get_transaction(signature).await })
         .retry(
             &ExponentialBuilder::default()
                 .with_jitter()
@@ -237,104 +1160,83 @@ impl Solana {
             e
         })?;
 
-        let meta = response
-            .transaction
-            .meta
-            .ok_or(SolanaAssetIdError::NoTransactionMeta)?;
-
-        let inner_instructions: Option<Vec<UiInnerInstructions>> = meta.inner_instructions.into();
-        let inner_instructions =
-            inner_instructions.ok_or(SolanaAssetIdError::NoInnerInstruction)?;
-
-        let instructions = inner_instructions
-            .get(0)
-            .ok_or(SolanaAssetIdError::NoInnerInstruction)?;
-        let noop_change_log_instruction = instructions
-            .instructions
-            .get(3)
-            .ok_or(SolanaAssetIdError::NoInnerInstruction)?;
-
-        if let UiInstruction::Compiled(instruction) = noop_change_log_instruction {
-            let data = bs58::decode(&instruction.data).into_vec()?;
-
-            match AccountCompressionEvent::try_from_slice(&data)? {
-                AccountCompressionEvent::ChangeLog(changelog_event) => {
-                    let ChangeLogEvent::V1(changelog_event) = changelog_event;
-                    let ChangeLogEventV1 { index, .. } = changelog_event;
-
-                    Ok(index)
-                },
-                AccountCompressionEvent::ApplicationData(_) => Err(SolanaAssetIdError::NoNonce),
-            }
-        } else {
-            Err(SolanaAssetIdError::NoNonce)
-        }
+        compression_nonce(&response.transaction)
     }
 
-    /// Res
+    /// Submit a signed transaction, waiting for the configured commitment if
+    /// there is one. Bundled transactions are only checked to have been
+    /// processed.
     ///
     /// # Errors
     /// This function fails if unable to submit transaction to Solana
     pub async fn submit_transaction(
         &self,
         transaction: &SolanaTransactionResult,
-    ) -> Result<String> {
-        let signatures = transaction
-            .signed_message_signatures
-            .iter()
-            .map(|s| Signature::from_str(s).context("failed to parse signature"))
-            .collect::<Result<Vec<Signature>>>()?;
-
-        let message = bincode::deserialize(
-            &transaction
-                .serialized_message
-                .clone()
-                .ok_or(SolanaErrorNotFoundMessage::Serialized)?,
-        )?;
+    ) -> Result<SubmittedTransaction> {
+        let transaction = signed_transaction(transaction)?;
 
-        let transaction = Transaction {
-            signatures,
-            message,
-        };
+        if let Some(jito) = &self.bundles {
+            let bundle_id = jito.send_bundle(&transaction).await?;
+            info!("submitted bundle {bundle_id}");
 
-        let signature = with_retry!(self.rpc().send_transaction_with_config(
-            &transaction,
-            RpcSendTransactionConfig {
-                skip_preflight: true,
-                ..Default::default()
-            }
-        ))
-        .when(|e| {
-            !matches!(
-                e.kind,
-                ClientErrorKind::TransactionError(_) | ClientErrorKind::SigningError(_)
-            )
-        })
-        .notify(|err: &ClientError, dur: Duration| {
-            error!(
-                "failed to send transaction retrying error {:?} in {:?}",
-                err, dur
-            );
-        })
-        .await
-        .map_err(|e| {
-            let msg = format!("failed to send transaction: {e}");
-            error!(msg);
-            anyhow!(msg)
-        })?;
+            return self.await_processed(&transaction).await;
+        }
+
+        if let Some(commitment) = self.submit_commitment {
+            return self.send_and_confirm(&transaction, commitment).await;
+        }
+
+        with_retry!(self.retries(), self.rpc_api.send_transaction(&transaction))
+            .when(|e| {
+                !e.is_circuit_open()
+                    && !matches!(
+                        e.kind,
+                        ClientErrorKind::TransactionError(_) | ClientErrorKind::SigningError(_)
+                    )
+            })
+            .notify(|err: &ClientError, dur: Duration| {
+                error!(
+                    "failed to send transaction retrying error {:?} in {:?}",
+                    err, dur
+                );
+            })
+            .await
+            .map_err(|e| {
+                let msg = format!("failed to send transaction: {e}");
+                error!(msg);
+                anyhow!(msg)
+            })?;
+
+        self.await_processed(&transaction).await
+    }
+
+    /// Wait for a sent transaction to be processed, failing if it errors or
+    /// its blockhash expires
+    async fn await_processed(&self, transaction: &Transaction) -> Result<SubmittedTransaction> {
+        let signature = *transaction.get_signature();
+
+        if let Some(res) = self.watch_signature(&signature).await {
+            return res.map(|()| SubmittedTransaction {
+                signature: signature.to_string(),
+                slot: None,
+                payer: fee_payer(transaction),
+            });
+        }
 
         let recent_blockhash = transaction.get_recent_blockhash();
 
         loop {
-            let signature_status = with_retry!(self.rpc().get_signature_status(&signature)).await?;
+            let signature_status = with_retry!(
+                self.retries(),
+                self.rpc_pool
+                    .call(|rpc| async move { rpc.get_signature_status(&signature).await })
+            )
+            .await?;
 
             match signature_status {
                 Some(Ok(_)) => break,
                 None => {
-                    let valid_blockhash = self
-                        .rpc()
-                        .is_blockhash_valid(recent_blockhash, CommitmentConfig::finalized())
-                        .await?;
+                    let valid_blockhash = self.is_blockhash_valid(recent_blockhash).await?;
 
                     if valid_blockhash {
                         tokio::time::sleep(std::time::Duration::from_millis(250)).await;
@@ -353,16 +1255,293 @@ impl Solana {
             }
         }
 
-        Ok(signature.to_string())
+        Ok(SubmittedTransaction {
+            signature: signature.to_string(),
+            slot: None,
+            payer: fee_payer(transaction),
+        })
+    }
+
+    /// Determine what became of a signed transaction whose submission
+    /// failed, waiting for its blockhash to expire if it has not landed yet.
+    /// A transaction using a durable nonce is reported without waiting, as
+    /// it only expires once its nonce is advanced.
+    ///
+    /// # Errors
+    /// This function fails if the transaction cannot be decoded or its status
+    /// cannot be fetched
+    pub async fn settle(&self, transaction: &SolanaTransactionResult) -> Result<Settled> {
+        let transaction = signed_transaction(transaction)?;
+        let signature = *transaction.get_signature();
+        let recent_blockhash = transaction.get_recent_blockhash();
+        let durable = uses_durable_nonce(&transaction.message);
+
+        loop {
+            // Checked before the status so a transaction landing in between
+            // is not reported as expired
+            let valid_blockhash = durable || self.is_blockhash_valid(recent_blockhash).await?;
+
+            let status = with_retry!(
+                self.retries(),
+                self.rpc_pool.call(|rpc| async move {
+                    rpc.get_signature_statuses_with_history(&[signature]).await
+                })
+            )
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+
+            match status {
+                Some(status) => {
+                    return Ok(status.err.map_or_else(
+                        || {
+                            Settled::Landed(SubmittedTransaction {
+                                signature: signature.to_string(),
+                                slot: None,
+                                payer: fee_payer(&transaction),
+                            })
+                        },
+                        Settled::Failed,
+                    ));
+                },
+                None if durable => return Ok(Settled::Durable),
+                None if !valid_blockhash => return Ok(Settled::Expired),
+                None => tokio::time::sleep(std::time::Duration::from_millis(250)).await,
+            }
+        }
+    }
+
+    /// Wait for a processed notification for `signature` over WebSocket.
+    /// Returns `None` if no WebSocket endpoint is configured, or the
+    /// subscription fails or times out, in which case the caller should poll.
+    async fn watch_signature(&self, signature: &Signature) -> Option<Result<()>> {
+        let pubsub = self.pubsub.as_ref()?;
+
+        let client = pubsub
+            .client()
+            .await
+            .map_err(|e| {
+                warn!(
+                    "failed to connect to {}, polling for {signature}: {e}",
+                    pubsub.url
+                );
+            })
+            .ok()?;
+
+        let subscription = client
+            .signature_subscribe(
+                signature,
+                Some(RpcSignatureSubscribeConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                    enable_received_notification: Some(false),
+                }),
+            )
+            .await;
+
+        let (mut notifications, unsubscribe) = match subscription {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                warn!("failed to subscribe to {signature}, polling instead: {e}");
+                pubsub.reset(&client).await;
+
+                return None;
+            },
+        };
+
+        // The transaction may have landed before the subscription was opened
+        let status = self
+            .rpc_pool
+            .call(|rpc| async move { rpc.get_signature_status(signature).await })
+            .await
+            .ok()
+            .flatten();
+
+        let res = if let Some(status) = status {
+            Some(status.map_err(|e| anyhow!("failed to send transaction: {e}")))
+        } else {
+            let notification =
+                tokio::time::timeout(SIGNATURE_SUBSCRIPTION_TIMEOUT, notifications.next()).await;
+
+            match notification {
+                Ok(Some(Response {
+                    value: RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err }),
+                    ..
+                })) => {
+                    Some(err.map_or(Ok(()), |e| Err(anyhow!("failed to send transaction: {e}"))))
+                },
+                Ok(Some(_)) | Err(_) => None,
+                Ok(None) => {
+                    warn!("signature subscription for {signature} dropped, polling instead");
+                    pubsub.reset(&client).await;
+                    None
+                },
+            }
+        };
+
+        drop(notifications);
+        unsubscribe().await;
+
+        if let Some(Err(e)) = &res {
+            error!("{e}");
+        }
+
+        res
+    }
+
+    /// Send a transaction with preflight checks and poll for its status
+    /// until it reaches `commitment`, failing if it errors or does not land
+    /// before its blockhash expires
+    async fn send_and_confirm(
+        &self,
+        transaction: &Transaction,
+        commitment: CommitmentLevel,
+    ) -> Result<SubmittedTransaction> {
+        let signature = self
+            .rpc_pool
+            .call(|rpc| async move {
+                rpc.send_transaction_with_config(transaction, RpcSendTransactionConfig {
+                    preflight_commitment: Some(commitment),
+                    ..Default::default()
+                })
+                .await
+            })
+            .await
+            .map_err(|e| {
+                let msg = format!("failed to send transaction: {e}");
+                error!(msg);
+                anyhow!(msg)
+            })?;
+
+        let commitment = CommitmentConfig { commitment };
+        let recent_blockhash = transaction.get_recent_blockhash();
+        let durable = uses_durable_nonce(&transaction.message);
+        let deadline = tokio::time::Instant::now() + SIGNATURE_SUBSCRIPTION_TIMEOUT;
+
+        loop {
+            // Checked before the status so a transaction landing in between
+            // is not reported as expired
+            let valid_blockhash = if durable {
+                tokio::time::Instant::now() < deadline
+            } else {
+                self.is_blockhash_valid(recent_blockhash).await?
+            };
+
+            let status = with_retry!(
+                self.retries(),
+                self.rpc_pool
+                    .call(|rpc| async move { rpc.get_signature_statuses(&[signature]).await })
+            )
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+
+            match status {
+                Some(TransactionStatus { err: Some(e), .. }) => {
+                    let msg = format!("failed to send transaction: {e}");
+                    error!(msg);
+                    bail!(msg)
+                },
+                Some(status) if status.satisfies_commitment(commitment) => {
+                    return Ok(SubmittedTransaction {
+                        signature: signature.to_string(),
+                        slot: Some(status.slot),
+                        payer: fee_payer(transaction),
+                    });
+                },
+                None if !valid_blockhash => {
+                    let msg = format!("transaction {signature} did not land in time");
+                    error!(msg);
+                    bail!(msg)
+                },
+                Some(_) | None => tokio::time::sleep(std::time::Duration::from_millis(250)).await,
+            }
+        }
+    }
+
+    /// Whether `blockhash` can still be used by a transaction, as judged by
+    /// the finalized bank
+    async fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool> {
+        with_retry!(
+            self.retries(),
+            self.rpc_pool.call(|rpc| async move {
+                rpc.is_blockhash_valid(blockhash, CommitmentConfig::finalized())
+                    .await
+            })
+        )
+        .await
+        .map_err(Into::into)
     }
 }
 
+/// Rebuild a transaction signed by the treasury
+fn signed_transaction(transaction: &SolanaTransactionResult) -> Result<Transaction> {
+    let signatures = transaction
+        .signed_message_signatures
+        .iter()
+        .map(|s| Signature::from_str(s).context("failed to parse signature"))
+        .collect::<Result<Vec<Signature>>>()?;
+
+    let message = bincode::deserialize(
+        transaction
+            .serialized_message
+            .as_deref()
+            .ok_or(SolanaErrorNotFoundMessage::Serialized)?,
+    )?;
+
+    Ok(Transaction {
+        signatures,
+        message,
+    })
+}
+
+/// Whether the first instruction of `message` advances a durable nonce, in
+/// which case its blockhash is the nonce
+fn uses_durable_nonce(message: &Message) -> bool {
+    message.instructions.first().map_or(false, |ins| {
+        message.account_keys.get(usize::from(ins.program_id_index)) == Some(&system_program::ID)
+            && matches!(
+                bincode::deserialize(&ins.data),
+                Ok(SystemInstruction::AdvanceNonceAccount)
+            )
+    })
+}
+
+/// The durable nonce the signed message of `res` was assembled with, if it
+/// uses one
+#[must_use]
+pub fn durable_nonce(res: &SolanaTransactionResult) -> Option<Hash> {
+    let message: Message = bincode::deserialize(res.serialized_message.as_deref()?).ok()?;
+
+    uses_durable_nonce(&message).then_some(message.recent_blockhash)
+}
+
+/// The wallet paying the fees of `transaction`, which is always the first
+/// account of its message
+fn fee_payer(transaction: &Transaction) -> String {
+    transaction
+        .message
+        .account_keys
+        .first()
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
 #[repr(transparent)]
 pub struct UncompressedRef<'a>(pub &'a Solana);
 #[repr(transparent)]
 pub struct CompressedRef<'a>(pub &'a Solana);
 #[repr(transparent)]
 pub struct EditionRef<'a>(pub &'a Solana);
+#[repr(transparent)]
+pub struct SquadsRef<'a>(pub &'a Solana);
+#[repr(transparent)]
+pub struct HydraRef<'a>(pub &'a Solana);
+#[repr(transparent)]
+pub struct CandyMachineRef<'a>(pub &'a Solana);
 
 #[async_trait]
 impl<'a> CollectionBackend for UncompressedRef<'a> {
@@ -372,8 +1551,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
     ) -> hub_core::prelude::Result<TransactionResponse<MasterEditionAddresses>> {
         let MetaplexMasterEditionTransaction { master_edition, .. } = txn;
         let master_edition = master_edition.ok_or(SolanaErrorNotFoundMessage::MasterEdition)?;
-        let payer: Pubkey = self.0.treasury_wallet_address;
-        let rpc = &self.0.rpc_client;
+        let payer: Pubkey = self.0.payer();
         let mint = Keypair::new();
         let MasterEdition {
             name,
@@ -406,8 +1584,11 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
         );
         let len = spl_token::state::Mint::LEN;
 
-        let rent = with_retry!(rpc.get_minimum_balance_for_rent_exemption(len)).await?;
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
+        let rent = with_retry!(
+            self.0.retries(),
+            self.0.rpc_api.get_minimum_balance_for_rent_exemption(len)
+        )
+        .await?;
 
         let create_account_ins = solana_program::system_instruction::create_account(
             &payer,
@@ -448,13 +1629,8 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
                 name,
                 symbol,
                 metadata_uri,
-                Some(
-                    creators
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<Creator>, _>>()?,
-                ),
-                seller_fee_basis_points.try_into()?,
+                Some(metaplex::creators(creators)?),
+                metaplex::basis_points(seller_fee_basis_points)?,
                 true,
                 true,
                 None,
@@ -480,11 +1656,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
             create_master_edition_ins,
         ];
 
-        let message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        );
+        let message = self.0.message(&instructions, &payer, None).await?;
 
         let serialized_message = message.serialize();
         let mint_signature = mint.try_sign_message(&message.serialize())?;
@@ -496,6 +1668,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
                 mint_signature.to_string(),
                 owner.to_string(),
             ],
+            ephemeral_keypairs: vec![mint.to_bytes()],
             addresses: MasterEditionAddresses {
                 master_edition,
                 update_authority: owner,
@@ -512,8 +1685,6 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
         collection: &collections::Model,
         txn: MetaplexMasterEditionTransaction,
     ) -> hub_core::prelude::Result<TransactionResponse<UpdateMasterEditionAddresses>> {
-        let rpc = &self.0.rpc_client;
-
         let MetaplexMasterEditionTransaction { master_edition, .. } = txn;
 
         let master_edition = master_edition.ok_or(SolanaErrorNotFoundMessage::MasterEdition)?;
@@ -527,7 +1698,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
             ..
         } = master_edition;
 
-        let payer: Pubkey = self.0.treasury_wallet_address;
+        let payer: Pubkey = self.0.payer();
 
         let program_pubkey = mpl_token_metadata::id();
         let update_authority: Pubkey = master_edition.owner_address.parse()?;
@@ -542,13 +1713,8 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
                 name,
                 symbol,
                 uri: metadata_uri,
-                seller_fee_basis_points: seller_fee_basis_points.try_into()?,
-                creators: Some(
-                    creators
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<Creator>>>()?,
-                ),
+                seller_fee_basis_points: metaplex::basis_points(seller_fee_basis_points)?,
+                creators: Some(metaplex::creators(creators)?),
                 collection: None,
                 uses: None,
             }),
@@ -556,10 +1722,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
             None,
         );
 
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
-
-        let message =
-            solana_program::message::Message::new_with_blockhash(&[ins], Some(&payer), &blockhash);
+        let message = self.0.message(&[ins], &payer, None).await?;
 
         let serialized_message = message.serialize();
 
@@ -569,6 +1732,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
                 payer.to_string(),
                 update_authority.to_string(),
             ],
+            ephemeral_keypairs: vec![],
             addresses: UpdateMasterEditionAddresses {
                 metadata,
                 update_authority,
@@ -576,80 +1740,150 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
         })
     }
 
-    async fn update_mint(
+    async fn update_assets(
         &self,
         collection: &collections::Model,
-        collection_mint: &collection_mints::Model,
-        payload: UpdateSolanaMintPayload,
-    ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>> {
-        let metadata = payload
-            .metadata
-            .ok_or(SolanaErrorNotFoundMessage::Metadata)?;
-        let payer: Pubkey = self.0.treasury_wallet_address;
-        let rpc = &self.0.rpc_client;
-
-        let MetaplexMetadata {
-            name,
-            symbol,
-            seller_fee_basis_points,
-            metadata_uri,
-            creators,
-            owner_address,
-        } = metadata;
-        let update_authority: Pubkey = owner_address.parse()?;
-        let mint_pubkey: Pubkey = collection_mint.mint.parse()?;
-
-        let (metadata, _) = Pubkey::find_program_address(
-            &[
-                b"metadata",
-                mpl_token_metadata::ID.as_ref(),
-                mint_pubkey.as_ref(),
-            ],
-            &mpl_token_metadata::ID,
-        );
+        metadata_uri: String,
+    ) -> Result<TransactionResponse<UpdateMasterEditionAddresses>> {
+        let payer: Pubkey = self.0.payer();
+        let update_authority: Pubkey = collection.update_authority.parse()?;
+        let metadata: Pubkey = collection.metadata.parse()?;
 
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
+        let Metadata { data, .. } = self.0.metadata_account(metadata).await?;
 
-        let update_ins: Instruction = mpl_token_metadata::instruction::update_metadata_accounts_v2(
+        let ins = update_metadata_accounts_v2(
             mpl_token_metadata::ID,
             metadata,
             update_authority,
             None,
             Some(DataV2 {
-                name,
-                symbol,
+                name: unpad(&data.name),
+                symbol: unpad(&data.symbol),
                 uri: metadata_uri,
-                seller_fee_basis_points: seller_fee_basis_points.try_into()?,
-                creators: Some(
-                    creators
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<Creator>, _>>()?,
-                ),
-                collection: Some(mpl_token_metadata::state::Collection {
-                    verified: true,
-                    key: collection.mint.parse()?,
-                }),
+                seller_fee_basis_points: data.seller_fee_basis_points,
+                creators: data.creators,
+                collection: None,
                 uses: None,
             }),
             None,
-            Some(true),
+            None,
+        );
+
+        let message = self.0.message(&[ins], &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![
+                payer.to_string(),
+                update_authority.to_string(),
+            ],
+            ephemeral_keypairs: vec![],
+            addresses: UpdateMasterEditionAddresses {
+                metadata,
+                update_authority,
+            },
+        })
+    }
+
+    async fn lock_metadata(
+        &self,
+        collection: &collections::Model,
+        mints: &[collection_mints::Model],
+        lock_collection: bool,
+    ) -> Result<TransactionResponse<LockMetadataAddresses>> {
+        let payer: Pubkey = self.0.payer();
+        let update_authority: Pubkey = collection.update_authority.parse()?;
+
+        let mut metadata = Vec::new();
+
+        if lock_collection {
+            metadata.push(collection.metadata.parse()?);
+        }
+
+        for collection_mint in mints {
+            let mint: Pubkey = collection_mint.mint.parse()?;
+            let (address, _) = Pubkey::find_program_address(
+                &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+                &mpl_token_metadata::ID,
+            );
+
+            metadata.push(address);
+        }
+
+        let instructions: Vec<_> = metadata
+            .iter()
+            .map(|address| {
+                update_metadata_accounts_v2(
+                    mpl_token_metadata::ID,
+                    *address,
+                    update_authority,
+                    None,
+                    None,
+                    None,
+                    Some(false),
+                )
+            })
+            .collect();
+
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        let size = bincode::serialized_size(&Transaction::new_unsigned(message.clone()))?;
+
+        if size > PACKET_DATA_SIZE as u64 {
+            bail!(
+                "metadata lock of {} assets does not fit in a single transaction ({size} bytes)",
+                metadata.len()
+            );
+        }
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![
+                payer.to_string(),
+                update_authority.to_string(),
+            ],
+            ephemeral_keypairs: vec![],
+            addresses: LockMetadataAddresses {
+                update_authority,
+                metadata,
+            },
+        })
+    }
+
+    async fn mark_primary_sale(
+        &self,
+        collection: &collections::Model,
+        mint: &collection_mints::Model,
+    ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>> {
+        let payer: Pubkey = self.0.payer();
+        let update_authority: Pubkey = collection.update_authority.parse()?;
+        let mint: Pubkey = mint.mint.parse()?;
+
+        let (metadata, _) = Pubkey::find_program_address(
+            &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+            &mpl_token_metadata::ID,
         );
 
-        let message = solana_program::message::Message::new_with_blockhash(
-            &[update_ins],
-            Some(&payer),
-            &blockhash,
+        // The flag can be set on immutable metadata and never unset
+        let ins = update_metadata_accounts_v2(
+            mpl_token_metadata::ID,
+            metadata,
+            update_authority,
+            None,
+            None,
+            Some(true),
+            None,
         );
 
-        let serialized_message = message.serialize();
+        let message = self.0.message(&[ins], &payer, None).await?;
 
         Ok(TransactionResponse {
-            serialized_message,
+            serialized_message: message.serialize(),
             signatures_or_signers_public_keys: vec![
                 payer.to_string(),
                 update_authority.to_string(),
             ],
+            ephemeral_keypairs: vec![],
             addresses: UpdateCollectionMintAddresses {
                 payer,
                 metadata,
@@ -660,26 +1894,48 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
 
     async fn retry_update_mint(
         &self,
+        collection: &collections::Model,
+        collection_mint: &collection_mints::Model,
         revision: &update_revisions::Model,
     ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>> {
-        let rpc = &self.0.rpc_client;
-
         let update_authority: Pubkey = revision.update_authority.parse()?;
         let metadata = revision.metadata.parse()?;
         let payer = Pubkey::from_str(&revision.payer)?;
+        let mint_pubkey: Pubkey = collection_mint.mint.parse()?;
+
+        let (current_metadata, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                mpl_token_metadata::ID.as_ref(),
+                mint_pubkey.as_ref(),
+            ],
+            &mpl_token_metadata::ID,
+        );
+
+        if metadata != current_metadata {
+            return Err(StaleRevision::Metadata.into());
+        }
 
-        let mut message: solana_program::message::Message =
-            bincode::deserialize(&revision.serialized_message)?;
+        if revision.update_authority != collection.update_authority {
+            return Err(StaleRevision::UpdateAuthority.into());
+        }
+
+        if payer != self.0.payer() {
+            return Err(StaleRevision::Payer.into());
+        }
 
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
-        message.recent_blockhash = blockhash;
+        let serialized_message = self
+            .0
+            .refresh_blockhash(&revision.serialized_message)
+            .await?;
 
         Ok(TransactionResponse {
-            serialized_message: message.serialize(),
+            serialized_message,
             signatures_or_signers_public_keys: vec![
                 payer.to_string(),
                 update_authority.to_string(),
             ],
+            ephemeral_keypairs: vec![],
             addresses: UpdateCollectionMintAddresses {
                 payer,
                 metadata,
@@ -694,8 +1950,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
         collection: &collections::Model,
         new_collection: &collections::Model,
     ) -> Result<TransactionResponse<SwitchCollectionAddresses>> {
-        let rpc = &self.0.rpc_client;
-        let payer = self.0.treasury_wallet_address;
+        let payer = self.0.payer();
 
         let mint_pubkey = Pubkey::from_str(&mint.mint)?;
         let program_pubkey = mpl_token_metadata::id();
@@ -756,13 +2011,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
 
         let instructions = vec![unverify_ins, verify_ins];
 
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
-
-        let message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        );
+        let message = self.0.message(&instructions, &payer, None).await?;
 
         let serialized_message = message.serialize();
 
@@ -772,6 +2021,7 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
                 payer.to_string(),
                 collection_authority.to_string(),
             ],
+            ephemeral_keypairs: vec![],
             addresses: SwitchCollectionAddresses {
                 payer,
                 new_collection_authority,
@@ -780,6 +2030,285 @@ impl<'a> CollectionBackend for UncompressedRef<'a> {
     }
 }
 
+#[async_trait]
+impl<'a> UpdateMintBackend<collection_mints::Model, UpdateCollectionMintAddresses>
+    for UncompressedRef<'a>
+{
+    async fn update_mint(
+        &self,
+        collection: &collections::Model,
+        collection_mint: &collection_mints::Model,
+        payload: UpdateSolanaMintPayload,
+    ) -> Result<TransactionResponse<UpdateCollectionMintAddresses>> {
+        let payer: Pubkey = self.0.payer();
+        let mint_pubkey: Pubkey = collection_mint.mint.parse()?;
+
+        let (metadata, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                mpl_token_metadata::ID.as_ref(),
+                mint_pubkey.as_ref(),
+            ],
+            &mpl_token_metadata::ID,
+        );
+
+        let (update_authority, data) = match payload {
+            UpdateSolanaMintPayload {
+                metadata: Some(metadata),
+                ..
+            } => {
+                let MetaplexMetadata {
+                    name,
+                    symbol,
+                    seller_fee_basis_points,
+                    metadata_uri,
+                    creators,
+                    owner_address,
+                } = metadata;
+
+                (owner_address.parse()?, DataV2 {
+                    name,
+                    symbol,
+                    uri: metadata_uri,
+                    seller_fee_basis_points: metaplex::basis_points(seller_fee_basis_points)?,
+                    creators: Some(metaplex::creators(creators)?),
+                    collection: None,
+                    uses: None,
+                })
+            },
+            // Fields left out of a patch keep their current on-chain values
+            UpdateSolanaMintPayload {
+                metadata_patch: Some(patch),
+                ..
+            } => {
+                let Metadata { data, .. } = self.0.metadata_account(metadata).await?;
+                let MetaplexMetadataPatch {
+                    name,
+                    symbol,
+                    seller_fee_basis_points,
+                    metadata_uri,
+                    creators,
+                    owner_address,
+                } = patch;
+
+                (owner_address.parse()?, DataV2 {
+                    name: name.unwrap_or_else(|| unpad(&data.name)),
+                    symbol: symbol.unwrap_or_else(|| unpad(&data.symbol)),
+                    uri: metadata_uri.unwrap_or_else(|| unpad(&data.uri)),
+                    seller_fee_basis_points: seller_fee_basis_points
+                        .map(metaplex::basis_points)
+                        .transpose()?
+                        .unwrap_or(data.seller_fee_basis_points),
+                    creators: match creators {
+                        Some(MetaplexCreators { creators }) => Some(metaplex::creators(creators)?),
+                        None => data.creators,
+                    },
+                    collection: None,
+                    uses: None,
+                })
+            },
+            _ => return Err(SolanaErrorNotFoundMessage::Metadata.into()),
+        };
+
+        let update_ins: Instruction = mpl_token_metadata::instruction::update_metadata_accounts_v2(
+            mpl_token_metadata::ID,
+            metadata,
+            update_authority,
+            None,
+            Some(DataV2 {
+                collection: Some(mpl_token_metadata::state::Collection {
+                    verified: true,
+                    key: collection.mint.parse()?,
+                }),
+                ..data
+            }),
+            None,
+            Some(true),
+        );
+
+        let message = self.0.message(&[update_ins], &payer, None).await?;
+
+        let serialized_message = message.serialize();
+
+        Ok(TransactionResponse {
+            serialized_message,
+            signatures_or_signers_public_keys: vec![
+                payer.to_string(),
+                update_authority.to_string(),
+            ],
+            ephemeral_keypairs: vec![],
+            addresses: UpdateCollectionMintAddresses {
+                payer,
+                metadata,
+                update_authority,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> UpdateMintBackend<compression_leafs::Model, UpdateCompressedMintAddresses>
+    for CompressedRef<'a>
+{
+    async fn update_mint(
+        &self,
+        collection: &collections::Model,
+        compression_leaf: &compression_leafs::Model,
+        payload: UpdateSolanaMintPayload,
+    ) -> Result<TransactionResponse<UpdateCompressedMintAddresses>> {
+        // Bubblegum leaves fields of the update arguments that are not set as
+        // they are, so a patch maps onto them directly
+        let (owner_address, update_args) = match payload {
+            UpdateSolanaMintPayload {
+                metadata: Some(metadata),
+                ..
+            } => {
+                let MetaplexMetadata {
+                    name,
+                    symbol,
+                    seller_fee_basis_points,
+                    metadata_uri,
+                    creators,
+                    owner_address,
+                } = metadata;
+
+                (owner_address, UpdateArgs {
+                    name: Some(name),
+                    symbol: Some(symbol),
+                    uri: Some(metadata_uri),
+                    creators: Some(metaplex::creators(creators)?),
+                    seller_fee_basis_points: Some(metaplex::basis_points(seller_fee_basis_points)?),
+                    primary_sale_happened: None,
+                    is_mutable: None,
+                })
+            },
+            UpdateSolanaMintPayload {
+                metadata_patch: Some(patch),
+                ..
+            } => {
+                let MetaplexMetadataPatch {
+                    name,
+                    symbol,
+                    seller_fee_basis_points,
+                    metadata_uri,
+                    creators,
+                    owner_address,
+                } = patch;
+
+                (owner_address, UpdateArgs {
+                    name,
+                    symbol,
+                    uri: metadata_uri,
+                    creators: creators
+                        .map(|MetaplexCreators { creators }| metaplex::creators(creators))
+                        .transpose()?,
+                    seller_fee_basis_points: seller_fee_basis_points
+                        .map(metaplex::basis_points)
+                        .transpose()?,
+                    primary_sale_happened: None,
+                    is_mutable: None,
+                })
+            },
+            _ => return Err(SolanaErrorNotFoundMessage::Metadata.into()),
+        };
+        let payer = self.0.payer();
+        let update_authority: Pubkey = owner_address.parse()?;
+
+        let asset_api = &self.0.asset_rpc();
+
+        let tree_authority_address = Pubkey::from_str(&compression_leaf.tree_authority)?;
+        let merkle_tree_address = Pubkey::from_str(&compression_leaf.merkle_tree)?;
+
+        let asset_id = compression_leaf
+            .asset_id
+            .clone()
+            .ok_or(SolanaAssetIdError::NotFound)?;
+        let asset = asset_api
+            .get_asset(&asset_id)
+            .await
+            .context("fetching asset from DAA")?;
+        let asset_proof = asset_api
+            .get_asset_proof(&asset_id)
+            .await
+            .context("fetching asset proof from DAA")?;
+
+        let root: Vec<u8> = asset_proof.root.into();
+        let leaf_id = asset.compression.leaf_id;
+        let leaf_owner: Pubkey = asset.ownership.owner.clone().try_into()?;
+        let leaf_delegate: Pubkey = asset
+            .ownership
+            .delegate
+            .clone()
+            .map(TryInto::try_into)
+            .transpose()?
+            .unwrap_or(leaf_owner);
+        let proofs = asset_proof
+            .proof
+            .into_iter()
+            .map(|proof| Ok(AccountMeta::new_readonly(proof.try_into()?, false)))
+            .collect::<Result<Vec<AccountMeta>>>()?;
+
+        let current_metadata = asset.into_metadata_args()?;
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(tree_authority_address, false),
+            // Update authority [signer]
+            AccountMeta::new_readonly(update_authority, true),
+            // Collection mint
+            AccountMeta::new_readonly(collection.mint.parse()?, false),
+            // Collection metadata
+            AccountMeta::new_readonly(collection.metadata.parse()?, false),
+            // Collection authority record pda
+            AccountMeta::new_readonly(mpl_bubblegum::ID, false),
+            AccountMeta::new_readonly(leaf_owner, false),
+            AccountMeta::new_readonly(leaf_delegate, false),
+            // Payer [signer]
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(merkle_tree_address, false),
+            AccountMeta::new_readonly(spl_noop::ID, false),
+            AccountMeta::new_readonly(spl_account_compression::ID, false),
+            AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+
+        accounts.extend(proofs);
+
+        let instructions = [Instruction {
+            program_id: mpl_bubblegum::ID,
+            accounts,
+            data: mpl_bubblegum::instruction::UpdateMetadata {
+                root: root.try_into().map_err(|_| anyhow!("Invalid root hash"))?,
+                nonce: leaf_id.into(),
+                index: leaf_id,
+                current_metadata,
+                update_args,
+            }
+            .data(),
+        }];
+
+        let serialized_message = self
+            .0
+            .message(&instructions, &payer, None)
+            .await?
+            .serialize();
+
+        Ok(TransactionResponse {
+            serialized_message,
+            signatures_or_signers_public_keys: vec![
+                payer.to_string(),
+                update_authority.to_string(),
+            ],
+            ephemeral_keypairs: vec![],
+            addresses: UpdateCompressedMintAddresses {
+                payer,
+                update_authority,
+                merkle_tree: merkle_tree_address,
+                leaf_owner,
+            },
+        })
+    }
+}
+
 #[async_trait]
 
 impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for EditionRef<'a> {
@@ -789,7 +2318,6 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
         blockhash: Option<solana_program::hash::Hash>,
         txn: MintMetaplexEditionTransaction,
     ) -> hub_core::prelude::Result<TransactionResponse<MintEditionAddresses>> {
-        let rpc = &self.0.rpc_client;
         let MintMetaplexEditionTransaction {
             recipient_address,
             owner_address,
@@ -797,7 +2325,7 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
             ..
         } = txn;
 
-        let payer: Pubkey = self.0.treasury_wallet_address;
+        let payer: Pubkey = self.0.payer();
         let owner = owner_address.parse()?;
 
         let program_pubkey = mpl_token_metadata::id();
@@ -829,10 +2357,20 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
         ];
         let (metadata_key, _) = Pubkey::find_program_address(metadata_seeds, &program_pubkey);
 
-        let rent =
-            with_retry!(rpc.get_minimum_balance_for_rent_exemption(state::Mint::LEN)).await?;
+        let rent = with_retry!(
+            self.0.retries(),
+            self.0
+                .rpc_api
+                .get_minimum_balance_for_rent_exemption(state::Mint::LEN)
+        )
+        .await?;
+
+        let mut instructions = match self.0.payment_leg(collection, recipient)? {
+            Some(payment) => payment.instructions(&payer)?,
+            None => vec![],
+        };
 
-        let mut instructions = vec![
+        instructions.extend([
             create_account(
                 &payer,
                 &new_mint_key.pubkey(),
@@ -850,7 +2388,19 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
                 &[&owner],
                 1,
             )?,
-        ];
+        ]);
+
+        // The freeze authority of the new mint passes to its edition account
+        // below, so a soulbound edition is frozen while the owner still holds it
+        if collection.soulbound {
+            instructions.push(freeze_account(
+                &token_key,
+                &added_token_account,
+                &new_mint_pubkey,
+                &owner,
+                &[&owner],
+            )?);
+        }
 
         instructions.push(mint_new_edition_from_master_edition_via_token(
             program_pubkey,
@@ -868,28 +2418,18 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
             edition,
         ));
 
-        let blockhash = if let Some(blockhash) = blockhash {
-            blockhash
-        } else {
-            with_retry!(rpc.get_latest_blockhash()).await?
-        };
-
-        let message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        );
+        let message = self.0.message(&instructions, &payer, blockhash).await?;
 
         let serialized_message = message.serialize();
         let mint_signature = new_mint_key.try_sign_message(&message.serialize())?;
 
         Ok(TransactionResponse {
             serialized_message,
-            signatures_or_signers_public_keys: vec![
-                payer.to_string(),
-                mint_signature.to_string(),
-                owner.to_string(),
-            ],
+            signatures_or_signers_public_keys: signers(&message, &[(
+                new_mint_pubkey,
+                mint_signature,
+            )]),
+            ephemeral_keypairs: vec![new_mint_key.to_bytes()],
             addresses: MintEditionAddresses {
                 owner,
                 edition: edition_key,
@@ -902,25 +2442,17 @@ impl<'a> MintBackend<MintMetaplexEditionTransaction, MintEditionAddresses> for E
     }
 }
 
-#[async_trait]
-impl<'a> TransferBackend<collection_mints::Model, TransferAssetAddresses> for UncompressedRef<'a> {
-    async fn transfer(
+impl<'a> UncompressedRef<'a> {
+    /// Instructions moving a single token from the sender's associated token
+    /// account to the recipient's, closing the emptied source account.
+    fn transfer_instructions(
         &self,
         collection_mint: &collection_mints::Model,
-        txn: TransferMetaplexAssetTransaction,
-    ) -> hub_core::prelude::Result<TransactionResponse<TransferAssetAddresses>> {
-        let rpc = &self.0.rpc_client;
-        let TransferMetaplexAssetTransaction {
-            owner_address,
-            recipient_address,
-            ..
-        } = txn;
-
-        let sender: Pubkey = owner_address.parse()?;
-        let recipient: Pubkey = recipient_address.parse()?;
+        sender: Pubkey,
+        recipient: Pubkey,
+    ) -> Result<(Vec<Instruction>, TransferAssetAddresses)> {
         let mint_address: Pubkey = collection_mint.mint.parse()?;
-        let payer: Pubkey = self.0.treasury_wallet_address;
-        let blockhash = with_retry!(rpc.get_latest_blockhash()).await?;
+        let payer: Pubkey = self.0.payer();
         let source_ata = get_associated_token_address(&sender, &mint_address);
         let destination_ata = get_associated_token_address(&recipient, &mint_address);
 
@@ -937,53 +2469,68 @@ impl<'a> TransferBackend<collection_mints::Model, TransferAssetAddresses> for Un
         )
         .context("failed to create transfer instruction")?;
 
-        let close_ata = spl_token::instruction::close_account(
-            &spl_token::ID,
-            &source_ata,
-            &payer,
-            &sender,
-            &[&sender],
-        )?;
+        let close_ata = spl_token::instruction::close_account(
+            &spl_token::ID,
+            &source_ata,
+            &payer,
+            &sender,
+            &[&sender],
+        )?;
+
+        Ok((
+            vec![create_ata_token_account, transfer_instruction, close_ata],
+            TransferAssetAddresses {
+                owner: sender,
+                recipient,
+                recipient_associated_token_account: destination_ata,
+                owner_associated_token_account: source_ata,
+            },
+        ))
+    }
+}
+
+#[async_trait]
+impl<'a> TransferBackend<collection_mints::Model, TransferAssetAddresses> for UncompressedRef<'a> {
+    async fn transfer(
+        &self,
+        collection_mint: &collection_mints::Model,
+        txn: TransferMetaplexAssetTransaction,
+    ) -> hub_core::prelude::Result<TransactionResponse<TransferAssetAddresses>> {
+        let TransferMetaplexAssetTransaction {
+            owner_address,
+            recipient_address,
+            ..
+        } = txn;
+
+        let sender: Pubkey = owner_address.parse()?;
+        let recipient: Pubkey = recipient_address.parse()?;
+        let payer: Pubkey = self.0.payer();
+
+        let (instructions, addresses) =
+            self.transfer_instructions(collection_mint, sender, recipient)?;
 
-        let message = solana_program::message::Message::new_with_blockhash(
-            &[create_ata_token_account, transfer_instruction, close_ata],
-            Some(&payer),
-            &blockhash,
-        );
+        let message = self.0.message(&instructions, &payer, None).await?;
 
         let serialized_message = message.serialize();
 
         Ok(TransactionResponse {
             serialized_message,
             signatures_or_signers_public_keys: vec![payer.to_string(), sender.to_string()],
-            addresses: TransferAssetAddresses {
-                owner: sender,
-                recipient,
-                recipient_associated_token_account: destination_ata,
-                owner_associated_token_account: source_ata,
-            },
+            ephemeral_keypairs: vec![],
+            addresses,
         })
     }
 }
 
-#[async_trait]
-impl<'a> TransferBackend<compression_leafs::Model, TransferCompressedMintV1Addresses>
-    for CompressedRef<'a>
-{
-    async fn transfer(
+impl<'a> CompressedRef<'a> {
+    /// Bubblegum transfer instruction for a single leaf, including the proof
+    /// accounts fetched from the digital asset API.
+    async fn transfer_instruction(
         &self,
         compression_leaf: &compression_leafs::Model,
-        txn: TransferMetaplexAssetTransaction,
-    ) -> hub_core::prelude::Result<TransactionResponse<TransferCompressedMintV1Addresses>> {
-        let TransferMetaplexAssetTransaction {
-            recipient_address,
-            owner_address,
-            ..
-        } = txn;
-        let payer = self.0.treasury_wallet_address;
-        let recipient = recipient_address.parse()?;
-        let owner = owner_address.parse()?;
-
+        owner: Pubkey,
+        recipient: Pubkey,
+    ) -> Result<Instruction> {
         let asset_api = &self.0.asset_rpc();
 
         let tree_authority_address = Pubkey::from_str(&compression_leaf.tree_authority)?;
@@ -1029,7 +2576,7 @@ impl<'a> TransferBackend<compression_leafs::Model, TransferCompressedMintV1Addre
 
         accounts.extend(proofs);
 
-        let instructions = [Instruction {
+        Ok(Instruction {
             program_id: mpl_bubblegum::ID,
             accounts,
             data: mpl_bubblegum::instruction::Transfer {
@@ -1044,25 +2591,137 @@ impl<'a> TransferBackend<compression_leafs::Model, TransferCompressedMintV1Addre
                 index: leaf_id,
             }
             .data(),
-        }];
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> TransferBackend<compression_leafs::Model, TransferCompressedMintV1Addresses>
+    for CompressedRef<'a>
+{
+    async fn transfer(
+        &self,
+        compression_leaf: &compression_leafs::Model,
+        txn: TransferMetaplexAssetTransaction,
+    ) -> hub_core::prelude::Result<TransactionResponse<TransferCompressedMintV1Addresses>> {
+        let TransferMetaplexAssetTransaction {
+            recipient_address,
+            owner_address,
+            ..
+        } = txn;
+        let payer = self.0.payer();
+        let recipient = recipient_address.parse()?;
+        let owner = owner_address.parse()?;
 
-        let blockhash = with_retry!(self.0.rpc_client.get_latest_blockhash()).await?;
+        let instructions = [self
+            .transfer_instruction(compression_leaf, owner, recipient)
+            .await?];
 
-        let serialized_message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        )
-        .serialize();
+        let serialized_message = self
+            .0
+            .message(&instructions, &payer, None)
+            .await?
+            .serialize();
 
         Ok(TransactionResponse {
             serialized_message,
             signatures_or_signers_public_keys: vec![payer.to_string(), owner.to_string()],
+            ephemeral_keypairs: vec![],
             addresses: TransferCompressedMintV1Addresses { owner, recipient },
         })
     }
 }
 
+#[async_trait]
+impl<'a> BatchTransferBackend for UncompressedRef<'a> {
+    async fn transfer_many(
+        &self,
+        collection_mints: &[collection_mints::Model],
+        compression_leafs: &[compression_leafs::Model],
+        txn: TransferMetaplexAssetsTransaction,
+    ) -> Result<Vec<TransactionResponse<TransferAssetsAddresses>>> {
+        let TransferMetaplexAssetsTransaction {
+            owner_address,
+            recipient_address,
+            ..
+        } = txn;
+        let payer = self.0.payer();
+        let owner: Pubkey = owner_address.parse()?;
+        let recipient: Pubkey = recipient_address.parse()?;
+
+        let mut transfers = Vec::new();
+
+        for collection_mint in collection_mints {
+            let (ixs, _) = self.transfer_instructions(collection_mint, owner, recipient)?;
+
+            transfers.push((ixs, collection_mint.mint.parse()?));
+        }
+
+        for compression_leaf in compression_leafs {
+            let ix = CompressedRef(self.0)
+                .transfer_instruction(compression_leaf, owner, recipient)
+                .await?;
+            let asset_id = compression_leaf
+                .asset_id
+                .as_deref()
+                .ok_or(SolanaAssetIdError::NotFound)?
+                .parse()?;
+
+            transfers.push((vec![ix], asset_id));
+        }
+
+        // Fill each transaction with as many transfers as fit before
+        // starting the next
+        let mut chunks: Vec<(Vec<Instruction>, Vec<Pubkey>)> = Vec::new();
+
+        for (ixs, mint) in transfers {
+            if let Some((instructions, mints)) = chunks.last_mut() {
+                let candidate = [instructions.as_slice(), &ixs].concat();
+
+                if self.0.fits(&candidate, &payer)? {
+                    *instructions = candidate;
+                    mints.push(mint);
+                    continue;
+                }
+            }
+
+            chunks.push((ixs, vec![mint]));
+        }
+
+        let mut responses = Vec::with_capacity(chunks.len());
+
+        for (chunk, (instructions, mints)) in chunks.into_iter().enumerate() {
+            let message = self
+                .0
+                .for_chunk(chunk)
+                .message(&instructions, &payer, None)
+                .await?;
+
+            let size = bincode::serialized_size(&Transaction::new_unsigned(message.clone()))?;
+
+            if size > PACKET_DATA_SIZE as u64 {
+                bail!(
+                    "transfer of asset {} does not fit in a single transaction ({size} bytes)",
+                    mints[0]
+                );
+            }
+
+            responses.push(TransactionResponse {
+                serialized_message: message.serialize(),
+                signatures_or_signers_public_keys: vec![payer.to_string(), owner.to_string()],
+                ephemeral_keypairs: vec![],
+                addresses: TransferAssetsAddresses {
+                    owner,
+                    recipient,
+                    mints,
+                },
+            });
+        }
+
+        Ok(responses)
+    }
+}
+
 #[async_trait]
 
 impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addresses>
@@ -1088,11 +2747,12 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addres
             metadata_uri,
             owner_address,
         } = metadata.ok_or(SolanaErrorNotFoundMessage::Metadata)?;
-        let payer = self.0.treasury_wallet_address;
+        let payer = self.0.payer();
         let recipient = recipient_address.parse()?;
         let owner = owner_address.parse()?;
         let merkle_tree = self.0.bubblegum_merkle_tree;
         let tree_authority = self.0.bubblegum_tree_authority;
+        let tree_delegate = self.0.bubblegum_tree_delegate;
 
         let mut accounts = vec![
             // Tree authority
@@ -1107,7 +2767,7 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addres
             // Payer [signer]
             AccountMeta::new_readonly(payer, true),
             // Tree delegate [signer]
-            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new_readonly(tree_delegate, true),
             // Collection authority [signer]
             AccountMeta::new_readonly(owner, true),
             // Collection authority pda
@@ -1133,7 +2793,12 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addres
             accounts.push(AccountMeta::new_readonly(owner, true));
         }
 
-        let instructions = [Instruction {
+        let mut instructions = match self.0.payment_leg(collection, recipient)? {
+            Some(payment) => payment.instructions(&payer)?,
+            None => vec![],
+        };
+
+        instructions.push(Instruction {
             program_id: mpl_bubblegum::ID,
             accounts: accounts.into_iter().collect(),
             data: mpl_bubblegum::instruction::MintToCollectionV1 {
@@ -1141,7 +2806,7 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addres
                     name,
                     symbol,
                     uri: metadata_uri,
-                    seller_fee_basis_points: seller_fee_basis_points.try_into()?,
+                    seller_fee_basis_points: metaplex::basis_points(seller_fee_basis_points)?,
                     primary_sale_happened: false,
                     is_mutable: true,
                     edition_nonce: None,
@@ -1152,34 +2817,21 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintCompressedMintV1Addres
                     }),
                     uses: None,
                     token_program_version: TokenProgramVersion::Original,
-                    creators: creators
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<BubblegumCreator>>>()?,
+                    creators: metaplex::creators(creators)?,
                 },
             }
             .data(),
-        }];
-
-        let blockhash = if let Some(blockhash) = blockhash {
-            blockhash
-        } else {
-            with_retry!(self.0.rpc().get_latest_blockhash()).await?
-        };
+        });
 
-        let serialized_message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        )
-        .serialize();
+        let message = self.0.message(&instructions, &payer, blockhash).await?;
 
         Ok(TransactionResponse {
-            serialized_message,
-            signatures_or_signers_public_keys: vec![payer.to_string(), owner.to_string()],
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: signers(&message, &[]),
+            ephemeral_keypairs: vec![],
             addresses: MintCompressedMintV1Addresses {
                 leaf_owner: recipient,
-                tree_delegate: payer,
+                tree_delegate,
                 tree_authority,
                 merkle_tree,
             },
@@ -1203,8 +2855,7 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintMetaplexAddresses>
             ..
         } = txn;
         let metadata = metadata.ok_or(SolanaErrorNotFoundMessage::Metadata)?;
-        let payer: Pubkey = self.0.treasury_wallet_address;
-        let rpc = &self.0.rpc_client;
+        let payer: Pubkey = self.0.payer();
         let mint = Keypair::new();
         let MetaplexMetadata {
             name,
@@ -1230,13 +2881,11 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintMetaplexAddresses>
         );
         let associated_token_account = get_associated_token_address(&recipient, &mint.pubkey());
         let len = spl_token::state::Mint::LEN;
-        let rent = with_retry!(rpc.get_minimum_balance_for_rent_exemption(len)).await?;
-        let blockhash = if let Some(blockhash) = blockhash {
-            blockhash
-        } else {
-            with_retry!(rpc.get_latest_blockhash()).await?
-        };
-
+        let rent = with_retry!(
+            self.0.retries(),
+            self.0.rpc_api.get_minimum_balance_for_rent_exemption(len)
+        )
+        .await?;
         let create_account_ins = solana_program::system_instruction::create_account(
             &payer,
             &mint.pubkey(),
@@ -1276,13 +2925,8 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintMetaplexAddresses>
                 name,
                 symbol,
                 metadata_uri,
-                Some(
-                    creators
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<Creator>, _>>()?,
-                ),
-                seller_fee_basis_points.try_into()?,
+                Some(metaplex::creators(creators)?),
+                metaplex::basis_points(seller_fee_basis_points)?,
                 true,
                 true,
                 Some(mpl_token_metadata::state::Collection {
@@ -1304,31 +2948,42 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintMetaplexAddresses>
             None,
         );
 
-        let instructions = vec![
+        let mut instructions = match self.0.payment_leg(collection, recipient)? {
+            Some(payment) => payment.instructions(&payer)?,
+            None => vec![],
+        };
+
+        instructions.extend([
             create_account_ins,
             initialize_mint_ins,
             ata_ins,
             min_to_ins,
             create_metadata_account_ins,
             verify_collection,
-        ];
+        ]);
 
-        let message = solana_program::message::Message::new_with_blockhash(
-            &instructions,
-            Some(&payer),
-            &blockhash,
-        );
+        if collection.soulbound {
+            instructions.push(freeze_account(
+                &spl_token::ID,
+                &associated_token_account,
+                &mint.pubkey(),
+                &owner,
+                &[],
+            )?);
+        }
+
+        let message = self.0.message(&instructions, &payer, blockhash).await?;
 
         let serialized_message = message.serialize();
         let mint_signature = mint.try_sign_message(&message.serialize())?;
 
         Ok(TransactionResponse {
             serialized_message,
-            signatures_or_signers_public_keys: vec![
-                payer.to_string(),
-                mint_signature.to_string(),
-                owner.to_string(),
-            ],
+            signatures_or_signers_public_keys: signers(&message, &[(
+                mint.pubkey(),
+                mint_signature,
+            )]),
+            ephemeral_keypairs: vec![mint.to_bytes()],
             addresses: MintMetaplexAddresses {
                 update_authority: owner,
                 associated_token_account,
@@ -1340,3 +2995,396 @@ impl<'a> MintBackend<MintMetaplexMetadataTransaction, MintMetaplexAddresses>
         })
     }
 }
+
+#[async_trait]
+impl<'a> ProposalBackend for SquadsRef<'a> {
+    async fn propose(
+        &self,
+        tx: SolanaPendingTransaction,
+        reserved: Option<u64>,
+    ) -> Result<TransactionResponse<MultisigProposalAddresses>> {
+        let multisig = self
+            .0
+            .multisig
+            .context("project treasury is not a multisig")?;
+        let address = multisig.address;
+
+        let data = self
+            .0
+            .rpc_pool
+            .call(|rpc| async move { rpc.get_account_data(&address).await })
+            .await
+            .with_context(|| format!("failed to fetch multisig account {address}"))?;
+        let account = multisig.account(&data)?;
+
+        let transaction_index = account.transaction_index.max(reserved.unwrap_or(0)) + 1;
+        let message: Message = bincode::deserialize(&tx.serialized_message)?;
+        let instructions = multisig.propose(
+            transaction_index,
+            &message,
+            &tx.signatures_or_signers_public_keys,
+        )?;
+
+        let message = self
+            .0
+            .message(&instructions, &multisig.member, None)
+            .await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![multisig.member.to_string()],
+            ephemeral_keypairs: vec![],
+            addresses: MultisigProposalAddresses {
+                multisig: address,
+                vault: multisig.vault(),
+                member: multisig.member,
+                transaction: multisig.transaction(transaction_index),
+                proposal: multisig.proposal(transaction_index),
+                transaction_index,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> RoyaltySplitBackend for HydraRef<'a> {
+    async fn create_split(
+        &self,
+        name: String,
+        members: &[royalty_splits::RoyaltySplitMember],
+    ) -> Result<TransactionResponse<RoyaltySplitAddresses>> {
+        let payer = self.0.payer();
+        let members = members
+            .iter()
+            .map(|m| Ok((m.address.parse()?, m.shares)))
+            .collect::<Result<Vec<(Pubkey, u64)>>>()?;
+
+        let fanout = Fanout::new(name, payer);
+        let instructions = fanout.create(&members)?;
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![payer.to_string()],
+            ephemeral_keypairs: vec![],
+            addresses: RoyaltySplitAddresses {
+                payer,
+                fanout: fanout.address(),
+                holding_account: fanout.holding_account(),
+            },
+        })
+    }
+
+    async fn distribute(
+        &self,
+        split: &royalty_splits::Model,
+    ) -> Result<TransactionResponse<RoyaltySplitAddresses>> {
+        let payer = self.0.payer();
+        let members = split
+            .members
+            .0
+            .iter()
+            .map(|m| m.address.parse())
+            .collect::<Result<Vec<Pubkey>, _>>()?;
+
+        let fanout = Fanout::new(split.name.clone(), split.authority.parse()?);
+        let instructions = fanout.distribute(payer, &members);
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![payer.to_string()],
+            ephemeral_keypairs: vec![],
+            addresses: RoyaltySplitAddresses {
+                payer,
+                fanout: fanout.address(),
+                holding_account: fanout.holding_account(),
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> ListingBackend for UncompressedRef<'a> {
+    async fn list(
+        &self,
+        collection_mint: &collection_mints::Model,
+        seller: Pubkey,
+        price: u64,
+    ) -> Result<TransactionResponse<ListingAddresses>> {
+        let auction_house = self.0.auction_house_account().await?;
+        let payer = self.0.payer();
+        let mint: Pubkey = collection_mint.mint.parse()?;
+        let token_account = get_associated_token_address(&seller, &mint);
+
+        let instructions = [auction_house.sell(seller, token_account, mint, price)];
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![payer.to_string(), seller.to_string()],
+            ephemeral_keypairs: vec![],
+            addresses: ListingAddresses {
+                auction_house: auction_house.address,
+                seller,
+                token_account,
+                trade_state: auction_house
+                    .trade_state(&seller, &token_account, &mint, price)
+                    .0,
+            },
+        })
+    }
+
+    async fn cancel(
+        &self,
+        collection_mint: &collection_mints::Model,
+        listing: &listings::Model,
+    ) -> Result<TransactionResponse<ListingAddresses>> {
+        let auction_house = self.0.auction_house_account().await?;
+        ensure!(
+            auction_house.address.to_string() == listing.auction_house,
+            "listing {} was made on another auction house",
+            listing.id
+        );
+
+        let payer = self.0.payer();
+        let mint: Pubkey = collection_mint.mint.parse()?;
+        let seller: Pubkey = listing.seller.parse()?;
+        let token_account: Pubkey = listing.token_account.parse()?;
+        let price = listing.price.try_into().context("invalid listing price")?;
+
+        let instructions = [auction_house.cancel(seller, token_account, mint, price)];
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: vec![payer.to_string(), seller.to_string()],
+            ephemeral_keypairs: vec![],
+            addresses: ListingAddresses {
+                auction_house: auction_house.address,
+                seller,
+                token_account,
+                trade_state: listing.trade_state.parse()?,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> TreeBackend for CompressedRef<'a> {
+    async fn create_tree(
+        &self,
+        shape: TreeShape,
+    ) -> Result<TransactionResponse<CreateTreeAddresses>> {
+        let payer = self.0.payer();
+        let tree_creator = self.0.bubblegum_tree_delegate;
+        let keypair = Keypair::new();
+        let merkle_tree = keypair.pubkey();
+
+        let len = shape.space();
+        let rent = with_retry!(
+            self.0.retries(),
+            self.0.rpc_api.get_minimum_balance_for_rent_exemption(len)
+        )
+        .await?;
+
+        let instructions = [
+            create_account(
+                &payer,
+                &merkle_tree,
+                rent,
+                len.try_into()?,
+                &spl_account_compression::ID,
+            ),
+            shape.create_tree(merkle_tree, payer, tree_creator),
+        ];
+
+        let message = self.0.message(&instructions, &payer, None).await?;
+        let signature = keypair.try_sign_message(&message.serialize())?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: signers(&message, &[(merkle_tree, signature)]),
+            ephemeral_keypairs: vec![keypair.to_bytes()],
+            addresses: CreateTreeAddresses {
+                merkle_tree,
+                tree_authority: trees::tree_authority(merkle_tree),
+                tree_creator,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> CandyMachineBackend for CandyMachineRef<'a> {
+    async fn deploy(
+        &self,
+        collection: &collections::Model,
+        data: CandyMachineData,
+        guards: &Guards,
+    ) -> Result<TransactionResponse<CandyMachineAddresses>> {
+        let payer = self.0.payer();
+        let keypair = Keypair::new();
+        let authority: Pubkey = collection.update_authority.parse()?;
+        let candy_machine = CandyMachineAccounts::new(keypair.pubkey(), authority);
+        let collection = CollectionAccounts {
+            mint: collection.mint.parse()?,
+            metadata: collection.metadata.parse()?,
+            master_edition: collection.master_edition.parse()?,
+            update_authority: authority,
+        };
+
+        let len = data.space();
+        let rent = with_retry!(
+            self.0.retries(),
+            self.0.rpc_api.get_minimum_balance_for_rent_exemption(len)
+        )
+        .await?;
+
+        let mut instructions = vec![create_account(
+            &payer,
+            &keypair.pubkey(),
+            rent,
+            len.try_into()?,
+            &candy_machine::PROGRAM_ID,
+        )];
+        instructions.extend(candy_machine.initialize(payer, &collection, data, guards));
+
+        let message = self.0.message(&instructions, &payer, None).await?;
+        let signature = keypair.try_sign_message(&message.serialize())?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: signers(&message, &[(keypair.pubkey(), signature)]),
+            ephemeral_keypairs: vec![keypair.to_bytes()],
+            addresses: CandyMachineAddresses {
+                candy_machine: candy_machine.address,
+                candy_guard: candy_machine.candy_guard(),
+                authority,
+            },
+        })
+    }
+
+    async fn insert_items(
+        &self,
+        candy_machine: &candy_machines::Model,
+        index: u32,
+        lines: Vec<ConfigLine>,
+    ) -> Result<TransactionResponse<CandyMachineAddresses>> {
+        let payer = self.0.payer();
+        let authority: Pubkey = candy_machine.authority.parse()?;
+        let accounts = CandyMachineAccounts::new(candy_machine.address.parse()?, authority);
+
+        let instructions = [accounts.add_config_lines(index, lines)];
+        let message = self.0.message(&instructions, &payer, None).await?;
+
+        Ok(TransactionResponse {
+            serialized_message: message.serialize(),
+            signatures_or_signers_public_keys: signers(&message, &[]),
+            ephemeral_keypairs: vec![],
+            addresses: CandyMachineAddresses {
+                candy_machine: accounts.address,
+                candy_guard: accounts.candy_guard(),
+                authority,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_transaction_status::{
+        EncodedConfirmedTransactionWithStatusMeta, option_serializer::OptionSerializer,
+    };
+
+    use super::*;
+
+    fn fixture(json: &str) -> EncodedTransactionWithStatusMeta {
+        serde_json::from_str::<EncodedConfirmedTransactionWithStatusMeta>(json)
+            .unwrap()
+            .transaction
+    }
+
+    #[test]
+    fn finds_changelog_after_leaf_schema() {
+        let tx = fixture(include_str!(
+            "../fixtures/transactions/compressed_mint.json"
+        ));
+
+        assert_eq!(compression_nonce(&tx).unwrap(), 41);
+    }
+
+    #[test]
+    fn finds_changelog_behind_priority_fees_and_cpis() {
+        let tx = fixture(include_str!(
+            "../fixtures/transactions/compressed_mint_priority_fee.json"
+        ));
+
+        assert_eq!(compression_nonce(&tx).unwrap(), 7);
+    }
+
+    #[test]
+    fn resolves_noop_from_lookup_table() {
+        let tx = fixture(include_str!(
+            "../fixtures/transactions/compressed_mint_lookup_table.json"
+        ));
+
+        assert_eq!(compression_nonce(&tx).unwrap(), 1024);
+    }
+
+    #[test]
+    fn fails_without_changelog() {
+        let mut tx = fixture(include_str!(
+            "../fixtures/transactions/compressed_mint.json"
+        ));
+
+        if let Some(OptionSerializer::Some(sets)) =
+            tx.meta.as_mut().map(|meta| &mut meta.inner_instructions)
+        {
+            for set in sets {
+                set.instructions.pop();
+            }
+        }
+
+        assert!(matches!(
+            compression_nonce(&tx),
+            Err(SolanaAssetIdError::NoNonce)
+        ));
+    }
+    #[test]
+    fn detects_durable_nonce_messages() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let instructions = [system_instruction::transfer(&payer, &recipient, 1)];
+
+        let durable =
+            Message::new_with_nonce(instructions.to_vec(), Some(&payer), &nonce_account, &payer);
+        let recent = Message::new(&instructions, Some(&payer));
+
+        assert!(uses_durable_nonce(&durable));
+        assert!(!uses_durable_nonce(&recent));
+    }
+
+    #[test]
+    fn reads_the_durable_nonce_of_signed_messages() {
+        let payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let nonce = Hash::new_unique();
+        let instructions = [system_instruction::transfer(&payer, &recipient, 1)];
+
+        let mut durable =
+            Message::new_with_nonce(instructions.to_vec(), Some(&payer), &nonce_account, &payer);
+        durable.recent_blockhash = nonce;
+        let recent = Message::new_with_blockhash(&instructions, Some(&payer), &nonce);
+        let signed = |message: &Message| SolanaTransactionResult {
+            serialized_message: Some(bincode::serialize(message).unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(durable_nonce(&signed(&durable)), Some(nonce));
+        assert_eq!(durable_nonce(&signed(&recent)), None);
+        assert_eq!(durable_nonce(&SolanaTransactionResult::default()), None);
+    }
+}
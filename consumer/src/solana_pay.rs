@@ -0,0 +1,212 @@
+//! Solana Pay transaction requests for claims. A claim link encoded as
+//! `solana:<host>/solana-pay/claims/<claim id>` lets the claimant's wallet
+//! fetch the transfer of the reserved mint, signed by the project treasury
+//! and paid for by the wallet, which adds its own signature and lands it.
+//!
+//! Signing goes through hub-treasuries, so the first request for a claim
+//! only sends its transaction to be signed. Requests wait for the signed
+//! transaction for a short while and otherwise ask the wallet to retry.
+
+use std::str::FromStr;
+
+use holaplex_hub_nfts_solana_core::proto::treasury_events::SolanaTransactionResult;
+use hub_core::{prelude::*, tokio};
+use poem::{
+    get, handler,
+    http::StatusCode,
+    middleware::{AddData, Cors},
+    web::{Data, Json, Path},
+    Endpoint, EndpointExt, Error, Route,
+};
+use solana_program::message::Message;
+use solana_sdk::{signature::Signature, transaction::Transaction};
+
+use crate::events::{Processor, ProcessorErrorKind};
+
+/// How often a request waiting on the treasury checks for the signed
+/// transaction
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+struct PayState {
+    processor: Processor,
+    label: String,
+    icon: Option<String>,
+    wait: Duration,
+}
+
+/// Routes answering Solana Pay transaction requests for claims, waiting up
+/// to `wait` for the treasury to sign a transaction before asking the wallet
+/// to retry. `label` and `icon` are shown by the wallet.
+pub fn routes(
+    processor: Processor,
+    label: String,
+    icon: Option<String>,
+    wait: Duration,
+) -> impl Endpoint {
+    Route::new()
+        .at("/claims/:id", get(describe).post(transaction))
+        .with(AddData::new(PayState {
+            processor,
+            label,
+            icon,
+            wait,
+        }))
+        // Wallets running in a browser fetch transactions across origins
+        .with(Cors::new())
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Description {
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TransactionRequest {
+    account: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct TransactionResponse {
+    transaction: String,
+    message: String,
+}
+
+fn rejected(e: &ProcessorErrorKind) -> Error {
+    let status = match e {
+        ProcessorErrorKind::InvalidClaimId | ProcessorErrorKind::RecordNotFound => {
+            StatusCode::NOT_FOUND
+        },
+        ProcessorErrorKind::ClaimRedeemed | ProcessorErrorKind::ClaimExpired => StatusCode::GONE,
+        ProcessorErrorKind::RedemptionPending => StatusCode::CONFLICT,
+        ProcessorErrorKind::ClaimantMismatch | ProcessorErrorKind::SignerPolicy(_) => {
+            StatusCode::FORBIDDEN
+        },
+        ProcessorErrorKind::ParsePubkey(_)
+        | ProcessorErrorKind::SolanaPayUnsupported(_)
+        | ProcessorErrorKind::SoulboundTransfer(_)
+        | ProcessorErrorKind::AssetEscrowed(_)
+        | ProcessorErrorKind::AssetListed(_) => StatusCode::BAD_REQUEST,
+        _ => {
+            error!("Solana Pay request failed: {e:?}");
+            return Error::from_status(StatusCode::INTERNAL_SERVER_ERROR);
+        },
+    };
+
+    Error::from_string(e.to_string(), status)
+}
+
+#[handler]
+async fn describe(Data(state): Data<&PayState>) -> Json<Description> {
+    Json(Description {
+        label: state.label.clone(),
+        icon: state.icon.clone(),
+    })
+}
+
+#[handler]
+async fn transaction(
+    Data(state): Data<&PayState>,
+    Path(id): Path<String>,
+    Json(TransactionRequest { account }): Json<TransactionRequest>,
+) -> poem::Result<Json<TransactionResponse>> {
+    let deadline = tokio::time::Instant::now() + state.wait;
+
+    loop {
+        let signed = state
+            .processor
+            .claim_transaction(&id, &account)
+            .await
+            .map_err(|e| rejected(&e))?;
+
+        if let Some(tx) = signed {
+            return Ok(Json(TransactionResponse {
+                transaction: base64::encode(tx),
+                message: state.label.clone(),
+            }));
+        }
+
+        if tokio::time::Instant::now() + POLL_INTERVAL > deadline {
+            return Err(Error::from_string(
+                "the claim transaction is being prepared, retry shortly",
+                StatusCode::SERVICE_UNAVAILABLE,
+            ));
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Rebuild a transaction signed by the treasury, leaving the signatures it
+/// did not make empty for the wallet to fill in. The treasury only returns
+/// the signatures it made, so each is placed at the signer it verifies
+/// against.
+///
+/// # Errors
+/// This function fails if the result carries no message or it cannot be
+/// deserialized
+pub fn partially_signed(res: &SolanaTransactionResult) -> Result<Transaction> {
+    let message: Message = bincode::deserialize(
+        res.serialized_message
+            .as_deref()
+            .context("signed transaction has no message")?,
+    )?;
+    let data = message.serialize();
+    let signers = message
+        .account_keys
+        .get(..usize::from(message.header.num_required_signatures))
+        .context("signed message has more signers than accounts")?;
+    let mut signatures = vec![Signature::default(); signers.len()];
+
+    for signature in res
+        .signed_message_signatures
+        .iter()
+        .filter_map(|s| Signature::from_str(s).ok())
+    {
+        if let Some(i) = signers
+            .iter()
+            .position(|signer| signature.verify(signer.as_ref(), &data))
+        {
+            signatures[i] = signature;
+        }
+    }
+
+    Ok(Transaction {
+        signatures,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    };
+    use solana_sdk::signer::{keypair::Keypair, Signer};
+
+    use super::*;
+
+    #[test]
+    fn leaves_the_wallet_signature_empty() {
+        let wallet = Pubkey::new_unique();
+        let treasury = Keypair::new();
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![
+            AccountMeta::new_readonly(treasury.pubkey(), true),
+        ]);
+        let message = Message::new(&[instruction], Some(&wallet));
+        let signature = treasury.sign_message(&message.serialize());
+
+        let tx = partially_signed(&SolanaTransactionResult {
+            serialized_message: Some(bincode::serialize(&message).unwrap()),
+            signed_message_signatures: vec![signature.to_string(), "unsigned".to_owned()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(tx.signatures, vec![Signature::default(), signature]);
+        assert_eq!(tx.verify_with_results(), vec![false, true]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+use hub_core::prelude::*;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::RpcSendTransactionConfig,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_program::{hash::Hash, pubkey::Pubkey};
+use solana_sdk::{signature::Signature, transaction::Transaction};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+use crate::rpc_pool::RpcPool;
+
+/// The cluster calls made while assembling and submitting transactions.
+/// Backends go through this trait rather than a concrete RPC client so they
+/// can run against [`MockSolanaRpc`] without a live cluster.
+#[async_trait]
+pub trait SolanaRpc: Send + Sync {
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash>;
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> ClientResult<u64>;
+
+    /// Send `transaction` without preflight checks, returning its signature
+    async fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature>;
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<EncodedConfirmedTransactionWithStatusMeta>;
+
+    /// Up to `limit` signatures of transactions referencing `address`,
+    /// latest first, starting before `before` if given
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>>;
+}
+
+#[async_trait]
+impl SolanaRpc for RpcPool {
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        self.call(|rpc| async move { rpc.get_latest_blockhash().await })
+            .await
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> ClientResult<u64> {
+        self.call(|rpc| async move { rpc.get_minimum_balance_for_rent_exemption(data_len).await })
+            .await
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.call(|rpc| async move {
+            rpc.send_transaction_with_config(transaction, RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..Default::default()
+            })
+            .await
+        })
+        .await
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<EncodedConfirmedTransactionWithStatusMeta> {
+        self.call(|rpc| async move {
+            rpc.get_transaction(signature, UiTransactionEncoding::Json)
+                .await
+        })
+        .await
+    }
+
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.call(|rpc| async move {
+            rpc.get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(limit),
+                    ..Default::default()
+                },
+            )
+            .await
+        })
+        .await
+    }
+}
+
+/// An in-memory [`SolanaRpc`] returning a fixed blockhash and rent, and
+/// recording every transaction sent through it
+#[derive(Debug, Default)]
+pub struct MockSolanaRpc {
+    blockhash: Hash,
+    rent_lamports: u64,
+    sent: Mutex<Vec<Transaction>>,
+    transactions: Mutex<HashMap<Signature, EncodedConfirmedTransactionWithStatusMeta>>,
+    signatures: Mutex<HashMap<Pubkey, Vec<RpcConfirmedTransactionStatusWithSignature>>>,
+}
+
+impl MockSolanaRpc {
+    #[must_use]
+    pub fn new(blockhash: Hash, rent_lamports: u64) -> Self {
+        Self {
+            blockhash,
+            rent_lamports,
+            ..Self::default()
+        }
+    }
+
+    /// Make `transaction` available to [`SolanaRpc::get_transaction`]
+    pub fn insert_transaction(
+        &self,
+        signature: Signature,
+        transaction: EncodedConfirmedTransactionWithStatusMeta,
+    ) {
+        self.transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(signature, transaction);
+    }
+
+    /// Make `signatures`, latest first, available to
+    /// [`SolanaRpc::get_signatures_for_address`] for `address`
+    pub fn insert_signatures(
+        &self,
+        address: Pubkey,
+        signatures: Vec<RpcConfirmedTransactionStatusWithSignature>,
+    ) {
+        self.signatures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(address, signatures);
+    }
+
+    /// The transactions sent so far, in order
+    #[must_use]
+    pub fn sent(&self) -> Vec<Transaction> {
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl SolanaRpc for MockSolanaRpc {
+    async fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        Ok(self.blockhash)
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, _data_len: usize) -> ClientResult<u64> {
+        Ok(self.rent_lamports)
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(transaction.clone());
+
+        Ok(transaction.signatures.first().copied().unwrap_or_default())
+    }
+
+    async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<EncodedConfirmedTransactionWithStatusMeta> {
+        self.transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(signature)
+            .cloned()
+            .ok_or_else(|| {
+                ClientError::from(ClientErrorKind::Custom(format!(
+                    "transaction {signature} not found"
+                )))
+            })
+    }
+
+    async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let signatures = self
+            .signatures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let signatures = signatures.get(address).map_or(&[][..], Vec::as_slice);
+        let start = before.map_or(0, |before| {
+            let before = before.to_string();
+
+            signatures
+                .iter()
+                .position(|s| s.signature == before)
+                .map_or(signatures.len(), |i| i + 1)
+        });
+
+        Ok(signatures.iter().skip(start).take(limit).cloned().collect())
+    }
+}
@@ -0,0 +1,364 @@
+//! Proposals to Squads v4 multisigs.
+//!
+//! A project treasury may be the vault of a Squads multisig, which cannot
+//! sign for itself. Transactions paid by the vault are instead stored in a
+//! vault transaction, and a proposal to execute it is created and approved
+//! by the member of the multisig the treasury service signs for. The
+//! transaction lands once enough other members approve and execute it.
+
+use anchor_lang::{prelude::AccountMeta, AnchorDeserialize, AnchorSerialize};
+use hub_core::thiserror;
+use solana_program::{
+    hash::hash, instruction::Instruction, message::Message, pubkey, pubkey::Pubkey, system_program,
+};
+
+/// The Squads v4 program
+pub const PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+/// Member permission to create vault transactions and proposals
+const PERMISSION_INITIATE: u8 = 1 << 0;
+/// Member permission to approve proposals
+const PERMISSION_VOTE: u8 = 1 << 1;
+
+/// Why a transaction could not be proposed to a multisig
+#[derive(Debug, thiserror::Error)]
+pub enum SquadsError {
+    #[error("{0} is not a Squads multisig account")]
+    NotAMultisig(Pubkey),
+    #[error("{member} cannot create and approve proposals of multisig {multisig}")]
+    NotAMember { multisig: Pubkey, member: Pubkey },
+    #[error("Transaction is paid for by {found} rather than the multisig vault {vault}")]
+    Payer { vault: Pubkey, found: String },
+    #[error("Transaction requires a signature from {0}, which a multisig vault cannot give")]
+    ExtraSigner(String),
+    #[error("Transaction is too large to be stored as a vault transaction")]
+    TooLarge,
+}
+
+/// A Squads multisig whose vault pays for a project's transactions, and the
+/// member proposing them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Multisig {
+    pub address: Pubkey,
+    pub member: Pubkey,
+    pub vault_index: u8,
+}
+
+/// The fields of a multisig account read when proposing to it
+#[derive(Debug, AnchorDeserialize)]
+pub struct MultisigAccount {
+    pub create_key: Pubkey,
+    pub config_authority: Pubkey,
+    pub threshold: u16,
+    pub time_lock: u32,
+    pub transaction_index: u64,
+    pub stale_transaction_index: u64,
+    pub rent_collector: Option<Pubkey>,
+    pub bump: u8,
+    pub members: Vec<Member>,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct Member {
+    pub key: Pubkey,
+    pub permissions: u8,
+}
+
+#[derive(AnchorSerialize)]
+struct VaultTransactionCreateArgs {
+    vault_index: u8,
+    ephemeral_signers: u8,
+    transaction_message: Vec<u8>,
+    memo: Option<String>,
+}
+
+#[derive(AnchorSerialize)]
+struct ProposalCreateArgs {
+    transaction_index: u64,
+    draft: bool,
+}
+
+#[derive(AnchorSerialize)]
+struct ProposalVoteArgs {
+    memo: Option<String>,
+}
+
+impl Multisig {
+    #[must_use]
+    pub fn new(address: Pubkey, member: Pubkey) -> Self {
+        Self {
+            address,
+            member,
+            vault_index: 0,
+        }
+    }
+
+    /// The vault holding the funds and authorities of the multisig
+    #[must_use]
+    pub fn vault(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[SEED_PREFIX, self.address.as_ref(), SEED_VAULT, &[
+                self.vault_index
+            ]],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// The vault transaction stored at `index`
+    #[must_use]
+    pub fn transaction(&self, index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                SEED_PREFIX,
+                self.address.as_ref(),
+                SEED_TRANSACTION,
+                &index.to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// The proposal to execute the vault transaction at `index`
+    #[must_use]
+    pub fn proposal(&self, index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                SEED_PREFIX,
+                self.address.as_ref(),
+                SEED_TRANSACTION,
+                &index.to_le_bytes(),
+                SEED_PROPOSAL,
+            ],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Decode the multisig account, checking that the member may create
+    /// and approve proposals
+    ///
+    /// # Errors
+    /// This function fails if `data` is not a multisig account or the
+    /// member lacks the permissions to propose
+    pub fn account(&self, data: &[u8]) -> Result<MultisigAccount, SquadsError> {
+        let not_a_multisig = || SquadsError::NotAMultisig(self.address);
+
+        if data.len() < 8 || data[..8] != anchor_discriminator("account:Multisig") {
+            return Err(not_a_multisig());
+        }
+
+        let mut data = &data[8..];
+
+        let account = MultisigAccount::deserialize(&mut data).map_err(|_| not_a_multisig())?;
+        let permissions = PERMISSION_INITIATE | PERMISSION_VOTE;
+
+        if !account
+            .members
+            .iter()
+            .any(|m| m.key == self.member && m.permissions & permissions == permissions)
+        {
+            return Err(SquadsError::NotAMember {
+                multisig: self.address,
+                member: self.member,
+            });
+        }
+
+        Ok(account)
+    }
+
+    /// Instructions storing `message` as the vault transaction at `index`,
+    /// then creating and approving the proposal to execute it. The member
+    /// pays the rent of both accounts.
+    ///
+    /// # Errors
+    /// This function fails if `message` is not paid for by the vault alone
+    /// or is too large to be stored
+    pub fn propose(
+        &self,
+        index: u64,
+        message: &Message,
+        signers: &[String],
+    ) -> Result<Vec<Instruction>, SquadsError> {
+        let vault = self.vault();
+        let mut signers = signers.iter();
+
+        match signers.next() {
+            Some(payer) if *payer == vault.to_string() => (),
+            found => {
+                return Err(SquadsError::Payer {
+                    vault,
+                    found: found.cloned().unwrap_or_default(),
+                });
+            },
+        }
+
+        if let Some(signer) = signers.find(|s| **s != vault.to_string()) {
+            return Err(SquadsError::ExtraSigner(signer.clone()));
+        }
+
+        let transaction = self.transaction(index);
+        let proposal = self.proposal(index);
+
+        let create = VaultTransactionCreateArgs {
+            vault_index: self.vault_index,
+            ephemeral_signers: 0,
+            transaction_message: transaction_message(message)?,
+            memo: None,
+        };
+        let propose = ProposalCreateArgs {
+            transaction_index: index,
+            draft: false,
+        };
+        let approve = ProposalVoteArgs { memo: None };
+
+        Ok(vec![
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(self.address, false),
+                    AccountMeta::new(transaction, false),
+                    AccountMeta::new_readonly(self.member, true),
+                    AccountMeta::new(self.member, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+                data: instruction_data("vault_transaction_create", &create),
+            },
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(self.address, false),
+                    AccountMeta::new(proposal, false),
+                    AccountMeta::new_readonly(self.member, true),
+                    AccountMeta::new(self.member, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+                data: instruction_data("proposal_create", &propose),
+            },
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(self.address, false),
+                    AccountMeta::new(self.member, true),
+                    AccountMeta::new(proposal, false),
+                ],
+                data: instruction_data("proposal_approve", &approve),
+            },
+        ])
+    }
+}
+
+fn anchor_discriminator(preimage: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+pub(crate) fn instruction_data(name: &str, args: &impl AnchorSerialize) -> Vec<u8> {
+    let mut data = anchor_discriminator(&format!("global:{name}")).to_vec();
+    args.serialize(&mut data)
+        .unwrap_or_else(|_| unreachable!("serializing into a Vec cannot fail"));
+    data
+}
+
+/// Encode a legacy message in the compact layout Squads stores vault
+/// transactions in. Both order accounts as writable signers, readonly
+/// signers, writable non-signers then readonly non-signers, so the accounts
+/// and instructions carry over as they are.
+fn transaction_message(message: &Message) -> Result<Vec<u8>, SquadsError> {
+    let header = &message.header;
+    let len_u8 = |len: usize| u8::try_from(len).map_err(|_| SquadsError::TooLarge);
+
+    let num_signers = header.num_required_signatures;
+    let num_non_signers = len_u8(message.account_keys.len())?
+        .checked_sub(num_signers)
+        .ok_or(SquadsError::TooLarge)?;
+
+    let mut buf = vec![
+        num_signers,
+        num_signers - header.num_readonly_signed_accounts,
+        num_non_signers - header.num_readonly_unsigned_accounts,
+        len_u8(message.account_keys.len())?,
+    ];
+
+    for key in &message.account_keys {
+        buf.extend_from_slice(key.as_ref());
+    }
+
+    buf.push(len_u8(message.instructions.len())?);
+
+    for ix in &message.instructions {
+        let data_len = u16::try_from(ix.data.len()).map_err(|_| SquadsError::TooLarge)?;
+
+        buf.push(ix.program_id_index);
+        buf.push(len_u8(ix.accounts.len())?);
+        buf.extend_from_slice(&ix.accounts);
+        buf.extend_from_slice(&data_len.to_le_bytes());
+        buf.extend_from_slice(&ix.data);
+    }
+
+    // Legacy messages have no address table lookups
+    buf.push(0);
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::system_instruction;
+
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    #[test]
+    fn encodes_transaction_messages() {
+        let multisig = Multisig::new(key(1), key(2));
+        let vault = multisig.vault();
+        let message = Message::new(
+            &[system_instruction::transfer(&vault, &key(3), 42)],
+            Some(&vault),
+        );
+
+        let encoded = transaction_message(&message).unwrap();
+
+        // One writable signer and one writable non-signer
+        assert_eq!(&encoded[..4], &[1, 1, 1, 3]);
+        assert_eq!(&encoded[4..36], vault.as_ref());
+        assert_eq!(encoded[100], 1);
+        assert_eq!(&encoded[101..105], &[2, 2, 0, 1]);
+        assert_eq!(&encoded[105..107], &12u16.to_le_bytes());
+        assert_eq!(encoded.len(), 107 + 12 + 1);
+    }
+
+    #[test]
+    fn proposes_vault_transactions_only() {
+        let multisig = Multisig::new(key(1), key(2));
+        let vault = multisig.vault();
+        let message = Message::new(
+            &[system_instruction::transfer(&vault, &key(3), 42)],
+            Some(&vault),
+        );
+
+        let ixs = multisig.propose(7, &message, &[vault.to_string()]).unwrap();
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[1].accounts[1].pubkey, multisig.proposal(7));
+
+        assert!(matches!(
+            multisig.propose(7, &message, &[key(2).to_string()]),
+            Err(SquadsError::Payer { .. })
+        ));
+        assert!(matches!(
+            multisig.propose(7, &message, &[vault.to_string(), key(3).to_string()]),
+            Err(SquadsError::ExtraSigner(s)) if s == key(3).to_string()
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+//! Transfer history of imported mints. An import only stores who holds each
+//! mint now, so every imported mint is queued and its earlier transfers are
+//! reconstructed from the transactions referencing its mint account, walking
+//! back at most a configured number of signatures.
+//!
+//! Transfers are read from the token balances of each transaction, so they
+//! are only found if the transaction references the mint account, as
+//! `TransferChecked` and Token Metadata transfers do. Compressed mints have
+//! no mint account and are not backfilled.
+
+use std::{collections::HashMap, str::FromStr};
+
+use holaplex_hub_nfts_solana_core::{db::Connection, Collection, CollectionMint, TransferBackfill};
+use holaplex_hub_nfts_solana_entity::{mint_transfer_histories, transfer_backfills};
+use hub_core::{
+    anyhow::Result,
+    chrono::{NaiveDateTime, Utc},
+    prelude::*,
+    tokio,
+    uuid::Uuid,
+};
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+
+use crate::{cluster::Clusters, solana_rpc::SolanaRpc};
+
+/// Signatures requested at once, the most the RPC API returns
+const PAGE_SIZE: u64 = 1000;
+
+/// Failed runs after which a backfill is given up
+const MAX_ATTEMPTS: i32 = 5;
+
+/// Periodically walks the signatures of queued imported mints, recording the
+/// transfers found into `mint_transfer_histories`
+#[derive(Clone)]
+pub struct TransferBackfiller {
+    db: Connection,
+    clusters: Clusters,
+    interval: Duration,
+    batch_size: u64,
+    depth: u64,
+}
+
+impl TransferBackfiller {
+    /// Walk back at most `depth` signatures of each mint, `batch_size` mints
+    /// every `interval`
+    #[must_use]
+    pub fn new(
+        db: Connection,
+        clusters: Clusters,
+        interval: Duration,
+        batch_size: u64,
+        depth: u64,
+    ) -> Self {
+        Self {
+            db,
+            clusters,
+            interval,
+            batch_size,
+            depth,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.backfill().await {
+                error!("{:?}", e.context("failed to backfill transfer histories"));
+            }
+        }
+    }
+
+    /// Backfill the longest waiting mints. A mint which fails is retried
+    /// from where it stopped on a later run.
+    ///
+    /// # Errors
+    /// This function fails if the queued mints cannot be loaded
+    pub async fn backfill(&self) -> Result<()> {
+        let conn = self.db.get();
+        let backfills = TransferBackfill::find_pending(conn, MAX_ATTEMPTS, self.batch_size).await?;
+
+        for backfill in backfills {
+            let mint_id = backfill.mint_id;
+
+            if let Err(e) = self.walk(backfill).await {
+                warn!(
+                    "{:?}",
+                    e.context(format!("failed to backfill transfers of mint {mint_id}"))
+                );
+
+                TransferBackfill::fail(conn, mint_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn walk(&self, backfill: transfer_backfills::Model) -> Result<()> {
+        let conn = self.db.get();
+        let collection = Collection::find_by_id(conn, backfill.collection_id)
+            .await?
+            .context("collection not found")?;
+        let solana = self
+            .clusters
+            .get(collection.cluster)
+            .with_context(|| format!("{} cluster is not served", collection.cluster))?;
+        let rpc = solana.solana_rpc();
+
+        let mint = Pubkey::from_str(&backfill.mint_address)?;
+        let mut before = backfill
+            .before
+            .as_deref()
+            .map(Signature::from_str)
+            .transpose()?;
+        let mut scanned = u64::try_from(backfill.scanned)?;
+        let mut recorded = 0;
+
+        while scanned < self.depth {
+            let limit = (self.depth - scanned).min(PAGE_SIZE);
+            let page = rpc
+                .get_signatures_for_address(&mint, before, limit.try_into()?)
+                .await?;
+
+            for status in page.iter().filter(|s| s.err.is_none()) {
+                if self.record(rpc.as_ref(), &backfill, status).await? {
+                    recorded += 1;
+                }
+            }
+
+            scanned += u64::try_from(page.len())?;
+
+            let Some(last) = page.last() else {
+                break;
+            };
+
+            before = Some(Signature::from_str(&last.signature)?);
+            TransferBackfill::advance(
+                conn,
+                backfill.mint_id,
+                last.signature.clone(),
+                scanned.try_into()?,
+            )
+            .await?;
+
+            if u64::try_from(page.len())? < limit {
+                break;
+            }
+        }
+
+        TransferBackfill::complete(conn, backfill.mint_id).await?;
+
+        info!(
+            "backfilled {recorded} transfers of mint {} from {scanned} signatures",
+            backfill.mint_id
+        );
+
+        Ok(())
+    }
+
+    /// Record the transfer of the mint made by one transaction, if any,
+    /// returning whether it was not known yet
+    async fn record(
+        &self,
+        rpc: &dyn SolanaRpc,
+        backfill: &transfer_backfills::Model,
+        status: &RpcConfirmedTransactionStatusWithSignature,
+    ) -> Result<bool> {
+        let signature = Signature::from_str(&status.signature)?;
+        let tx = rpc.get_transaction(&signature).await?;
+
+        let Some((sender, recipient)) = tx
+            .transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| transfer_of(meta, &backfill.mint_address))
+        else {
+            return Ok(false);
+        };
+
+        let created_at = status
+            .block_time
+            .and_then(|secs| NaiveDateTime::from_timestamp_opt(secs, 0))
+            .unwrap_or_else(|| Utc::now().naive_utc());
+
+        let recorded =
+            CollectionMint::record_transfer(self.db.get(), mint_transfer_histories::Model {
+                id: Uuid::new_v4(),
+                mint_id: backfill.mint_id,
+                sender,
+                recipient,
+                signature: status.signature.clone(),
+                slot: status.slot.try_into()?,
+                created_at,
+            })
+            .await?;
+
+        Ok(recorded)
+    }
+}
+
+/// The wallet `mint` left and the wallet it went to in a successful
+/// transaction, read from the change of each owner's balance. Mints and
+/// burns have no sender or no recipient and are not transfers.
+fn transfer_of(meta: &UiTransactionStatusMeta, mint: &str) -> Option<(String, String)> {
+    if meta.err.is_some() {
+        return None;
+    }
+
+    let pre: Option<&Vec<UiTransactionTokenBalance>> = meta.pre_token_balances.as_ref().into();
+    let post: Option<&Vec<UiTransactionTokenBalance>> = meta.post_token_balances.as_ref().into();
+
+    let mut changes: HashMap<&str, i128> = HashMap::new();
+
+    let balances = pre
+        .into_iter()
+        .flatten()
+        .map(|b| (b, -1))
+        .chain(post.into_iter().flatten().map(|b| (b, 1)));
+
+    for (balance, sign) in balances.filter(|(b, _)| b.mint == mint) {
+        let OptionSerializer::Some(owner) = &balance.owner else {
+            continue;
+        };
+        let amount: i128 = balance.ui_token_amount.amount.parse().ok()?;
+
+        *changes.entry(owner.as_str()).or_default() += sign * amount;
+    }
+
+    let sender = changes.iter().find(|(_, change)| **change < 0)?.0;
+    let recipient = changes.iter().find(|(_, change)| **change > 0)?.0;
+
+    Some(((*sender).to_owned(), (*recipient).to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn balance(account_index: u8, mint: &str, owner: &str, amount: u64) -> serde_json::Value {
+        json!({
+            "accountIndex": account_index,
+            "mint": mint,
+            "owner": owner,
+            "uiTokenAmount": {
+                "amount": amount.to_string(),
+                "decimals": 0,
+                "uiAmount": null,
+                "uiAmountString": amount.to_string(),
+            },
+        })
+    }
+
+    fn meta(pre: &[serde_json::Value], post: &[serde_json::Value]) -> UiTransactionStatusMeta {
+        serde_json::from_value(json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": pre,
+            "postTokenBalances": post,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_transfers_from_token_balances() {
+        let transfer = meta(
+            &[
+                balance(1, "mint", "alice", 1),
+                balance(3, "other", "carol", 5),
+            ],
+            &[
+                balance(1, "mint", "alice", 0),
+                balance(2, "mint", "bob", 1),
+                balance(3, "other", "carol", 4),
+            ],
+        );
+        let minted = meta(&[], &[balance(1, "mint", "alice", 1)]);
+        let unrelated = meta(&[balance(3, "other", "carol", 5)], &[balance(
+            3, "other", "dave", 5,
+        )]);
+
+        assert_eq!(
+            transfer_of(&transfer, "mint"),
+            Some(("alice".to_owned(), "bob".to_owned()))
+        );
+        assert_eq!(transfer_of(&minted, "mint"), None);
+        assert_eq!(transfer_of(&unrelated, "mint"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Merkle trees created ahead of drops of compressed mints.
+//!
+//! A tree holds `2^max_depth` leaves and is created at the smallest depth
+//! the account compression program supports which fits the leaves requested.
+//! The top levels of the tree are cached on chain in its canopy, so that
+//! transfers and burns only pass the rest of the proof.
+
+use anchor_lang::{prelude::AccountMeta, InstructionData};
+use hub_core::thiserror;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_program};
+
+/// Depths supported by the account compression program with a changelog
+/// buffer of [`MAX_BUFFER_SIZE`]
+const DEPTHS: [u32; 8] = [14, 15, 16, 17, 18, 19, 20, 24];
+
+/// Concurrent changes to a tree accepted within the same slot
+const MAX_BUFFER_SIZE: u32 = 64;
+
+/// Proof nodes a transaction passes for a leaf, the rest coming from the
+/// canopy
+const MAX_PROOF_LENGTH: u32 = 10;
+
+/// Bytes of the account type, header version, buffer size, depth, authority,
+/// creation slot and padding preceding a tree
+const HEADER_SIZE: usize = 1 + 1 + 4 + 4 + 32 + 8 + 6;
+
+const NODE_SIZE: usize = 32;
+
+/// Why a tree could not be sized
+#[derive(Debug, thiserror::Error)]
+pub enum TreeError {
+    #[error("Reserving {0} leaves takes more than a single tree")]
+    TooManyLeaves(u64),
+}
+
+/// The dimensions a tree is created with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeShape {
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+    pub canopy_depth: u32,
+}
+
+impl TreeShape {
+    /// The smallest tree holding at least `leaves` leaves
+    ///
+    /// # Errors
+    /// This function fails if no single tree is deep enough
+    pub fn for_capacity(leaves: u64) -> Result<Self, TreeError> {
+        let max_depth = DEPTHS
+            .into_iter()
+            .find(|depth| 1_u64 << depth >= leaves)
+            .ok_or(TreeError::TooManyLeaves(leaves))?;
+
+        Ok(Self {
+            max_depth,
+            max_buffer_size: MAX_BUFFER_SIZE,
+            canopy_depth: max_depth.saturating_sub(MAX_PROOF_LENGTH),
+        })
+    }
+
+    /// Leaves the tree holds
+    #[must_use]
+    pub fn capacity(&self) -> u64 {
+        1 << self.max_depth
+    }
+
+    /// Size of the tree account: its header, the changelog buffer, the
+    /// rightmost proof and the canopy
+    #[must_use]
+    pub fn space(&self) -> usize {
+        let depth = self.max_depth as usize;
+        let buffer_size = self.max_buffer_size as usize;
+        // Each changelog holds a root, a path and an index. The rightmost
+        // proof holds a path, the leaf and its index.
+        let changelogs = buffer_size * (NODE_SIZE + depth * NODE_SIZE + 8);
+        let rightmost_proof = depth * NODE_SIZE + NODE_SIZE + 8;
+        // A canopy of depth d caches 2^(d + 1) - 2 nodes
+        let canopy = ((1 << (self.canopy_depth + 1)) - 2) * NODE_SIZE;
+
+        HEADER_SIZE + 24 + changelogs + rightmost_proof + canopy
+    }
+
+    /// The Bubblegum instruction initializing a tree in its already created
+    /// account. `tree_creator` becomes the tree's delegate, the only wallet
+    /// allowed to mint into it.
+    #[must_use]
+    pub fn create_tree(
+        &self,
+        merkle_tree: Pubkey,
+        payer: Pubkey,
+        tree_creator: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: mpl_bubblegum::ID,
+            accounts: vec![
+                AccountMeta::new(tree_authority(merkle_tree), false),
+                AccountMeta::new(merkle_tree, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(tree_creator, true),
+                AccountMeta::new_readonly(spl_noop::ID, false),
+                AccountMeta::new_readonly(spl_account_compression::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: mpl_bubblegum::instruction::CreateTree {
+                max_depth: self.max_depth,
+                max_buffer_size: self.max_buffer_size,
+                public: Some(false),
+            }
+            .data(),
+        }
+    }
+}
+
+/// The Bubblegum config of the tree at `merkle_tree`
+#[must_use]
+pub fn tree_authority(merkle_tree: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &mpl_bubblegum::ID).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TreeShape;
+
+    #[test]
+    fn sizes_trees_for_capacity() {
+        let small = TreeShape::for_capacity(500).unwrap();
+        let large = TreeShape::for_capacity(100_000).unwrap();
+
+        assert_eq!((small.max_depth, small.canopy_depth), (14, 4));
+        assert_eq!((large.max_depth, large.canopy_depth), (17, 7));
+        assert_eq!(large.capacity(), 131_072);
+        assert!(TreeShape::for_capacity(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn matches_account_compression_sizes() {
+        // Sizes reported by `getConcurrentMerkleTreeAccountSize`
+        let shape = TreeShape {
+            max_depth: 14,
+            max_buffer_size: 64,
+            canopy_depth: 0,
+        };
+
+        assert_eq!(shape.space(), 31_800);
+    }
+}
@@ -0,0 +1,54 @@
+//! Mint updates keep the message they were assembled with so a failed
+//! update can be retried with the same accounts. Revisions are only retried
+//! for a while, after which they are deleted here.
+
+use holaplex_hub_nfts_solana_core::{db::Connection, UpdateRevision};
+use hub_core::{
+    anyhow::Result,
+    chrono::{self, Utc},
+    prelude::*,
+    tokio,
+};
+
+/// Periodically deletes update revisions older than a TTL
+#[derive(Clone)]
+pub struct RevisionCleanup {
+    db: Connection,
+    ttl: Duration,
+    interval: Duration,
+}
+
+impl RevisionCleanup {
+    #[must_use]
+    pub fn new(db: Connection, ttl: Duration, interval: Duration) -> Self {
+        Self { db, ttl, interval }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.clean().await {
+                error!("{:?}", e.context("failed to delete stale update revisions"));
+            }
+        }
+    }
+
+    /// Delete the revisions created more than the TTL ago
+    ///
+    /// # Errors
+    /// This function fails if the TTL is out of range or the rows cannot be
+    /// deleted
+    pub async fn clean(&self) -> Result<()> {
+        let cutoff = Utc::now().naive_utc() - chrono::Duration::from_std(self.ttl)?;
+        let deleted = UpdateRevision::delete_stale(self.db.get(), cutoff).await?;
+
+        if deleted > 0 {
+            info!("deleted {deleted} stale update revisions");
+        }
+
+        Ok(())
+    }
+}
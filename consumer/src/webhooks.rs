@@ -0,0 +1,228 @@
+use holaplex_hub_nfts_solana_core::{
+    db::Connection,
+    proto::{
+        solana_nft_events::Event::{PrimarySaleDetected, UpdateMintOwner},
+        MintOwnershipUpdate, SolanaNftEventKey, SolanaNftEvents, SolanaPrimarySale,
+    },
+    sea_orm::ConnectionTrait,
+    AppliedTransfer, CollectionMint, CompressionLeaf, MintTransfer, ObservedTransfer, Outbox,
+};
+use hub_core::prelude::*;
+use poem::{
+    handler,
+    http::StatusCode,
+    middleware::AddData,
+    post,
+    web::{Data, Json},
+    Endpoint, EndpointExt, Error, Route,
+};
+
+use crate::auth;
+
+/// Routes receiving webhook deliveries from third-party indexers. Every
+/// request must carry the configured secret as its `Authorization` header.
+pub fn routes(db: Connection, secret: String) -> impl Endpoint {
+    Route::new()
+        .at("/helius", post(helius))
+        .with(AddData::new(db))
+        .with(auth::RequireSecret::raw(secret))
+}
+
+/// An enhanced transaction as delivered by a Helius webhook. Only the fields
+/// needed to follow ownership changes are decoded.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeliusTransaction {
+    signature: String,
+    slot: u64,
+    /// Unix timestamp of the block
+    #[serde(default)]
+    timestamp: i64,
+    #[serde(default)]
+    token_transfers: Vec<TokenTransfer>,
+    #[serde(default)]
+    events: TransactionEvents,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenTransfer {
+    #[serde(default)]
+    from_token_account: String,
+    #[serde(default)]
+    to_token_account: String,
+    #[serde(default)]
+    to_user_account: String,
+    token_amount: f64,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TransactionEvents {
+    #[serde(default)]
+    compressed: Vec<CompressedEvent>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompressedEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    asset_id: String,
+    new_leaf_owner: Option<String>,
+}
+
+#[handler]
+async fn helius(
+    Data(db): Data<&Connection>,
+    Json(txs): Json<Vec<HeliusTransaction>>,
+) -> poem::Result<StatusCode> {
+    for tx in txs {
+        process_transaction(db, &tx).await.map_err(|e| {
+            let sig = &tx.signature;
+            error!("failed to process Helius webhook for {sig}: {e:?}");
+            Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+    }
+
+    Ok(StatusCode::OK)
+}
+
+/// Apply the NFT transfers in `tx` to the tracked mints the same way the
+/// indexer does. Transfers already applied, e.g. by the indexer or a
+/// redelivered webhook, were recorded and are skipped. Burns move the token
+/// to no one and are not transfers.
+async fn process_transaction(db: &Connection, tx: &HeliusTransaction) -> Result<()> {
+    for transfer in &tx.token_transfers {
+        #[allow(clippy::float_cmp)]
+        if transfer.token_amount != 1.0 || transfer.to_user_account.is_empty() {
+            continue;
+        }
+
+        transfer_mint(db, tx, transfer).await?;
+    }
+
+    for event in &tx.events.compressed {
+        if event.kind != "COMPRESSED_NFT_TRANSFER" {
+            continue;
+        }
+
+        if let Some(new_leaf_owner) = &event.new_leaf_owner {
+            transfer_leaf(db, tx, &event.asset_id, new_leaf_owner).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn observed(tx: &HeliusTransaction, recipient: &str) -> Result<ObservedTransfer> {
+    Ok(ObservedTransfer {
+        signature: tx.signature.clone(),
+        slot: tx.slot.try_into()?,
+        block_time: tx.timestamp,
+        recipient: recipient.to_owned(),
+    })
+}
+
+async fn transfer_mint(
+    db: &Connection,
+    tx: &HeliusTransaction,
+    transfer: &TokenTransfer,
+) -> Result<()> {
+    let Some(mint) =
+        CollectionMint::find_by_ata(db.read(), transfer.from_token_account.clone()).await?
+    else {
+        return Ok(());
+    };
+
+    let txn = db.begin().await?;
+
+    let Some(applied) = MintTransfer::apply_to_mint(
+        &txn,
+        mint.id,
+        transfer.to_token_account.clone(),
+        &observed(tx, &transfer.to_user_account)?,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+
+    report(&txn, tx, applied, transfer.to_user_account.clone()).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+async fn transfer_leaf(
+    db: &Connection,
+    tx: &HeliusTransaction,
+    asset_id: &str,
+    new_leaf_owner: &str,
+) -> Result<()> {
+    let Some(leaf) = CompressionLeaf::find_by_asset_id(db.read(), asset_id.to_owned()).await?
+    else {
+        return Ok(());
+    };
+
+    let txn = db.begin().await?;
+
+    let Some(applied) =
+        MintTransfer::apply_to_leaf(&txn, leaf.id, &observed(tx, new_leaf_owner)?).await?
+    else {
+        return Ok(());
+    };
+
+    report(&txn, tx, applied, new_leaf_owner.to_owned()).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Queue the events the indexer sends for an applied transfer
+async fn report(
+    conn: &impl ConnectionTrait,
+    tx: &HeliusTransaction,
+    applied: AppliedTransfer,
+    recipient: String,
+) -> Result<()> {
+    let key = SolanaNftEventKey {
+        id: applied.mint_id.to_string(),
+        correlation_id: applied.mint_id.to_string(),
+        ..Default::default()
+    };
+
+    Outbox::enqueue(
+        conn,
+        &key,
+        &SolanaNftEvents::new(UpdateMintOwner(MintOwnershipUpdate {
+            mint_address: applied.mint_address.clone(),
+            sender: applied.sender.clone(),
+            recipient: recipient.clone(),
+            tx_signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.timestamp,
+        })),
+    )
+    .await?;
+
+    if applied.primary_sale {
+        info!("primary sale of mint {} detected", applied.mint_id);
+
+        Outbox::enqueue(
+            conn,
+            &key,
+            &SolanaNftEvents::new(PrimarySaleDetected(SolanaPrimarySale {
+                mint_id: applied.mint_id.to_string(),
+                mint_address: applied.mint_address,
+                seller: applied.sender,
+                buyer: recipient,
+                tx_signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.timestamp,
+            })),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
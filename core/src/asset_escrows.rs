@@ -0,0 +1,99 @@
+use holaplex_hub_nfts_solana_entity::asset_escrows::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+};
+
+/// Mints held in escrow by a project treasury on behalf of their depositor
+pub struct AssetEscrow;
+
+impl AssetEscrow {
+    /// Record a requested escrow of a mint, replacing an earlier request
+    /// which never landed or an escrow which has been released
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn request_escrow(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<(), DbErr> {
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::MintId)
+                    .update_columns([
+                        Column::Depositor,
+                        Column::EscrowWallet,
+                        Column::EscrowEventId,
+                        Column::ReleaseEventId,
+                        Column::ReleaseRecipient,
+                        Column::CreatedAt,
+                        Column::EscrowedAt,
+                        Column::ReleasedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The escrow currently holding a mint, if its transfer into escrow has
+    /// landed and it has not been released yet
+    pub async fn find_held(
+        conn: &impl ConnectionTrait,
+        mint_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(mint_id)
+            .filter(Column::EscrowedAt.is_not_null())
+            .filter(Column::ReleasedAt.is_null())
+            .one(conn)
+            .await
+    }
+
+    /// Record the requested release of an escrowed mint to `recipient`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn request_release(
+        conn: &impl ConnectionTrait,
+        mint_id: Uuid,
+        event_id: Uuid,
+        recipient: String,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::ReleaseEventId, Expr::value(event_id))
+            .col_expr(Column::ReleaseRecipient, Expr::value(recipient))
+            .filter(Column::MintId.eq(mint_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the escrow requested by `event_id` as holding its mint
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_escrow(conn: &impl ConnectionTrait, event_id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::EscrowedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::EscrowEventId.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the escrow whose release was requested by `event_id` as released
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_release(conn: &impl ConnectionTrait, event_id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::ReleasedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::ReleaseEventId.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+}
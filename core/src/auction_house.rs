@@ -0,0 +1,283 @@
+//! Listings on the Metaplex Auction House.
+//!
+//! Listing a mint records a seller trade state for its price, which the
+//! auction house matches against bids when a sale executes. The seller keeps
+//! the token in their wallet, delegated to the auction house until the
+//! listing is sold or cancelled.
+
+use hub_core::thiserror;
+use solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// The Auction House program
+pub const PROGRAM_ID: Pubkey = pubkey!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk");
+
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+const PREFIX: &[u8] = b"auction_house";
+const SIGNER: &[u8] = b"signer";
+
+/// Listings are always for a whole NFT
+const TOKEN_SIZE: u64 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuctionHouseError {
+    #[error("{0} is not an auction house account")]
+    NotAnAuctionHouse(Pubkey),
+    #[error("Auction house {0} requires its authority to sign off on listings")]
+    RequiresSignOff(Pubkey),
+}
+
+/// The fields of an auction house account needed to list on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionHouse {
+    pub address: Pubkey,
+    pub fee_account: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// A sale executed by an auction house, matching a listing by its seller
+/// trade state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutedSale {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub price: u64,
+}
+
+impl AuctionHouse {
+    /// Decode the auction house account at `address`
+    ///
+    /// # Errors
+    /// This function fails if `data` is not an auction house account or the
+    /// auction house only accepts listings signed off by its authority
+    pub fn from_account(address: Pubkey, data: &[u8]) -> Result<Self, AuctionHouseError> {
+        let not_an_auction_house = || AuctionHouseError::NotAnAuctionHouse(address);
+
+        if data.len() < 8 || data[..8] != discriminator("account:AuctionHouse") {
+            return Err(not_an_auction_house());
+        }
+
+        // Seven addresses, then three bumps and the seller fee basis points
+        // precede the sign off flag
+        let key = |i: usize| {
+            data.get(8 + i * 32..8 + (i + 1) * 32)
+                .and_then(|k| Pubkey::try_from(k).ok())
+                .ok_or_else(not_an_auction_house)
+        };
+        let requires_sign_off = *data
+            .get(8 + 7 * 32 + 3 + 2)
+            .ok_or_else(not_an_auction_house)?;
+
+        if requires_sign_off != 0 {
+            return Err(AuctionHouseError::RequiresSignOff(address));
+        }
+
+        Ok(Self {
+            address,
+            fee_account: key(0)?,
+            treasury_mint: key(4)?,
+            authority: key(5)?,
+        })
+    }
+
+    /// The trade state recording a listing of `mint` by `seller` at `price`
+    #[must_use]
+    pub fn trade_state(
+        &self,
+        seller: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        price: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                PREFIX,
+                seller.as_ref(),
+                self.address.as_ref(),
+                token_account.as_ref(),
+                self.treasury_mint.as_ref(),
+                mint.as_ref(),
+                &price.to_le_bytes(),
+                &TOKEN_SIZE.to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        )
+    }
+
+    /// The `sell` instruction listing `mint`, held by `seller` in
+    /// `token_account`, at `price` base units of the treasury mint
+    #[must_use]
+    pub fn sell(
+        &self,
+        seller: Pubkey,
+        token_account: Pubkey,
+        mint: Pubkey,
+        price: u64,
+    ) -> Instruction {
+        let (trade_state, trade_state_bump) =
+            self.trade_state(&seller, &token_account, &mint, price);
+        let (free_trade_state, free_trade_state_bump) =
+            self.trade_state(&seller, &token_account, &mint, 0);
+        let (program_as_signer, program_as_signer_bump) =
+            Pubkey::find_program_address(&[PREFIX, SIGNER], &PROGRAM_ID);
+
+        let mut data = discriminator("global:sell").to_vec();
+        data.extend_from_slice(&[
+            trade_state_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+        ]);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&TOKEN_SIZE.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(seller, true),
+                AccountMeta::new(token_account, false),
+                AccountMeta::new_readonly(metadata(&mint), false),
+                AccountMeta::new_readonly(self.authority, false),
+                AccountMeta::new_readonly(self.address, false),
+                AccountMeta::new(self.fee_account, false),
+                AccountMeta::new(trade_state, false),
+                AccountMeta::new(free_trade_state, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(program_as_signer, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+            ],
+            data,
+        }
+    }
+
+    /// The `cancel` instruction closing the listing of `mint` at `price`
+    #[must_use]
+    pub fn cancel(
+        &self,
+        seller: Pubkey,
+        token_account: Pubkey,
+        mint: Pubkey,
+        price: u64,
+    ) -> Instruction {
+        let (trade_state, _) = self.trade_state(&seller, &token_account, &mint, price);
+
+        let mut data = discriminator("global:cancel").to_vec();
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&TOKEN_SIZE.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(seller, true),
+                AccountMeta::new(token_account, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(self.authority, false),
+                AccountMeta::new_readonly(self.address, false),
+                AccountMeta::new(self.fee_account, false),
+                AccountMeta::new(trade_state, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data,
+        }
+    }
+}
+
+/// Decode an `execute_sale` instruction, given its accounts in order
+#[must_use]
+pub fn executed_sale(accounts: &[Pubkey], data: &[u8]) -> Option<ExecutedSale> {
+    if data.get(..8)? != discriminator("global:execute_sale") {
+        return None;
+    }
+
+    // Three bumps precede the price
+    let price = data.get(11..19)?.try_into().ok().map(u64::from_le_bytes)?;
+
+    Some(ExecutedSale {
+        buyer: *accounts.first()?,
+        seller: *accounts.get(1)?,
+        mint: *accounts.get(3)?,
+        seller_trade_state: *accounts.get(14)?,
+        price,
+    })
+}
+
+fn metadata(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+fn discriminator(preimage: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    fn auction_house() -> AuctionHouse {
+        AuctionHouse {
+            address: key(1),
+            fee_account: key(2),
+            treasury_mint: key(3),
+            authority: key(4),
+        }
+    }
+
+    #[test]
+    fn decodes_auction_house_accounts() {
+        let mut data = discriminator("account:AuctionHouse").to_vec();
+        for i in 0..7 {
+            data.extend_from_slice(key(i + 10).as_ref());
+        }
+        data.extend_from_slice(&[255, 254, 253, 0xf4, 0x01, 0]);
+
+        let decoded = AuctionHouse::from_account(key(1), &data).unwrap();
+        assert_eq!(decoded.fee_account, key(10));
+        assert_eq!(decoded.treasury_mint, key(14));
+        assert_eq!(decoded.authority, key(15));
+
+        *data.last_mut().unwrap() = 1;
+        assert!(matches!(
+            AuctionHouse::from_account(key(1), &data),
+            Err(AuctionHouseError::RequiresSignOff(_))
+        ));
+    }
+
+    #[test]
+    fn matches_sales_to_listings() {
+        let house = auction_house();
+        let sell = house.sell(key(5), key(6), key(7), 1_000);
+
+        let mut data = discriminator("global:execute_sale").to_vec();
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&TOKEN_SIZE.to_le_bytes());
+
+        let mut accounts = vec![key(8), key(5), key(6), key(7)];
+        accounts.resize(14, key(0));
+        accounts.push(sell.accounts[6].pubkey);
+
+        let sale = executed_sale(&accounts, &data).unwrap();
+        assert_eq!(sale.seller_trade_state, sell.accounts[6].pubkey);
+        assert_eq!(sale.buyer, key(8));
+        assert_eq!(sale.price, 1_000);
+    }
+}
@@ -0,0 +1,143 @@
+use holaplex_hub_nfts_solana_entity::{
+    candy_machine_batches,
+    candy_machines::{ActiveModel, Column, Entity, Model},
+};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    Set,
+};
+
+/// Candy machines deployed for collections, and the batches of config lines
+/// inserted into them
+pub struct CandyMachine;
+
+impl CandyMachine {
+    /// Record a requested deployment, replacing the candy machine assembled
+    /// by an earlier attempt at the same event
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn request_deploy(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<(), DbErr> {
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::Address,
+                        Column::CandyGuard,
+                        Column::Authority,
+                        Column::Items,
+                        Column::IsSequential,
+                        Column::ItemsAvailable,
+                        Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).one(conn).await
+    }
+
+    /// The candy machine of a collection, if one has been deployed on-chain
+    pub async fn find_deployed(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::DeployedAt.is_not_null())
+            .one(conn)
+            .await
+    }
+
+    /// Mark the candy machine deployed by `event_id` as created on-chain
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_deploy(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::update_many()
+            .col_expr(Column::DeployedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Self::find_by_id(conn, event_id).await
+    }
+
+    /// Record the batch of config lines from `start_index` up to
+    /// `end_index` requested by `event_id`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn request_batch(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+        candy_machine_id: Uuid,
+        start_index: i64,
+        end_index: i64,
+    ) -> Result<(), DbErr> {
+        let am = candy_machine_batches::ActiveModel {
+            event_id: Set(event_id),
+            candy_machine_id: Set(candy_machine_id),
+            start_index: Set(start_index),
+            end_index: Set(end_index),
+            created_at: Set(Utc::now().naive_utc()),
+            inserted_at: Set(None),
+        };
+
+        candy_machine_batches::Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(candy_machine_batches::Column::EventId)
+                    .update_columns([
+                        candy_machine_batches::Column::StartIndex,
+                        candy_machine_batches::Column::EndIndex,
+                        candy_machine_batches::Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the batch requested by `event_id` as inserted, advancing the
+    /// lines loaded into its candy machine past it. Batches of the same
+    /// lines may land more than once, so the count never moves back.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn confirm_batch(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        let Some(batch) = candy_machine_batches::Entity::find_by_id(event_id)
+            .one(conn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut am: candy_machine_batches::ActiveModel = batch.clone().into();
+        am.inserted_at = Set(Some(Utc::now().naive_utc()));
+        am.update(conn).await?;
+
+        Entity::update_many()
+            .col_expr(Column::ItemsLoaded, Expr::value(batch.end_index))
+            .filter(Column::Id.eq(batch.candy_machine_id))
+            .filter(Column::ItemsLoaded.lt(batch.end_index))
+            .exec(conn)
+            .await?;
+
+        Self::find_by_id(conn, batch.candy_machine_id).await
+    }
+}
@@ -0,0 +1,209 @@
+use holaplex_hub_nfts_solana_entity::claims::{ActiveModel, Column, Entity, Model};
+use hub_core::{bs58, chrono::Utc};
+use prost::Message;
+use sea_orm::{prelude::*, sea_query::Expr, QuerySelect, Set};
+
+use crate::proto::MintMetaplexMetadataTransaction;
+
+pub struct Claim;
+
+impl Claim {
+    /// Encode a claim id as the 22-character base58 string handed to drop
+    /// pages, which is safe to embed in a URL without escaping
+    #[must_use]
+    pub fn encode_id(id: Uuid) -> String {
+        bs58::encode(id.as_bytes()).into_string()
+    }
+
+    /// Parse a claim id given either in the form returned by
+    /// [`Claim::encode_id`] or as a UUID
+    #[must_use]
+    pub fn decode_id(id: &str) -> Option<Uuid> {
+        Uuid::parse_str(id).ok().or_else(|| {
+            bs58::decode(id)
+                .into_vec()
+                .ok()
+                .and_then(|bytes| Uuid::from_slice(&bytes).ok())
+        })
+    }
+
+    /// Store a new claim. `mint_request` is the mint assembled on redemption
+    /// for claims that do not reserve an existing mint.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn create(
+        conn: &impl ConnectionTrait,
+        model: Model,
+        mint_request: Option<&MintMetaplexMetadataTransaction>,
+    ) -> Result<Model, DbErr> {
+        let am = ActiveModel {
+            mint_request: Set(mint_request.map(Message::encode_to_vec)),
+            created_at: Set(Utc::now().naive_utc()),
+            ..model.into()
+        };
+
+        am.insert(conn).await
+    }
+
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).one(conn).await
+    }
+
+    /// A claim locked for the duration of the surrounding transaction, so
+    /// concurrent redemptions of it are made one after the other
+    pub async fn find_by_id_for_update(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).lock_exclusive().one(conn).await
+    }
+
+    pub async fn find_by_redemption_id(
+        conn: &impl ConnectionTrait,
+        redemption_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::RedemptionId.eq(redemption_id))
+            .one(conn)
+            .await
+    }
+
+    /// Claims reserving `collection_mint_id` that have not been redeemed
+    pub async fn find_unredeemed_by_collection_mint(
+        conn: &impl ConnectionTrait,
+        collection_mint_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::CollectionMintId.eq(collection_mint_id))
+            .filter(Column::RedeemedAt.is_null())
+            .all(conn)
+            .await
+    }
+
+    /// Record the event assembling the redemption of a claim. Only one
+    /// redemption is pending at a time; a failed one is cleared by
+    /// [`Claim::fail_redemption`] so the claim can be redeemed again.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn start_redemption(
+        conn: &impl ConnectionTrait,
+        model: Model,
+        redemption_id: Uuid,
+    ) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.redemption_id = Set(Some(redemption_id));
+
+        am.update(conn).await
+    }
+
+    /// Clear the redemption `redemption_id` of the claim it was started
+    /// for, once the redemption has failed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn fail_redemption(
+        conn: &impl ConnectionTrait,
+        redemption_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::RedemptionId, Expr::value(None::<Uuid>))
+            .filter(Column::RedemptionId.eq(redemption_id))
+            .filter(Column::RedeemedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark a claim redeemed once its redemption transaction has landed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn mark_redeemed(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.redeemed_at = Set(Some(Utc::now().naive_utc()));
+
+        am.update(conn).await
+    }
+
+    /// Mark the claims reserving `collection_mint_id` for `recipient`
+    /// redeemed once the mint is seen landing in their wallet, however it
+    /// was transferred there
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn redeem_transferred(
+        conn: &impl ConnectionTrait,
+        collection_mint_id: Uuid,
+        recipient: &str,
+    ) -> Result<u64, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::RedeemedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::CollectionMintId.eq(collection_mint_id))
+            .filter(Column::Claimant.eq(recipient))
+            .filter(Column::RedeemedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
+
+    /// Record that the Solana Pay transaction of a claim was sent to be
+    /// signed, dropping any transaction signed before
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn request_transaction(
+        conn: &impl ConnectionTrait,
+        model: Model,
+    ) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.pay_transaction = Set(None);
+        am.pay_requested_at = Set(Some(Utc::now().naive_utc()));
+        am.pay_signed_at = Set(None);
+
+        am.update(conn).await
+    }
+
+    /// Store the Solana Pay transaction of a claim once the treasury has
+    /// signed it, or clear the request if signing failed so the next one
+    /// asks again
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn store_transaction(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+        transaction: Option<Vec<u8>>,
+    ) -> Result<(), DbErr> {
+        let signed = transaction.is_some();
+        let mut update = Entity::update_many()
+            .col_expr(Column::PayTransaction, Expr::value(transaction))
+            .filter(Column::Id.eq(id));
+
+        update = if signed {
+            update.col_expr(Column::PaySignedAt, Expr::value(Utc::now().naive_utc()))
+        } else {
+            update.col_expr(Column::PayRequestedAt, Expr::value(None::<DateTime>))
+        };
+
+        update.exec(conn).await?;
+
+        Ok(())
+    }
+
+    /// Decode the mint request stored with a claim, if any
+    ///
+    /// # Errors
+    /// This function fails if the stored bytes are not a valid mint request
+    pub fn mint_request(model: &Model) -> Result<Option<MintMetaplexMetadataTransaction>, DbErr> {
+        model
+            .mint_request
+            .as_deref()
+            .map(MintMetaplexMetadataTransaction::decode)
+            .transpose()
+            .map_err(|e| DbErr::Custom(format!("invalid mint request for claim {}: {e}", model.id)))
+    }
+}
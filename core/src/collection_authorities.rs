@@ -0,0 +1,86 @@
+use holaplex_hub_nfts_solana_entity::collection_authorities::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::{NaiveDateTime, Utc};
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+/// Challenges signed by the update authority of a collection to prove a
+/// project may import it, one per project and collection
+pub struct CollectionAuthority;
+
+impl CollectionAuthority {
+    pub async fn find(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+        mint_address: &str,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id((project_id, mint_address.to_owned()))
+            .one(conn)
+            .await
+    }
+
+    /// The authority verified for importing `mint_address` into
+    /// `project_id`, if its challenge was signed
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_verified(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+        mint_address: &str,
+    ) -> Result<Option<Model>, DbErr> {
+        Ok(Self::find(conn, project_id, mint_address)
+            .await?
+            .filter(|authority| authority.verified_at.is_some()))
+    }
+
+    /// Issue a new challenge for `update_authority` to sign, replacing any
+    /// earlier challenge or verification for the collection
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn issue(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+        mint_address: String,
+        update_authority: String,
+        challenge: String,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            project_id: Set(project_id),
+            mint_address: Set(mint_address),
+            update_authority: Set(update_authority),
+            challenge: Set(challenge),
+            expires_at: Set(expires_at),
+            verified_at: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::columns([Column::ProjectId, Column::MintAddress])
+                    .update_columns([
+                        Column::UpdateAuthority,
+                        Column::Challenge,
+                        Column::ExpiresAt,
+                        Column::VerifiedAt,
+                        Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record that the challenge of `authority` was signed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn verify(conn: &impl ConnectionTrait, authority: Model) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = authority.into();
+        am.verified_at = Set(Some(Utc::now().naive_utc()));
+
+        am.update(conn).await
+    }
+}
@@ -0,0 +1,91 @@
+use holaplex_hub_nfts_solana_entity::collection_imports::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+use crate::proto::SolanaImportProgress;
+
+/// Progress of the most recent import of each collection
+pub struct ImportStatus;
+
+impl ImportStatus {
+    pub async fn find_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(collection_id).one(conn).await
+    }
+
+    /// Record the start of an import of `collection_id`, replacing the
+    /// progress of any earlier import
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn start(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        mint_address: String,
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+        let am = ActiveModel {
+            collection_id: Set(collection_id),
+            mint_address: Set(mint_address),
+            imported: Set(0),
+            skipped_burned: Set(0),
+            skipped_fungible: Set(0),
+            skipped_unverified: Set(0),
+            started_at: Set(now),
+            updated_at: Set(now),
+            completed_at: Set(None),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::CollectionId)
+                    .update_columns([
+                        Column::MintAddress,
+                        Column::Imported,
+                        Column::SkippedBurned,
+                        Column::SkippedFungible,
+                        Column::SkippedUnverified,
+                        Column::StartedAt,
+                        Column::UpdatedAt,
+                        Column::CompletedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store the progress of an import, marking it complete if `completed`
+    /// is set
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn update(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        progress: &SolanaImportProgress,
+        completed: bool,
+    ) -> Result<(), DbErr> {
+        let count = |n: u64| i64::try_from(n).unwrap_or(i64::MAX);
+        let now = Utc::now().naive_utc();
+
+        let am = ActiveModel {
+            collection_id: Set(collection_id),
+            imported: Set(count(progress.imported)),
+            skipped_burned: Set(count(progress.skipped_burned)),
+            skipped_fungible: Set(count(progress.skipped_fungible)),
+            skipped_unverified: Set(count(progress.skipped_unverified)),
+            updated_at: Set(now),
+            completed_at: Set(completed.then_some(now)),
+            ..Default::default()
+        };
+
+        am.update(conn).await?;
+
+        Ok(())
+    }
+}
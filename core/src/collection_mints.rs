@@ -1,24 +1,189 @@
+use std::collections::HashMap;
+
 use holaplex_hub_nfts_solana_entity::{
     collection_mints::{ActiveModel, Column, Entity, Model},
-    collections,
+    collections, compression_leafs, mint_transfer_histories,
+};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    Condition, PaginatorTrait, QueryOrder, QuerySelect, Set,
 };
-use sea_orm::{prelude::*, Set};
+
+/// Position after the last mint of a page. Pages are ordered by creation
+/// time, with the id breaking ties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintCursor {
+    pub created_at: DateTime,
+    pub id: Uuid,
+}
+
+impl MintCursor {
+    /// Encode the cursor as an opaque string for handing to clients
+    #[must_use]
+    pub fn encode(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.created_at.timestamp(),
+            self.created_at.timestamp_subsec_nanos(),
+            self.id
+        )
+    }
+
+    /// Parse a cursor produced by [`MintCursor::encode`]
+    #[must_use]
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let mut parts = cursor.splitn(3, '.');
+        let secs = parts.next()?.parse().ok()?;
+        let nanos = parts.next()?.parse().ok()?;
+        let id = parts.next()?;
+        let created_at = DateTime::from_timestamp_opt(secs, nanos)?;
+
+        Some(Self {
+            created_at,
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
+
+impl From<&Model> for MintCursor {
+    fn from(model: &Model) -> Self {
+        Self {
+            created_at: model.created_at,
+            id: model.id,
+        }
+    }
+}
+
+fn after(cursor: Option<MintCursor>) -> Condition {
+    let Some(MintCursor { created_at, id }) = cursor else {
+        return Condition::all();
+    };
+
+    Condition::any().add(Column::CreatedAt.gt(created_at)).add(
+        Condition::all()
+            .add(Column::CreatedAt.eq(created_at))
+            .add(Column::Id.gt(id)),
+    )
+}
 
 pub struct CollectionMint;
 
 impl CollectionMint {
-    pub async fn create(conn: &DatabaseConnection, model: Model) -> Result<Model, DbErr> {
+    pub async fn create(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
         let active_model: ActiveModel = model.into();
 
         active_model.insert(conn).await
     }
 
-    pub async fn find_by_id(conn: &DatabaseConnection, id: Uuid) -> Result<Option<Model>, DbErr> {
-        Entity::find().filter(Column::Id.eq(id)).one(conn).await
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Self::active().filter(Column::Id.eq(id)).one(conn).await
+    }
+
+    /// Find a mint, locking it for the rest of the surrounding transaction
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_for_update(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(Column::Id.eq(id))
+            .lock_exclusive()
+            .one(conn)
+            .await
+    }
+
+    /// The mint holding print edition `edition` of a collection, if any
+    pub async fn find_by_edition(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        edition: i64,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::Edition.eq(edition))
+            .one(conn)
+            .await
+    }
+
+    /// Insert `models`, updating the owner, token account, edition, archive
+    /// time and collection of any mint already stored at the same address or
+    /// ID, even if it was archived or belongs to another collection. The IDs
+    /// and creation times of existing mints are kept, so the stored rows of
+    /// the addressed mints are returned.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be written or read back
+    pub async fn upsert_many(
+        conn: &impl ConnectionTrait,
+        models: Vec<ActiveModel>,
+    ) -> Result<Vec<Model>, DbErr> {
+        if models.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Compressed mints have no address until they are confirmed, so only
+        // addressed mints are unique
+        let addresses = models
+            .iter()
+            .filter_map(|model| model.mint.clone().take())
+            .filter(|mint| !mint.is_empty())
+            .collect::<Vec<_>>();
+
+        // Only active mints are unique by address, so a model for an address
+        // already in use takes over the ID of its row and updates it in place
+        let existing = Self::active()
+            .filter(Column::Mint.is_in(addresses.clone()))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|model| (model.mint, model.id))
+            .collect::<HashMap<_, _>>();
+
+        let models = models.into_iter().map(|mut model| {
+            let id = model
+                .mint
+                .clone()
+                .take()
+                .and_then(|mint| existing.get(&mint).copied());
+
+            if let Some(id) = id {
+                model.id = Set(id);
+            }
+
+            model
+        });
+
+        Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::Owner,
+                        Column::AssociatedTokenAccount,
+                        Column::Edition,
+                        Column::DeletedAt,
+                        Column::CollectionId,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        if addresses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::active()
+            .filter(Column::Mint.is_in(addresses))
+            .all(conn)
+            .await
     }
 
     pub async fn update_owner_and_ata(
-        conn: &DatabaseConnection,
+        conn: &impl ConnectionTrait,
         model: &Model,
         owner: String,
         ata: String,
@@ -29,28 +194,355 @@ impl CollectionMint {
         active_model.update(conn).await
     }
 
+    pub async fn find_by_mint(
+        conn: &impl ConnectionTrait,
+        mint: String,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active().filter(Column::Mint.eq(mint)).one(conn).await
+    }
+
     pub async fn find_by_ata(
-        conn: &DatabaseConnection,
+        conn: &impl ConnectionTrait,
         ata: String,
     ) -> Result<Option<Model>, DbErr> {
-        Entity::find()
+        Self::active()
             .filter(Column::AssociatedTokenAccount.eq(ata))
             .one(conn)
             .await
     }
 
     pub async fn find_by_id_with_collection(
-        conn: &DatabaseConnection,
+        conn: &impl ConnectionTrait,
         id: Uuid,
     ) -> Result<Option<(Model, Option<collections::Model>)>, DbErr> {
-        Entity::find()
+        Self::active()
             .find_also_related(collections::Entity)
             .filter(Column::Id.eq(id))
             .one(conn)
             .await
     }
 
-    pub async fn update(conn: &DatabaseConnection, model: ActiveModel) -> Result<Model, DbErr> {
+    /// A page of the mints of a collection, starting after `cursor`
+    pub async fn find_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        cursor: Option<MintCursor>,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(after(cursor))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .limit(limit)
+            .all(conn)
+            .await
+    }
+
+    /// A page of the mints held by `owner`, starting after `cursor`
+    pub async fn find_by_owner(
+        conn: &impl ConnectionTrait,
+        owner: &str,
+        cursor: Option<MintCursor>,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::Owner.eq(owner))
+            .filter(after(cursor))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .limit(limit)
+            .all(conn)
+            .await
+    }
+
+    /// A page of every Hub-managed asset held by `owner`, starting after
+    /// `cursor`. Compressed assets come with the leaf backing them.
+    pub async fn find_all_by_owner(
+        conn: &impl ConnectionTrait,
+        owner: &str,
+        cursor: Option<MintCursor>,
+        limit: u64,
+    ) -> Result<Vec<(Model, Option<compression_leafs::Model>)>, DbErr> {
+        let mints = Self::find_by_owner(conn, owner, cursor, limit).await?;
+
+        Self::with_leafs(conn, mints).await
+    }
+
+    /// A page of the mints of a collection, starting after `cursor`.
+    /// Compressed mints come with the leaf backing them.
+    pub async fn find_all_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        cursor: Option<MintCursor>,
+        limit: u64,
+    ) -> Result<Vec<(Model, Option<compression_leafs::Model>)>, DbErr> {
+        let mints = Self::find_by_collection(conn, collection_id, cursor, limit).await?;
+
+        Self::with_leafs(conn, mints).await
+    }
+
+    /// Pair each compressed mint with the leaf backing it
+    async fn with_leafs(
+        conn: &impl ConnectionTrait,
+        mints: Vec<Model>,
+    ) -> Result<Vec<(Model, Option<compression_leafs::Model>)>, DbErr> {
+        let leaf_ids: Vec<Uuid> = mints
+            .iter()
+            .filter(|m| m.compressed)
+            .map(|m| m.id)
+            .collect();
+        let mut leafs: HashMap<Uuid, compression_leafs::Model> = if leaf_ids.is_empty() {
+            HashMap::new()
+        } else {
+            compression_leafs::Entity::find()
+                .filter(compression_leafs::Column::Id.is_in(leaf_ids))
+                .filter(compression_leafs::Column::DeletedAt.is_null())
+                .all(conn)
+                .await?
+                .into_iter()
+                .map(|leaf| (leaf.id, leaf))
+                .collect()
+        };
+
+        Ok(mints
+            .into_iter()
+            .map(|mint| {
+                let leaf = leafs.remove(&mint.id);
+                (mint, leaf)
+            })
+            .collect())
+    }
+
+    /// Mints of a collection held by `wallet`, as last observed by the
+    /// indexer
+    pub async fn holders_of(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        wallet: &str,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::Owner.eq(wallet))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(conn)
+            .await
+    }
+
+    pub async fn count_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<u64, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .count(conn)
+            .await
+    }
+
+    /// A sample of uncompressed mints along with their collections. Mints
+    /// are read in id order from a random id, wrapping around to the first,
+    /// so the sample is served by the primary key instead of sorting the
+    /// table.
+    pub async fn sample_with_collection(
+        conn: &impl ConnectionTrait,
+        limit: u64,
+    ) -> Result<Vec<(Model, Option<collections::Model>)>, DbErr> {
+        let start = Uuid::new_v4();
+        let page = |from: Condition, limit: u64| {
+            Self::active()
+                .find_also_related(collections::Entity)
+                .filter(Column::Compressed.eq(false))
+                .filter(from)
+                .order_by_asc(Column::Id)
+                .limit(limit)
+        };
+
+        let mut sample = page(Condition::all().add(Column::Id.gte(start)), limit)
+            .all(conn)
+            .await?;
+        let rest = limit.saturating_sub(sample.len() as u64);
+
+        if rest > 0 {
+            sample.extend(
+                page(Condition::all().add(Column::Id.lt(start)), rest)
+                    .all(conn)
+                    .await?,
+            );
+        }
+
+        Ok(sample)
+    }
+
+    /// Uncompressed mints of a collection whose metadata has not been locked
+    pub async fn find_unlocked_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::Compressed.eq(false))
+            .filter(Column::MetadataLockedAt.is_null())
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(conn)
+            .await
+    }
+
+    /// Tag the mints `ids` with the lock of their metadata being submitted
+    /// as `lock_id`
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn start_metadata_lock(
+        conn: &impl ConnectionTrait,
+        ids: Vec<Uuid>,
+        lock_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::MetadataLockId, Expr::value(lock_id))
+            .filter(Column::Id.is_in(ids))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the mints tagged with `lock_id` as locked once the lock of their
+    /// metadata has landed
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn confirm_metadata_lock(
+        conn: &impl ConnectionTrait,
+        lock_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(
+                Column::MetadataLockedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(Column::MetadataLockId.eq(lock_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record the primary sale of a mint unless one has been recorded
+    /// already, returning whether this call recorded it
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn mark_primary_sale(conn: &impl ConnectionTrait, id: Uuid) -> Result<bool, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::PrimarySaleAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .filter(Column::PrimarySaleAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Forget the primary sale of a mint, e.g. one detected in a slot which
+    /// was rolled back
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn clear_primary_sale(conn: &impl ConnectionTrait, id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::PrimarySaleAt, Expr::value(Option::<DateTime>::None))
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update(conn: &impl ConnectionTrait, model: ActiveModel) -> Result<Model, DbErr> {
         model.update(conn).await
     }
+
+    /// Record an ownership change observed on chain. `mint_id` may refer to
+    /// either a collection mint or a compression leaf. Returns `false` if
+    /// the transfer of the mint by the same transaction was already recorded,
+    /// e.g. when it is observed again by another subscription.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn record_transfer(
+        conn: &impl ConnectionTrait,
+        model: mint_transfer_histories::Model,
+    ) -> Result<bool, DbErr> {
+        let active_model: mint_transfer_histories::ActiveModel = model.into();
+
+        let rows = mint_transfer_histories::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    mint_transfer_histories::Column::MintId,
+                    mint_transfer_histories::Column::Signature,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(rows > 0)
+    }
+
+    /// Forget a recorded ownership change which did not happen after all
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be deleted
+    pub async fn delete_transfer(conn: &impl ConnectionTrait, id: Uuid) -> Result<(), DbErr> {
+        mint_transfer_histories::Entity::delete_by_id(id)
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Ownership changes of a mint, oldest first
+    pub async fn history(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Vec<mint_transfer_histories::Model>, DbErr> {
+        mint_transfer_histories::Entity::find()
+            .filter(mint_transfer_histories::Column::MintId.eq(id))
+            .order_by_asc(mint_transfer_histories::Column::Slot)
+            .all(conn)
+            .await
+    }
+
+    /// Archive a mint and the leaf backing it if it is compressed, keeping
+    /// its history but leaving it out of every lookup
+    ///
+    /// # Errors
+    /// This function fails if either row cannot be updated
+    pub async fn archive(conn: &impl ConnectionTrait, id: Uuid) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+
+        compression_leafs::Entity::update_many()
+            .col_expr(compression_leafs::Column::DeletedAt, Expr::value(now))
+            .filter(compression_leafs::Column::Id.eq(id))
+            .filter(compression_leafs::Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Entity::update_many()
+            .col_expr(Column::DeletedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mints which have not been archived
+    fn active() -> Select<Entity> {
+        Entity::find().filter(Column::DeletedAt.is_null())
+    }
 }
@@ -1,25 +1,218 @@
-use holaplex_hub_nfts_solana_entity::collections::{ActiveModel, Column, Entity, Model};
-use sea_orm::prelude::*;
+use holaplex_hub_nfts_solana_entity::{
+    collection_mints,
+    collections::{ActiveModel, Column, Entity, Model},
+    compression_leafs,
+    sea_orm_active_enums::SolanaCluster,
+};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::Expr, Condition, QueryOrder, QuerySelect};
 
 pub struct Collection;
 
 impl Collection {
-    pub async fn create(conn: &DatabaseConnection, am: ActiveModel) -> Result<Model, DbErr> {
+    pub async fn create(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<Model, DbErr> {
         am.insert(conn).await
     }
 
-    pub async fn find_by_id(conn: &DatabaseConnection, id: Uuid) -> Result<Option<Model>, DbErr> {
-        Entity::find().filter(Column::Id.eq(id)).one(conn).await
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Self::active().filter(Column::Id.eq(id)).one(conn).await
     }
 
     pub async fn find_by_mint(
-        conn: &DatabaseConnection,
+        conn: &impl ConnectionTrait,
         mint: String,
     ) -> Result<Option<Model>, DbErr> {
-        Entity::find().filter(Column::Mint.eq(mint)).one(conn).await
+        Self::active().filter(Column::Mint.eq(mint)).one(conn).await
     }
 
-    pub async fn update(conn: &DatabaseConnection, model: ActiveModel) -> Result<Model, DbErr> {
+    /// The cluster the newest collection of `project_id` was created on
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_cluster_by_project(
+        conn: &impl ConnectionTrait,
+        project_id: String,
+    ) -> Result<Option<SolanaCluster>, DbErr> {
+        let collection = Self::active()
+            .filter(Column::ProjectId.eq(project_id))
+            .order_by_desc(Column::CreatedAt)
+            .one(conn)
+            .await?;
+
+        Ok(collection.map(|c| c.cluster))
+    }
+
+    /// Every collection, used to build the set of accounts the indexer
+    /// watches
+    pub async fn find_all(conn: &impl ConnectionTrait) -> Result<Vec<Model>, DbErr> {
+        Self::active().all(conn).await
+    }
+
+    /// The collection whose metadata or master edition account is `address`
+    pub async fn find_by_account(
+        conn: &impl ConnectionTrait,
+        address: String,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(
+                Condition::any()
+                    .add(Column::Metadata.eq(address.clone()))
+                    .add(Column::MasterEdition.eq(address)),
+            )
+            .one(conn)
+            .await
+    }
+
+    pub async fn update(conn: &impl ConnectionTrait, model: ActiveModel) -> Result<Model, DbErr> {
         model.update(conn).await
     }
+
+    /// Count a landed edition mint against the supply of a drop and return
+    /// the updated collection
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated or read back
+    pub async fn increment_editions_minted(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::update_many()
+            .col_expr(
+                Column::EditionsMinted,
+                Expr::col(Column::EditionsMinted).add(1),
+            )
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Self::find_by_id(conn, id).await
+    }
+
+    /// Bump the metadata version of a collection once an update of its
+    /// metadata has landed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn increment_metadata_version(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(
+                Column::MetadataVersion,
+                Expr::col(Column::MetadataVersion).add(1),
+            )
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tag a collection with the lock of its metadata being submitted as
+    /// `lock_id`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn start_metadata_lock(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+        lock_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::MetadataLockId, Expr::value(lock_id))
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the collection tagged with `lock_id` as locked once the lock of
+    /// its metadata has landed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_metadata_lock(
+        conn: &impl ConnectionTrait,
+        lock_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(
+                Column::MetadataLockedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(Column::MetadataLockId.eq(lock_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Collections whose mint window opened at or before `now` without the
+    /// opening having been announced, locked for the duration of the
+    /// surrounding transaction
+    pub async fn find_unannounced_openings(
+        conn: &impl ConnectionTrait,
+        now: DateTime,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::MintOpenAt.lte(now))
+            .filter(Column::OpenAnnouncedAt.is_null())
+            .lock_exclusive()
+            .all(conn)
+            .await
+    }
+
+    /// Collections whose mint window closed at or before `now` without the
+    /// closing having been announced, locked for the duration of the
+    /// surrounding transaction
+    pub async fn find_unannounced_closings(
+        conn: &impl ConnectionTrait,
+        now: DateTime,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::MintCloseAt.lte(now))
+            .filter(Column::CloseAnnouncedAt.is_null())
+            .lock_exclusive()
+            .all(conn)
+            .await
+    }
+
+    /// Archive a collection along with its mints and their leaves. Archived
+    /// rows are kept for their history but left out of every lookup.
+    ///
+    /// # Errors
+    /// This function fails if any of the rows cannot be updated
+    pub async fn archive(conn: &impl ConnectionTrait, id: Uuid) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+
+        compression_leafs::Entity::update_many()
+            .col_expr(compression_leafs::Column::DeletedAt, Expr::value(now))
+            .filter(compression_leafs::Column::CollectionId.eq(id))
+            .filter(compression_leafs::Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        collection_mints::Entity::update_many()
+            .col_expr(collection_mints::Column::DeletedAt, Expr::value(now))
+            .filter(collection_mints::Column::CollectionId.eq(id))
+            .filter(collection_mints::Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Entity::update_many()
+            .col_expr(Column::DeletedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Collections which have not been archived
+    fn active() -> Select<Entity> {
+        Entity::find().filter(Column::DeletedAt.is_null())
+    }
 }
@@ -1,30 +1,271 @@
-use holaplex_hub_nfts_solana_entity::compression_leafs::{ActiveModel, Column, Entity, Model};
-use sea_orm::prelude::*;
+use holaplex_hub_nfts_solana_entity::{
+    collection_mints,
+    compression_leafs::{ActiveModel, Column, Entity, Model},
+};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::Expr, QueryOrder, QuerySelect, Set};
 
 pub struct CompressionLeaf;
 
+/// The `collection_mints` row sharing a leaf's id, so compressed assets can be
+/// queried alongside uncompressed ones
+fn linked_mint(leaf: &Model) -> collection_mints::Model {
+    collection_mints::Model {
+        id: leaf.id,
+        collection_id: leaf.collection_id,
+        mint: leaf.asset_id.clone().unwrap_or_default(),
+        owner: leaf.leaf_owner.clone(),
+        associated_token_account: String::new(),
+        created_at: leaf.created_at,
+        compressed: true,
+        edition: None,
+        metadata_lock_id: None,
+        metadata_locked_at: None,
+        primary_sale_at: None,
+        deleted_at: None,
+    }
+}
+
 impl CompressionLeaf {
-    pub async fn create(conn: &DatabaseConnection, model: Model) -> Result<Model, DbErr> {
+    pub async fn create(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
+        let mint: collection_mints::ActiveModel = linked_mint(&model).into();
         let active_model: ActiveModel = model.into();
 
-        active_model.insert(conn).await
+        let leaf = active_model.insert(conn).await?;
+        mint.insert(conn).await?;
+
+        Ok(leaf)
+    }
+
+    pub async fn create_many(conn: &impl ConnectionTrait, models: Vec<Model>) -> Result<(), DbErr> {
+        if models.is_empty() {
+            return Ok(());
+        }
+
+        let mints: Vec<collection_mints::ActiveModel> =
+            models.iter().map(|m| linked_mint(m).into()).collect();
+        let leafs: Vec<ActiveModel> = models.into_iter().map(Into::into).collect();
+
+        Entity::insert_many(leafs).exec(conn).await?;
+        collection_mints::Entity::insert_many(mints)
+            .exec(conn)
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn find_by_id(conn: &DatabaseConnection, id: Uuid) -> Result<Option<Model>, DbErr> {
-        Entity::find().filter(Column::Id.eq(id)).one(conn).await
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Self::active().filter(Column::Id.eq(id)).one(conn).await
     }
 
     pub async fn find_by_asset_id(
-        conn: &DatabaseConnection,
+        conn: &impl ConnectionTrait,
         address: String,
     ) -> Result<Option<Model>, DbErr> {
-        Entity::find()
+        Self::active()
             .filter(Column::AssetId.eq(address))
             .one(conn)
             .await
     }
 
-    pub async fn update(conn: &DatabaseConnection, model: ActiveModel) -> Result<Model, DbErr> {
-        model.update(conn).await
+    /// Leaves minted into a collection, oldest first
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_by_collection(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::CollectionId.eq(collection_id))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(conn)
+            .await
+    }
+
+    /// Leaves owned by `leaf_owner`, as last observed by the indexer, oldest
+    /// first
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_by_leaf_owner(
+        conn: &impl ConnectionTrait,
+        leaf_owner: String,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::active()
+            .filter(Column::LeafOwner.eq(leaf_owner))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(conn)
+            .await
+    }
+
+    /// Update a leaf and keep the owner and asset id of its linked
+    /// `collection_mints` row in sync.
+    ///
+    /// # Errors
+    /// This function fails if either row cannot be updated
+    pub async fn update(conn: &impl ConnectionTrait, model: ActiveModel) -> Result<Model, DbErr> {
+        let leaf = model.update(conn).await?;
+
+        collection_mints::Entity::update_many()
+            .col_expr(
+                collection_mints::Column::Mint,
+                Expr::value(leaf.asset_id.clone().unwrap_or_default()),
+            )
+            .col_expr(
+                collection_mints::Column::Owner,
+                Expr::value(leaf.leaf_owner.clone()),
+            )
+            .filter(collection_mints::Column::Id.eq(leaf.id))
+            .exec(conn)
+            .await?;
+
+        Ok(leaf)
+    }
+
+    /// Find a leaf, locking it for the rest of the surrounding transaction
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_for_update(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(Column::Id.eq(id))
+            .lock_exclusive()
+            .one(conn)
+            .await
+    }
+
+    /// The unconfirmed leaf minted by the transaction with `signature`,
+    /// locked for the rest of the surrounding transaction
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_unconfirmed_by_signature(
+        conn: &impl ConnectionTrait,
+        signature: String,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(Column::MintSignature.eq(signature))
+            .filter(Column::AssetIdConfirmedAt.is_null())
+            .lock_exclusive()
+            .one(conn)
+            .await
+    }
+
+    /// Remember the signature of the transaction minting an unconfirmed
+    /// leaf, so whichever of the consumer and indexer sees it land first can
+    /// confirm its asset id
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn record_signature(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+        signature: String,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::MintSignature, Expr::value(signature))
+            .filter(Column::Id.eq(id))
+            .filter(Column::AssetIdConfirmedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Number of leaves of `merkle_tree` whose asset id was predicted but
+    /// has not been confirmed
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn count_predicted(
+        conn: &impl ConnectionTrait,
+        merkle_tree: &str,
+    ) -> Result<u64, DbErr> {
+        Self::predicted()
+            .filter(Column::MerkleTree.eq(merkle_tree))
+            .count(conn)
+            .await
+    }
+
+    /// The oldest leaves whose asset id was predicted but has not been
+    /// confirmed
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_predicted(
+        conn: &impl ConnectionTrait,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::predicted()
+            .order_by_asc(Column::CreatedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+    }
+
+    /// Leaves which have not been archived
+    fn active() -> Select<Entity> {
+        Entity::find().filter(Column::DeletedAt.is_null())
+    }
+
+    fn predicted() -> Select<Entity> {
+        Self::active()
+            .filter(Column::AssetId.is_not_null())
+            .filter(Column::MintSignature.is_not_null())
+            .filter(Column::AssetIdConfirmedAt.is_null())
+    }
+
+    /// Store the asset id read from the landed mint of a leaf. Returns the
+    /// asset id predicted for the leaf if the prediction was wrong.
+    ///
+    /// Other unconfirmed leaves predicted at the same asset id were
+    /// mispredicted, so their linked mints give up the address until they
+    /// are confirmed in turn. Their leaves keep the prediction so it can be
+    /// corrected.
+    ///
+    /// # Errors
+    /// This function fails if the leaf cannot be updated, or if another leaf
+    /// was already confirmed at `asset_id`
+    pub async fn confirm_asset_id(
+        conn: &impl ConnectionTrait,
+        leaf: Model,
+        asset_id: String,
+    ) -> Result<Option<String>, DbErr> {
+        let mispredicted = leaf
+            .asset_id
+            .clone()
+            .filter(|predicted| *predicted != asset_id);
+
+        let contenders: Vec<Uuid> = Self::active()
+            .filter(Column::AssetId.eq(asset_id.as_str()))
+            .filter(Column::AssetIdConfirmedAt.is_null())
+            .filter(Column::Id.ne(leaf.id))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|l| l.id)
+            .collect();
+
+        if !contenders.is_empty() {
+            collection_mints::Entity::update_many()
+                .col_expr(collection_mints::Column::Mint, Expr::value(""))
+                .filter(collection_mints::Column::Id.is_in(contenders))
+                .exec(conn)
+                .await?;
+        }
+
+        let mut leaf: ActiveModel = leaf.into();
+        leaf.asset_id = Set(Some(asset_id));
+        leaf.asset_id_confirmed_at = Set(Some(Utc::now().naive_utc()));
+
+        Self::update(conn, leaf).await?;
+
+        Ok(mispredicted)
     }
 }
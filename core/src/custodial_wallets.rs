@@ -0,0 +1,43 @@
+use holaplex_hub_nfts_solana_entity::custodial_wallets::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+/// Wallets resolved for custodial recipients, cached by email so each
+/// address is only looked up once
+pub struct CustodialWallet;
+
+impl CustodialWallet {
+    pub async fn find_by_email(
+        conn: &impl ConnectionTrait,
+        email: &str,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(email.to_owned()).one(conn).await
+    }
+
+    /// Cache the wallet resolved for `email`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(
+        conn: &impl ConnectionTrait,
+        email: String,
+        wallet_address: String,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            email: Set(email),
+            wallet_address: Set(wallet_address),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Email)
+                    .update_columns([Column::WalletAddress, Column::CreatedAt])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+}
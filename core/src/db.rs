@@ -1,57 +1,172 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
 use hub_core::{anyhow::Result, clap, prelude::*};
-pub use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+pub use sea_orm::{ConnectOptions, Database, DatabaseConnection, DatabaseTransaction};
+use sea_orm::{sqlx, DbErr, RuntimeErr, TransactionTrait};
+
+/// SQLSTATE Postgres reports when a row violates a unique constraint or index
+const UNIQUE_VIOLATION: &str = "23505";
 
 /// Arguments for establishing a database connection
-#[derive(Debug, clap::Args)]
+#[derive(Debug, Clone, clap::Args)]
 pub struct DbArgs {
     #[arg(long, env, default_value_t = 500)]
     pub max_connections: u32,
+    /// Connections each pool keeps open while idle
+    #[arg(long, env, default_value_t = 0)]
+    pub min_connections: u32,
     #[arg(long, env, default_value_t = 60)]
     pub connection_timeout: u64,
     #[arg(long, env, default_value_t = 60)]
     pub acquire_timeout: u64,
     #[arg(long, env, default_value_t = 20)]
     pub idle_timeout: u64,
+    /// Milliseconds after which the database aborts a statement. The
+    /// server's setting applies if this is not set.
+    #[arg(long, env)]
+    pub statement_timeout: Option<u64>,
     #[arg(long, env)]
     pub database_url: String,
+    /// Comma-separated URLs of read replicas serving queries which tolerate
+    /// replication lag. Those queries go to `database_url` if none are given.
+    #[arg(long, env, value_delimiter = ',')]
+    pub database_read_replica_urls: Vec<String>,
 }
 
+/// A pool of connections to the primary database, which takes every write,
+/// and pools to its read replicas, if any
 #[derive(Debug, Clone)]
-pub struct Connection(DatabaseConnection);
+pub struct Connection {
+    writer: DatabaseConnection,
+    readers: Arc<[DatabaseConnection]>,
+    next_reader: Arc<AtomicUsize>,
+}
 
 impl Connection {
-    /// Res
+    /// Connect to the primary database and each of its read replicas
     ///
     /// # Errors
-    /// This function fails if ...
+    /// This function fails if any of the databases cannot be connected to
     pub async fn new(args: DbArgs) -> Result<Self> {
         let DbArgs {
             max_connections,
+            min_connections,
             connection_timeout,
             acquire_timeout,
             idle_timeout,
+            statement_timeout,
             database_url,
+            database_read_replica_urls,
         } = args;
 
-        let options = ConnectOptions::new(database_url)
-            .max_connections(max_connections)
-            .connect_timeout(Duration::from_secs(connection_timeout))
-            .acquire_timeout(Duration::from_secs(acquire_timeout))
-            .idle_timeout(Duration::from_secs(idle_timeout))
-            .clone();
+        let connect = |url: String| {
+            let url = match statement_timeout {
+                Some(timeout) => with_statement_timeout(&url, timeout),
+                None => url,
+            };
+            let options = ConnectOptions::new(url)
+                .max_connections(max_connections)
+                .min_connections(min_connections)
+                .connect_timeout(Duration::from_secs(connection_timeout))
+                .acquire_timeout(Duration::from_secs(acquire_timeout))
+                .idle_timeout(Duration::from_secs(idle_timeout))
+                .clone();
+
+            Database::connect(options)
+        };
 
-        let connection = Database::connect(options)
+        let writer = connect(database_url)
             .await
             .context("failed to get database connection")?;
 
-        Ok(Self(connection))
+        let mut readers = Vec::with_capacity(database_read_replica_urls.len());
+
+        for url in database_read_replica_urls {
+            readers.push(
+                connect(url)
+                    .await
+                    .context("failed to get read replica connection")?,
+            );
+        }
+
+        Ok(Self {
+            writer,
+            readers: readers.into(),
+            next_reader: Arc::default(),
+        })
     }
 
+    /// The primary database, for writes and for reads which must see them
     #[must_use]
-
     pub fn get(&self) -> &DatabaseConnection {
-        &self.0
+        &self.writer
+    }
+
+    /// A read replica, taken in turn, or the primary database if there are
+    /// none. Rows written moments ago may not be visible yet.
+    #[must_use]
+    pub fn read(&self) -> &DatabaseConnection {
+        if self.readers.is_empty() {
+            return &self.writer;
+        }
+
+        let next = self.next_reader.fetch_add(1, Ordering::Relaxed);
+
+        &self.readers[next % self.readers.len()]
+    }
+
+    /// The pools of the read replicas, in the order they were given
+    #[must_use]
+    pub fn replicas(&self) -> &[DatabaseConnection] {
+        &self.readers
+    }
+
+    /// Begin a database transaction. Writes made through the transaction are
+    /// rolled back if it is dropped without being committed.
+    ///
+    /// # Errors
+    /// This function fails if a connection cannot be acquired from the pool
+    pub async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
+        self.writer.begin().await
+    }
+}
+
+/// Whether `err` was raised by a row violating a unique constraint or index
+#[must_use]
+pub fn is_unique_violation(err: &DbErr) -> bool {
+    match err {
+        DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+        | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e))) => {
+            e.code().as_deref() == Some(UNIQUE_VIOLATION)
+        },
+        _ => false,
+    }
+}
+
+/// Append `statement_timeout` to the startup options sent by every
+/// connection made with `url`
+fn with_statement_timeout(url: &str, timeout_ms: u64) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+
+    format!("{url}{separator}options=-c%20statement_timeout%3D{timeout_ms}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_statement_timeout;
+
+    #[test]
+    fn appends_statement_timeout() {
+        assert_eq!(
+            with_statement_timeout("postgres://localhost/hub", 5000),
+            "postgres://localhost/hub?options=-c%20statement_timeout%3D5000"
+        );
+        assert_eq!(
+            with_statement_timeout("postgres://localhost/hub?sslmode=require", 100),
+            "postgres://localhost/hub?sslmode=require&options=-c%20statement_timeout%3D100"
+        );
     }
 }
@@ -0,0 +1,68 @@
+use holaplex_hub_nfts_solana_entity::dead_letters::{ActiveModel, Column, Entity, Model};
+use hub_core::{chrono::Utc, consumer::RecvError};
+use sea_orm::{prelude::*, QueryOrder, QuerySelect, Set};
+
+use crate::Services;
+
+pub struct DeadLetter;
+
+impl DeadLetter {
+    /// Store a consumed message whose processing failed permanently, along
+    /// with the error it failed with
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn create(
+        conn: &impl ConnectionTrait,
+        msg: &Services,
+        error: String,
+    ) -> Result<Model, DbErr> {
+        let (key, payload) = msg.encode();
+
+        let am = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            topic: Set(msg.topic().to_owned()),
+            key: Set(key),
+            payload: Set(payload),
+            error: Set(error),
+            created_at: Set(Utc::now().naive_utc()),
+            replayed_at: Set(None),
+        };
+
+        am.insert(conn).await
+    }
+
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).one(conn).await
+    }
+
+    /// Most recent dead letters first
+    pub async fn list(
+        conn: &impl ConnectionTrait,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .order_by_desc(Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await
+    }
+
+    pub async fn mark_replayed(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.replayed_at = Set(Some(Utc::now().naive_utc()));
+
+        am.update(conn).await
+    }
+
+    /// Decode the message stored in a dead letter row
+    ///
+    /// # Errors
+    /// This function fails if the topic is unknown or the stored bytes are
+    /// not a valid message for it
+    pub fn decode(model: &Model) -> Result<Services, RecvError> {
+        Services::decode(&model.topic, &model.key, &model.payload)
+    }
+}
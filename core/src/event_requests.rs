@@ -0,0 +1,61 @@
+use holaplex_hub_nfts_solana_entity::event_requests::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use prost::{DecodeError, Message};
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+use crate::proto::{NftEventKey, NftEvents};
+
+pub struct EventRequest;
+
+impl EventRequest {
+    /// Store the latest hub-nfts request received for an event so it can be
+    /// replayed by an operator if the event fails.
+    ///
+    /// # Errors
+    /// This function fails if the event id is not a valid UUID or the row
+    /// cannot be written
+    pub async fn record(
+        conn: &impl ConnectionTrait,
+        key: &NftEventKey,
+        event: &NftEvents,
+    ) -> Result<(), DbErr> {
+        let event_id = Uuid::parse_str(&key.id)
+            .map_err(|e| DbErr::Custom(format!("invalid event id: {e}")))?;
+
+        let am = ActiveModel {
+            event_id: Set(event_id),
+            key: Set(key.encode_to_vec()),
+            payload: Set(event.encode_to_vec()),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::EventId)
+                    .update_columns([Column::Key, Column::Payload, Column::CreatedAt])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(event_id).one(conn).await
+    }
+
+    /// Decode the key and event stored in an event request row
+    ///
+    /// # Errors
+    /// This function fails if the stored bytes are not a valid key or event
+    pub fn decode(model: &Model) -> Result<(NftEventKey, NftEvents), DecodeError> {
+        let key = NftEventKey::decode(model.key.as_slice())?;
+        let event = NftEvents::decode(model.payload.as_slice())?;
+
+        Ok((key, event))
+    }
+}
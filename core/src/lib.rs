@@ -2,23 +2,85 @@
 #![warn(clippy::pedantic, clippy::cargo)]
 #![allow(clippy::module_name_repetitions)]
 
+mod asset_escrows;
+pub mod auction_house;
+mod candy_machines;
+mod claims;
+mod collection_authorities;
+mod collection_imports;
 mod collection_mints;
 mod collections;
 mod compression_leafs;
+mod custodial_wallets;
 pub mod db;
-
-pub use collection_mints::CollectionMint;
+mod dead_letters;
+mod event_requests;
+mod listings;
+mod merkle_trees;
+pub mod metaplex;
+mod mint_allowlists;
+mod mint_payments;
+mod mint_transfers;
+mod mints;
+mod multisig_proposals;
+mod nonce_accounts;
+mod outbox;
+mod project_treasuries;
+mod royalty_splits;
+mod signer_policies;
+mod token_balances;
+mod transaction_audit;
+mod transaction_costs;
+mod transaction_failures;
+mod transaction_revisions;
+mod transfer_backfills;
+mod unfinalized_transfers;
+mod update_revisions;
+mod wallet_mint_counts;
+
+pub use asset_escrows::AssetEscrow;
+pub use candy_machines::CandyMachine;
+pub use claims::Claim;
+pub use collection_authorities::CollectionAuthority;
+pub use collection_imports::ImportStatus;
+pub use collection_mints::{CollectionMint, MintCursor};
 pub use collections::Collection;
 pub use compression_leafs::CompressionLeaf;
+pub use custodial_wallets::CustodialWallet;
+pub use dead_letters::DeadLetter;
+pub use event_requests::EventRequest;
 use hub_core::{consumer::RecvError, prelude::*};
+pub use listings::{Listing, ListingStatus};
+pub use merkle_trees::MerkleTree;
+pub use mint_allowlists::MintAllowlist;
+pub use mint_payments::MintPayment;
+pub use mint_transfers::{AppliedTransfer, MintTransfer, ObservedTransfer};
+pub use mints::Mint;
+pub use multisig_proposals::{MultisigProposal, ProposalStatus};
+pub use nonce_accounts::NonceAccount;
+pub use outbox::Outbox;
+pub use project_treasuries::ProjectTreasury;
 use proto::{NftEventKey, SolanaNftEventKey, TreasuryEventKey};
+pub use royalty_splits::RoyaltySplit;
 pub use sea_orm;
+pub use signer_policies::SignerPolicy;
+pub use token_balances::TokenBalance;
+pub use transaction_audit::TransactionAudit;
+pub use transaction_costs::TransactionCost;
+pub use transaction_failures::TransactionFailure;
+pub use transaction_revisions::TransactionRevision;
+pub use transfer_backfills::TransferBackfill;
+pub use unfinalized_transfers::UnfinalizedTransfer;
+pub use update_revisions::{RevisionStatus, UpdateRevision};
+pub use wallet_mint_counts::WalletMintCount;
 
 #[allow(clippy::pedantic)]
 pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/nfts.proto.rs"));
     include!(concat!(env!("OUT_DIR"), "/solana_nfts.proto.rs"));
     include!(concat!(env!("OUT_DIR"), "/treasury.proto.rs"));
+
+    pub mod compat;
 }
 
 #[derive(Debug, Clone)]
@@ -31,26 +93,46 @@ impl hub_core::producer::Message for proto::SolanaNftEvents {
     type Key = proto::SolanaNftEventKey;
 }
 
-impl hub_core::consumer::MessageGroup for Services {
-    const REQUESTED_TOPICS: &'static [&'static str] = &["hub-nfts", "hub-treasuries"];
+impl hub_core::producer::Message for proto::SolanaDeadLetter {
+    type Key = proto::SolanaNftEventKey;
+}
 
-    fn from_message<M: hub_core::consumer::Message>(msg: &M) -> Result<Self, RecvError> {
-        let topic = msg.topic();
-        let key = msg.key().ok_or(RecvError::MissingKey)?;
-        let val = msg.payload().ok_or(RecvError::MissingPayload)?;
+impl Services {
+    /// The topic a message of this kind is consumed from
+    #[must_use]
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Self::Nfts(..) => "hub-nfts",
+            Self::Treasury(..) => "hub-treasuries",
+        }
+    }
 
-        info!(topic, ?key, ?val);
+    /// Encode the key and payload of the message as they were consumed
+    #[must_use]
+    pub fn encode(&self) -> (Vec<u8>, Vec<u8>) {
+        match self {
+            Self::Nfts(key, val) => (key.encode_to_vec(), val.encode_to_vec()),
+            Self::Treasury(key, val) => (key.encode_to_vec(), val.encode_to_vec()),
+        }
+    }
 
+    /// Decode a message consumed from `topic`
+    ///
+    /// # Errors
+    /// This function fails if the topic is unknown or the key or payload is
+    /// not a valid message for it
+    pub fn decode(topic: &str, key: &[u8], val: &[u8]) -> Result<Self, RecvError> {
         match topic {
             "hub-nfts" => {
                 let key = proto::NftEventKey::decode(key)?;
-                let val = proto::NftEvents::decode(val)?;
+                let val = proto::compat::upgrade_nft_events(proto::NftEvents::decode(val)?);
 
                 Ok(Services::Nfts(key, val))
             },
             "hub-treasuries" => {
                 let key = proto::TreasuryEventKey::decode(key)?;
-                let val = proto::TreasuryEvents::decode(val)?;
+                let val =
+                    proto::compat::upgrade_treasury_events(proto::TreasuryEvents::decode(val)?);
 
                 Ok(Services::Treasury(key, val))
             },
@@ -59,17 +141,35 @@ impl hub_core::consumer::MessageGroup for Services {
     }
 }
 
+impl hub_core::consumer::MessageGroup for Services {
+    const REQUESTED_TOPICS: &'static [&'static str] = &["hub-nfts", "hub-treasuries"];
+
+    fn from_message<M: hub_core::consumer::Message>(msg: &M) -> Result<Self, RecvError> {
+        let topic = msg.topic();
+        let key = msg.key().ok_or(RecvError::MissingKey)?;
+        let val = msg.payload().ok_or(RecvError::MissingPayload)?;
+
+        info!(topic, ?key, ?val);
+
+        Self::decode(topic, key, val)
+    }
+}
+
 impl From<TreasuryEventKey> for SolanaNftEventKey {
     fn from(key: TreasuryEventKey) -> Self {
         let TreasuryEventKey {
             user_id,
             id,
             project_id,
+            correlation_id,
         } = key;
+        let correlation_id = correlation_or_id(correlation_id, &id);
+
         Self {
             id,
             user_id,
             project_id,
+            correlation_id,
         }
     }
 }
@@ -80,54 +180,61 @@ impl From<NftEventKey> for SolanaNftEventKey {
             user_id,
             project_id,
             id,
+            correlation_id,
         } = key;
+        let correlation_id = correlation_or_id(correlation_id, &id);
 
         Self {
             id,
             user_id,
             project_id,
+            correlation_id,
         }
     }
 }
 
-use mpl_token_metadata::state::Creator;
+/// Events produced before correlation ids were introduced fall back to the
+/// key id, which is shared by every event for the same mint or collection
+fn correlation_or_id(correlation_id: String, id: &str) -> String {
+    if correlation_id.is_empty() {
+        id.to_owned()
+    } else {
+        correlation_id
+    }
+}
 
-use crate::proto::Creator as ProtoCreator;
+use holaplex_hub_nfts_solana_entity::collections::CollectionCreator;
 
-impl TryFrom<ProtoCreator> for Creator {
-    type Error = Error;
+use crate::proto::Creator as ProtoCreator;
 
-    fn try_from(
+impl From<ProtoCreator> for CollectionCreator {
+    fn from(
         ProtoCreator {
             address,
             verified,
             share,
         }: ProtoCreator,
-    ) -> Result<Self> {
-        Ok(Self {
-            address: address.parse()?,
+    ) -> Self {
+        Self {
+            address,
             verified,
-            share: share.try_into()?,
-        })
+            share,
+        }
     }
 }
 
-use mpl_bubblegum::state::metaplex_adapter::Creator as BubblegumCreator;
-
-impl TryFrom<ProtoCreator> for BubblegumCreator {
-    type Error = Error;
-
-    fn try_from(
-        ProtoCreator {
+impl From<CollectionCreator> for ProtoCreator {
+    fn from(
+        CollectionCreator {
             address,
             verified,
             share,
-        }: ProtoCreator,
-    ) -> Result<Self> {
-        Ok(Self {
-            address: address.parse()?,
+        }: CollectionCreator,
+    ) -> Self {
+        Self {
+            address,
             verified,
-            share: share.try_into()?,
-        })
+            share,
+        }
     }
 }
@@ -0,0 +1,169 @@
+use holaplex_hub_nfts_solana_entity::listings::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, IntoCondition, OnConflict},
+    QueryOrder, Set,
+};
+
+/// Progress of a listing of a mint on an auction house
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListingStatus {
+    /// Assembled and sent to the treasury for the seller's signature
+    Requested,
+    /// Listed on-chain and waiting for a buyer
+    Listed,
+    /// Cancelled on-chain by the seller
+    Cancelled,
+    /// Bought through the auction house
+    Sold,
+}
+
+impl ListingStatus {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Requested => "requested",
+            Self::Listed => "listed",
+            Self::Cancelled => "cancelled",
+            Self::Sold => "sold",
+        }
+    }
+}
+
+/// Listings of Hub mints on Metaplex Auction Houses
+pub struct Listing;
+
+impl Listing {
+    /// Record a requested listing, replacing the listing assembled by an
+    /// earlier attempt at the same event
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn request(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<(), DbErr> {
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::AuctionHouse,
+                        Column::Seller,
+                        Column::TokenAccount,
+                        Column::Price,
+                        Column::TradeState,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The listing of a mint which is live on its auction house, if any
+    pub async fn find_listed(
+        conn: &impl ConnectionTrait,
+        mint_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::MintId.eq(mint_id))
+            .filter(Column::Status.eq(ListingStatus::Listed.as_str()))
+            .order_by_desc(Column::CreatedAt)
+            .one(conn)
+            .await
+    }
+
+    /// Record the event cancelling a listing
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn request_cancel(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+        cancel_event_id: Uuid,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::CancelEventId, Expr::value(cancel_event_id))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the listing requested by `event_id` as live
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_listing(conn: &impl ConnectionTrait, event_id: Uuid) -> Result<(), DbErr> {
+        Self::settle(
+            conn,
+            Column::Id.eq(event_id),
+            ListingStatus::Requested,
+            ListingStatus::Listed,
+        )
+        .await
+    }
+
+    /// Mark the listing whose cancellation was requested by `event_id` as
+    /// cancelled
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_cancel(conn: &impl ConnectionTrait, event_id: Uuid) -> Result<(), DbErr> {
+        Self::settle(
+            conn,
+            Column::CancelEventId.eq(event_id),
+            ListingStatus::Listed,
+            ListingStatus::Cancelled,
+        )
+        .await
+    }
+
+    /// Mark the live listing recorded in `trade_state` as sold to `buyer`,
+    /// returning it if the sale matched a listing
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn mark_sold(
+        conn: &impl ConnectionTrait,
+        trade_state: &str,
+        buyer: String,
+        signature: String,
+    ) -> Result<Option<Model>, DbErr> {
+        let Some(listing) = Entity::find()
+            .filter(Column::TradeState.eq(trade_state))
+            .filter(Column::Status.eq(ListingStatus::Listed.as_str()))
+            .one(conn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut am: ActiveModel = listing.into();
+        am.status = Set(ListingStatus::Sold.as_str().to_owned());
+        am.buyer = Set(Some(buyer));
+        am.sale_signature = Set(Some(signature));
+        am.updated_at = Set(Utc::now().naive_utc());
+
+        am.update(conn).await.map(Some)
+    }
+
+    async fn settle(
+        conn: &impl ConnectionTrait,
+        filter: impl IntoCondition,
+        from: ListingStatus,
+        to: ListingStatus,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(to.as_str()))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(filter)
+            .filter(Column::Status.eq(from.as_str()))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use holaplex_hub_nfts_solana_entity::merkle_trees::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    Condition, QueryOrder, Set,
+};
+
+/// Number of leaves appended to each merkle tree compressed mints are made
+/// into, used to predict the asset id of a mint before its leaf nonce can be
+/// read from the landed transaction. Trees created to reserve capacity for a
+/// collection are pinned to it.
+pub struct MerkleTree;
+
+impl MerkleTree {
+    /// Known leaf count of the tree at `address`, if it is tracked
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn leaf_count(
+        conn: &impl ConnectionTrait,
+        address: &str,
+    ) -> Result<Option<i64>, DbErr> {
+        Ok(Entity::find_by_id(address.to_owned())
+            .one(conn)
+            .await?
+            .map(|tree| tree.leaf_count))
+    }
+
+    /// Record that the tree at `address` holds a leaf at `index`, tracking
+    /// the tree if it is not tracked yet
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn observe_leaf(
+        conn: &impl ConnectionTrait,
+        address: String,
+        index: i64,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            address: Set(address.clone()),
+            leaf_count: Set(index + 1),
+            updated_at: Set(Utc::now().naive_utc()),
+            max_depth: Set(None),
+            max_buffer_size: Set(None),
+            canopy_depth: Set(None),
+            last_leaf_slot: Set(None),
+            collection_id: Set(None),
+            reserve_event_id: Set(None),
+            reserved_at: Set(None),
+        };
+
+        Entity::insert(am)
+            .on_conflict(OnConflict::column(Column::Address).do_nothing().to_owned())
+            .exec_without_returning(conn)
+            .await?;
+
+        Self::advance(conn, address, index).await
+    }
+
+    /// Raise the leaf count of the tree at `address` past `index`. Trees
+    /// which are not tracked are left alone.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn advance(
+        conn: &impl ConnectionTrait,
+        address: String,
+        index: i64,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::LeafCount, Expr::value(index + 1))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Address.eq(address))
+            .filter(Column::LeafCount.lte(index))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record that a leaf of the tree at `address` changed in `slot`.
+    /// Changelogs may be processed out of order, so the slot never moves
+    /// back.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn observe_slot(
+        conn: &impl ConnectionTrait,
+        address: String,
+        slot: i64,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::LastLeafSlot, Expr::value(slot))
+            .filter(Column::Address.eq(address))
+            .filter(
+                Condition::any()
+                    .add(Column::LastLeafSlot.is_null())
+                    .add(Column::LastLeafSlot.lt(slot)),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record the dimensions of the tree at `address` as read from its
+    /// account
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn set_dimensions(
+        conn: &impl ConnectionTrait,
+        address: String,
+        max_depth: i32,
+        max_buffer_size: i32,
+        canopy_depth: i32,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::MaxDepth, Expr::value(max_depth))
+            .col_expr(Column::MaxBufferSize, Expr::value(max_buffer_size))
+            .col_expr(Column::CanopyDepth, Expr::value(canopy_depth))
+            .filter(Column::Address.eq(address))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Every tracked tree, leaving out reserved trees not created yet
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_all(conn: &impl ConnectionTrait) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::ReserveEventId.is_null())
+                    .add(Column::ReservedAt.is_not_null()),
+            )
+            .all(conn)
+            .await
+    }
+
+    /// Record a tree requested to reserve capacity for a collection,
+    /// replacing the tree assembled by an earlier attempt at the same event
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be written
+    pub async fn request_reservation(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+        mut am: ActiveModel,
+    ) -> Result<(), DbErr> {
+        Entity::delete_many()
+            .filter(Column::ReserveEventId.eq(event_id))
+            .filter(Column::ReservedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        am.reserve_event_id = Set(Some(event_id));
+        am.reserved_at = Set(None);
+
+        Entity::insert(am).exec_without_returning(conn).await?;
+
+        Ok(())
+    }
+
+    /// Mark the tree requested by `event_id` as created on-chain
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm_reservation(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::update_many()
+            .col_expr(Column::ReservedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::ReserveEventId.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Entity::find()
+            .filter(Column::ReserveEventId.eq(event_id))
+            .one(conn)
+            .await
+    }
+
+    /// The trees created for a collection, oldest first
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_reserved(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::ReservedAt.is_not_null())
+            .order_by_asc(Column::ReservedAt)
+            .all(conn)
+            .await
+    }
+
+    /// Leaves that can still be appended to `tree`, or none if its depth is
+    /// not known yet
+    #[must_use]
+    pub fn remaining(tree: &Model) -> Option<i64> {
+        let capacity = 1_i64.checked_shl(tree.max_depth?.try_into().ok()?)?;
+
+        Some(capacity.saturating_sub(tree.leaf_count).max(0))
+    }
+}
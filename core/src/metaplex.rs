@@ -0,0 +1,259 @@
+//! Conversions from the creators and royalties carried by hub requests into
+//! the Token Metadata and Bubblegum types, checked against the rules both
+//! programs enforce on chain
+
+use std::collections::HashSet;
+
+use hub_core::thiserror;
+use mpl_bubblegum::state::metaplex_adapter::Creator as BubblegumCreator;
+use mpl_token_metadata::state::{Creator, MAX_CREATOR_LIMIT};
+use solana_program::pubkey::Pubkey;
+
+use crate::proto::Creator as ProtoCreator;
+
+/// Royalties are expressed in basis points of the sale price
+pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MetaplexError {
+    #[error("Invalid creator address {0:?}")]
+    InvalidCreatorAddress(String),
+    #[error("Creator {address} has a share of {share}, which exceeds 100")]
+    InvalidCreatorShare { address: String, share: u32 },
+    #[error("{0} creators were given but at most {max} are allowed", max = MAX_CREATOR_LIMIT)]
+    TooManyCreators(usize),
+    #[error("Creator {0} is listed more than once")]
+    DuplicateCreator(String),
+    #[error("Creator shares add up to {0} instead of 100")]
+    InvalidShareTotal(u32),
+    #[error("Seller fee of {0} basis points exceeds {max}", max = MAX_BASIS_POINTS)]
+    InvalidBasisPoints(u32),
+}
+
+fn address_and_share(
+    ProtoCreator { address, share, .. }: &ProtoCreator,
+) -> Result<(Pubkey, u8), MetaplexError> {
+    let pubkey = address
+        .parse()
+        .map_err(|_| MetaplexError::InvalidCreatorAddress(address.clone()))?;
+    let share = u8::try_from(*share)
+        .ok()
+        .filter(|s| *s <= 100)
+        .ok_or_else(|| MetaplexError::InvalidCreatorShare {
+            address: address.clone(),
+            share: *share,
+        })?;
+
+    Ok((pubkey, share))
+}
+
+impl TryFrom<ProtoCreator> for Creator {
+    type Error = MetaplexError;
+
+    fn try_from(creator: ProtoCreator) -> Result<Self, Self::Error> {
+        let (address, share) = address_and_share(&creator)?;
+
+        Ok(Self {
+            address,
+            verified: creator.verified,
+            share,
+        })
+    }
+}
+
+impl TryFrom<ProtoCreator> for BubblegumCreator {
+    type Error = MetaplexError;
+
+    fn try_from(creator: ProtoCreator) -> Result<Self, Self::Error> {
+        let (address, share) = address_and_share(&creator)?;
+
+        Ok(Self {
+            address,
+            verified: creator.verified,
+            share,
+        })
+    }
+}
+
+/// Convert the creators of a request, checking that there are at most
+/// [`MAX_CREATOR_LIMIT`] distinct creators whose shares add up to 100. An
+/// empty list is accepted and leaves the asset without creators.
+///
+/// # Errors
+/// This function fails if any creator is invalid or the list as a whole
+/// would be rejected by the metadata program
+pub fn creators<T>(creators: Vec<ProtoCreator>) -> Result<Vec<T>, MetaplexError>
+where
+    T: TryFrom<ProtoCreator, Error = MetaplexError>,
+{
+    if creators.len() > MAX_CREATOR_LIMIT {
+        return Err(MetaplexError::TooManyCreators(creators.len()));
+    }
+
+    let mut seen = HashSet::with_capacity(creators.len());
+    let mut total = 0;
+
+    for creator in &creators {
+        let (address, share) = address_and_share(creator)?;
+
+        if !seen.insert(address) {
+            return Err(MetaplexError::DuplicateCreator(creator.address.clone()));
+        }
+
+        total += u32::from(share);
+    }
+
+    if !creators.is_empty() && total != 100 {
+        return Err(MetaplexError::InvalidShareTotal(total));
+    }
+
+    creators.into_iter().map(T::try_from).collect()
+}
+
+/// Convert a seller fee, checking that it does not exceed 100%
+///
+/// # Errors
+/// This function fails if `bps` is greater than [`MAX_BASIS_POINTS`]
+pub fn basis_points(bps: u32) -> Result<u16, MetaplexError> {
+    if bps > MAX_BASIS_POINTS {
+        return Err(MetaplexError::InvalidBasisPoints(bps));
+    }
+
+    u16::try_from(bps).map_err(|_| MetaplexError::InvalidBasisPoints(bps))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn creator(address: Pubkey, share: u32) -> ProtoCreator {
+        ProtoCreator {
+            address: address.to_string(),
+            verified: false,
+            share,
+        }
+    }
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    /// Between one and five distinct creators whose shares add up to 100
+    fn valid_creators() -> impl Strategy<Value = Vec<ProtoCreator>> {
+        prop::collection::hash_set(pubkey(), 1..=MAX_CREATOR_LIMIT)
+            .prop_flat_map(|addresses| {
+                let n = addresses.len();
+
+                (Just(addresses), prop::collection::vec(0..=100_u32, n - 1))
+            })
+            .prop_map(|(addresses, mut cuts)| {
+                cuts.push(0);
+                cuts.push(100);
+                cuts.sort_unstable();
+
+                addresses
+                    .into_iter()
+                    .zip(cuts.windows(2).map(|w| w[1] - w[0]))
+                    .map(|(address, share)| creator(address, share))
+                    .collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn valid_creators_round_trip(creators in valid_creators()) {
+            let converted = super::creators::<Creator>(creators.clone()).unwrap();
+
+            prop_assert_eq!(converted.len(), creators.len());
+
+            for (proto, creator) in creators.iter().zip(&converted) {
+                prop_assert_eq!(creator.address.to_string(), proto.address.clone());
+                prop_assert_eq!(u32::from(creator.share), proto.share);
+                prop_assert_eq!(creator.verified, proto.verified);
+            }
+
+            let bubblegum = super::creators::<BubblegumCreator>(creators).unwrap();
+
+            for (a, b) in converted.iter().zip(&bubblegum) {
+                prop_assert_eq!(a.address, b.address);
+                prop_assert_eq!(a.share, b.share);
+            }
+        }
+
+        #[test]
+        fn share_total_must_be_100(creators in valid_creators(), extra in 1..=100_u32) {
+            let mut creators = creators;
+            let last = creators.last_mut().unwrap();
+            prop_assume!(last.share + extra <= 100);
+            last.share += extra;
+
+            prop_assert_eq!(
+                super::creators::<Creator>(creators),
+                Err(MetaplexError::InvalidShareTotal(100 + extra))
+            );
+        }
+
+        #[test]
+        fn too_many_creators_rejected(
+            addresses in prop::collection::vec(pubkey(), MAX_CREATOR_LIMIT + 1..20),
+        ) {
+            let n = addresses.len();
+            let creators = addresses.into_iter().map(|a| creator(a, 0)).collect();
+
+            prop_assert_eq!(
+                super::creators::<Creator>(creators),
+                Err(MetaplexError::TooManyCreators(n))
+            );
+        }
+
+        #[test]
+        fn oversized_shares_rejected(address in pubkey(), share in 101_u32..) {
+            prop_assert_eq!(
+                Creator::try_from(creator(address, share)),
+                Err(MetaplexError::InvalidCreatorShare {
+                    address: address.to_string(),
+                    share,
+                })
+            );
+        }
+
+        #[test]
+        fn invalid_addresses_rejected(address in "[^1-9A-HJ-NP-Za-km-z]{1,44}") {
+            prop_assert_eq!(
+                BubblegumCreator::try_from(ProtoCreator {
+                    address: address.clone(),
+                    verified: true,
+                    share: 100,
+                }),
+                Err(MetaplexError::InvalidCreatorAddress(address))
+            );
+        }
+
+        #[test]
+        fn basis_points_in_range(bps in 0..=MAX_BASIS_POINTS) {
+            prop_assert_eq!(basis_points(bps), Ok(u16::try_from(bps).unwrap()));
+        }
+
+        #[test]
+        fn basis_points_out_of_range(bps in MAX_BASIS_POINTS + 1..) {
+            prop_assert_eq!(basis_points(bps), Err(MetaplexError::InvalidBasisPoints(bps)));
+        }
+    }
+
+    #[test]
+    fn duplicate_creators_rejected() {
+        let address = Pubkey::new_unique();
+
+        assert_eq!(
+            creators::<Creator>(vec![creator(address, 50), creator(address, 50)]),
+            Err(MetaplexError::DuplicateCreator(address.to_string()))
+        );
+    }
+
+    #[test]
+    fn no_creators_accepted() {
+        assert_eq!(creators::<Creator>(vec![]), Ok(vec![]));
+    }
+}
@@ -0,0 +1,86 @@
+use holaplex_hub_nfts_solana_entity::mint_allowlists::{ActiveModel, Column, Entity};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, PaginatorTrait, Set};
+
+pub struct MintAllowlist;
+
+impl MintAllowlist {
+    /// Allow `addresses` to receive mints of a collection. Addresses already
+    /// on the list are left as they are.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be written
+    pub async fn add(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        addresses: impl IntoIterator<Item = String>,
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+        let ams: Vec<_> = addresses
+            .into_iter()
+            .map(|address| ActiveModel {
+                collection_id: Set(collection_id),
+                address: Set(address),
+                created_at: Set(now),
+            })
+            .collect();
+
+        if ams.is_empty() {
+            return Ok(());
+        }
+
+        Entity::insert_many(ams)
+            .on_conflict(
+                OnConflict::columns([Column::CollectionId, Column::Address])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove `addresses` from the allowlist of a collection
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be deleted
+    pub async fn remove(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        addresses: Vec<String>,
+    ) -> Result<(), DbErr> {
+        Entity::delete_many()
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::Address.is_in(addresses))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether `address` may receive mints of a collection. Collections
+    /// without any allowlisted address are open to every recipient.
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn is_allowed(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        address: &str,
+    ) -> Result<bool, DbErr> {
+        let listed = Entity::find()
+            .filter(Column::CollectionId.eq(collection_id))
+            .count(conn)
+            .await?;
+
+        if listed == 0 {
+            return Ok(true);
+        }
+
+        Ok(Entity::find_by_id((collection_id, address.to_owned()))
+            .one(conn)
+            .await?
+            .is_some())
+    }
+}
@@ -0,0 +1,42 @@
+use holaplex_hub_nfts_solana_entity::mint_payments::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+/// Payments to a project's revenue wallet assembled into mint transactions,
+/// keyed by the id of the mint they pay for
+pub struct MintPayment;
+
+impl MintPayment {
+    /// Record the payment assembled into a mint, replacing the payment of an
+    /// earlier attempt at the same mint
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(conn: &impl ConnectionTrait, model: Model) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            created_at: Set(Utc::now().naive_utc()),
+            ..model.into()
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::Payer,
+                        Column::RevenueWallet,
+                        Column::Amount,
+                        Column::SplMint,
+                        Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).one(conn).await
+    }
+}
@@ -0,0 +1,185 @@
+use holaplex_hub_nfts_solana_entity::{compression_leafs, mint_transfer_histories};
+use hub_core::chrono::{NaiveDateTime, Utc};
+use sea_orm::{prelude::*, Set};
+
+use crate::{Claim, Collection, CollectionMint, CompressionLeaf, WalletMintCount};
+
+/// A transfer of a tracked mint seen in a landed transaction
+#[derive(Debug, Clone)]
+pub struct ObservedTransfer {
+    pub signature: String,
+    pub slot: i64,
+    /// Unix timestamp of the block, or 0 if it is not known
+    pub block_time: i64,
+    pub recipient: String,
+}
+
+/// The ownership change an observed transfer made, with the state it
+/// replaced
+#[derive(Debug, Clone)]
+pub struct AppliedTransfer {
+    /// Id of the recorded transfer
+    pub id: Uuid,
+    pub mint_id: Uuid,
+    pub collection_id: Uuid,
+    pub mint_address: String,
+    pub sender: String,
+    pub previous_token_account: Option<String>,
+    /// Whether the transfer was the primary sale of the mint
+    pub primary_sale: bool,
+}
+
+/// Ownership changes of tracked mints observed on chain. The indexer and
+/// webhooks may observe the same transfer, so the mint is locked for the
+/// rest of the surrounding transaction and a transfer already recorded is
+/// not applied again.
+pub struct MintTransfer;
+
+impl MintTransfer {
+    /// Move the uncompressed mint `mint_id` to the recipient of `transfer`,
+    /// who now holds it in `token_account`. Returns `None` if the mint is
+    /// not tracked or the transfer was already applied.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be read or written
+    pub async fn apply_to_mint(
+        conn: &impl ConnectionTrait,
+        mint_id: Uuid,
+        token_account: String,
+        transfer: &ObservedTransfer,
+    ) -> Result<Option<AppliedTransfer>, DbErr> {
+        let Some(mint) = CollectionMint::find_for_update(conn, mint_id).await? else {
+            return Ok(None);
+        };
+
+        let Some(id) = record(conn, mint.id, &mint.owner, transfer).await? else {
+            return Ok(None);
+        };
+
+        CollectionMint::update_owner_and_ata(
+            conn,
+            &mint,
+            transfer.recipient.clone(),
+            token_account,
+        )
+        .await?;
+
+        let primary_sale = settle(conn, mint.collection_id, mint.id, &mint.owner, transfer).await?;
+
+        Ok(Some(AppliedTransfer {
+            id,
+            mint_id: mint.id,
+            collection_id: mint.collection_id,
+            mint_address: mint.mint,
+            sender: mint.owner,
+            previous_token_account: Some(mint.associated_token_account),
+            primary_sale,
+        }))
+    }
+
+    /// Move the compressed mint `leaf_id` to the recipient of `transfer`.
+    /// Returns `None` if the leaf is not tracked or the transfer was already
+    /// applied.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be read or written
+    pub async fn apply_to_leaf(
+        conn: &impl ConnectionTrait,
+        leaf_id: Uuid,
+        transfer: &ObservedTransfer,
+    ) -> Result<Option<AppliedTransfer>, DbErr> {
+        let Some(leaf) = CompressionLeaf::find_for_update(conn, leaf_id).await? else {
+            return Ok(None);
+        };
+
+        let Some(id) = record(conn, leaf.id, &leaf.leaf_owner, transfer).await? else {
+            return Ok(None);
+        };
+
+        let mint_id = leaf.id;
+        let collection_id = leaf.collection_id;
+        let mint_address = leaf.asset_id.clone().unwrap_or_default();
+        let sender = leaf.leaf_owner.clone();
+
+        let mut leaf: compression_leafs::ActiveModel = leaf.into();
+        leaf.leaf_owner = Set(transfer.recipient.clone());
+
+        CompressionLeaf::update(conn, leaf).await?;
+
+        let primary_sale = settle(conn, collection_id, mint_id, &sender, transfer).await?;
+
+        Ok(Some(AppliedTransfer {
+            id,
+            mint_id,
+            collection_id,
+            mint_address,
+            sender,
+            previous_token_account: None,
+            primary_sale,
+        }))
+    }
+}
+
+/// Record `transfer` of `mint_id` from `sender` at the time of its block,
+/// returning the id of the record unless the transfer was recorded already
+async fn record(
+    conn: &impl ConnectionTrait,
+    mint_id: Uuid,
+    sender: &str,
+    transfer: &ObservedTransfer,
+) -> Result<Option<Uuid>, DbErr> {
+    let id = Uuid::new_v4();
+    let created_at = Some(transfer.block_time)
+        .filter(|time| *time > 0)
+        .and_then(|time| NaiveDateTime::from_timestamp_opt(time, 0))
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    let recorded = CollectionMint::record_transfer(conn, mint_transfer_histories::Model {
+        id,
+        mint_id,
+        sender: sender.to_owned(),
+        recipient: transfer.recipient.clone(),
+        signature: transfer.signature.clone(),
+        slot: transfer.slot,
+        created_at,
+    })
+    .await?;
+
+    Ok(recorded.then_some(id))
+}
+
+/// Update the wallet counts and claims affected by a transfer of `mint_id`
+/// from `sender`, returning whether it was the primary sale of the mint
+async fn settle(
+    conn: &impl ConnectionTrait,
+    collection_id: Uuid,
+    mint_id: Uuid,
+    sender: &str,
+    transfer: &ObservedTransfer,
+) -> Result<bool, DbErr> {
+    WalletMintCount::transfer(conn, collection_id, sender, transfer.recipient.clone()).await?;
+    Claim::redeem_transferred(conn, mint_id, &transfer.recipient).await?;
+
+    primary_sale(conn, collection_id, mint_id, sender, &transfer.recipient).await
+}
+
+/// Record a transfer of `mint_id` from `seller` to `buyer` as its primary
+/// sale if it is the first to leave the wallet holding its collection,
+/// returning whether it was
+async fn primary_sale(
+    conn: &impl ConnectionTrait,
+    collection_id: Uuid,
+    mint_id: Uuid,
+    seller: &str,
+    buyer: &str,
+) -> Result<bool, DbErr> {
+    let Some(collection) = Collection::find_by_id(conn, collection_id).await? else {
+        return Ok(false);
+    };
+
+    if collection.owner != seller || seller == buyer {
+        return Ok(false);
+    }
+
+    CollectionMint::mark_primary_sale(conn, mint_id).await
+}
@@ -0,0 +1,64 @@
+use holaplex_hub_nfts_solana_entity::{collection_mints, compression_leafs};
+use sea_orm::prelude::*;
+
+use crate::{CollectionMint, CompressionLeaf};
+
+/// A minted asset, stored as a collection mint if it is a regular token or
+/// as a compression leaf if it is a compressed NFT. Both are keyed by the
+/// same mint id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mint {
+    Uncompressed(collection_mints::Model),
+    Compressed(compression_leafs::Model),
+}
+
+impl Mint {
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Self>, DbErr> {
+        if let Some(leaf) = CompressionLeaf::find_by_id(conn, id).await? {
+            return Ok(Some(Self::Compressed(leaf)));
+        }
+
+        Ok(CollectionMint::find_by_id(conn, id)
+            .await?
+            .map(Self::Uncompressed))
+    }
+
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::Uncompressed(mint) => mint.id,
+            Self::Compressed(leaf) => leaf.id,
+        }
+    }
+
+    #[must_use]
+    pub fn collection_id(&self) -> Uuid {
+        match self {
+            Self::Uncompressed(mint) => mint.collection_id,
+            Self::Compressed(leaf) => leaf.collection_id,
+        }
+    }
+
+    #[must_use]
+    pub fn owner(&self) -> &str {
+        match self {
+            Self::Uncompressed(mint) => &mint.owner,
+            Self::Compressed(leaf) => &leaf.leaf_owner,
+        }
+    }
+
+    /// The mint address, or the asset id of a compressed NFT. `None` if the
+    /// asset id of a compressed NFT has not been indexed yet.
+    #[must_use]
+    pub fn address(&self) -> Option<&str> {
+        match self {
+            Self::Uncompressed(mint) => Some(&mint.mint),
+            Self::Compressed(leaf) => leaf.asset_id.as_deref(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        matches!(self, Self::Compressed(_))
+    }
+}
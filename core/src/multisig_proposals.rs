@@ -0,0 +1,89 @@
+use holaplex_hub_nfts_solana_entity::multisig_proposals::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::Expr, QueryOrder};
+
+/// Progress of a proposal made to the Squads multisig of a project treasury
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    /// Assembled and sent to the treasury for its member's signature
+    Requested,
+    /// Created on-chain and approved by the treasury's member, waiting on
+    /// the other members
+    Created,
+    /// Never created, so its transaction index may be proposed again
+    Failed,
+}
+
+impl ProposalStatus {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Requested => "requested",
+            Self::Created => "created",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Proposals assembled for projects whose treasury is a Squads multisig
+pub struct MultisigProposal;
+
+impl MultisigProposal {
+    pub async fn create(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<Model, DbErr> {
+        am.insert(conn).await
+    }
+
+    /// Highest transaction index of the proposals to `multisig` which have
+    /// been requested but not created yet. Those indices are taken even
+    /// though the multisig account does not count them yet.
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn highest_requested_index(
+        conn: &impl ConnectionTrait,
+        multisig: &str,
+    ) -> Result<Option<i64>, DbErr> {
+        Ok(Entity::find()
+            .filter(Column::Multisig.eq(multisig))
+            .filter(Column::Status.eq(ProposalStatus::Requested.as_str()))
+            .order_by_desc(Column::TransactionIndex)
+            .one(conn)
+            .await?
+            .map(|proposal| proposal.transaction_index))
+    }
+
+    /// Every proposal made for an event, oldest first
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::EventId.eq(event_id))
+            .order_by_asc(Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// Move the requested proposals of an event to `status`, recording the
+    /// signature of the transaction which created them
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn settle(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+        status: ProposalStatus,
+        signature: Option<String>,
+    ) -> Result<u64, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status.as_str()))
+            .col_expr(Column::Signature, Expr::value(signature))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::EventId.eq(event_id))
+            .filter(Column::Status.eq(ProposalStatus::Requested.as_str()))
+            .exec(conn)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
+}
@@ -0,0 +1,170 @@
+use holaplex_hub_nfts_solana_entity::{
+    nonce_accounts::{ActiveModel, Column, Entity, Model},
+    sea_orm_active_enums::SolanaCluster,
+};
+use hub_core::chrono::{Duration, Utc};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, LockBehavior, LockType, OnConflict},
+    Condition, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+
+/// Durable nonce accounts of treasuries, each leased to the event whose
+/// message consumes its nonce. An account released while a message using
+/// it may still land keeps that nonce as stale, and is not leased again
+/// until the nonce has been advanced.
+pub struct NonceAccount;
+
+impl NonceAccount {
+    /// Add the nonce account at `address` to the pool of `authority`,
+    /// keeping its lease if it is already pooled
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn register(
+        conn: &impl ConnectionTrait,
+        address: String,
+        cluster: SolanaCluster,
+        authority: String,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            address: Set(address),
+            cluster: Set(cluster),
+            authority: Set(authority),
+            event_id: Set(None),
+            leased_at: Set(None),
+            stale_nonce: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Address)
+                    .update_columns([Column::Cluster, Column::Authority])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lease a nonce account of `authority` to `event_id`. Accounts whose
+    /// lease is older than `lease` are taken back, since their event never
+    /// settled. Rows locked by a concurrent lease are skipped, and the lease
+    /// is committed before returning.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be read or updated
+    pub async fn lease(
+        conn: &(impl ConnectionTrait + TransactionTrait),
+        cluster: SolanaCluster,
+        authority: String,
+        event_id: Uuid,
+        lease: Duration,
+    ) -> Result<Option<Model>, DbErr> {
+        let now = Utc::now().naive_utc();
+        let txn = conn.begin().await?;
+
+        let mut select = Entity::find()
+            .filter(Column::Cluster.eq(cluster))
+            .filter(Column::Authority.eq(authority))
+            .filter(Column::StaleNonce.is_null())
+            .filter(
+                Condition::any()
+                    .add(Column::LeasedAt.is_null())
+                    .add(Column::LeasedAt.lt(now - lease)),
+            )
+            .order_by_asc(Column::LeasedAt)
+            .limit(1);
+        QueryTrait::query(&mut select)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked);
+
+        let Some(account) = select.one(&txn).await? else {
+            return Ok(None);
+        };
+
+        let mut am: ActiveModel = account.into();
+        am.event_id = Set(Some(event_id));
+        am.leased_at = Set(Some(now));
+        let account = am.update(&txn).await?;
+
+        txn.commit().await?;
+
+        Ok(Some(account))
+    }
+
+    /// Return the nonce accounts leased to `event_ids` to their pools. Their
+    /// nonces must have been advanced, or never signed for.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn release(
+        conn: &impl ConnectionTrait,
+        event_ids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::EventId, Expr::value(Option::<Uuid>::None))
+            .col_expr(Column::LeasedAt, Expr::value(Option::<DateTime>::None))
+            .filter(Column::EventId.is_in(event_ids))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Return the nonce accounts leased to `event_id` to their pools while a
+    /// message signed with `nonce` may still land, holding them back until
+    /// their nonces are advanced
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be updated
+    pub async fn release_stale(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+        nonce: String,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::EventId, Expr::value(Option::<Uuid>::None))
+            .col_expr(Column::LeasedAt, Expr::value(Option::<DateTime>::None))
+            .col_expr(Column::StaleNonce, Expr::value(nonce))
+            .filter(Column::EventId.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Nonce accounts of `authority` held back until their nonces are
+    /// advanced
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_stale(
+        conn: &impl ConnectionTrait,
+        cluster: SolanaCluster,
+        authority: String,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Cluster.eq(cluster))
+            .filter(Column::Authority.eq(authority))
+            .filter(Column::StaleNonce.is_not_null())
+            .all(conn)
+            .await
+    }
+
+    /// Return the nonce account at `address` to its pool once its nonce no
+    /// longer matches the stale one
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn advanced(conn: &impl ConnectionTrait, address: String) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::StaleNonce, Expr::value(Option::<String>::None))
+            .filter(Column::Address.eq(address))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use holaplex_hub_nfts_solana_entity::outbox::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::{Duration, Utc};
+use prost::{DecodeError, Message};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, LockBehavior, LockType},
+    Condition, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+
+use crate::proto::{SolanaNftEventKey, SolanaNftEvents};
+
+pub struct Outbox;
+
+impl Outbox {
+    /// Queue an event to be published by the outbox relay. Callers should
+    /// pass the same transaction used to write the models the event refers
+    /// to so that either both or neither are persisted.
+    pub async fn enqueue(
+        conn: &impl ConnectionTrait,
+        key: &SolanaNftEventKey,
+        event: &SolanaNftEvents,
+    ) -> Result<Model, DbErr> {
+        let am = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            key: Set(key.encode_to_vec()),
+            payload: Set(event.encode_to_vec()),
+            created_at: Set(Utc::now().naive_utc()),
+            sent_at: Set(None),
+            claimed_at: Set(None),
+            failed_at: Set(None),
+            error: Set(None),
+        };
+
+        am.insert(conn).await
+    }
+
+    /// Oldest events which are neither sent nor failed
+    pub async fn find_pending(
+        conn: &impl ConnectionTrait,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::pending()
+            .order_by_asc(Column::CreatedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+    }
+
+    /// Claim the oldest pending events which are not claimed by another
+    /// relay, or whose claim is older than `lease`. Rows locked by a
+    /// concurrent claim are skipped rather than waited on, and the claim is
+    /// committed before returning so no transaction stays open while the
+    /// events are produced.
+    ///
+    /// Skipping claimed rows lets concurrent relays produce events of the
+    /// same key out of order, so events are only produced in the order they
+    /// were queued while a single relay runs.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be read or updated
+    pub async fn claim_pending(
+        conn: &(impl ConnectionTrait + TransactionTrait),
+        limit: u64,
+        lease: Duration,
+    ) -> Result<Vec<Model>, DbErr> {
+        let now = Utc::now().naive_utc();
+        let txn = conn.begin().await?;
+
+        let mut select = Self::pending()
+            .filter(
+                Condition::any()
+                    .add(Column::ClaimedAt.is_null())
+                    .add(Column::ClaimedAt.lt(now - lease)),
+            )
+            .order_by_asc(Column::CreatedAt)
+            .limit(limit);
+        QueryTrait::query(&mut select)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked);
+        let pending = select.all(&txn).await?;
+
+        if !pending.is_empty() {
+            Entity::update_many()
+                .col_expr(Column::ClaimedAt, Expr::value(now))
+                .filter(Column::Id.is_in(pending.iter().map(|model| model.id)))
+                .exec(&txn)
+                .await?;
+        }
+
+        txn.commit().await?;
+
+        Ok(pending)
+    }
+
+    /// Release the claim on events which were not produced so the next
+    /// relay picks them up without waiting for the lease to expire
+    pub async fn release(
+        conn: &impl ConnectionTrait,
+        ids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::ClaimedAt, Expr::value(Option::<DateTime>::None))
+            .filter(Column::Id.is_in(ids))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Creation time of the oldest unsent event, if any
+    pub async fn oldest_pending(conn: &impl ConnectionTrait) -> Result<Option<DateTime>, DbErr> {
+        Ok(Self::pending()
+            .order_by_asc(Column::CreatedAt)
+            .one(conn)
+            .await?
+            .map(|model| model.created_at))
+    }
+
+    pub async fn mark_sent(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.sent_at = Set(Some(Utc::now().naive_utc()));
+
+        am.update(conn).await
+    }
+
+    /// Delete the events sent before `cutoff`, returning how many were
+    /// deleted. Failed events are kept for inspection.
+    pub async fn delete_sent(conn: &impl ConnectionTrait, cutoff: DateTime) -> Result<u64, DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::SentAt.lt(cutoff))
+            .exec(conn)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
+
+    /// Set aside an event which can never be produced, such as one whose
+    /// stored bytes fail to decode, so it no longer blocks the relay
+    pub async fn mark_failed(
+        conn: &impl ConnectionTrait,
+        model: Model,
+        error: String,
+    ) -> Result<Model, DbErr> {
+        let mut am: ActiveModel = model.into();
+        am.failed_at = Set(Some(Utc::now().naive_utc()));
+        am.error = Set(Some(error));
+
+        am.update(conn).await
+    }
+
+    /// Decode the key and event stored in an outbox row
+    ///
+    /// # Errors
+    /// This function fails if the stored bytes are not a valid key or event
+    pub fn decode(model: &Model) -> Result<(SolanaNftEventKey, SolanaNftEvents), DecodeError> {
+        let key = SolanaNftEventKey::decode(model.key.as_slice())?;
+        let event = SolanaNftEvents::decode(model.payload.as_slice())?;
+
+        Ok((key, event))
+    }
+
+    fn pending() -> Select<Entity> {
+        Entity::find()
+            .filter(Column::SentAt.is_null())
+            .filter(Column::FailedAt.is_null())
+    }
+}
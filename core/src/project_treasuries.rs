@@ -0,0 +1,64 @@
+use holaplex_hub_nfts_solana_entity::{
+    project_treasuries::{ActiveModel, Column, Entity, Model},
+    sea_orm_active_enums::FeePayerMode,
+};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+pub struct ProjectTreasury;
+
+impl ProjectTreasury {
+    /// Record the treasury wallet paying for a project's transactions, the
+    /// wallet receiving its mint payments, the Squads multisig the treasury
+    /// wallet proposes transactions to and the auction house its mints are
+    /// listed on, replacing any previously assigned wallets.
+    /// `fee_payer_mode` decides whether the treasury wallet or the service's
+    /// own treasury pays for the project's transactions.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+        wallet_address: String,
+        revenue_wallet_address: Option<String>,
+        multisig_address: Option<String>,
+        fee_payer_mode: FeePayerMode,
+        auction_house_address: Option<String>,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            project_id: Set(project_id),
+            wallet_address: Set(wallet_address),
+            revenue_wallet_address: Set(revenue_wallet_address),
+            multisig_address: Set(multisig_address),
+            fee_payer_mode: Set(fee_payer_mode),
+            auction_house_address: Set(auction_house_address),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::ProjectId)
+                    .update_columns([
+                        Column::WalletAddress,
+                        Column::RevenueWalletAddress,
+                        Column::MultisigAddress,
+                        Column::FeePayerMode,
+                        Column::AuctionHouseAddress,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_project_id(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(project_id).one(conn).await
+    }
+}
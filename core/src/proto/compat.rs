@@ -0,0 +1,150 @@
+//! Compatibility between schema versions of the events this service
+//! consumes and produces, so hub services can be deployed one at a time.
+//!
+//! Renamed events keep their legacy variants in the schema. Incoming events
+//! are upgraded to the current names before they are processed, and
+//! produced events can be downgraded to the names understood by consumers
+//! that have not been deployed yet.
+
+use super::{
+    nft_events::Event as NftEvent, solana_nft_events::Event as SolanaNftEvent,
+    treasury_events::Event as TreasuryEvent, NftEvents, SolanaNftEvents, TreasuryEvents,
+};
+
+/// Version of the `solana_nfts` schema stamped on every produced event
+pub const SCHEMA_VERSION: u32 = 52;
+
+/// The `solana_nfts` schema version which renamed the `*Drop` events to
+/// `*EditionDrop`. Consumers on an older version only know the legacy names.
+pub const EDITION_DROP_VERSION: u32 = 28;
+
+impl SolanaNftEvents {
+    /// Wrap an event, stamped with the current [`SCHEMA_VERSION`]
+    #[must_use]
+    pub fn new(event: SolanaNftEvent) -> Self {
+        Self {
+            event: Some(event),
+            schema_version: SCHEMA_VERSION,
+        }
+    }
+}
+
+/// Rename legacy requests from producers which have not been upgraded yet
+#[must_use]
+pub fn upgrade_nft_events(NftEvents { event }: NftEvents) -> NftEvents {
+    let event = event.map(|event| match event {
+        NftEvent::SolanaCreateDrop(p) => NftEvent::SolanaCreateEditionDrop(p),
+        NftEvent::SolanaMintDrop(p) => NftEvent::SolanaMintEditionDrop(p),
+        NftEvent::SolanaUpdateDrop(p) => NftEvent::SolanaUpdateEditionDrop(p),
+        NftEvent::SolanaRetryDrop(p) => NftEvent::SolanaRetryEditionDrop(p),
+        NftEvent::SolanaRetryMintDrop(p) => NftEvent::SolanaRetryMintEditionDrop(p),
+        event => event,
+    });
+
+    NftEvents { event }
+}
+
+/// Rename legacy signed transactions from a treasury service which has not
+/// been upgraded yet
+#[must_use]
+pub fn upgrade_treasury_events(TreasuryEvents { event }: TreasuryEvents) -> TreasuryEvents {
+    let event = event.map(|event| match event {
+        TreasuryEvent::SolanaCreateDropSigned(r) => TreasuryEvent::SolanaCreateEditionDropSigned(r),
+        TreasuryEvent::SolanaMintDropSigned(r) => TreasuryEvent::SolanaMintEditionDropSigned(r),
+        TreasuryEvent::SolanaUpdateDropSigned(r) => TreasuryEvent::SolanaUpdateEditionDropSigned(r),
+        TreasuryEvent::SolanaRetryCreateDropSigned(r) => {
+            TreasuryEvent::SolanaRetryCreateEditionDropSigned(r)
+        },
+        TreasuryEvent::SolanaRetryMintDropSigned(r) => {
+            TreasuryEvent::SolanaRetryMintEditionDropSigned(r)
+        },
+        event => event,
+    });
+
+    TreasuryEvents { event }
+}
+
+/// Rewrite a produced event for consumers on schema version `target`,
+/// stamping it with the version it is encoded for. Targets at or above the
+/// current version leave the event unchanged.
+#[must_use]
+pub fn downgrade(
+    SolanaNftEvents {
+        event,
+        schema_version,
+    }: SolanaNftEvents,
+    target: u32,
+) -> SolanaNftEvents {
+    if target >= schema_version {
+        return SolanaNftEvents {
+            event,
+            schema_version,
+        };
+    }
+
+    let event = if target < EDITION_DROP_VERSION {
+        event.map(legacy_drop_names)
+    } else {
+        event
+    };
+
+    SolanaNftEvents {
+        event,
+        schema_version: target,
+    }
+}
+
+fn legacy_drop_names(event: SolanaNftEvent) -> SolanaNftEvent {
+    use SolanaNftEvent as E;
+
+    match event {
+        E::CreateEditionDropSigningRequested(t) => E::CreateDropSigningRequested(t),
+        E::CreateEditionDropSubmitted(t) => E::CreateDropSubmitted(t),
+        E::CreateEditionDropFailed(t) => E::CreateDropFailed(t),
+        E::MintEditionDropSigningRequested(t) => E::MintDropSigningRequested(t),
+        E::MintEditionDropSubmitted(t) => E::MintDropSubmitted(t),
+        E::MintEditionDropFailed(t) => E::MintDropFailed(t),
+        E::UpdateEditionDropSigningRequested(t) => E::UpdateDropSigningRequested(t),
+        E::UpdateEditionDropSubmitted(t) => E::UpdateDropSubmitted(t),
+        E::UpdateEditionDropFailed(t) => E::UpdateDropFailed(t),
+        E::RetryCreateEditionDropSigningRequested(t) => E::RetryCreateDropSigningRequested(t),
+        E::RetryCreateEditionDropSubmitted(t) => E::RetryCreateDropSubmitted(t),
+        E::RetryCreateEditionDropFailed(t) => E::RetryCreateDropFailed(t),
+        E::RetryMintEditionDropSigningRequested(t) => E::RetryMintDropSigningRequested(t),
+        E::RetryMintEditionDropSubmitted(t) => E::RetryMintDropSubmitted(t),
+        E::RetryMintEditionDropFailed(t) => E::RetryMintDropFailed(t),
+        event => event,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{MetaplexMasterEditionTransaction, SolanaFailedTransaction};
+
+    #[test]
+    fn legacy_requests_are_upgraded() {
+        let payload = MetaplexMasterEditionTransaction::default();
+        let upgraded = upgrade_nft_events(NftEvents {
+            event: Some(NftEvent::SolanaCreateDrop(payload.clone())),
+        });
+
+        assert_eq!(
+            upgraded.event,
+            Some(NftEvent::SolanaCreateEditionDrop(payload))
+        );
+    }
+
+    #[test]
+    fn downgrade_renames_for_old_consumers() {
+        let failure = SolanaFailedTransaction::default();
+        let event = SolanaNftEvents::new(SolanaNftEvent::CreateEditionDropFailed(failure.clone()));
+
+        let current = downgrade(event.clone(), SCHEMA_VERSION);
+        assert_eq!(current, event);
+
+        let legacy = downgrade(event, EDITION_DROP_VERSION - 1);
+        assert_eq!(legacy.schema_version, EDITION_DROP_VERSION - 1);
+        assert_eq!(legacy.event, Some(SolanaNftEvent::CreateDropFailed(failure)));
+    }
+}
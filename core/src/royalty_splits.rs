@@ -0,0 +1,75 @@
+use holaplex_hub_nfts_solana_entity::royalty_splits::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+};
+
+/// Hydra fanout wallets receiving the royalties of a collection
+pub struct RoyaltySplit;
+
+impl RoyaltySplit {
+    /// Record the split requested for a collection, replacing an earlier
+    /// request whose transaction never landed
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<(), DbErr> {
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::CollectionId)
+                    .update_columns([
+                        Column::EventId,
+                        Column::Name,
+                        Column::Authority,
+                        Column::Fanout,
+                        Column::HoldingAccount,
+                        Column::Members,
+                        Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_collection_id(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(collection_id).one(conn).await
+    }
+
+    /// The split of a collection, if its fanout has been created on-chain
+    pub async fn find_confirmed(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(collection_id)
+            .filter(Column::ConfirmedAt.is_not_null())
+            .one(conn)
+            .await
+    }
+
+    /// Mark the split requested by `event_id` as created on-chain
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn confirm(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::update_many()
+            .col_expr(Column::ConfirmedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::EventId.eq(event_id))
+            .exec(conn)
+            .await?;
+
+        Entity::find()
+            .filter(Column::EventId.eq(event_id))
+            .one(conn)
+            .await
+    }
+}
@@ -0,0 +1,59 @@
+use holaplex_hub_nfts_solana_entity::signer_policies::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+pub struct SignerPolicy;
+
+impl SignerPolicy {
+    /// Record the wallets allowed to sign transactions of a project,
+    /// replacing any previously assigned policy.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+        signer_addresses: &[String],
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            project_id: Set(project_id),
+            signer_addresses: Set(signer_addresses.into()),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::ProjectId)
+                    .update_columns([Column::SignerAddresses, Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_project_id(
+        conn: &impl ConnectionTrait,
+        project_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(project_id).one(conn).await
+    }
+
+    /// The wallets listed in a policy
+    ///
+    /// # Errors
+    /// This function fails if the stored addresses are not a list of strings
+    pub fn signer_addresses(model: &Model) -> Result<Vec<String>, DbErr> {
+        model
+            .signer_addresses
+            .as_array()
+            .and_then(|addresses| {
+                addresses
+                    .iter()
+                    .map(|a| a.as_str().map(ToOwned::to_owned))
+                    .collect()
+            })
+            .ok_or_else(|| DbErr::Json("signer addresses must be a list of strings".to_owned()))
+    }
+}
@@ -0,0 +1,47 @@
+use holaplex_hub_nfts_solana_entity::token_balances::{ActiveModel, Column, Entity, Model};
+use sea_orm::{prelude::*, sea_query::OnConflict};
+
+pub struct TokenBalance;
+
+impl TokenBalance {
+    /// Store the balance of a token account observed at `model.slot`,
+    /// replacing any earlier balance of the same account
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(conn: &impl ConnectionTrait, model: Model) -> Result<(), DbErr> {
+        let am: ActiveModel = model.into();
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::TokenAccount)
+                    .update_columns([
+                        Column::CollectionMintId,
+                        Column::Mint,
+                        Column::Owner,
+                        Column::Amount,
+                        Column::Slot,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Balances of every token account holding `collection_mint_id`
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_by_collection_mint(
+        conn: &impl ConnectionTrait,
+        collection_mint_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::CollectionMintId.eq(collection_mint_id))
+            .all(conn)
+            .await
+    }
+}
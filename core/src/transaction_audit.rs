@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use holaplex_hub_nfts_solana_entity::transaction_audit::{ActiveModel, Column, Entity, Model};
+use sea_orm::{prelude::*, QueryOrder};
+
+pub struct TransactionAudit;
+
+impl TransactionAudit {
+    pub async fn create(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<Model, DbErr> {
+        am.insert(conn).await
+    }
+
+    /// Every signing request and signed result of an event, oldest first
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::EventId.eq(event_id))
+            .order_by_asc(Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// The signed results carrying a transaction signature, oldest first
+    pub async fn find_by_signature(
+        conn: &impl ConnectionTrait,
+        signature: String,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Signature.eq(signature))
+            .order_by_asc(Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// The latest transaction signature recorded for each of `event_ids`.
+    /// Events without a signed result are left out.
+    pub async fn latest_signatures(
+        conn: &impl ConnectionTrait,
+        event_ids: Vec<Uuid>,
+    ) -> Result<HashMap<Uuid, String>, DbErr> {
+        if event_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let entries = Entity::find()
+            .filter(Column::EventId.is_in(event_ids))
+            .filter(Column::Signature.is_not_null())
+            .order_by_asc(Column::CreatedAt)
+            .all(conn)
+            .await?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| Some((entry.event_id, entry.signature?)))
+            .collect())
+    }
+}
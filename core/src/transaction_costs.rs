@@ -0,0 +1,22 @@
+use holaplex_hub_nfts_solana_entity::transaction_costs::{ActiveModel, Column, Entity, Model};
+use sea_orm::prelude::*;
+
+pub struct TransactionCost;
+
+impl TransactionCost {
+    pub async fn create(conn: &impl ConnectionTrait, model: Model) -> Result<Model, DbErr> {
+        let active_model: ActiveModel = model.into();
+
+        active_model.insert(conn).await
+    }
+
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::EventId.eq(event_id))
+            .all(conn)
+            .await
+    }
+}
@@ -0,0 +1,35 @@
+use holaplex_hub_nfts_solana_entity::transaction_failures::{ActiveModel, Column, Entity, Model};
+use sea_orm::{prelude::*, QueryOrder, QuerySelect};
+
+pub struct TransactionFailure;
+
+impl TransactionFailure {
+    pub async fn create(conn: &impl ConnectionTrait, am: ActiveModel) -> Result<Model, DbErr> {
+        am.insert(conn).await
+    }
+
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::EventId.eq(event_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// Most recent failures first
+    pub async fn list(
+        conn: &impl ConnectionTrait,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .order_by_desc(Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await
+    }
+}
@@ -0,0 +1,103 @@
+use holaplex_hub_nfts_solana_entity::transaction_revisions::{
+    ActiveModel, Column, Entity, Model,
+};
+use hub_core::chrono::Utc;
+use sea_orm::{prelude::*, sea_query::OnConflict, Set};
+
+pub struct TransactionRevision;
+
+impl TransactionRevision {
+    /// Store the latest message assembled for an event so a failed submit
+    /// can be retried with a fresh blockhash. Reassembling an event resets
+    /// its attempt count. `ephemeral_keypairs` holds the keypairs of
+    /// accounts created by the transaction itself, which sign it again when
+    /// it is resubmitted.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn record(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+        event_kind: &str,
+        serialized_message: Vec<u8>,
+        signers: &[String],
+        ephemeral_keypairs: Option<Vec<u8>>,
+    ) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+
+        let am = ActiveModel {
+            event_id: Set(event_id),
+            event_kind: Set(event_kind.to_owned()),
+            serialized_message: Set(serialized_message),
+            signers: Set(signers.into()),
+            attempts: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ephemeral_keypairs: Set(ephemeral_keypairs),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::EventId)
+                    .update_columns([
+                        Column::EventKind,
+                        Column::SerializedMessage,
+                        Column::Signers,
+                        Column::Attempts,
+                        Column::UpdatedAt,
+                        Column::EphemeralKeypairs,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_event_id(
+        conn: &impl ConnectionTrait,
+        event_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(event_id).one(conn).await
+    }
+
+    /// Replace the message of a revision with a re-blockhashed copy and its
+    /// signers, and count the attempt.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn resubmitted(
+        conn: &impl ConnectionTrait,
+        revision: Model,
+        serialized_message: Vec<u8>,
+        signers: &[String],
+    ) -> Result<Model, DbErr> {
+        let attempts = revision.attempts + 1;
+        let mut am: ActiveModel = revision.into();
+
+        am.serialized_message = Set(serialized_message);
+        am.signers = Set(signers.into());
+        am.attempts = Set(attempts);
+        am.updated_at = Set(Utc::now().naive_utc());
+
+        am.update(conn).await
+    }
+
+    /// The signers listed for a revision, in message order
+    ///
+    /// # Errors
+    /// This function fails if the stored signers are not a list of strings
+    pub fn signers(model: &Model) -> Result<Vec<String>, DbErr> {
+        model
+            .signers
+            .as_array()
+            .and_then(|signers| {
+                signers
+                    .iter()
+                    .map(|s| s.as_str().map(ToOwned::to_owned))
+                    .collect()
+            })
+            .ok_or_else(|| DbErr::Json("signers must be a list of strings".to_owned()))
+    }
+}
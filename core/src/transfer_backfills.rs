@@ -0,0 +1,131 @@
+use holaplex_hub_nfts_solana_entity::transfer_backfills::{ActiveModel, Column, Entity, Model};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    QueryOrder, QuerySelect, Set,
+};
+
+/// Queue of imported mints whose transfer history is reconstructed from the
+/// signatures of their mint account, with how far back each has been walked
+pub struct TransferBackfill;
+
+impl TransferBackfill {
+    /// Queue the `(mint id, mint address)` pairs imported into
+    /// `collection_id`. A mint imported again starts over from its latest
+    /// signature.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be written
+    pub async fn enqueue(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        mints: Vec<(Uuid, String)>,
+    ) -> Result<(), DbErr> {
+        if mints.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now().naive_utc();
+        let models = mints
+            .into_iter()
+            .map(|(mint_id, mint_address)| ActiveModel {
+                mint_id: Set(mint_id),
+                collection_id: Set(collection_id),
+                mint_address: Set(mint_address),
+                before: Set(None),
+                scanned: Set(0),
+                attempts: Set(0),
+                queued_at: Set(now),
+                completed_at: Set(None),
+            });
+
+        Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::column(Column::MintId)
+                    .update_columns([
+                        Column::CollectionId,
+                        Column::MintAddress,
+                        Column::Before,
+                        Column::Scanned,
+                        Column::Attempts,
+                        Column::QueuedAt,
+                        Column::CompletedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The longest waiting backfills which have not failed `max_attempts`
+    /// times
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_pending(
+        conn: &impl ConnectionTrait,
+        max_attempts: i32,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::CompletedAt.is_null())
+            .filter(Column::Attempts.lt(max_attempts))
+            .order_by_asc(Column::QueuedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+    }
+
+    /// Store how far back the signatures of a mint have been walked, so the
+    /// next run resumes before `before`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn advance(
+        conn: &impl ConnectionTrait,
+        mint_id: Uuid,
+        before: String,
+        scanned: i64,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Before, Expr::value(before))
+            .col_expr(Column::Scanned, Expr::value(scanned))
+            .filter(Column::MintId.eq(mint_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark a backfill as done
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn complete(conn: &impl ConnectionTrait, mint_id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::CompletedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::MintId.eq(mint_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Count a failed run and move the backfill to the back of the queue
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn fail(conn: &impl ConnectionTrait, mint_id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Attempts, Expr::col(Column::Attempts).add(1))
+            .col_expr(Column::QueuedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::MintId.eq(mint_id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+}
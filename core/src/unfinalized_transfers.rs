@@ -0,0 +1,60 @@
+use holaplex_hub_nfts_solana_entity::unfinalized_transfers::{ActiveModel, Column, Entity, Model};
+use sea_orm::{prelude::*, QueryOrder};
+
+/// Ownership changes indexed from confirmed slots which have not been
+/// finalized yet, with the state they replaced so they can be reverted if
+/// their slot is dropped by a fork
+pub struct UnfinalizedTransfer;
+
+impl UnfinalizedTransfer {
+    /// Remember the state replaced by a transfer
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn record(conn: &impl ConnectionTrait, model: Model) -> Result<(), DbErr> {
+        let active_model: ActiveModel = model.into();
+
+        Entity::insert(active_model)
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget the transfers of a slot once it is finalized
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be deleted
+    pub async fn finalize(conn: &impl ConnectionTrait, slot: i64) -> Result<(), DbErr> {
+        Entity::delete_many()
+            .filter(Column::Slot.eq(slot))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Transfers of slots before `slot` which were never reported finalized,
+    /// latest first so they can be reverted in order
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_before(conn: &impl ConnectionTrait, slot: i64) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Slot.lt(slot))
+            .order_by_desc(Column::Slot)
+            .order_by_desc(Column::CreatedAt)
+            .all(conn)
+            .await
+    }
+
+    /// Forget a single transfer, once it has been reverted or found final
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be deleted
+    pub async fn delete(conn: &impl ConnectionTrait, id: Uuid) -> Result<(), DbErr> {
+        Entity::delete_by_id(id).exec(conn).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use holaplex_hub_nfts_solana_entity::update_revisions::{ActiveModel, Column, Entity, Model};
+use prost::Message;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    Set,
+};
+
+use crate::proto::UpdateSolanaMintPayload;
+
+/// Progress of the transaction updating a mint from a stored revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionStatus {
+    /// Assembled and sent to the treasury for signing
+    Pending,
+    /// Sent to the cluster without waiting for a commitment
+    Submitted,
+    /// Landed on chain at the commitment the consumer submits with
+    Confirmed,
+    /// Rejected by the treasury or the cluster, so it may be retried
+    Failed,
+}
+
+impl RevisionStatus {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Submitted => "submitted",
+            Self::Confirmed => "confirmed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// The status of `revision`, treating unknown values as pending
+    #[must_use]
+    pub fn of(revision: &Model) -> Self {
+        match revision.status.as_str() {
+            "submitted" => Self::Submitted,
+            "confirmed" => Self::Confirmed,
+            "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// Messages of mint updates, kept so a failed update can be retried with the
+/// same accounts
+pub struct UpdateRevision;
+
+impl UpdateRevision {
+    pub async fn find_by_id(conn: &impl ConnectionTrait, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id).one(conn).await
+    }
+
+    /// Store the revision of an update along with the payload it was
+    /// assembled from, replacing the one stored for an earlier delivery of
+    /// the same event
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn upsert(
+        conn: &impl ConnectionTrait,
+        am: ActiveModel,
+        payload: &UpdateSolanaMintPayload,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            payload: Set(Some(payload.encode_to_vec())),
+            ..am
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([
+                        Column::MintId,
+                        Column::SerializedMessage,
+                        Column::Payer,
+                        Column::Metadata,
+                        Column::UpdateAuthority,
+                        Column::Status,
+                        Column::Signature,
+                        Column::CreatedAt,
+                        Column::Payload,
+                    ])
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Move a revision to `status`, keeping its last signature unless a new
+    /// one is given. Returns whether a revision was updated.
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be updated
+    pub async fn set_status(
+        conn: &impl ConnectionTrait,
+        id: Uuid,
+        status: RevisionStatus,
+        signature: Option<String>,
+    ) -> Result<bool, DbErr> {
+        let mut update = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status.as_str()))
+            .filter(Column::Id.eq(id));
+
+        if let Some(signature) = signature {
+            update = update.col_expr(Column::Signature, Expr::value(signature));
+        }
+
+        let res = update.exec(conn).await?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Which of `mint_ids` have an update assembled or sent but not yet
+    /// confirmed or failed
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find_in_flight(
+        conn: &impl ConnectionTrait,
+        mint_ids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<HashSet<Uuid>, DbErr> {
+        let revisions = Entity::find()
+            .filter(Column::MintId.is_in(mint_ids))
+            .filter(Column::Status.is_in([
+                RevisionStatus::Pending.as_str(),
+                RevisionStatus::Submitted.as_str(),
+            ]))
+            .all(conn)
+            .await?;
+
+        Ok(revisions.into_iter().map(|r| r.mint_id).collect())
+    }
+
+    /// Delete revisions created before `cutoff`, returning how many were
+    /// deleted
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be deleted
+    pub async fn delete_stale(conn: &impl ConnectionTrait, cutoff: DateTime) -> Result<u64, DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::CreatedAt.lt(cutoff))
+            .exec(conn)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
+
+    /// Decode the payload a revision was assembled from. Revisions stored
+    /// before payloads were kept have none.
+    ///
+    /// # Errors
+    /// This function fails if the stored bytes are not a valid payload
+    pub fn payload(model: &Model) -> Result<Option<UpdateSolanaMintPayload>, DbErr> {
+        model
+            .payload
+            .as_deref()
+            .map(UpdateSolanaMintPayload::decode)
+            .transpose()
+            .map_err(|e| DbErr::Custom(format!("invalid payload for revision {}: {e}", model.id)))
+    }
+}
@@ -0,0 +1,81 @@
+use holaplex_hub_nfts_solana_entity::wallet_mint_counts::{ActiveModel, Column, Entity};
+use hub_core::chrono::Utc;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    Set,
+};
+
+/// Number of mints of each collection held by each wallet, used to enforce
+/// the collection's per-wallet mint limit
+pub struct WalletMintCount;
+
+impl WalletMintCount {
+    /// Mints of `collection_id` currently held by `wallet`
+    ///
+    /// # Errors
+    /// This function fails if the query fails
+    pub async fn find(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        wallet: &str,
+    ) -> Result<i32, DbErr> {
+        Ok(Entity::find_by_id((collection_id, wallet.to_owned()))
+            .one(conn)
+            .await?
+            .map_or(0, |model| model.count))
+    }
+
+    /// Count one more mint of `collection_id` held by `wallet`
+    ///
+    /// # Errors
+    /// This function fails if the row cannot be written
+    pub async fn increment(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        wallet: String,
+    ) -> Result<(), DbErr> {
+        let am = ActiveModel {
+            collection_id: Set(collection_id),
+            wallet: Set(wallet),
+            count: Set(1),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        Entity::insert(am)
+            .on_conflict(
+                OnConflict::columns([Column::CollectionId, Column::Wallet])
+                    .value(Column::Count, Expr::col((Entity, Column::Count)).add(1))
+                    .update_column(Column::UpdatedAt)
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Move one mint of `collection_id` from `sender` to `recipient`. Counts
+    /// never drop below zero, so transfers of mints that were never counted
+    /// (e.g. imported ones) are harmless.
+    ///
+    /// # Errors
+    /// This function fails if the rows cannot be written
+    pub async fn transfer(
+        conn: &impl ConnectionTrait,
+        collection_id: Uuid,
+        sender: &str,
+        recipient: String,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Count, Expr::col(Column::Count).sub(1))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::CollectionId.eq(collection_id))
+            .filter(Column::Wallet.eq(sender))
+            .filter(Column::Count.gt(0))
+            .exec(conn)
+            .await?;
+
+        Self::increment(conn, collection_id, recipient).await
+    }
+}
@@ -16,6 +16,12 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub associated_token_account: String,
     pub created_at: DateTime,
+    pub compressed: bool,
+    pub edition: Option<i64>,
+    pub metadata_lock_id: Option<Uuid>,
+    pub metadata_locked_at: Option<DateTime>,
+    pub primary_sale_at: Option<DateTime>,
+    pub deleted_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,6 +1,9 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.5
 
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, FromJsonQueryResult};
+use serde::{Deserialize, Serialize};
+
+use super::sea_orm_active_enums::SolanaCluster;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Default)]
 #[sea_orm(table_name = "collections")]
@@ -14,13 +17,50 @@ pub struct Model {
     pub mint: String,
     pub metadata: String,
     pub created_at: DateTime,
-    // TODO: add supply column to help denote mcc from editions
+    pub cluster: SolanaCluster,
+    pub seller_fee_basis_points: Option<i32>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub creators: Option<CollectionCreators>,
+    pub max_mints_per_wallet: Option<i32>,
+    pub mint_open_at: Option<DateTime>,
+    pub mint_close_at: Option<DateTime>,
+    pub open_announced_at: Option<DateTime>,
+    pub close_announced_at: Option<DateTime>,
+    pub mint_price: Option<i64>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub payment_mint: Option<String>,
+    pub soulbound: bool,
+    /// Editions printable from the master edition, unset when the supply is
+    /// unlimited or the mints of the collection are not editions
+    pub max_supply: Option<i64>,
+    pub editions_minted: i64,
+    pub metadata_version: i32,
+    pub metadata_lock_id: Option<Uuid>,
+    pub metadata_locked_at: Option<DateTime>,
+    pub deleted_at: Option<DateTime>,
+    /// The project the collection was created in or imported into. Unset
+    /// for collections stored before projects were kept.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub project_id: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionCreator {
+    pub address: String,
+    pub verified: bool,
+    pub share: u32,
 }
 
+/// Default creators applied to mints that do not specify their own
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct CollectionCreators(pub Vec<CollectionCreator>);
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::collection_mints::Entity")]
     CollectionMints,
+    #[sea_orm(has_many = "super::compression_leafs::Entity")]
+    CompressionLeafs,
 }
 
 impl Related<super::collection_mints::Entity> for Entity {
@@ -29,4 +69,10 @@ impl Related<super::collection_mints::Entity> for Entity {
     }
 }
 
+impl Related<super::compression_leafs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CompressionLeafs.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -19,9 +19,28 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub asset_id: Option<String>,
     pub created_at: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub mint_signature: Option<String>,
+    pub asset_id_confirmed_at: Option<DateTime>,
+    pub deleted_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::collections::Entity",
+        from = "Column::CollectionId",
+        to = "super::collections::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Collections,
+}
+
+impl Related<super::collections::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Collections.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,35 @@
+pub mod asset_escrows;
+pub mod candy_machine_batches;
+pub mod candy_machines;
+pub mod claims;
+pub mod collection_authorities;
+pub mod collection_imports;
 pub mod collection_mints;
 pub mod collections;
 pub mod compression_leafs;
+pub mod custodial_wallets;
+pub mod dead_letters;
+pub mod event_requests;
+pub mod listings;
+pub mod merkle_trees;
+pub mod mint_allowlists;
+pub mod mint_payments;
+pub mod mint_transfer_histories;
+pub mod multisig_proposals;
+pub mod nonce_accounts;
+pub mod outbox;
+pub mod project_treasuries;
+pub mod royalty_splits;
+pub mod sea_orm_active_enums;
+pub mod signer_policies;
+pub mod token_balances;
+pub mod transaction_audit;
+pub mod transaction_costs;
+pub mod transaction_failures;
+pub mod transaction_revisions;
+pub mod transfer_backfills;
+pub mod unfinalized_transfers;
 pub mod update_revisions;
+pub mod wallet_mint_counts;
 
 pub mod prelude;
@@ -1,9 +0,0 @@
-//! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.5
-
-pub mod prelude;
-
-pub mod collection_mints;
-pub mod editions;
-pub mod certified_collections;
-pub mod compression_leafs;
-pub mod update_revisions;
\ No newline at end of file
@@ -1,6 +1,24 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.5
 
 pub use super::{
-    collection_mints::Entity as CollectionMints, collections::Entity as Collections,
-    compression_leafs::Entity as CompressionLeafs, update_revisions::Entity as UpdateRevisions,
+    asset_escrows::Entity as AssetEscrows, candy_machine_batches::Entity as CandyMachineBatches,
+    candy_machines::Entity as CandyMachines, claims::Entity as Claims,
+    collection_authorities::Entity as CollectionAuthorities,
+    collection_imports::Entity as CollectionImports, collection_mints::Entity as CollectionMints,
+    collections::Entity as Collections, compression_leafs::Entity as CompressionLeafs,
+    custodial_wallets::Entity as CustodialWallets, dead_letters::Entity as DeadLetters,
+    event_requests::Entity as EventRequests, listings::Entity as Listings,
+    merkle_trees::Entity as MerkleTrees, mint_allowlists::Entity as MintAllowlists,
+    mint_payments::Entity as MintPayments,
+    mint_transfer_histories::Entity as MintTransferHistories,
+    multisig_proposals::Entity as MultisigProposals, nonce_accounts::Entity as NonceAccounts,
+    outbox::Entity as Outbox,
+    project_treasuries::Entity as ProjectTreasuries, royalty_splits::Entity as RoyaltySplits,
+    signer_policies::Entity as SignerPolicies, token_balances::Entity as TokenBalances,
+    transaction_audit::Entity as TransactionAudit, transaction_costs::Entity as TransactionCosts,
+    transaction_failures::Entity as TransactionFailures,
+    transaction_revisions::Entity as TransactionRevisions,
+    transfer_backfills::Entity as TransferBackfills,
+    unfinalized_transfers::Entity as UnfinalizedTransfers,
+    update_revisions::Entity as UpdateRevisions, wallet_mint_counts::Entity as WalletMintCounts,
 };
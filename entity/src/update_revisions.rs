@@ -13,6 +13,13 @@ pub struct Model {
     pub payer: String,
     pub metadata: String,
     pub update_authority: String,
+    #[sea_orm(column_type = "Text")]
+    pub status: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub signature: Option<String>,
+    pub created_at: DateTime,
+    #[sea_orm(column_type = "Binary(BlobSize::Blob(None))", nullable)]
+    pub payload: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
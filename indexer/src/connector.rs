@@ -1,11 +1,29 @@
 use std::{collections::HashMap, vec};
 
 use futures::{channel::mpsc::SendError, Sink, Stream};
-use hub_core::prelude::*;
+use hub_core::{clap, prelude::*};
 use solana_program::pubkey::Pubkey;
 use yellowstone_grpc_client::GeyserGrpcClient;
 use yellowstone_grpc_proto::{prelude::*, tonic::Status};
 
+/// The commitment level transactions are streamed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Commitment {
+    /// Index transactions as soon as their slot is confirmed. Changes made
+    /// by slots which are not finalized in the end are rolled back.
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentLevel {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Confirmed => Self::Confirmed,
+            Commitment::Finalized => Self::Finalized,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct GeyserGrpcConnector {
     endpoint: String,
@@ -30,7 +48,7 @@ impl GeyserGrpcConnector {
         Ok((subscribe_tx, stream))
     }
 
-    pub fn build_request(program_id: Pubkey) -> SubscribeRequest {
+    pub fn build_request(program_id: Pubkey, commitment: Commitment) -> SubscribeRequest {
         let mut slots = HashMap::new();
         slots.insert("client".to_owned(), SubscribeRequestFilterSlots {});
 
@@ -47,6 +65,27 @@ impl GeyserGrpcConnector {
         SubscribeRequest {
             slots,
             transactions,
+            commitment: Some(CommitmentLevel::from(commitment) as i32),
+            ..Default::default()
+        }
+    }
+
+    /// Subscribe to updates of the given accounts, whichever program writes
+    /// them. An empty filter would match every account, so no filter is sent
+    /// when there is nothing to track.
+    pub fn build_accounts_request(accounts: Vec<String>) -> SubscribeRequest {
+        let mut filters = HashMap::new();
+
+        if !accounts.is_empty() {
+            filters.insert("client".to_owned(), SubscribeRequestFilterAccounts {
+                account: accounts,
+                owner: Vec::new(),
+                filters: Vec::new(),
+            });
+        }
+
+        SubscribeRequest {
+            accounts: filters,
             commitment: Some(CommitmentLevel::Finalized as i32),
             ..Default::default()
         }
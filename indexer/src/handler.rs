@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 use futures::{sink::SinkExt, stream::StreamExt};
 use holaplex_hub_nfts_solana_core::{db::Connection, proto::SolanaNftEvents};
@@ -15,13 +19,20 @@ use hub_core::{
         task::{self, JoinSet},
     },
 };
-use solana_client::rpc_client::RpcClient;
+use poem::{get, listener::TcpListener, middleware::AddData, EndpointExt, Route, Server};
+use solana_client::nonblocking::rpc_client::RpcClient;
 use yellowstone_grpc_client::GeyserGrpcClientError;
 use yellowstone_grpc_proto::prelude::{
-    subscribe_update::UpdateOneof, SubscribeRequest, SubscribeUpdateTransaction,
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest, SubscribeUpdateTransaction,
 };
 
-use crate::{processor::Processor, Args, GeyserGrpcConnector};
+use crate::{
+    metrics::{metrics_handler, Metrics},
+    processor::Processor,
+    trees::TreeMonitor,
+    watchdog::{Slots, Watchdog},
+    Args, Commitment, GeyserGrpcConnector,
+};
 
 #[derive(Clone)]
 pub struct MessageHandler {
@@ -30,15 +41,28 @@ pub struct MessageHandler {
     tx: UnboundedSender<SubscribeUpdateTransaction>,
     rx: Arc<Mutex<UnboundedReceiver<SubscribeUpdateTransaction>>>,
     parallelism: usize,
+    commitment: Commitment,
+    account_refresh_interval: Duration,
+    slots: Arc<Slots>,
+    watchdog: Watchdog,
+    trees: TreeMonitor,
+    metrics: Metrics,
+    port: u16,
 }
 
 impl MessageHandler {
     pub async fn new(args: Args, producer: Producer<SolanaNftEvents>) -> Result<Self> {
         let Args {
+            port,
             dragon_mouth_endpoint,
             dragon_mouth_x_token,
             solana_endpoint,
             parallelism,
+            account_refresh_interval,
+            slot_lag_threshold,
+            slot_lag_check_interval,
+            tree_health_interval,
+            commitment,
             db,
         } = args;
 
@@ -49,7 +73,28 @@ impl MessageHandler {
         let rpc = Arc::new(RpcClient::new(solana_endpoint));
         let connector = GeyserGrpcConnector::new(dragon_mouth_endpoint, dragon_mouth_x_token);
         let (tx, rx) = mpsc::unbounded_channel();
-        let processor = Processor::new(db, rpc, producer);
+        let processor = Processor::new(
+            db.clone(),
+            rpc.clone(),
+            producer.clone(),
+            commitment == Commitment::Confirmed,
+        );
+
+        let metrics = Metrics::new()?;
+        let slots = Arc::new(Slots::new(parallelism));
+        let watchdog = Watchdog::new(
+            slots.clone(),
+            producer,
+            metrics.clone(),
+            Duration::from_secs(slot_lag_check_interval),
+            slot_lag_threshold,
+        );
+        let trees = TreeMonitor::new(
+            db,
+            rpc,
+            metrics.clone(),
+            Duration::from_secs(tree_health_interval),
+        );
 
         Ok(Self {
             connector,
@@ -57,6 +102,13 @@ impl MessageHandler {
             tx,
             rx: Arc::new(Mutex::new(rx)),
             parallelism,
+            commitment,
+            account_refresh_interval: Duration::from_secs(account_refresh_interval),
+            slots,
+            watchdog,
+            trees,
+            metrics,
+            port,
         })
     }
 
@@ -76,9 +128,20 @@ impl MessageHandler {
                             hashmap.entry(tx.slot).or_insert(Vec::new()).push(tx);
                         },
                         Some(UpdateOneof::Slot(slot)) => {
-                            if let Some(transactions) = hashmap.remove(&slot.slot) {
-                                for tx in transactions {
-                                    self.tx.send(tx)?;
+                            // Transactions are counted before their slot is
+                            // seen, so it is not reported processed early
+                            for tx in hashmap.remove(&slot.slot).into_iter().flatten() {
+                                self.slots.queued(tx.slot);
+                                self.tx.send(tx)?;
+                            }
+
+                            self.slots.seen(slot.slot);
+
+                            if self.commitment == Commitment::Confirmed
+                                && slot.status == CommitmentLevel::Finalized as i32
+                            {
+                                if let Err(e) = self.processor.finalize(slot.slot).await {
+                                    error!("failed to finalize slot {}: {:?}", slot.slot, e);
                                 }
                             }
                         },
@@ -100,12 +163,58 @@ impl MessageHandler {
         .await
     }
 
+    /// Follow the metadata and master edition accounts of every collection.
+    /// The subscription is replaced on each refresh to pick up collections
+    /// created since it was opened.
+    async fn connect_accounts(&self) -> Result<()> {
+        (|| async {
+            let (mut subscribe_tx, mut stream) = self.connector.subscribe().await?;
+            let mut refresh = tokio::time::interval(self.account_refresh_interval);
+
+            loop {
+                tokio::select! {
+                    _ = refresh.tick() => {
+                        let accounts = self.processor.tracked_accounts().await?;
+
+                        subscribe_tx
+                            .send(GeyserGrpcConnector::build_accounts_request(accounts))
+                            .await
+                            .map_err(GeyserGrpcClientError::SubscribeSendError)?;
+                    },
+                    message = stream.next() => match message {
+                        Some(Ok(msg)) => {
+                            if let Some(UpdateOneof::Account(account)) = msg.update_oneof {
+                                if let Err(e) = self.processor.process_account(account).await {
+                                    error!("failed to process account update: {:?}", e);
+                                }
+                            }
+                        },
+                        Some(Err(error)) => bail!("stream error: {:?}", error),
+                        None => return Ok(()),
+                    },
+                }
+            }
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_max_times(10)
+                .with_jitter(),
+        )
+        .notify(|err: &Error, dur: Duration| {
+            error!("account stream error: {:?} retrying in {:?}", err, dur);
+        })
+        .await
+    }
+
     pub async fn run(self) -> Result<()> {
         let spl_token_stream = tokio::spawn({
             let handler = self.clone();
             async move {
                 handler
-                    .connect(GeyserGrpcConnector::build_request(spl_token::ID))
+                    .connect(GeyserGrpcConnector::build_request(
+                        spl_token::ID,
+                        handler.commitment,
+                    ))
                     .await
             }
         });
@@ -114,38 +223,33 @@ impl MessageHandler {
             let handler = self.clone();
             async move {
                 handler
-                    .connect(GeyserGrpcConnector::build_request(mpl_bubblegum::ID))
+                    .connect(GeyserGrpcConnector::build_request(
+                        mpl_bubblegum::ID,
+                        handler.commitment,
+                    ))
                     .await
             }
         });
 
-        let processor = self.processor;
+        let account_stream = tokio::spawn({
+            let handler = self.clone();
+            async move { handler.connect_accounts().await }
+        });
 
-        let process_task = task::spawn(async move {
-            let mut set = JoinSet::new();
+        tokio::spawn(self.watchdog.clone().run());
+        tokio::spawn(self.trees.clone().run());
 
-            loop {
-                let processor = processor.clone();
-                let mut rx = self.rx.lock().await;
-
-                while set.len() >= self.parallelism {
-                    match set.join_next().await {
-                        Some(Err(e)) => {
-                            return Result::<(), Error>::Err(anyhow!(
-                                "failed to join task {:?}",
-                                e
-                            ));
-                        },
-                        Some(Ok(_)) | None => (),
-                    }
-                }
+        let metrics_server = tokio::spawn({
+            let routes = Route::new().at(
+                "/metrics",
+                get(metrics_handler).with(AddData::new(self.metrics.clone())),
+            );
 
-                if let Some(tx) = rx.recv().await {
-                    set.spawn(processor.process_transaction(tx));
-                }
-            }
+            Server::new(TcpListener::bind(format!("0.0.0.0:{}", self.port))).run(routes)
         });
 
+        let process_task = task::spawn(self.dispatch());
+
         tokio::select! {
             Err(e) = spl_token_stream => {
                 bail!("spl token stream error: {:?}", e)
@@ -153,9 +257,149 @@ impl MessageHandler {
             Err(e) = mpl_bubblegum_stream => {
                 bail!("mpl bumblegum stream error: {:?}", e)
             }
-            Err(e) = process_task => {
-                bail!("Receiver err: {:?}", e)
+            Err(e) = account_stream => {
+                bail!("account stream error: {:?}", e)
+            }
+            Err(e) = metrics_server => {
+                bail!("metrics server error: {:?}", e)
             }
+            res = process_task => {
+                bail!("Receiver err: {:?}", res)
+            }
+        }
+    }
+
+    /// Route received transactions to `parallelism` workers. Transactions
+    /// with the same shard key always reach the same worker, so updates to a
+    /// mint or tree are applied in the order they were received while
+    /// unrelated ones proceed in parallel. A transaction whose keys belong
+    /// to several workers waits for them to finish what they hold, and is
+    /// processed before anything received after it.
+    async fn dispatch(self) -> Result<()> {
+        let mut workers = JoinSet::new();
+        let shards = (0..self.parallelism.max(1))
+            .map(|shard| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                workers.spawn(work(self.processor.clone(), self.slots.clone(), shard, rx));
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        let mut rx = self.rx.lock().await;
+
+        loop {
+            tokio::select! {
+                Some(tx) = rx.recv() => {
+                    let slot = tx.slot;
+                    let targets = shard_keys(&tx)
+                        .into_iter()
+                        .map(|key| {
+                            let mut hasher = DefaultHasher::new();
+                            key.hash(&mut hasher);
+                            usize::try_from(hasher.finish() % shards.len() as u64)
+                        })
+                        .collect::<Result<BTreeSet<_>, _>>()?;
+                    let shard = targets.iter().next().copied().unwrap_or_default();
+                    let shared = targets.len() > 1;
+
+                    if shared {
+                        self.drain(&mut workers, &targets).await?;
+                    }
+
+                    self.slots.dispatched(shard, slot);
+                    shards[shard].send(tx)?;
+
+                    if shared {
+                        self.drain(&mut workers, &[shard]).await?;
+                    }
+                },
+                Some(res) = workers.join_next() => {
+                    res.context("indexer worker panicked")?;
+                    bail!("indexer worker exited");
+                },
+                else => bail!("transaction channel closed"),
+            }
+        }
+    }
+
+    /// Wait for the workers of `shards` to finish the transactions they hold
+    async fn drain<'a>(
+        &self,
+        workers: &mut JoinSet<()>,
+        shards: impl IntoIterator<Item = &'a usize>,
+    ) -> Result<()> {
+        let drained = async {
+            for shard in shards {
+                self.slots.drained(*shard).await;
+            }
+        };
+
+        tokio::select! {
+            () = drained => Ok(()),
+            Some(res) = workers.join_next() => {
+                res.context("indexer worker panicked")?;
+                bail!("indexer worker exited");
+            },
         }
     }
 }
+
+/// Process the transactions of one shard one at a time, in order
+async fn work(
+    processor: Processor,
+    slots: Arc<Slots>,
+    shard: usize,
+    mut rx: UnboundedReceiver<SubscribeUpdateTransaction>,
+) {
+    while let Some(tx) = rx.recv().await {
+        let slot = tx.slot;
+
+        if let Err(e) = processor.clone().process_transaction(tx).await {
+            error!("failed to process transaction: {:?}", e);
+        }
+
+        slots.processed(shard, slot);
+    }
+}
+
+/// The accounts whose updates must be ordered: every mint with a token
+/// balance in the transaction, and the merkle trees of its compressed
+/// transfers. Transactions touching neither are keyed by their signature.
+fn shard_keys(tx: &SubscribeUpdateTransaction) -> BTreeSet<Vec<u8>> {
+    let Some(info) = tx.transaction.as_ref() else {
+        return BTreeSet::new();
+    };
+
+    let mut keys = info
+        .meta
+        .iter()
+        .flat_map(|meta| {
+            meta.pre_token_balances
+                .iter()
+                .chain(&meta.post_token_balances)
+        })
+        .map(|balance| balance.mint.as_bytes().to_vec())
+        .collect::<BTreeSet<_>>();
+
+    if let Some(message) = info.transaction.as_ref().and_then(|t| t.message.as_ref()) {
+        let account_keys = &message.account_keys;
+
+        keys.extend(message.instructions.iter().filter_map(|ins| {
+            let program = account_keys.get(usize::try_from(ins.program_id_index).ok()?)?;
+
+            if program.as_slice() != mpl_bubblegum::ID.as_ref() {
+                return None;
+            }
+
+            account_keys
+                .get(usize::from(*ins.accounts.get(4)?))
+                .cloned()
+        }));
+    }
+
+    if keys.is_empty() {
+        keys.insert(info.signature.clone());
+    }
+
+    keys
+}
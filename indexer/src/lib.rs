@@ -1,14 +1,20 @@
 mod connector;
 mod handler;
+mod metrics;
 mod processor;
+mod trees;
+mod watchdog;
 use clap::{arg, command};
-pub use connector::GeyserGrpcConnector;
+pub use connector::{Commitment, GeyserGrpcConnector};
 pub use handler::MessageHandler;
 use holaplex_hub_nfts_solana_core::db::{self};
 use hub_core::clap;
 
 #[derive(Debug, clap::Args)]
 pub struct Args {
+    #[arg(long, env, default_value_t = 3005)]
+    pub port: u16,
+
     #[arg(long, env)]
     pub dragon_mouth_endpoint: String,
 
@@ -18,9 +24,35 @@ pub struct Args {
     #[arg(long, env)]
     pub solana_endpoint: String,
 
+    /// Number of workers processing transactions. Transactions for the same
+    /// mint or merkle tree are always handled by the same worker.
     #[arg(long, short = 'p', env, default_value_t = 8)]
     pub parallelism: usize,
 
+    /// Interval in seconds between reloads of the collection metadata and
+    /// master edition accounts the indexer subscribes to
+    #[arg(long, env, default_value_t = 300)]
+    pub account_refresh_interval: u64,
+
+    /// Number of slots the latest processed transaction may trail the latest
+    /// slot seen from Geyser before the indexer reports itself as lagging
+    #[arg(long, env, default_value_t = 150)]
+    pub slot_lag_threshold: u64,
+
+    /// Interval in seconds between slot lag checks
+    #[arg(long, env, default_value_t = 15)]
+    pub slot_lag_check_interval: u64,
+
+    /// Interval in seconds between reads of the merkle tree accounts whose
+    /// health is reported as metrics
+    #[arg(long, env, default_value_t = 60)]
+    pub tree_health_interval: u64,
+
+    /// Commitment level transactions are indexed at. At `confirmed`,
+    /// ownership changes from slots which are never finalized are reverted.
+    #[arg(long, env, value_enum, default_value = "finalized")]
+    pub commitment: Commitment,
+
     #[command(flatten)]
     pub db: db::DbArgs,
 }
@@ -0,0 +1,100 @@
+#[allow(clippy::wildcard_imports)]
+use hub_core::{
+    anyhow::{anyhow, Result},
+    metrics::*,
+};
+use poem::{handler, web::Data};
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub registry: Registry,
+    pub provider: MeterProvider,
+    pub slot_lag_bucket: Histogram<i64>,
+    pub lagging_counter: Counter<u64>,
+    pub merkle_tree_leaves: UpDownCounter<i64>,
+    pub merkle_tree_capacity: UpDownCounter<i64>,
+    pub merkle_tree_last_leaf_slot: UpDownCounter<i64>,
+    pub merkle_tree_proof_length: UpDownCounter<i64>,
+    pub merkle_tree_canopy_depth: UpDownCounter<i64>,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        let exporter = hub_core::metrics::exporter()
+            .with_registry(registry.clone())
+            .with_namespace("hub_nfts_solana_indexer")
+            .build()
+            .map_err(|e| anyhow!("Failed to build exporter: {}", e))?;
+
+        let provider = MeterProvider::builder()
+            .with_reader(exporter)
+            .with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                "hub-nfts-solana-indexer",
+            )]))
+            .build();
+
+        let meter = provider.meter("hub-nfts-solana-indexer");
+
+        let slot_lag_bucket = meter
+            .i64_histogram("slot_lag")
+            .with_description(
+                "Slots between the latest slot seen from Geyser and the latest processed slot.",
+            )
+            .init();
+
+        let lagging_counter = meter
+            .u64_counter("lagging")
+            .with_description("Number of times the indexer fell behind the lag threshold.")
+            .init();
+
+        let merkle_tree_leaves = meter
+            .i64_up_down_counter("merkle_tree_leaves")
+            .with_description("Number of leaves appended to each merkle tree.")
+            .init();
+
+        let merkle_tree_capacity = meter
+            .i64_up_down_counter("merkle_tree_capacity")
+            .with_description("Number of leaves each merkle tree can hold.")
+            .init();
+
+        let merkle_tree_last_leaf_slot = meter
+            .i64_up_down_counter("merkle_tree_last_leaf_slot")
+            .with_description("Slot a leaf of each merkle tree last changed in.")
+            .init();
+
+        let merkle_tree_proof_length = meter
+            .i64_up_down_counter("merkle_tree_proof_length")
+            .with_description(
+                "Number of proof nodes a transaction must pass for each merkle tree, after its \
+                 canopy.",
+            )
+            .init();
+
+        let merkle_tree_canopy_depth = meter
+            .i64_up_down_counter("merkle_tree_canopy_depth")
+            .with_description("Depth of the canopy cached on chain for each merkle tree.")
+            .init();
+
+        Ok(Self {
+            registry,
+            provider,
+            slot_lag_bucket,
+            lagging_counter,
+            merkle_tree_leaves,
+            merkle_tree_capacity,
+            merkle_tree_last_leaf_slot,
+            merkle_tree_proof_length,
+            merkle_tree_canopy_depth,
+        })
+    }
+}
+
+#[handler]
+pub fn metrics_handler(Data(metrics): Data<&Metrics>) -> Result<String> {
+    let mut buffer = vec![];
+    let encoder = TextEncoder::new();
+    encoder.encode(&metrics.registry.gather(), &mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
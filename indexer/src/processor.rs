@@ -1,30 +1,78 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
 
-use anchor_lang::AnchorDeserialize;
+use anchor_lang::{AnchorDeserialize, Discriminator};
 use backoff::ExponentialBackoff;
 use holaplex_hub_nfts_solana_core::{
     db::Connection,
     proto::{
-        solana_nft_events::Event::UpdateMintOwner, MintOwnershipUpdate, SolanaNftEventKey,
-        SolanaNftEvents,
+        solana_nft_events::Event::{
+            CollectionAccountUpdated, CompressedAssetIdCorrected, ListingSold,
+            MintOwnershipRolledBack, PrimarySaleDetected, TokenBalanceChanged, UpdateMintOwner,
+        },
+        MintOwnershipUpdate, SolanaCollectionAccountUpdate, SolanaCompressedAssetIdCorrection,
+        SolanaListingSale, SolanaMintOwnershipRollback, SolanaNftEventKey, SolanaNftEvents,
+        SolanaPrimarySale, SolanaTokenBalanceChange,
     },
-    sea_orm::Set,
-    CollectionMint, CompressionLeaf,
+    auction_house,
+    sea_orm::{ConnectionTrait, Set},
+    AppliedTransfer, Collection, CollectionMint, CompressionLeaf, Listing, MerkleTree,
+    MintTransfer, ObservedTransfer, TokenBalance, UnfinalizedTransfer, WalletMintCount,
 };
-use holaplex_hub_nfts_solana_entity::compression_leafs;
-use hub_core::{prelude::*, producer::Producer};
-use mpl_bubblegum::utils::get_asset_id;
-use solana_client::rpc_client::RpcClient;
+use holaplex_hub_nfts_solana_entity::{compression_leafs, token_balances, unfinalized_transfers};
+use hub_core::{chrono::Utc, prelude::*, producer::Producer, uuid::Uuid};
+use mpl_bubblegum::{instruction::Transfer, utils::get_asset_id};
+use mpl_token_metadata::state::{MasterEditionV2, Metadata, TokenMetadataAccount};
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::program_pack::Pack;
-use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use spl_account_compression::{
+    events::{AccountCompressionEvent, ChangeLogEventV1},
+    ChangeLogEvent,
+};
 use spl_token::{instruction::TokenInstruction, state::Account};
-use yellowstone_grpc_proto::prelude::{Message, SubscribeUpdateTransaction};
+use yellowstone_grpc_proto::prelude::{
+    SubscribeUpdateAccount, SubscribeUpdateTransaction, TransactionStatusMeta,
+};
+
+/// A top-level or inner instruction of a transaction
+struct TransactionInstruction<'a> {
+    program_id_index: u32,
+    accounts: &'a [u8],
+    data: &'a [u8],
+}
+
+/// The balance of a token account after an observed transaction
+struct TokenAccountBalance {
+    mint: String,
+    owner: String,
+    amount: u64,
+}
+
+/// An associated token account created by an observed transaction
+struct CreatedTokenAccount {
+    owner: Pubkey,
+    mint: Pubkey,
+}
 
 #[derive(Clone)]
 pub struct Processor {
     db: Connection,
     rpc: Arc<RpcClient>,
     producer: Producer<SolanaNftEvents>,
+    /// The slot and time of the block last looked up. Transactions arrive
+    /// roughly in slot order, so most lookups hit it.
+    last_block_time: Arc<Mutex<Option<(u64, i64)>>>,
+    /// Whether transactions are indexed before their slot is finalized, in
+    /// which case transfers are tracked until it is
+    track_forks: bool,
+    last_finalized: Arc<AtomicU64>,
 }
 
 impl Processor {
@@ -32,36 +80,275 @@ impl Processor {
         db: Connection,
         rpc: Arc<RpcClient>,
         producer: Producer<SolanaNftEvents>,
+        track_forks: bool,
     ) -> Self {
-        Self { db, rpc, producer }
+        Self {
+            db,
+            rpc,
+            producer,
+            last_block_time: Arc::default(),
+            track_forks,
+            last_finalized: Arc::default(),
+        }
+    }
+
+    /// Unix timestamp of the block at `slot`, or 0 if the RPC node does not
+    /// know it. Events carry it so consumers can show when a change happened
+    /// rather than when it was indexed.
+    async fn block_time(&self, slot: u64) -> i64 {
+        let cached = *self
+            .last_block_time
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some((cached_slot, time)) = cached {
+            if cached_slot == slot {
+                return time;
+            }
+        }
+
+        match self.rpc.get_block_time(slot).await {
+            Ok(time) => {
+                *self
+                    .last_block_time
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some((slot, time));
+
+                time
+            },
+            Err(e) => {
+                warn!("failed to look up the time of block {slot}: {e:?}");
+
+                0
+            },
+        }
+    }
+
+    /// Remember the state a transfer replaced while its slot may still be
+    /// dropped by a fork
+    async fn track_transfer(
+        &self,
+        conn: &impl ConnectionTrait,
+        transfer: unfinalized_transfers::Model,
+    ) -> Result<()> {
+        if self.track_forks {
+            UnfinalizedTransfer::record(conn, transfer).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Settle the transfers tracked up to a newly finalized slot. Those of
+    /// the slot itself are final. Those of earlier slots were never reported
+    /// finalized, so each of their slots is checked with the RPC node, and
+    /// the transfers of slots skipped by the cluster are rolled back.
+    pub(crate) async fn finalize(&self, slot: u64) -> Result<()> {
+        // Every transaction stream reports the same slots
+        if !self.track_forks || self.last_finalized.fetch_max(slot, Ordering::Relaxed) >= slot {
+            return Ok(());
+        }
+
+        let conn = self.db.get();
+        let slot = i64::try_from(slot)?;
+
+        UnfinalizedTransfer::finalize(conn, slot).await?;
+
+        let mut finalized = HashMap::new();
+
+        for transfer in UnfinalizedTransfer::find_before(conn, slot).await? {
+            let transfer_slot = u64::try_from(transfer.slot)?;
+
+            let is_finalized = match finalized.get(&transfer_slot) {
+                Some(&is_finalized) => is_finalized,
+                None => {
+                    let blocks = self
+                        .rpc
+                        .get_blocks_with_commitment(
+                            transfer_slot,
+                            Some(transfer_slot),
+                            CommitmentConfig::finalized(),
+                        )
+                        .await?;
+                    let is_finalized = blocks.contains(&transfer_slot);
+
+                    finalized.insert(transfer_slot, is_finalized);
+                    is_finalized
+                },
+            };
+
+            if is_finalized {
+                UnfinalizedTransfer::delete(conn, transfer.id).await?;
+            } else {
+                self.roll_back(transfer).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Revert a transfer from a slot which was dropped by a fork, restoring
+    /// the owner it replaced, and report the correction. A transaction which
+    /// landed again in a finalized slot is kept.
+    async fn roll_back(&self, transfer: unfinalized_transfers::Model) -> Result<()> {
+        let signature: Signature = transfer.signature.parse()?;
+        let landed = self
+            .rpc
+            .get_signature_status_with_commitment(&signature, CommitmentConfig::finalized())
+            .await?;
+
+        let txn = self.db.begin().await?;
+
+        let mint_address = if landed.is_some() {
+            None
+        } else if transfer.compressed {
+            match CompressionLeaf::find_by_id(&txn, transfer.mint_id).await? {
+                Some(leaf) => {
+                    let asset_id = leaf.asset_id.clone().unwrap_or_default();
+                    let mut leaf: compression_leafs::ActiveModel = leaf.into();
+                    leaf.leaf_owner = Set(transfer.previous_owner.clone());
+
+                    CompressionLeaf::update(&txn, leaf).await?;
+                    Some(asset_id)
+                },
+                None => None,
+            }
+        } else {
+            match CollectionMint::find_by_id(&txn, transfer.mint_id).await? {
+                Some(mint) => {
+                    CollectionMint::update_owner_and_ata(
+                        &txn,
+                        &mint,
+                        transfer.previous_owner.clone(),
+                        transfer.previous_token_account.clone().unwrap_or_default(),
+                    )
+                    .await?;
+                    Some(mint.mint)
+                },
+                None => None,
+            }
+        };
+
+        if mint_address.is_some() {
+            WalletMintCount::transfer(
+                &txn,
+                transfer.collection_id,
+                &transfer.recipient,
+                transfer.previous_owner.clone(),
+            )
+            .await?;
+            CollectionMint::delete_transfer(&txn, transfer.id).await?;
+
+            if transfer.primary_sale {
+                CollectionMint::clear_primary_sale(&txn, transfer.mint_id).await?;
+            }
+        }
+
+        UnfinalizedTransfer::delete(&txn, transfer.id).await?;
+        txn.commit().await?;
+
+        let Some(mint_address) = mint_address else {
+            return Ok(());
+        };
+
+        warn!(
+            "rolled back transfer {} of mint {} from dropped slot {}",
+            transfer.signature, transfer.mint_id, transfer.slot
+        );
+
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(MintOwnershipRolledBack(
+                    SolanaMintOwnershipRollback {
+                        mint_id: transfer.mint_id.to_string(),
+                        mint_address,
+                        restored_owner: transfer.previous_owner,
+                        reverted_owner: transfer.recipient,
+                        tx_signature: transfer.signature,
+                        slot: transfer.slot.try_into()?,
+                    },
+                ))),
+                Some(&SolanaNftEventKey {
+                    id: transfer.mint_id.to_string(),
+                    correlation_id: transfer.mint_id.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        Ok(())
     }
 
     pub(crate) async fn process_transaction(self, tx: SubscribeUpdateTransaction) -> Result<()> {
-        let message = tx
+        let info = tx
             .transaction
-            .clone()
-            .context("SubscribeTransactionInfo not found")?
+            .as_ref()
+            .context("SubscribeTransactionInfo not found")?;
+        let message = info
             .transaction
+            .as_ref()
             .context("Transaction not found")?
             .message
-            .context("Message not found")?;
-        let sig = tx
-            .transaction
             .as_ref()
-            .ok_or_else(|| anyhow!("failed to get transaction"))?
-            .signature
-            .clone();
+            .context("Message not found")?;
+        let sig = &info.signature;
+
+        let mut keys = message.account_keys.clone();
+        let mut instructions = Vec::new();
+
+        for (idx, ins) in message.instructions.iter().enumerate() {
+            instructions.push(TransactionInstruction {
+                program_id_index: ins.program_id_index,
+                accounts: &ins.accounts,
+                data: &ins.data,
+            });
 
-        let keys = message.clone().account_keys;
+            // Instructions invoked by CPI follow the top-level instruction
+            // that made them
+            let inner = info
+                .meta
+                .iter()
+                .flat_map(|meta| &meta.inner_instructions)
+                .filter(|set| usize::try_from(set.index).map_or(false, |i| i == idx))
+                .flat_map(|set| &set.instructions);
 
-        for (idx, key) in message.clone().account_keys.iter().enumerate() {
+            instructions.extend(inner.map(|ins| TransactionInstruction {
+                program_id_index: ins.program_id_index,
+                accounts: &ins.accounts,
+                data: &ins.data,
+            }));
+        }
+
+        // Accounts loaded from address lookup tables are indexed after the
+        // static keys, writable before readonly
+        if let Some(meta) = &info.meta {
+            keys.extend(meta.loaded_writable_addresses.iter().cloned());
+            keys.extend(meta.loaded_readonly_addresses.iter().cloned());
+        }
+
+        let created_accounts = created_token_accounts(&keys, &instructions)?;
+        let balances = token_account_balances(&keys, info.meta.as_ref())?;
+
+        for (idx, key) in keys.iter().enumerate() {
             let key: &[u8] = key;
             let k = Pubkey::try_from(key)?;
             if k == spl_token::ID {
-                self.process_spl_token_transaction(idx, &keys, &sig, &message)
-                    .await?;
+                self.process_spl_token_transaction(
+                    idx,
+                    &keys,
+                    sig,
+                    tx.slot,
+                    &instructions,
+                    &created_accounts,
+                    &balances,
+                )
+                .await?;
             } else if k == mpl_bubblegum::ID {
-                self.process_mpl_bubblegum_transaction(idx, &keys, &sig, &message)
+                self.process_changelogs(&keys, sig, tx.slot, &instructions)
+                    .await?;
+                self.process_mpl_bubblegum_transaction(idx, &keys, sig, tx.slot, &instructions)
+                    .await?;
+            } else if k == auction_house::PROGRAM_ID {
+                self.process_auction_house_transaction(idx, &keys, sig, tx.slot, &instructions)
                     .await?;
             }
         }
@@ -69,62 +356,154 @@ impl Processor {
         Ok(())
     }
 
-    pub(crate) async fn process_mpl_bubblegum_transaction(
+    /// Addresses of the metadata and master edition accounts of every
+    /// collection
+    pub(crate) async fn tracked_accounts(&self) -> Result<Vec<String>> {
+        let collections = Collection::find_all(self.db.read()).await?;
+
+        Ok(collections
+            .into_iter()
+            .flat_map(|c| [c.metadata, c.master_edition])
+            .filter(|address| !address.is_empty())
+            .collect())
+    }
+
+    /// Report a change to a collection's metadata or master edition account.
+    /// Account updates are observed regardless of the program that wrote
+    /// them, so changes made by CPI from unknown programs are still caught.
+    pub(crate) async fn process_account(&self, update: SubscribeUpdateAccount) -> Result<()> {
+        let account = update.account.context("account info not found")?;
+        let address = Pubkey::try_from(account.pubkey.as_slice())?.to_string();
+
+        let Some(collection) = Collection::find_by_account(self.db.read(), address.clone()).await?
+        else {
+            return Ok(());
+        };
+
+        let closed = account.lamports == 0 || account.data.is_empty();
+
+        let mut event = SolanaCollectionAccountUpdate {
+            collection_id: collection.id.to_string(),
+            address: address.clone(),
+            slot: update.slot,
+            signature: account
+                .txn_signature
+                .map(|sig| Signature::new(sig.as_slice()).to_string())
+                .unwrap_or_default(),
+            closed,
+            expected_update_authority: collection.update_authority.clone(),
+            block_time: self.block_time(update.slot).await,
+            ..Default::default()
+        };
+
+        if !closed && address == collection.metadata {
+            let metadata = Metadata::safe_deserialize(&account.data)?;
+
+            event.actual_update_authority = metadata.update_authority.to_string();
+            event.name = metadata.data.name.trim_end_matches('\0').to_owned();
+            event.symbol = metadata.data.symbol.trim_end_matches('\0').to_owned();
+            event.uri = metadata.data.uri.trim_end_matches('\0').to_owned();
+        } else if !closed {
+            let edition = MasterEditionV2::safe_deserialize(&account.data)?;
+
+            event.supply = Some(edition.supply);
+            event.max_supply = edition.max_supply;
+        }
+
+        if !event.actual_update_authority.is_empty()
+            && event.actual_update_authority != collection.update_authority
+        {
+            warn!(
+                "update authority of collection {} changed on chain to {}",
+                collection.id, event.actual_update_authority
+            );
+        }
+
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(CollectionAccountUpdated(event))),
+                Some(&SolanaNftEventKey {
+                    id: collection.id.to_string(),
+                    correlation_id: collection.id.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Keep the leaf counts and last changed slots of tracked merkle trees
+    /// in sync with the changelogs emitted through the noop program, and
+    /// confirm the asset id of a compressed mint whose transaction is seen
+    /// landing
+    async fn process_changelogs(
         &self,
-        program_account_index: usize,
         keys: &[Vec<u8>],
-        sig: &Vec<u8>,
-        message: &Message,
+        sig: &[u8],
+        slot: u64,
+        instructions: &[TransactionInstruction<'_>],
     ) -> Result<()> {
-        for ins in message.instructions.iter() {
-            let account_indices = ins.accounts.clone();
-            let program_idx: usize = ins.program_id_index.try_into()?;
+        let changelogs = instructions
+            .iter()
+            .filter(|ins| {
+                usize::try_from(ins.program_id_index)
+                    .ok()
+                    .and_then(|idx| keys.get(idx))
+                    .map_or(false, |key| key.as_slice() == spl_noop::id().as_ref())
+            })
+            .filter_map(
+                |ins| match AccountCompressionEvent::try_from_slice(ins.data) {
+                    Ok(AccountCompressionEvent::ChangeLog(ChangeLogEvent::V1(changelog))) => {
+                        Some(changelog)
+                    },
+                    _ => None,
+                },
+            );
+        let signature = Signature::new(sig).to_string();
 
-            if program_idx == program_account_index {
-                let conn = self.db.get();
-                let data = ins.data.clone();
-                let data = data.as_slice();
-
-                let tkn_instruction =
-                    mpl_bubblegum::instruction::Transfer::try_from_slice(&data[8..])?;
-                let new_leaf_owner_account_index = account_indices[3];
-                let merkle_tree_account_index = account_indices[4];
-                let new_leaf_owner_bytes: &[u8] = &keys[new_leaf_owner_account_index as usize];
-                let merkle_tree_bytes: &[u8] = &keys[merkle_tree_account_index as usize];
-                let new_leaf_owner = Pubkey::try_from(new_leaf_owner_bytes)?;
-                let merkle_tree = Pubkey::try_from(merkle_tree_bytes)?;
+        for ChangeLogEventV1 {
+            id: merkle_tree,
+            index,
+            ..
+        } in changelogs
+        {
+            let txn = self.db.begin().await?;
 
-                let asset_id = get_asset_id(&merkle_tree, tkn_instruction.nonce);
+            MerkleTree::advance(&txn, merkle_tree.to_string(), index.into()).await?;
+            MerkleTree::observe_slot(&txn, merkle_tree.to_string(), slot.try_into()?).await?;
 
-                let compression_leaf =
-                    CompressionLeaf::find_by_asset_id(conn, asset_id.to_string()).await?;
+            let leaf =
+                CompressionLeaf::find_unconfirmed_by_signature(&txn, signature.clone()).await?;
+            let mut correction = None;
 
-                if compression_leaf.is_none() {
-                    return Ok(());
-                }
-
-                let compression_leaf = compression_leaf.context("Compression leaf not found")?;
+            if let Some(leaf) = leaf.filter(|l| l.merkle_tree == merkle_tree.to_string()) {
+                let id = leaf.id;
+                let asset_id = get_asset_id(&merkle_tree, index.into()).to_string();
 
-                let collection_mint_id = compression_leaf.id;
-                let leaf_owner = compression_leaf.leaf_owner.clone();
-                let mut compression_leaf: compression_leafs::ActiveModel = compression_leaf.into();
+                correction = CompressionLeaf::confirm_asset_id(&txn, leaf, asset_id.clone())
+                    .await?
+                    .map(|predicted| (id, predicted, asset_id));
+            }
 
-                compression_leaf.leaf_owner = Set(new_leaf_owner.to_string());
+            txn.commit().await?;
 
-                CompressionLeaf::update(conn, compression_leaf).await?;
+            if let Some((id, predicted_address, address)) = correction {
+                warn!("asset id of mint {id} was mispredicted as {predicted_address}");
 
                 self.producer
                     .send(
-                        Some(&SolanaNftEvents {
-                            event: Some(UpdateMintOwner(MintOwnershipUpdate {
-                                mint_address: asset_id.to_string(),
-                                sender: leaf_owner,
-                                recipient: new_leaf_owner.to_string(),
-                                tx_signature: Signature::new(sig.as_slice()).to_string(),
-                            })),
-                        }),
+                        Some(&SolanaNftEvents::new(CompressedAssetIdCorrected(
+                            SolanaCompressedAssetIdCorrection {
+                                collection_mint_id: id.to_string(),
+                                predicted_address,
+                                address,
+                                signature: signature.clone(),
+                            },
+                        ))),
                         Some(&SolanaNftEventKey {
-                            id: collection_mint_id.to_string(),
+                            id: id.to_string(),
+                            correlation_id: id.to_string(),
                             ..Default::default()
                         }),
                     )
@@ -135,21 +514,118 @@ impl Processor {
         Ok(())
     }
 
+    pub(crate) async fn process_mpl_bubblegum_transaction(
+        &self,
+        program_account_index: usize,
+        keys: &[Vec<u8>],
+        sig: &Vec<u8>,
+        slot: u64,
+        instructions: &[TransactionInstruction<'_>],
+    ) -> Result<()> {
+        for ins in instructions {
+            let account_indices = ins.accounts;
+            let program_idx: usize = ins.program_id_index.try_into()?;
+
+            if program_idx == program_account_index {
+                let data = ins.data;
+
+                if data.get(..8) != Some(&Transfer::DISCRIMINATOR[..]) {
+                    continue;
+                }
+
+                // An instruction missing the new owner or tree would have
+                // failed on chain, so it is skipped
+                let Some((new_leaf_owner_bytes, merkle_tree_bytes)) = account_indices
+                    .get(3)
+                    .zip(account_indices.get(4))
+                    .and_then(|(owner, tree)| {
+                        Some((
+                            keys.get(usize::from(*owner))?,
+                            keys.get(usize::from(*tree))?,
+                        ))
+                    })
+                else {
+                    continue;
+                };
+
+                let tkn_instruction = Transfer::try_from_slice(&data[8..])?;
+                let new_leaf_owner = Pubkey::try_from(new_leaf_owner_bytes.as_slice())?;
+                let merkle_tree = Pubkey::try_from(merkle_tree_bytes.as_slice())?;
+
+                let asset_id = get_asset_id(&merkle_tree, tkn_instruction.nonce);
+
+                let Some(compression_leaf) =
+                    CompressionLeaf::find_by_asset_id(self.db.read(), asset_id.to_string()).await?
+                else {
+                    continue;
+                };
+
+                let tx_signature = Signature::new(sig.as_slice()).to_string();
+                let block_time = self.block_time(slot).await;
+                let txn = self.db.begin().await?;
+
+                let applied =
+                    MintTransfer::apply_to_leaf(&txn, compression_leaf.id, &ObservedTransfer {
+                        signature: tx_signature.clone(),
+                        slot: slot.try_into()?,
+                        block_time,
+                        recipient: new_leaf_owner.to_string(),
+                    })
+                    .await?;
+
+                // The transfer was already observed, e.g. by another
+                // subscription or a webhook, and its owner change reported
+                let Some(applied) = applied else {
+                    continue;
+                };
+
+                self.track_transfer(&txn, unfinalized_transfers::Model {
+                    id: applied.id,
+                    mint_id: applied.mint_id,
+                    collection_id: applied.collection_id,
+                    compressed: true,
+                    slot: slot.try_into()?,
+                    signature: tx_signature.clone(),
+                    previous_owner: applied.sender.clone(),
+                    previous_token_account: None,
+                    recipient: new_leaf_owner.to_string(),
+                    primary_sale: applied.primary_sale,
+                    created_at: Utc::now().naive_utc(),
+                })
+                .await?;
+
+                txn.commit().await?;
+
+                self.send_transfer(
+                    applied,
+                    new_leaf_owner.to_string(),
+                    tx_signature,
+                    slot,
+                    block_time,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn process_spl_token_transaction(
         &self,
         program_account_index: usize,
         keys: &[Vec<u8>],
         sig: &Vec<u8>,
-        message: &Message,
+        slot: u64,
+        instructions: &[TransactionInstruction<'_>],
+        created_accounts: &HashMap<Pubkey, CreatedTokenAccount>,
+        balances: &HashMap<Pubkey, TokenAccountBalance>,
     ) -> Result<()> {
-        let conn = self.db.get();
-        for ins in message.instructions.iter() {
-            let account_indices = ins.accounts.clone();
+        for ins in instructions {
+            let account_indices = ins.accounts;
             let program_idx: usize = ins.program_id_index.try_into()?;
 
             if program_idx == program_account_index {
-                let data = ins.data.clone();
-                let data = data.as_slice();
+                let data = ins.data;
                 let tkn_instruction = spl_token::instruction::TokenInstruction::unpack(data)?;
 
                 let transfer_info = match tkn_instruction {
@@ -162,50 +638,119 @@ impl Processor {
                     continue;
                 }
 
+                // An instruction missing its source or destination would have
+                // failed on chain, so it is skipped
+                let account = |position: usize| {
+                    account_indices
+                        .get(position)
+                        .and_then(|idx| keys.get(usize::from(*idx)))
+                };
+
+                // Transfers of more than one token move part of a
+                // semi-fungible supply and change balances rather than the
+                // owner of the mint
+                if let Some((amount @ 2.., destination_ata_index)) = transfer_info {
+                    let Some((source_bytes, destination_bytes)) =
+                        account(0).zip(account(destination_ata_index))
+                    else {
+                        continue;
+                    };
+
+                    self.process_balance_change(
+                        Pubkey::try_from(source_bytes.as_slice())?,
+                        Pubkey::try_from(destination_bytes.as_slice())?,
+                        amount,
+                        sig,
+                        slot,
+                        balances,
+                    )
+                    .await?;
+
+                    continue;
+                }
+
                 if let Some((1, destination_ata_index)) = transfer_info {
-                    let source_account_index = account_indices[0];
-                    let source_bytes: &[u8] = &keys[source_account_index as usize];
-                    let source = Pubkey::try_from(source_bytes)?;
+                    let Some((source_bytes, destination_bytes)) =
+                        account(0).zip(account(destination_ata_index))
+                    else {
+                        continue;
+                    };
+
+                    let source = Pubkey::try_from(source_bytes.as_slice())?;
+                    let destination = Pubkey::try_from(destination_bytes.as_slice())?;
+                    let created = created_accounts.get(&destination);
 
                     let collection_mint =
-                        CollectionMint::find_by_ata(conn, source.to_string()).await?;
+                        CollectionMint::find_by_ata(self.db.read(), source.to_string()).await?;
 
-                    if collection_mint.is_none() {
-                        return Ok(());
-                    }
+                    // A tracked mint whose last transfer was missed is still
+                    // recognized when the destination account is created in
+                    // the same transaction, since its creation names the mint
+                    let collection_mint = match (collection_mint, created) {
+                        (Some(mint), _) => Some(mint),
+                        (None, Some(created)) => {
+                            CollectionMint::find_by_mint(self.db.read(), created.mint.to_string())
+                                .await?
+                        },
+                        (None, None) => None,
+                    };
 
-                    let destination_account_index = account_indices[destination_ata_index];
-                    let destination_bytes: &[u8] = &keys[destination_account_index as usize];
-                    let destination = Pubkey::try_from(destination_bytes)?;
+                    let Some(mint) = collection_mint else {
+                        continue;
+                    };
 
-                    let acct = fetch_account(&self.rpc, &destination).await?;
-                    let destination_tkn_act = Account::unpack(&acct.data)?;
-                    let new_owner = destination_tkn_act.owner.to_string();
-                    let mint = collection_mint.context("No mint found")?;
+                    // The owner of an account created by this transaction is
+                    // known without a lookup, which could fail if the account
+                    // has since been closed
+                    let new_owner = if let Some(created) = created {
+                        created.owner.to_string()
+                    } else {
+                        let acct = fetch_account(&self.rpc, &destination).await?;
+                        Account::unpack(&acct.data)?.owner.to_string()
+                    };
 
-                    CollectionMint::update_owner_and_ata(
-                        conn,
-                        &mint,
-                        new_owner.clone(),
+                    let tx_signature = Signature::new(sig.as_slice()).to_string();
+                    let block_time = self.block_time(slot).await;
+                    let txn = self.db.begin().await?;
+
+                    let applied = MintTransfer::apply_to_mint(
+                        &txn,
+                        mint.id,
                         destination.to_string(),
+                        &ObservedTransfer {
+                            signature: tx_signature.clone(),
+                            slot: slot.try_into()?,
+                            block_time,
+                            recipient: new_owner.clone(),
+                        },
                     )
                     .await?;
 
-                    self.producer
-                        .send(
-                            Some(&SolanaNftEvents {
-                                event: Some(UpdateMintOwner(MintOwnershipUpdate {
-                                    mint_address: destination_tkn_act.mint.to_string(),
-                                    sender: mint.owner.to_string(),
-                                    recipient: new_owner,
-                                    tx_signature: Signature::new(sig.as_slice()).to_string(),
-                                })),
-                            }),
-                            Some(&SolanaNftEventKey {
-                                id: mint.id.to_string(),
-                                ..Default::default()
-                            }),
-                        )
+                    // The transfer was already observed, e.g. by another
+                    // subscription or a webhook, and its owner change
+                    // reported
+                    let Some(applied) = applied else {
+                        continue;
+                    };
+
+                    self.track_transfer(&txn, unfinalized_transfers::Model {
+                        id: applied.id,
+                        mint_id: applied.mint_id,
+                        collection_id: applied.collection_id,
+                        compressed: false,
+                        slot: slot.try_into()?,
+                        signature: tx_signature.clone(),
+                        previous_owner: applied.sender.clone(),
+                        previous_token_account: applied.previous_token_account.clone(),
+                        recipient: new_owner.clone(),
+                        primary_sale: applied.primary_sale,
+                        created_at: Utc::now().naive_utc(),
+                    })
+                    .await?;
+
+                    txn.commit().await?;
+
+                    self.send_transfer(applied, new_owner, tx_signature, slot, block_time)
                         .await?;
                 }
             }
@@ -213,6 +758,294 @@ impl Processor {
 
         Ok(())
     }
+
+    /// Mark listings filled by `execute_sale` instructions of the auction
+    /// house program as sold and report the sales. The token transfer made
+    /// by the sale is indexed with the other SPL token transfers.
+    async fn process_auction_house_transaction(
+        &self,
+        program_account_index: usize,
+        keys: &[Vec<u8>],
+        sig: &[u8],
+        slot: u64,
+        instructions: &[TransactionInstruction<'_>],
+    ) -> Result<()> {
+        for ins in instructions {
+            let program_idx: usize = ins.program_id_index.try_into()?;
+
+            if program_idx != program_account_index {
+                continue;
+            }
+
+            let accounts = ins
+                .accounts
+                .iter()
+                .map(|&i| {
+                    let key: &[u8] = keys
+                        .get(usize::from(i))
+                        .context("account index out of range")?;
+                    Ok(Pubkey::try_from(key)?)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let Some(sale) = auction_house::executed_sale(&accounts, ins.data) else {
+                continue;
+            };
+
+            let tx_signature = Signature::new(sig).to_string();
+            let Some(listing) = Listing::mark_sold(
+                self.db.get(),
+                &sale.seller_trade_state.to_string(),
+                sale.buyer.to_string(),
+                tx_signature.clone(),
+            )
+            .await?
+            else {
+                continue;
+            };
+
+            info!(
+                "listing {} of mint {} sold to {} for {}",
+                listing.id, listing.mint_id, sale.buyer, sale.price
+            );
+
+            self.producer
+                .send(
+                    Some(&SolanaNftEvents::new(ListingSold(SolanaListingSale {
+                        listing_id: listing.id.to_string(),
+                        mint_id: listing.mint_id.to_string(),
+                        auction_house: listing.auction_house,
+                        seller: sale.seller.to_string(),
+                        buyer: sale.buyer.to_string(),
+                        price: sale.price,
+                        tx_signature,
+                        slot,
+                        block_time: self.block_time(slot).await,
+                    }))),
+                    Some(&SolanaNftEventKey {
+                        id: listing.id.to_string(),
+                        correlation_id: listing.mint_id.to_string(),
+                        ..Default::default()
+                    }),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Report the owner change made by an applied transfer, and the primary
+    /// sale it made if any
+    async fn send_transfer(
+        &self,
+        applied: AppliedTransfer,
+        recipient: String,
+        tx_signature: String,
+        slot: u64,
+        block_time: i64,
+    ) -> Result<()> {
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(UpdateMintOwner(
+                    MintOwnershipUpdate {
+                        mint_address: applied.mint_address.clone(),
+                        sender: applied.sender.clone(),
+                        recipient: recipient.clone(),
+                        tx_signature: tx_signature.clone(),
+                        slot,
+                        block_time,
+                    },
+                ))),
+                Some(&SolanaNftEventKey {
+                    id: applied.mint_id.to_string(),
+                    correlation_id: applied.mint_id.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        if applied.primary_sale {
+            self.send_primary_sale(applied.mint_id, SolanaPrimarySale {
+                mint_id: applied.mint_id.to_string(),
+                mint_address: applied.mint_address,
+                seller: applied.sender,
+                buyer: recipient,
+                tx_signature,
+                slot,
+                block_time,
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Report the primary sale of a mint, so hub-nfts can have the
+    /// `primary_sale_happened` flag of its metadata set
+    async fn send_primary_sale(&self, mint_id: Uuid, sale: SolanaPrimarySale) -> Result<()> {
+        info!("primary sale of mint {mint_id} detected");
+
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(PrimarySaleDetected(sale))),
+                Some(&SolanaNftEventKey {
+                    id: mint_id.to_string(),
+                    correlation_id: mint_id.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record the balances of both sides of a semi-fungible transfer of a
+    /// tracked mint and report the change
+    async fn process_balance_change(
+        &self,
+        source: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+        sig: &[u8],
+        slot: u64,
+        balances: &HashMap<Pubkey, TokenAccountBalance>,
+    ) -> Result<()> {
+        let (Some(from), Some(to)) = (balances.get(&source), balances.get(&destination)) else {
+            return Ok(());
+        };
+
+        let Some(mint) = CollectionMint::find_by_mint(self.db.read(), to.mint.clone()).await?
+        else {
+            return Ok(());
+        };
+
+        let tx_signature = Signature::new(sig).to_string();
+        let txn = self.db.begin().await?;
+
+        for (account, balance) in [(source, from), (destination, to)] {
+            TokenBalance::upsert(&txn, token_balances::Model {
+                token_account: account.to_string(),
+                collection_mint_id: mint.id,
+                mint: balance.mint.clone(),
+                owner: balance.owner.clone(),
+                amount: balance.amount.try_into()?,
+                slot: slot.try_into()?,
+                updated_at: Utc::now().naive_utc(),
+            })
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        let block_time = self.block_time(slot).await;
+
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(TokenBalanceChanged(
+                    SolanaTokenBalanceChange {
+                        mint_address: mint.mint.clone(),
+                        sender: from.owner.clone(),
+                        recipient: to.owner.clone(),
+                        amount,
+                        sender_balance: from.amount,
+                        recipient_balance: to.amount,
+                        tx_signature,
+                        slot,
+                        block_time,
+                    },
+                ))),
+                Some(&SolanaNftEventKey {
+                    id: mint.id.to_string(),
+                    correlation_id: mint.id.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Associated token accounts created by `Create` or `CreateIdempotent`
+/// instructions of the associated token account program, keyed by address
+fn created_token_accounts(
+    keys: &[Vec<u8>],
+    instructions: &[TransactionInstruction<'_>],
+) -> Result<HashMap<Pubkey, CreatedTokenAccount>> {
+    let key = |idx: &u8| {
+        keys.get(usize::from(*idx))
+            .map(|bytes| Pubkey::try_from(bytes.as_slice()))
+    };
+
+    let mut created = HashMap::new();
+
+    for ins in instructions {
+        let Some(program) = keys.get(usize::try_from(ins.program_id_index)?) else {
+            continue;
+        };
+
+        if program.as_slice() != spl_associated_token_account::ID.as_ref() {
+            continue;
+        }
+
+        // An empty instruction is the legacy encoding of `Create`
+        if !matches!(ins.data.first(), None | Some(0 | 1)) {
+            continue;
+        }
+
+        // An instruction missing any of its accounts would have failed on
+        // chain, so it is skipped
+        let (Some(account), Some(owner), Some(mint)) = (
+            ins.accounts.get(1).and_then(key),
+            ins.accounts.get(2).and_then(key),
+            ins.accounts.get(3).and_then(key),
+        ) else {
+            continue;
+        };
+
+        created.insert(account?, CreatedTokenAccount {
+            owner: owner?,
+            mint: mint?,
+        });
+    }
+
+    Ok(created)
+}
+
+/// Token balances after the transaction, keyed by token account. Accounts
+/// with a balance before the transaction but none after were closed and are
+/// reported as empty.
+fn token_account_balances(
+    keys: &[Vec<u8>],
+    meta: Option<&TransactionStatusMeta>,
+) -> Result<HashMap<Pubkey, TokenAccountBalance>> {
+    let mut balances = HashMap::new();
+
+    let Some(meta) = meta else {
+        return Ok(balances);
+    };
+
+    let pre = meta.pre_token_balances.iter().map(|b| (b, false));
+    let post = meta.post_token_balances.iter().map(|b| (b, true));
+
+    for (balance, after) in pre.chain(post) {
+        let account: &[u8] = keys
+            .get(usize::try_from(balance.account_index)?)
+            .context("token balance account index out of range")?;
+
+        let amount = match &balance.ui_token_amount {
+            Some(ui_amount) if after => ui_amount.amount.parse()?,
+            _ => 0,
+        };
+
+        balances.insert(Pubkey::try_from(account)?, TokenAccountBalance {
+            mint: balance.mint.clone(),
+            owner: balance.owner.clone(),
+            amount,
+        });
+    }
+
+    Ok(balances)
 }
 
 async fn fetch_account(
@@ -220,7 +1053,7 @@ async fn fetch_account(
     address: &Pubkey,
 ) -> Result<solana_sdk::account::Account, solana_client::client_error::ClientError> {
     backoff::future::retry(ExponentialBackoff::default(), || async {
-        let acct = rpc.get_account(address)?;
+        let acct = rpc.get_account(address).await?;
 
         Ok(acct)
     })
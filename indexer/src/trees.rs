@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use holaplex_hub_nfts_solana_core::{db::Connection, MerkleTree};
+use holaplex_hub_nfts_solana_entity::merkle_trees;
+use hub_core::{
+    metrics::{KeyValue, UpDownCounter},
+    prelude::*,
+    tokio,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::metrics::Metrics;
+
+/// Bytes of the account type, header version, buffer size, depth, authority,
+/// creation slot and padding preceding a tree
+const HEADER_SIZE: usize = 1 + 1 + 4 + 4 + 32 + 8 + 6;
+
+const NODE_SIZE: usize = 32;
+
+/// The shape of a concurrent merkle tree as read from its account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TreeDimensions {
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    /// Leaves appended to the tree, the index of its rightmost leaf plus one
+    leaves: u32,
+}
+
+impl TreeDimensions {
+    /// Read the header of a tree account and the rightmost proof following
+    /// its changelog buffer. Every byte after the tree belongs to the canopy,
+    /// which caches the top levels of the tree.
+    fn from_account(data: &[u8]) -> Option<Self> {
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_le_bytes)
+        };
+
+        let max_buffer_size = u32_at(2)?;
+        let max_depth = u32_at(6)?;
+        let depth = usize::try_from(max_depth).ok()?;
+        let buffer_size = usize::try_from(max_buffer_size).ok()?;
+
+        // Each changelog holds a root, a path and an index. The rightmost
+        // proof holds a path, the leaf and its index.
+        let changelog_size = NODE_SIZE + depth * NODE_SIZE + 8;
+        let rightmost_proof = HEADER_SIZE + 24 + buffer_size.checked_mul(changelog_size)?;
+        let leaves = u32_at(rightmost_proof + depth * NODE_SIZE + NODE_SIZE)?;
+        let tree_size = rightmost_proof + depth * NODE_SIZE + NODE_SIZE + 8;
+
+        // A canopy of depth d caches 2^(d + 1) - 2 nodes
+        let canopy_nodes = data.len().checked_sub(tree_size)? / NODE_SIZE;
+        let canopy_depth = (canopy_nodes + 2).ilog2().saturating_sub(1);
+
+        Some(Self {
+            max_depth,
+            max_buffer_size,
+            canopy_depth,
+            leaves,
+        })
+    }
+
+    fn capacity(self) -> i64 {
+        1_i64.checked_shl(self.max_depth).unwrap_or(i64::MAX)
+    }
+}
+
+/// Periodically reads the account of every tracked merkle tree and reports
+/// how full it is, how recently a leaf changed and how long its proofs are,
+/// so trees can be replaced before they run out of leaves.
+#[derive(Clone)]
+pub struct TreeMonitor {
+    db: Connection,
+    rpc: Arc<RpcClient>,
+    metrics: Metrics,
+    interval: Duration,
+}
+
+impl TreeMonitor {
+    pub fn new(db: Connection, rpc: Arc<RpcClient>, metrics: Metrics, interval: Duration) -> Self {
+        Self {
+            db,
+            rpc,
+            metrics,
+            interval,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        let mut gauges = Gauges::default();
+
+        loop {
+            interval.tick().await;
+
+            let trees = match MerkleTree::find_all(self.db.read()).await {
+                Ok(trees) => trees,
+                Err(e) => {
+                    error!("failed to load merkle trees: {e:?}");
+                    continue;
+                },
+            };
+
+            for tree in trees {
+                if let Err(e) = self.check(&mut gauges, tree).await {
+                    error!("failed to check merkle tree health: {e:?}");
+                }
+            }
+        }
+    }
+
+    async fn check(&self, gauges: &mut Gauges, tree: merkle_trees::Model) -> Result<()> {
+        let pubkey: Pubkey = tree.address.parse()?;
+        let account = self.rpc.get_account(&pubkey).await?;
+        let dimensions = TreeDimensions::from_account(&account.data)
+            .with_context(|| format!("invalid merkle tree account {}", tree.address))?;
+
+        MerkleTree::set_dimensions(
+            self.db.get(),
+            tree.address.clone(),
+            dimensions.max_depth.try_into()?,
+            dimensions.max_buffer_size.try_into()?,
+            dimensions.canopy_depth.try_into()?,
+        )
+        .await?;
+
+        // Leaves appended by transactions the indexer has not seen yet are
+        // already counted by the account
+        let leaves = tree.leaf_count.max(dimensions.leaves.into());
+        let capacity = dimensions.capacity();
+
+        if leaves >= capacity {
+            warn!("merkle tree {} is full", tree.address);
+        }
+
+        let metrics = &self.metrics;
+        let address = tree.address.as_str();
+        let proof_length = dimensions.max_depth.saturating_sub(dimensions.canopy_depth);
+
+        gauges.set(("leaves", &metrics.merkle_tree_leaves), address, leaves);
+        gauges.set(
+            ("capacity", &metrics.merkle_tree_capacity),
+            address,
+            capacity,
+        );
+        gauges.set(
+            ("proof_length", &metrics.merkle_tree_proof_length),
+            address,
+            proof_length.into(),
+        );
+        gauges.set(
+            ("canopy_depth", &metrics.merkle_tree_canopy_depth),
+            address,
+            dimensions.canopy_depth.into(),
+        );
+
+        if let Some(slot) = tree.last_leaf_slot {
+            gauges.set(
+                ("last_leaf_slot", &metrics.merkle_tree_last_leaf_slot),
+                address,
+                slot,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A gauge and the name its last values are kept under
+type Gauge<'a> = (&'static str, &'a UpDownCounter<i64>);
+
+/// The values last reported for each tree. Up-down counters only take
+/// deltas, so each gauge moves by the difference from its last value.
+#[derive(Default)]
+struct Gauges(HashMap<(&'static str, String), i64>);
+
+impl Gauges {
+    fn set(&mut self, gauge: Gauge<'_>, tree: &str, value: i64) {
+        let (name, counter) = gauge;
+        let last = self.0.insert((name, tree.to_owned()), value).unwrap_or(0);
+
+        counter.add(value - last, &[KeyValue::new("tree", tree.to_owned())]);
+    }
+}
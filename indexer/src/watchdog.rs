@@ -0,0 +1,227 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use holaplex_hub_nfts_solana_core::proto::{
+    solana_nft_events::Event::IndexerLagging, SolanaIndexerLagging, SolanaNftEventKey,
+    SolanaNftEvents,
+};
+use hub_core::{
+    prelude::*,
+    producer::Producer,
+    tokio::{self, sync::Notify},
+};
+
+use crate::metrics::Metrics;
+
+/// The slots of transactions held by one worker, or awaiting dispatch to
+/// one, with the number held of each
+#[derive(Debug, Default)]
+struct InFlight {
+    slots: Mutex<BTreeMap<u64, usize>>,
+    idle: Notify,
+}
+
+impl InFlight {
+    fn lock(&self) -> MutexGuard<BTreeMap<u64, usize>> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn add(&self, slot: u64) {
+        *self.lock().entry(slot).or_default() += 1;
+    }
+
+    fn remove(&self, slot: u64) {
+        let mut slots = self.lock();
+
+        if let Entry::Occupied(mut held) = slots.entry(slot) {
+            *held.get_mut() -= 1;
+
+            if *held.get() == 0 {
+                held.remove();
+            }
+        }
+
+        if slots.is_empty() {
+            self.idle.notify_waiters();
+        }
+    }
+
+    /// The oldest slot with a transaction still held
+    fn oldest(&self) -> Option<u64> {
+        self.lock().keys().next().copied()
+    }
+
+    async fn drained(&self) {
+        loop {
+            let idle = self.idle.notified();
+            let empty = self.lock().is_empty();
+
+            if empty {
+                return;
+            }
+
+            idle.await;
+        }
+    }
+}
+
+/// The latest slot announced by Geyser, and the slots of the transactions
+/// each worker has yet to process. A slot is processed once no worker holds
+/// a transaction of it or an earlier slot, so a worker behind the others
+/// holds the reported slot back.
+#[derive(Debug)]
+pub struct Slots {
+    seen: AtomicU64,
+    queued: InFlight,
+    shards: Vec<InFlight>,
+}
+
+impl Slots {
+    #[must_use]
+    pub fn new(shards: usize) -> Self {
+        Self {
+            seen: AtomicU64::default(),
+            queued: InFlight::default(),
+            shards: (0..shards.max(1)).map(|_| InFlight::default()).collect(),
+        }
+    }
+
+    pub fn seen(&self, slot: u64) {
+        self.seen.fetch_max(slot, Ordering::Relaxed);
+    }
+
+    /// A transaction of `slot` was received and awaits dispatch to a worker
+    pub fn queued(&self, slot: u64) {
+        self.queued.add(slot);
+    }
+
+    /// A transaction of `slot` was handed to the worker of `shard`
+    pub fn dispatched(&self, shard: usize, slot: u64) {
+        if let Some(worker) = self.shards.get(shard) {
+            worker.add(slot);
+        }
+
+        self.queued.remove(slot);
+    }
+
+    /// The worker of `shard` finished a transaction of `slot`
+    pub fn processed(&self, shard: usize, slot: u64) {
+        if let Some(worker) = self.shards.get(shard) {
+            worker.remove(slot);
+        }
+    }
+
+    /// Wait until the worker of `shard` holds no transactions
+    pub async fn drained(&self, shard: usize) {
+        if let Some(worker) = self.shards.get(shard) {
+            worker.drained().await;
+        }
+    }
+
+    /// `(seen, processed)`, or `None` until a slot has been seen
+    fn get(&self) -> Option<(u64, u64)> {
+        let seen = self.seen.load(Ordering::Relaxed);
+
+        if seen == 0 {
+            return None;
+        }
+
+        let oldest = std::iter::once(&self.queued)
+            .chain(&self.shards)
+            .filter_map(InFlight::oldest)
+            .min();
+
+        Some((seen, oldest.map_or(seen, |slot| slot.saturating_sub(1))))
+    }
+}
+
+/// Periodically compares the seen and processed slots, reporting the lag as
+/// a metric and emitting an event each time it first exceeds `threshold`.
+#[derive(Clone)]
+pub struct Watchdog {
+    slots: Arc<Slots>,
+    producer: Producer<SolanaNftEvents>,
+    metrics: Metrics,
+    interval: Duration,
+    threshold: u64,
+}
+
+impl Watchdog {
+    pub fn new(
+        slots: Arc<Slots>,
+        producer: Producer<SolanaNftEvents>,
+        metrics: Metrics,
+        interval: Duration,
+        threshold: u64,
+    ) -> Self {
+        Self {
+            slots,
+            producer,
+            metrics,
+            interval,
+            threshold,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        let mut lagging = false;
+
+        loop {
+            interval.tick().await;
+
+            let Some((seen, processed)) = self.slots.get() else {
+                continue;
+            };
+
+            let lag = seen.saturating_sub(processed);
+
+            self.metrics
+                .slot_lag_bucket
+                .record(lag.try_into().unwrap_or(i64::MAX), &[]);
+
+            if lag <= self.threshold {
+                if lagging {
+                    info!("indexer caught up, {lag} slots behind");
+                }
+
+                lagging = false;
+                continue;
+            }
+
+            if lagging {
+                continue;
+            }
+
+            lagging = true;
+            warn!("indexer is {lag} slots behind (seen {seen}, processed {processed})");
+            self.metrics.lagging_counter.add(1, &[]);
+
+            if let Err(e) = self.report(seen, processed, lag).await {
+                error!("failed to emit indexer lag event: {e:?}");
+            }
+        }
+    }
+
+    async fn report(&self, seen: u64, processed: u64, lag: u64) -> Result<()> {
+        self.producer
+            .send(
+                Some(&SolanaNftEvents::new(IndexerLagging(
+                    SolanaIndexerLagging {
+                        latest_slot: seen,
+                        processed_slot: processed,
+                        lag,
+                    },
+                ))),
+                Some(&SolanaNftEventKey::default()),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -7,6 +7,64 @@ mod m20230616_091724_backfill_associated_token_account_on_collection_mints;
 mod m20230721_135829_set_default_collection_and_mint_id;
 mod m20230725_143421_add_compression_leafs_table;
 mod m20230807_135202_update_revisions;
+mod m20230818_104512_create_transaction_failures_table;
+mod m20230822_091204_create_outbox_table;
+mod m20230828_142237_create_mint_transfer_histories_table;
+mod m20230904_103318_create_transaction_costs_table;
+mod m20230911_153027_create_event_requests_table;
+mod m20230915_110942_add_cluster_to_collections;
+mod m20230919_084516_add_default_royalties_to_collections;
+mod m20230921_143805_add_pagination_indexes_to_collection_mints;
+mod m20230925_102233_link_compression_leafs_to_collection_mints;
+mod m20230927_091538_create_project_treasuries_table;
+mod m20230929_120417_create_transaction_revisions_table;
+mod m20231002_093114_create_token_balances_table;
+mod m20231004_101522_create_claims_table;
+mod m20231006_141209_create_mint_allowlists_table;
+mod m20231009_112650_add_wallet_mint_limits;
+mod m20231011_093042_add_drop_schedule_to_collections;
+mod m20231013_150112_add_revenue_wallet_to_project_treasuries;
+mod m20231013_150326_add_mint_payments;
+mod m20231016_101847_create_custodial_wallets_table;
+mod m20231018_094512_add_soulbound_to_collections;
+mod m20231020_103527_add_edition_to_collection_mints;
+mod m20231023_140218_add_edition_supply_to_collections;
+mod m20231025_111904_add_metadata_version_to_collections;
+mod m20231027_133051_add_metadata_locks;
+mod m20231030_082615_add_primary_sale_to_collection_mints;
+mod m20231101_120344_create_collection_imports_table;
+mod m20231103_091527_create_dead_letters_table;
+mod m20231106_101415_track_merkle_tree_leaf_counts;
+mod m20231108_093742_create_transaction_audit_table;
+mod m20231110_141806_create_signer_policies_table;
+mod m20231113_102934_add_multisig_proposals;
+mod m20231115_090412_add_fee_payer_mode_to_project_treasuries;
+mod m20231117_103615_create_royalty_splits_table;
+mod m20231120_094127_create_asset_escrows_table;
+mod m20231122_083015_add_auction_house_to_project_treasuries;
+mod m20231122_083542_create_listings_table;
+mod m20231124_131207_create_candy_machines_table;
+mod m20231127_102231_add_health_to_merkle_trees;
+mod m20231128_091544_add_reservations_to_merkle_trees;
+mod m20231129_104826_create_collection_authorities_table;
+mod m20231130_093412_add_deleted_at_to_collections;
+mod m20231201_091236_reconcile_editions_with_collections;
+mod m20231204_101853_add_collection_foreign_key_to_compression_leafs;
+mod m20231206_142317_add_status_to_update_revisions;
+mod m20231208_110524_add_payload_to_update_revisions;
+mod m20231211_093412_make_mint_unique_on_collection_mints;
+mod m20231213_152046_make_asset_id_unique_on_compression_leafs;
+mod m20231215_104731_add_transfer_signature_index_to_mint_transfer_histories;
+mod m20231218_091455_create_unfinalized_transfers_table;
+mod m20231220_103248_create_transfer_backfills_table;
+mod m20231222_141530_add_solana_pay_to_claims;
+mod m20231226_091318_add_claims_and_failures_to_outbox;
+mod m20231227_101642_add_ephemeral_keypairs_to_transaction_revisions;
+mod m20231227_143018_add_unredeemed_mint_index_to_claims;
+mod m20231228_094215_create_nonce_accounts_table;
+mod m20231229_101427_add_project_id_to_collections;
+mod m20231229_142806_add_project_id_index_to_collections;
+mod m20240102_091524_add_pending_and_sent_indexes_to_outbox;
 
 pub struct Migrator;
 
@@ -21,6 +79,64 @@ impl MigratorTrait for Migrator {
             Box::new(m20230721_135829_set_default_collection_and_mint_id::Migration),
             Box::new(m20230725_143421_add_compression_leafs_table::Migration),
             Box::new(m20230807_135202_update_revisions::Migration),
+            Box::new(m20230818_104512_create_transaction_failures_table::Migration),
+            Box::new(m20230822_091204_create_outbox_table::Migration),
+            Box::new(m20230828_142237_create_mint_transfer_histories_table::Migration),
+            Box::new(m20230904_103318_create_transaction_costs_table::Migration),
+            Box::new(m20230911_153027_create_event_requests_table::Migration),
+            Box::new(m20230915_110942_add_cluster_to_collections::Migration),
+            Box::new(m20230919_084516_add_default_royalties_to_collections::Migration),
+            Box::new(m20230921_143805_add_pagination_indexes_to_collection_mints::Migration),
+            Box::new(m20230925_102233_link_compression_leafs_to_collection_mints::Migration),
+            Box::new(m20230927_091538_create_project_treasuries_table::Migration),
+            Box::new(m20230929_120417_create_transaction_revisions_table::Migration),
+            Box::new(m20231002_093114_create_token_balances_table::Migration),
+            Box::new(m20231004_101522_create_claims_table::Migration),
+            Box::new(m20231006_141209_create_mint_allowlists_table::Migration),
+            Box::new(m20231009_112650_add_wallet_mint_limits::Migration),
+            Box::new(m20231011_093042_add_drop_schedule_to_collections::Migration),
+            Box::new(m20231013_150112_add_revenue_wallet_to_project_treasuries::Migration),
+            Box::new(m20231013_150326_add_mint_payments::Migration),
+            Box::new(m20231016_101847_create_custodial_wallets_table::Migration),
+            Box::new(m20231018_094512_add_soulbound_to_collections::Migration),
+            Box::new(m20231020_103527_add_edition_to_collection_mints::Migration),
+            Box::new(m20231023_140218_add_edition_supply_to_collections::Migration),
+            Box::new(m20231025_111904_add_metadata_version_to_collections::Migration),
+            Box::new(m20231027_133051_add_metadata_locks::Migration),
+            Box::new(m20231030_082615_add_primary_sale_to_collection_mints::Migration),
+            Box::new(m20231101_120344_create_collection_imports_table::Migration),
+            Box::new(m20231103_091527_create_dead_letters_table::Migration),
+            Box::new(m20231106_101415_track_merkle_tree_leaf_counts::Migration),
+            Box::new(m20231108_093742_create_transaction_audit_table::Migration),
+            Box::new(m20231110_141806_create_signer_policies_table::Migration),
+            Box::new(m20231113_102934_add_multisig_proposals::Migration),
+            Box::new(m20231115_090412_add_fee_payer_mode_to_project_treasuries::Migration),
+            Box::new(m20231117_103615_create_royalty_splits_table::Migration),
+            Box::new(m20231120_094127_create_asset_escrows_table::Migration),
+            Box::new(m20231122_083015_add_auction_house_to_project_treasuries::Migration),
+            Box::new(m20231122_083542_create_listings_table::Migration),
+            Box::new(m20231124_131207_create_candy_machines_table::Migration),
+            Box::new(m20231127_102231_add_health_to_merkle_trees::Migration),
+            Box::new(m20231128_091544_add_reservations_to_merkle_trees::Migration),
+            Box::new(m20231129_104826_create_collection_authorities_table::Migration),
+            Box::new(m20231130_093412_add_deleted_at_to_collections::Migration),
+            Box::new(m20231201_091236_reconcile_editions_with_collections::Migration),
+            Box::new(m20231204_101853_add_collection_foreign_key_to_compression_leafs::Migration),
+            Box::new(m20231206_142317_add_status_to_update_revisions::Migration),
+            Box::new(m20231208_110524_add_payload_to_update_revisions::Migration),
+            Box::new(m20231211_093412_make_mint_unique_on_collection_mints::Migration),
+            Box::new(m20231213_152046_make_asset_id_unique_on_compression_leafs::Migration),
+            Box::new(m20231215_104731_add_transfer_signature_index_to_mint_transfer_histories::Migration),
+            Box::new(m20231218_091455_create_unfinalized_transfers_table::Migration),
+            Box::new(m20231220_103248_create_transfer_backfills_table::Migration),
+            Box::new(m20231222_141530_add_solana_pay_to_claims::Migration),
+            Box::new(m20231226_091318_add_claims_and_failures_to_outbox::Migration),
+            Box::new(m20231227_101642_add_ephemeral_keypairs_to_transaction_revisions::Migration),
+            Box::new(m20231227_143018_add_unredeemed_mint_index_to_claims::Migration),
+            Box::new(m20231228_094215_create_nonce_accounts_table::Migration),
+            Box::new(m20231229_101427_add_project_id_to_collections::Migration),
+            Box::new(m20231229_142806_add_project_id_index_to_collections::Migration),
+            Box::new(m20240102_091524_add_pending_and_sent_indexes_to_outbox::Migration),
         ]
     }
 }
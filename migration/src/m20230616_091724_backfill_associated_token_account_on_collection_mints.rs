@@ -1,9 +1,9 @@
 use std::str::FromStr;
 
-use holaplex_hub_nfts_solana_entity::collection_mints::{ActiveModel, Entity};
+use holaplex_hub_nfts_solana_entity::collection_mints::{Column, Entity};
 use sea_orm_migration::{
     prelude::*,
-    sea_orm::{ActiveModelTrait, EntityTrait, Set},
+    sea_orm::{prelude::Uuid, ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect},
     DbErr,
 };
 use solana_sdk::pubkey::Pubkey;
@@ -12,6 +12,13 @@ use spl_associated_token_account::get_associated_token_address;
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
+#[derive(FromQueryResult)]
+struct Mint {
+    id: Uuid,
+    owner: String,
+    mint: String,
+}
+
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
@@ -30,7 +37,16 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        let mints = Entity::find().all(manager.get_connection()).await?;
+        // Select only the columns that exist at this point in the schema so
+        // later columns added to the entity don't break fresh migrations
+        let mints = Entity::find()
+            .select_only()
+            .column(Column::Id)
+            .column(Column::Owner)
+            .column(Column::Mint)
+            .into_model::<Mint>()
+            .all(db)
+            .await?;
 
         for mint in mints {
             let wallet_address = Pubkey::from_str(&mint.owner)
@@ -40,10 +56,14 @@ impl MigrationTrait for Migration {
             let associated_token_account =
                 get_associated_token_address(&wallet_address, &mint_address);
 
-            let mut mint: ActiveModel = mint.into();
-            mint.associated_token_account = Set(associated_token_account.to_string());
-
-            mint.update(db).await?;
+            Entity::update_many()
+                .col_expr(
+                    Column::AssociatedTokenAccount,
+                    Expr::value(associated_token_account.to_string()),
+                )
+                .filter(Column::Id.eq(mint.id))
+                .exec(db)
+                .await?;
         }
 
         manager
@@ -63,7 +63,7 @@ impl MigrationTrait for Migration {
 
 /// Learn more at https://docs.rs/sea-query#iden
 #[derive(Iden)]
-enum CompressionLeafs {
+pub enum CompressionLeafs {
     Table,
     Id,
     MerkleTree,
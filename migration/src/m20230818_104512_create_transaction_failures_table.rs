@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionFailures::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransactionFailures::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionFailures::EventId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionFailures::EventKind)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionFailures::Reason)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionFailures::Message)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionFailures::Logs)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TransactionFailures::Signature).text())
+                    .col(
+                        ColumnDef::new(TransactionFailures::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("transaction_failures-event_id_idx")
+                    .table(TransactionFailures::Table)
+                    .col(TransactionFailures::EventId)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionFailures::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TransactionFailures {
+    Table,
+    Id,
+    EventId,
+    EventKind,
+    Reason,
+    Message,
+    Logs,
+    Signature,
+    CreatedAt,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Outbox::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Outbox::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Outbox::Key).binary().not_null())
+                    .col(ColumnDef::new(Outbox::Payload).binary().not_null())
+                    .col(
+                        ColumnDef::new(Outbox::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(Outbox::SentAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("outbox-created_at_idx")
+                    .table(Outbox::Table)
+                    .col(Outbox::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Outbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Outbox {
+    Table,
+    Id,
+    Key,
+    Payload,
+    CreatedAt,
+    SentAt,
+}
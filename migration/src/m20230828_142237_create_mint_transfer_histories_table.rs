@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MintTransferHistories::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MintTransferHistories::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::MintId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::Sender)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::Recipient)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::Signature)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::Slot)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintTransferHistories::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("mint_transfer_histories-mint_id_idx")
+                    .table(MintTransferHistories::Table)
+                    .col(MintTransferHistories::MintId)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MintTransferHistories::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum MintTransferHistories {
+    Table,
+    Id,
+    MintId,
+    Sender,
+    Recipient,
+    Signature,
+    Slot,
+    CreatedAt,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionCosts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransactionCosts::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TransactionCosts::EventId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TransactionCosts::EventKind)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionCosts::Signature)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionCosts::FeeLamports)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionCosts::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("transaction_costs-event_id_idx")
+                    .table(TransactionCosts::Table)
+                    .col(TransactionCosts::EventId)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionCosts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TransactionCosts {
+    Table,
+    Id,
+    EventId,
+    EventKind,
+    Signature,
+    FeeLamports,
+    CreatedAt,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventRequests::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EventRequests::EventId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EventRequests::Key).binary().not_null())
+                    .col(ColumnDef::new(EventRequests::Payload).binary().not_null())
+                    .col(
+                        ColumnDef::new(EventRequests::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EventRequests::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum EventRequests {
+    Table,
+    EventId,
+    Key,
+    Payload,
+    CreatedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(
+                        ColumnDef::new(Cluster::Cluster)
+                            .text()
+                            .not_null()
+                            .default("mainnet"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(Cluster::Cluster)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Cluster {
+    Cluster,
+}
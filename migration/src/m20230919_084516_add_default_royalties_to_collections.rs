@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(ColumnDef::new(DefaultRoyalties::SellerFeeBasisPoints).integer())
+                    .add_column(ColumnDef::new(DefaultRoyalties::Creators).json_binary())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(DefaultRoyalties::SellerFeeBasisPoints)
+                    .drop_column(DefaultRoyalties::Creators)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DefaultRoyalties {
+    SellerFeeBasisPoints,
+    Creators,
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230530_131917_create_collection_mints_table::CollectionMints;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("collection-mints_collection_id_created_at_id_idx")
+                    .table(CollectionMints::Table)
+                    .col(CollectionMints::CollectionId)
+                    .col(CollectionMints::CreatedAt)
+                    .col(CollectionMints::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("collection-mints_owner_created_at_id_idx")
+                    .table(CollectionMints::Table)
+                    .col(CollectionMints::Owner)
+                    .col(CollectionMints::CreatedAt)
+                    .col(CollectionMints::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collection-mints_collection_id_created_at_id_idx")
+                    .table(CollectionMints::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collection-mints_owner_created_at_id_idx")
+                    .table(CollectionMints::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230530_131917_create_collection_mints_table::CollectionMints;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CollectionMints::Table)
+                    .add_column(
+                        ColumnDef::new(Compressed::Compressed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Compressed NFTs have no token account, and their asset id is only
+        // known once the mint is confirmed
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO collection_mints
+                    (id, collection_id, mint, owner, associated_token_account, created_at, \
+                 compressed)
+                SELECT id, collection_id, COALESCE(asset_id, ''), leaf_owner, '', created_at, \
+                 true
+                FROM compression_leafs
+                ON CONFLICT (id) DO NOTHING",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM collection_mints WHERE compressed")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CollectionMints::Table)
+                    .drop_column(Compressed::Compressed)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Compressed {
+    Compressed,
+}
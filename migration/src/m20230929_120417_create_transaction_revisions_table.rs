@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransactionRevisions::EventId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::EventKind)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::SerializedMessage)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::Signers)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionRevisions::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TransactionRevisions {
+    Table,
+    EventId,
+    EventKind,
+    SerializedMessage,
+    Signers,
+    Attempts,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TokenBalances::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TokenBalances::TokenAccount)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TokenBalances::CollectionMintId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TokenBalances::Mint).text().not_null())
+                    .col(ColumnDef::new(TokenBalances::Owner).text().not_null())
+                    .col(
+                        ColumnDef::new(TokenBalances::Amount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TokenBalances::Slot).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(TokenBalances::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("token_balances-collection_mint_id_idx")
+                    .table(TokenBalances::Table)
+                    .col(TokenBalances::CollectionMintId)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("token_balances-owner_idx")
+                    .table(TokenBalances::Table)
+                    .col(TokenBalances::Owner)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TokenBalances::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TokenBalances {
+    Table,
+    TokenAccount,
+    CollectionMintId,
+    Mint,
+    Owner,
+    Amount,
+    Slot,
+    UpdatedAt,
+}
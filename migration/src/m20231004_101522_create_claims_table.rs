@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Claims::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Claims::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Claims::CollectionId).uuid().not_null())
+                    .col(ColumnDef::new(Claims::CollectionMintId).uuid())
+                    .col(ColumnDef::new(Claims::MintRequest).binary())
+                    .col(ColumnDef::new(Claims::Claimant).text().not_null())
+                    .col(ColumnDef::new(Claims::ExpiresAt).timestamp())
+                    .col(ColumnDef::new(Claims::RedemptionId).uuid())
+                    .col(ColumnDef::new(Claims::RedeemedAt).timestamp())
+                    .col(
+                        ColumnDef::new(Claims::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-claims_collection_id")
+                            .from(Claims::Table, Claims::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("claims-redemption_id_idx")
+                    .table(Claims::Table)
+                    .col(Claims::RedemptionId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("claims-collection_mint_id_idx")
+                    .table(Claims::Table)
+                    .col(Claims::CollectionMintId)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Claims::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Claims {
+    Table,
+    Id,
+    CollectionId,
+    CollectionMintId,
+    MintRequest,
+    Claimant,
+    ExpiresAt,
+    RedemptionId,
+    RedeemedAt,
+    CreatedAt,
+}
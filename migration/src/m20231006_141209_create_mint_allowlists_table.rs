@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MintAllowlists::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MintAllowlists::CollectionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MintAllowlists::Address).text().not_null())
+                    .col(
+                        ColumnDef::new(MintAllowlists::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(MintAllowlists::CollectionId)
+                            .col(MintAllowlists::Address),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-mint_allowlists_collection_id")
+                            .from(MintAllowlists::Table, MintAllowlists::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MintAllowlists::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MintAllowlists {
+    Table,
+    CollectionId,
+    Address,
+    CreatedAt,
+}
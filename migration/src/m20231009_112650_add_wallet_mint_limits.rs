@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(ColumnDef::new(MintLimits::MaxMintsPerWallet).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletMintCounts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WalletMintCounts::CollectionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WalletMintCounts::Wallet).text().not_null())
+                    .col(
+                        ColumnDef::new(WalletMintCounts::Count)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(WalletMintCounts::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(WalletMintCounts::CollectionId)
+                            .col(WalletMintCounts::Wallet),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-wallet_mint_counts_collection_id")
+                            .from(WalletMintCounts::Table, WalletMintCounts::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WalletMintCounts::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(MintLimits::MaxMintsPerWallet)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MintLimits {
+    MaxMintsPerWallet,
+}
+
+#[derive(Iden)]
+enum WalletMintCounts {
+    Table,
+    CollectionId,
+    Wallet,
+    Count,
+    UpdatedAt,
+}
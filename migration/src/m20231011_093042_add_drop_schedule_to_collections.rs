@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(ColumnDef::new(DropSchedule::MintOpenAt).timestamp())
+                    .add_column(ColumnDef::new(DropSchedule::MintCloseAt).timestamp())
+                    .add_column(ColumnDef::new(DropSchedule::OpenAnnouncedAt).timestamp())
+                    .add_column(ColumnDef::new(DropSchedule::CloseAnnouncedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(DropSchedule::MintOpenAt)
+                    .drop_column(DropSchedule::MintCloseAt)
+                    .drop_column(DropSchedule::OpenAnnouncedAt)
+                    .drop_column(DropSchedule::CloseAnnouncedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DropSchedule {
+    MintOpenAt,
+    MintCloseAt,
+    OpenAnnouncedAt,
+    CloseAnnouncedAt,
+}
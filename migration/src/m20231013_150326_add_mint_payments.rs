@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(ColumnDef::new(MintPrice::MintPrice).big_integer())
+                    .add_column(ColumnDef::new(MintPrice::PaymentMint).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(MintPayments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MintPayments::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MintPayments::CollectionId).uuid().not_null())
+                    .col(ColumnDef::new(MintPayments::Payer).text().not_null())
+                    .col(
+                        ColumnDef::new(MintPayments::RevenueWallet)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MintPayments::Amount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MintPayments::SplMint).text())
+                    .col(
+                        ColumnDef::new(MintPayments::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-mint_payments_collection_id")
+                            .from(MintPayments::Table, MintPayments::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MintPayments::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(MintPrice::MintPrice)
+                    .drop_column(MintPrice::PaymentMint)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MintPrice {
+    MintPrice,
+    PaymentMint,
+}
+
+#[derive(Iden)]
+enum MintPayments {
+    Table,
+    Id,
+    CollectionId,
+    Payer,
+    RevenueWallet,
+    Amount,
+    SplMint,
+    CreatedAt,
+}
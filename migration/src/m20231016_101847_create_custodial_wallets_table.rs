@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CustodialWallets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CustodialWallets::Email)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CustodialWallets::WalletAddress)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CustodialWallets::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CustodialWallets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CustodialWallets {
+    Table,
+    Email,
+    WalletAddress,
+    CreatedAt,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20230529_134752_create_collections_table::Collections,
+    m20230530_131917_create_collection_mints_table::CollectionMints,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column(ColumnDef::new(MetadataLocks::MetadataLockId).uuid())
+                    .add_column(ColumnDef::new(MetadataLocks::MetadataLockedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CollectionMints::Table)
+                    .add_column(ColumnDef::new(MetadataLocks::MetadataLockId).uuid())
+                    .add_column(ColumnDef::new(MetadataLocks::MetadataLockedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CollectionMints::Table)
+                    .drop_column(MetadataLocks::MetadataLockId)
+                    .drop_column(MetadataLocks::MetadataLockedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(MetadataLocks::MetadataLockId)
+                    .drop_column(MetadataLocks::MetadataLockedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MetadataLocks {
+    MetadataLockId,
+    MetadataLockedAt,
+}
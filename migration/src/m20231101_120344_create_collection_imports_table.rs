@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionImports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CollectionImports::CollectionId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::MintAddress)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::Imported)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::SkippedBurned)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::SkippedFungible)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::SkippedUnverified)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::StartedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionImports::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(CollectionImports::CompletedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CollectionImports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CollectionImports {
+    Table,
+    CollectionId,
+    MintAddress,
+    Imported,
+    SkippedBurned,
+    SkippedFungible,
+    SkippedUnverified,
+    StartedAt,
+    UpdatedAt,
+    CompletedAt,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeadLetters::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeadLetters::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DeadLetters::Topic).text().not_null())
+                    .col(ColumnDef::new(DeadLetters::Key).binary().not_null())
+                    .col(ColumnDef::new(DeadLetters::Payload).binary().not_null())
+                    .col(ColumnDef::new(DeadLetters::Error).text().not_null())
+                    .col(
+                        ColumnDef::new(DeadLetters::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(DeadLetters::ReplayedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("dead_letters-created_at_idx")
+                    .table(DeadLetters::Table)
+                    .col(DeadLetters::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeadLetters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DeadLetters {
+    Table,
+    Id,
+    Topic,
+    Key,
+    Payload,
+    Error,
+    CreatedAt,
+    ReplayedAt,
+}
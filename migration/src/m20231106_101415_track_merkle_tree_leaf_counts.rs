@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MerkleTrees::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MerkleTrees::Address)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MerkleTrees::LeafCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MerkleTrees::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CompressionLeafs::Table)
+                    .add_column(ColumnDef::new(CompressionLeafs::MintSignature).text())
+                    .add_column(ColumnDef::new(CompressionLeafs::AssetIdConfirmedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CompressionLeafs::Table)
+                    .drop_column(CompressionLeafs::MintSignature)
+                    .drop_column(CompressionLeafs::AssetIdConfirmedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(MerkleTrees::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum MerkleTrees {
+    Table,
+    Address,
+    LeafCount,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum CompressionLeafs {
+    Table,
+    MintSignature,
+    AssetIdConfirmedAt,
+}
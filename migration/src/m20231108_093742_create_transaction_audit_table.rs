@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionAudit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransactionAudit::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TransactionAudit::EventId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TransactionAudit::EventKind)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TransactionAudit::UserId).text().not_null())
+                    .col(
+                        ColumnDef::new(TransactionAudit::ProjectId)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TransactionAudit::Status).text().not_null())
+                    .col(ColumnDef::new(TransactionAudit::MessageHash).text())
+                    .col(
+                        ColumnDef::new(TransactionAudit::Signers)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TransactionAudit::Signature).text())
+                    .col(
+                        ColumnDef::new(TransactionAudit::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("transaction_audit-event_id_idx")
+                    .table(TransactionAudit::Table)
+                    .col(TransactionAudit::EventId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("transaction_audit-signature_idx")
+                    .table(TransactionAudit::Table)
+                    .col(TransactionAudit::Signature)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransactionAudit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TransactionAudit {
+    Table,
+    Id,
+    EventId,
+    EventKind,
+    UserId,
+    ProjectId,
+    Status,
+    MessageHash,
+    Signers,
+    Signature,
+    CreatedAt,
+}
@@ -0,0 +1,135 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProjectTreasuries::Table)
+                    .add_column(ColumnDef::new(Multisig::MultisigAddress).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(MultisigProposals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MultisigProposals::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MultisigProposals::EventId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(MultisigProposals::EventKind)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MultisigProposals::ProjectId)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MultisigProposals::Multisig)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MultisigProposals::TransactionIndex)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MultisigProposals::Proposal)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MultisigProposals::Status).text().not_null())
+                    .col(ColumnDef::new(MultisigProposals::Signature).text())
+                    .col(
+                        ColumnDef::new(MultisigProposals::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(MultisigProposals::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("multisig_proposals-event_id_idx")
+                    .table(MultisigProposals::Table)
+                    .col(MultisigProposals::EventId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("multisig_proposals-multisig-status_idx")
+                    .table(MultisigProposals::Table)
+                    .col(MultisigProposals::Multisig)
+                    .col(MultisigProposals::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MultisigProposals::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProjectTreasuries::Table)
+                    .drop_column(Multisig::MultisigAddress)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Multisig {
+    MultisigAddress,
+}
+
+#[derive(Iden)]
+enum MultisigProposals {
+    Table,
+    Id,
+    EventId,
+    EventKind,
+    ProjectId,
+    Multisig,
+    TransactionIndex,
+    Proposal,
+    Status,
+    Signature,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum ProjectTreasuries {
+    Table,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProjectTreasuries::Table)
+                    .add_column(
+                        ColumnDef::new(FeePayer::FeePayerMode)
+                            .text()
+                            .not_null()
+                            .default("project"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProjectTreasuries::Table)
+                    .drop_column(FeePayer::FeePayerMode)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum FeePayer {
+    FeePayerMode,
+}
+
+#[derive(Iden)]
+enum ProjectTreasuries {
+    Table,
+}
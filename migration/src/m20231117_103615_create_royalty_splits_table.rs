@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoyaltySplits::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RoyaltySplits::CollectionId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RoyaltySplits::EventId).uuid().not_null())
+                    .col(ColumnDef::new(RoyaltySplits::Name).text().not_null())
+                    .col(ColumnDef::new(RoyaltySplits::Authority).text().not_null())
+                    .col(ColumnDef::new(RoyaltySplits::Fanout).text().not_null())
+                    .col(
+                        ColumnDef::new(RoyaltySplits::HoldingAccount)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoyaltySplits::Members)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoyaltySplits::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(RoyaltySplits::ConfirmedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-royalty_splits_collection_id")
+                            .from(RoyaltySplits::Table, RoyaltySplits::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("royalty_splits-event_id_idx")
+                    .table(RoyaltySplits::Table)
+                    .col(RoyaltySplits::EventId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RoyaltySplits::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RoyaltySplits {
+    Table,
+    CollectionId,
+    EventId,
+    Name,
+    Authority,
+    Fanout,
+    HoldingAccount,
+    Members,
+    CreatedAt,
+    ConfirmedAt,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AssetEscrows::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AssetEscrows::MintId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AssetEscrows::Depositor).text().not_null())
+                    .col(ColumnDef::new(AssetEscrows::EscrowWallet).text().not_null())
+                    .col(
+                        ColumnDef::new(AssetEscrows::EscrowEventId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AssetEscrows::ReleaseEventId).uuid())
+                    .col(ColumnDef::new(AssetEscrows::ReleaseRecipient).text())
+                    .col(
+                        ColumnDef::new(AssetEscrows::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(AssetEscrows::EscrowedAt).timestamp())
+                    .col(ColumnDef::new(AssetEscrows::ReleasedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("asset_escrows-escrow_event_id_idx")
+                    .table(AssetEscrows::Table)
+                    .col(AssetEscrows::EscrowEventId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("asset_escrows-release_event_id_idx")
+                    .table(AssetEscrows::Table)
+                    .col(AssetEscrows::ReleaseEventId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AssetEscrows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AssetEscrows {
+    Table,
+    MintId,
+    Depositor,
+    EscrowWallet,
+    EscrowEventId,
+    ReleaseEventId,
+    ReleaseRecipient,
+    CreatedAt,
+    EscrowedAt,
+    ReleasedAt,
+}
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Listings::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Listings::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Listings::MintId).uuid().not_null())
+                    .col(ColumnDef::new(Listings::AuctionHouse).text().not_null())
+                    .col(ColumnDef::new(Listings::Seller).text().not_null())
+                    .col(ColumnDef::new(Listings::TokenAccount).text().not_null())
+                    .col(ColumnDef::new(Listings::Price).big_integer().not_null())
+                    .col(ColumnDef::new(Listings::TradeState).text().not_null())
+                    .col(
+                        ColumnDef::new(Listings::Status)
+                            .text()
+                            .not_null()
+                            .default("requested"),
+                    )
+                    .col(ColumnDef::new(Listings::CancelEventId).uuid())
+                    .col(ColumnDef::new(Listings::Buyer).text())
+                    .col(ColumnDef::new(Listings::SaleSignature).text())
+                    .col(
+                        ColumnDef::new(Listings::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(
+                        ColumnDef::new(Listings::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("listings-mint_id_idx")
+                    .table(Listings::Table)
+                    .col(Listings::MintId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("listings-trade_state_idx")
+                    .table(Listings::Table)
+                    .col(Listings::TradeState)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("listings-cancel_event_id_idx")
+                    .table(Listings::Table)
+                    .col(Listings::CancelEventId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Listings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Listings {
+    Table,
+    Id,
+    MintId,
+    AuctionHouse,
+    Seller,
+    TokenAccount,
+    Price,
+    TradeState,
+    Status,
+    CancelEventId,
+    Buyer,
+    SaleSignature,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,166 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230529_134752_create_collections_table::Collections;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CandyMachines::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CandyMachines::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachines::CollectionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CandyMachines::Address).text().not_null())
+                    .col(ColumnDef::new(CandyMachines::CandyGuard).text().not_null())
+                    .col(ColumnDef::new(CandyMachines::Authority).text().not_null())
+                    .col(
+                        ColumnDef::new(CandyMachines::Items)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachines::IsSequential)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachines::ItemsAvailable)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachines::ItemsLoaded)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachines::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(CandyMachines::DeployedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-candy_machines_collection_id")
+                            .from(CandyMachines::Table, CandyMachines::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("candy_machines-collection_id_idx")
+                    .table(CandyMachines::Table)
+                    .col(CandyMachines::CollectionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CandyMachineBatches::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CandyMachineBatches::EventId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachineBatches::CandyMachineId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachineBatches::StartIndex)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachineBatches::EndIndex)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CandyMachineBatches::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(CandyMachineBatches::InsertedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-candy_machine_batches_candy_machine_id")
+                            .from(
+                                CandyMachineBatches::Table,
+                                CandyMachineBatches::CandyMachineId,
+                            )
+                            .to(CandyMachines::Table, CandyMachines::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CandyMachineBatches::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CandyMachines::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CandyMachines {
+    Table,
+    Id,
+    CollectionId,
+    Address,
+    CandyGuard,
+    Authority,
+    Items,
+    IsSequential,
+    ItemsAvailable,
+    ItemsLoaded,
+    CreatedAt,
+    DeployedAt,
+}
+
+#[derive(Iden)]
+enum CandyMachineBatches {
+    Table,
+    EventId,
+    CandyMachineId,
+    StartIndex,
+    EndIndex,
+    CreatedAt,
+    InsertedAt,
+}
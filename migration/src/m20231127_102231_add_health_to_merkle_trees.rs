@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231106_101415_track_merkle_tree_leaf_counts::MerkleTrees;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerkleTrees::Table)
+                    .add_column(ColumnDef::new(TreeHealth::MaxDepth).integer())
+                    .add_column(ColumnDef::new(TreeHealth::MaxBufferSize).integer())
+                    .add_column(ColumnDef::new(TreeHealth::CanopyDepth).integer())
+                    .add_column(ColumnDef::new(TreeHealth::LastLeafSlot).big_integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerkleTrees::Table)
+                    .drop_column(TreeHealth::MaxDepth)
+                    .drop_column(TreeHealth::MaxBufferSize)
+                    .drop_column(TreeHealth::CanopyDepth)
+                    .drop_column(TreeHealth::LastLeafSlot)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TreeHealth {
+    MaxDepth,
+    MaxBufferSize,
+    CanopyDepth,
+    LastLeafSlot,
+}
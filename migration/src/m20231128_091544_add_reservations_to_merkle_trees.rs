@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231106_101415_track_merkle_tree_leaf_counts::MerkleTrees;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerkleTrees::Table)
+                    .add_column(ColumnDef::new(Reservations::CollectionId).uuid())
+                    .add_column(ColumnDef::new(Reservations::ReserveEventId).uuid())
+                    .add_column(ColumnDef::new(Reservations::ReservedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("merkle-trees_collection_id_idx")
+                    .table(MerkleTrees::Table)
+                    .col(Reservations::CollectionId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("merkle-trees_collection_id_idx")
+                    .table(MerkleTrees::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerkleTrees::Table)
+                    .drop_column(Reservations::CollectionId)
+                    .drop_column(Reservations::ReserveEventId)
+                    .drop_column(Reservations::ReservedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Reservations {
+    CollectionId,
+    ReserveEventId,
+    ReservedAt,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionAuthorities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::ProjectId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::MintAddress)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::UpdateAuthority)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::Challenge)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::ExpiresAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CollectionAuthorities::VerifiedAt).timestamp())
+                    .col(
+                        ColumnDef::new(CollectionAuthorities::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(CollectionAuthorities::ProjectId)
+                            .col(CollectionAuthorities::MintAddress),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CollectionAuthorities::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CollectionAuthorities {
+    Table,
+    ProjectId,
+    MintAddress,
+    UpdateAuthority,
+    Challenge,
+    ExpiresAt,
+    VerifiedAt,
+    CreatedAt,
+}
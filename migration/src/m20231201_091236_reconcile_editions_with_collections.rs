@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Master editions and certified collections both live in
+        // `collections`, told apart by `max_supply`. Databases which ran an
+        // earlier split of the table into `editions` and
+        // `certified_collections` are brought back to that single table.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DO $$
+                BEGIN
+                    IF to_regclass('editions') IS NOT NULL
+                        AND to_regclass('collections') IS NULL THEN
+                        ALTER TABLE editions RENAME TO collections;
+                    END IF;
+
+                    IF to_regclass('certified_collections') IS NOT NULL THEN
+                        IF EXISTS (SELECT 1 FROM certified_collections) THEN
+                            RAISE EXCEPTION 'certified_collections still holds rows, move \
+                 them into collections first';
+                        END IF;
+
+                        DROP TABLE certified_collections;
+                    END IF;
+                END $$",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // The split schema is not restored, `collections` is the only model
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20230529_134752_create_collections_table::Collections,
+    m20230725_143421_add_compression_leafs_table::CompressionLeafs,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Leaves of collections deleted before collections were archived
+        // would otherwise fail the constraint
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM compression_leafs
+                WHERE collection_id NOT IN (SELECT id FROM collections)",
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-compression_leafs_collection_id")
+                    .from(CompressionLeafs::Table, CompressionLeafs::CollectionId)
+                    .to(Collections::Table, Collections::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("compression-leafs_collection_id_idx")
+                    .table(CompressionLeafs::Table)
+                    .col(CompressionLeafs::CollectionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("compression-leafs_asset_id_idx")
+                    .table(CompressionLeafs::Table)
+                    .col(CompressionLeafs::AssetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("compression-leafs_leaf_owner_idx")
+                    .table(CompressionLeafs::Table)
+                    .col(CompressionLeafs::LeafOwner)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [
+            "compression-leafs_leaf_owner_idx",
+            "compression-leafs_asset_id_idx",
+            "compression-leafs_collection_id_idx",
+        ] {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name(name)
+                        .table(CompressionLeafs::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk-compression_leafs_collection_id")
+                    .table(CompressionLeafs::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
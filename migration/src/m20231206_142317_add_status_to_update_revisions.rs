@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UpdateRevisions::Table)
+                    .add_column(
+                        ColumnDef::new(UpdateRevisions::Status)
+                            .text()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .add_column(ColumnDef::new(UpdateRevisions::Signature).text())
+                    .add_column(
+                        ColumnDef::new(UpdateRevisions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("update-revisions_created_at_idx")
+                    .table(UpdateRevisions::Table)
+                    .col(UpdateRevisions::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("update-revisions_created_at_idx")
+                    .table(UpdateRevisions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UpdateRevisions::Table)
+                    .drop_column(UpdateRevisions::Status)
+                    .drop_column(UpdateRevisions::Signature)
+                    .drop_column(UpdateRevisions::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum UpdateRevisions {
+    Table,
+    Status,
+    Signature,
+    CreatedAt,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230530_131917_create_collection_mints_table::CollectionMints;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Keep the first active row stored at each address, archiving the
+        // rest rather than deleting them, as they may still be referenced by
+        // their id. Compressed mints have no address until they are
+        // confirmed.
+        conn.execute_unprepared(
+            "WITH duplicates AS (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY mint
+                        ORDER BY created_at, id
+                    ) AS rank
+                    FROM collection_mints
+                    WHERE mint <> '' AND deleted_at IS NULL
+                ) ranked
+                WHERE rank > 1
+            )
+            UPDATE collection_mints SET deleted_at = NOW()
+            WHERE id IN (SELECT id FROM duplicates)",
+        )
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collection-mints_address_idx")
+                    .table(CollectionMints::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        conn.execute_unprepared(
+            "CREATE UNIQUE INDEX \"collection-mints_mint_unique_idx\"
+            ON collection_mints (mint)
+            WHERE mint <> '' AND deleted_at IS NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collection-mints_mint_unique_idx")
+                    .table(CollectionMints::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("collection-mints_address_idx")
+                    .table(CollectionMints::Table)
+                    .col(CollectionMints::Mint)
+                    .index_type(IndexType::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Keep the first leaf confirmed at each asset id, archiving the rest
+        // along with their linked mints
+        conn.execute_unprepared(
+            "WITH duplicates AS (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY asset_id
+                        ORDER BY created_at, id
+                    ) AS rank
+                    FROM compression_leafs
+                    WHERE asset_id_confirmed_at IS NOT NULL AND deleted_at IS NULL
+                ) ranked
+                WHERE rank > 1
+            ), archived AS (
+                UPDATE compression_leafs SET deleted_at = NOW()
+                WHERE id IN (SELECT id FROM duplicates)
+            )
+            UPDATE collection_mints SET deleted_at = NOW()
+            WHERE id IN (SELECT id FROM duplicates) AND deleted_at IS NULL",
+        )
+        .await?;
+
+        // Predicted asset ids are only unique once confirmed, as a wrong
+        // prediction may name the asset of another leaf
+        conn.execute_unprepared(
+            "CREATE UNIQUE INDEX \"compression-leafs_asset_id_unique_idx\"
+            ON compression_leafs (asset_id)
+            WHERE asset_id_confirmed_at IS NOT NULL AND deleted_at IS NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX \"compression-leafs_asset_id_unique_idx\"")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230828_142237_create_mint_transfer_histories_table::MintTransferHistories;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Transfers observed more than once were each recorded before
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM mint_transfer_histories
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, ROW_NUMBER() OVER (
+                            PARTITION BY mint_id, signature
+                            ORDER BY created_at, id
+                        ) AS rank
+                        FROM mint_transfer_histories
+                    ) ranked
+                    WHERE rank > 1
+                )",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("mint_transfer_histories-mint_id_signature_idx")
+                    .table(MintTransferHistories::Table)
+                    .col(MintTransferHistories::MintId)
+                    .col(MintTransferHistories::Signature)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("mint_transfer_histories-mint_id_signature_idx")
+                    .table(MintTransferHistories::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UnfinalizedTransfers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::MintId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::CollectionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::Compressed)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::Slot)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::Signature)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::PreviousOwner)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UnfinalizedTransfers::PreviousTokenAccount).text())
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::Recipient)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::PrimarySale)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(UnfinalizedTransfers::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("unfinalized_transfers_slot_idx")
+                    .table(UnfinalizedTransfers::Table)
+                    .col(UnfinalizedTransfers::Slot)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UnfinalizedTransfers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum UnfinalizedTransfers {
+    Table,
+    Id,
+    MintId,
+    CollectionId,
+    Compressed,
+    Slot,
+    Signature,
+    PreviousOwner,
+    PreviousTokenAccount,
+    Recipient,
+    PrimarySale,
+    CreatedAt,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransferBackfills::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransferBackfills::MintId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TransferBackfills::CollectionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TransferBackfills::MintAddress)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TransferBackfills::Before).text())
+                    .col(
+                        ColumnDef::new(TransferBackfills::Scanned)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(TransferBackfills::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(TransferBackfills::QueuedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .col(ColumnDef::new(TransferBackfills::CompletedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX transfer_backfills_pending_idx ON transfer_backfills (queued_at) \
+                 WHERE completed_at IS NULL;",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TransferBackfills::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TransferBackfills {
+    Table,
+    MintId,
+    CollectionId,
+    MintAddress,
+    Before,
+    Scanned,
+    Attempts,
+    QueuedAt,
+    CompletedAt,
+}
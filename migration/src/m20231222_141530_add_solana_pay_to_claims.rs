@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Claims::Table)
+                    .add_column(ColumnDef::new(Claims::ProjectId).uuid())
+                    .add_column(ColumnDef::new(Claims::PayTransaction).binary())
+                    .add_column(ColumnDef::new(Claims::PayRequestedAt).timestamp())
+                    .add_column(ColumnDef::new(Claims::PaySignedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Claims::Table)
+                    .drop_column(Claims::ProjectId)
+                    .drop_column(Claims::PayTransaction)
+                    .drop_column(Claims::PayRequestedAt)
+                    .drop_column(Claims::PaySignedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Claims {
+    Table,
+    ProjectId,
+    PayTransaction,
+    PayRequestedAt,
+    PaySignedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Outbox::Table)
+                    .add_column(ColumnDef::new(Outbox::ClaimedAt).timestamp())
+                    .add_column(ColumnDef::new(Outbox::FailedAt).timestamp())
+                    .add_column(ColumnDef::new(Outbox::Error).text())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Outbox::Table)
+                    .drop_column(Outbox::ClaimedAt)
+                    .drop_column(Outbox::FailedAt)
+                    .drop_column(Outbox::Error)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Outbox {
+    Table,
+    ClaimedAt,
+    FailedAt,
+    Error,
+}
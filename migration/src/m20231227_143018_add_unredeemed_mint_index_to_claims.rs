@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, sea_orm::Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Concurrent claims may already reserve the same mint. Which claimant
+        // keeps it is for the project to decide, so report them rather than
+        // dropping any.
+        let duplicates = conn
+            .query_all(Statement::from_string(
+                manager.get_database_backend(),
+                "SELECT collection_mint_id::text AS collection_mint_id,
+                    string_agg(id::text, ', ' ORDER BY created_at) AS claims
+                FROM claims
+                WHERE collection_mint_id IS NOT NULL AND redeemed_at IS NULL
+                GROUP BY collection_mint_id
+                HAVING COUNT(*) > 1"
+                    .to_owned(),
+            ))
+            .await?
+            .into_iter()
+            .map(|row| {
+                let mint: String = row.try_get("", "collection_mint_id")?;
+                let claims: String = row.try_get("", "claims")?;
+
+                Ok(format!("mint {mint} is reserved by claims {claims}"))
+            })
+            .collect::<Result<Vec<_>, DbErr>>()?;
+
+        if !duplicates.is_empty() {
+            return Err(DbErr::Migration(format!(
+                "unredeemed claims share a mint, delete all but one claim of each: {}",
+                duplicates.join("; ")
+            )));
+        }
+
+        conn.execute_unprepared(
+            "CREATE UNIQUE INDEX \"claims_unredeemed_collection_mint_unique_idx\"
+            ON claims (collection_mint_id)
+            WHERE redeemed_at IS NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX \"claims_unredeemed_collection_mint_unique_idx\"")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NonceAccounts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NonceAccounts::Address)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(NonceAccounts::Cluster).text().not_null())
+                    .col(ColumnDef::new(NonceAccounts::Authority).text().not_null())
+                    .col(ColumnDef::new(NonceAccounts::EventId).uuid())
+                    .col(ColumnDef::new(NonceAccounts::LeasedAt).timestamp())
+                    .col(ColumnDef::new(NonceAccounts::StaleNonce).text())
+                    .col(
+                        ColumnDef::new(NonceAccounts::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("default now()".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("nonce-accounts_cluster_authority_idx")
+                    .table(NonceAccounts::Table)
+                    .col(NonceAccounts::Cluster)
+                    .col(NonceAccounts::Authority)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("nonce-accounts_event_id_idx")
+                    .table(NonceAccounts::Table)
+                    .col(NonceAccounts::EventId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NonceAccounts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum NonceAccounts {
+    Table,
+    Address,
+    Cluster,
+    Authority,
+    EventId,
+    LeasedAt,
+    StaleNonce,
+    CreatedAt,
+}
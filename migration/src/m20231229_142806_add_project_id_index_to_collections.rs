@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("collections-project_id_created_at_idx")
+                    .table(Collections::Table)
+                    .col(Collections::ProjectId)
+                    .col(Collections::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collections-project_id_created_at_idx")
+                    .table(Collections::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Collections {
+    Table,
+    ProjectId,
+    CreatedAt,
+}
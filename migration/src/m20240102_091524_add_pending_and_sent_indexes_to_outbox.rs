@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // The relay only reads pending events, which are a small tail of a
+        // table mostly holding sent ones
+        conn.execute_unprepared(
+            "CREATE INDEX \"outbox-pending_created_at_idx\"
+            ON outbox (created_at)
+            WHERE sent_at IS NULL AND failed_at IS NULL",
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            "CREATE INDEX \"outbox-sent_at_idx\"
+            ON outbox (sent_at)
+            WHERE sent_at IS NOT NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("DROP INDEX \"outbox-sent_at_idx\"")
+            .await?;
+        conn.execute_unprepared("DROP INDEX \"outbox-pending_created_at_idx\"")
+            .await?;
+
+        Ok(())
+    }
+}
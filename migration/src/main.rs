@@ -1,6 +1,99 @@
-use sea_orm_migration::prelude::*;
+//! Applies the migrations of the service while holding a Postgres advisory
+//! lock, so replicas starting at the same time run them one after the other
+//! rather than racing each other.
+
+use clap::{Parser, Subcommand};
+use migration::Migrator;
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Statement},
+};
+
+/// Key of the advisory lock held while migrations run, shared by every
+/// replica of the service
+const LOCK_KEY: i64 = 0x6875_625f_6e66_7473;
+
+#[derive(Parser)]
+#[clap(about = "Apply or inspect the migrations of the database")]
+struct Cli {
+    #[clap(short = 'u', long, env)]
+    database_url: String,
+
+    /// What to run, applying every pending migration if left out
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply pending migrations, all of them unless a number is given
+    Up {
+        #[clap(short, long)]
+        num: Option<u32>,
+    },
+    /// Roll back applied migrations, the last one unless a number is given
+    Down {
+        #[clap(short, long, default_value_t = 1)]
+        num: u32,
+    },
+    /// Drop every table, then apply all migrations
+    Fresh,
+    /// Roll back every applied migration, then apply them all again
+    Refresh,
+    /// Roll back every applied migration
+    Reset,
+    /// List the migrations and whether each has been applied
+    Status,
+}
 
 #[async_std::main]
 async fn main() {
-    cli::run_cli(migration::Migrator).await;
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("migration failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), DbErr> {
+    // Session-level advisory locks belong to the connection taking them, so
+    // the lock and the migrations share a pool of one connection
+    let mut opts = ConnectOptions::new(cli.database_url);
+    opts.max_connections(1).min_connections(1);
+    let db = Database::connect(opts).await?;
+
+    println!("waiting for the migration lock");
+    advisory_lock(&db, "pg_advisory_lock").await?;
+
+    let res = migrate(&db, cli.command.unwrap_or(Command::Up { num: None })).await;
+
+    advisory_lock(&db, "pg_advisory_unlock").await?;
+
+    res
+}
+
+async fn migrate(db: &DatabaseConnection, command: Command) -> Result<(), DbErr> {
+    match command {
+        Command::Up { num } => Migrator::up(db, num).await?,
+        Command::Down { num } => Migrator::down(db, Some(num)).await?,
+        Command::Fresh => Migrator::fresh(db).await?,
+        Command::Refresh => Migrator::refresh(db).await?,
+        Command::Reset => Migrator::reset(db).await?,
+        Command::Status => {
+            for migration in Migrator::get_migration_with_status(db).await? {
+                println!("{}\t{}", migration.status(), migration.name());
+            }
+        },
+    }
+
+    Ok(())
+}
+
+async fn advisory_lock(db: &DatabaseConnection, function: &str) -> Result<(), DbErr> {
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        format!("SELECT {function}({LOCK_KEY})"),
+    ))
+    .await?;
+
+    Ok(())
 }
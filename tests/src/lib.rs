@@ -0,0 +1,249 @@
+//! Harness for end-to-end tests of the event processor.
+//!
+//! Each [`TestContext`] migrates a fresh database on the Postgres server at
+//! `DATABASE_URL` and submits transactions to the validator at
+//! `SOLANA_TEST_VALIDATOR_URL` (`http://127.0.0.1:8899` if unset). The tests
+//! are ignored by default; start a validator with `ci/start-test-validator.sh`
+//! and run them with
+//! `cargo test -p holaplex-hub-nfts-solana-integration-tests -- --ignored`.
+
+#![deny(clippy::disallowed_methods, clippy::suspicious, clippy::style)]
+#![warn(clippy::pedantic, clippy::cargo)]
+#![allow(clippy::module_name_repetitions, clippy::missing_errors_doc)]
+
+use std::env;
+
+use holaplex_hub_nfts_solana::{
+    cluster::{Clusters, TestClusterArgs},
+    events::Processor,
+    metrics::Metrics,
+    solana::SolanaArgs,
+};
+use holaplex_hub_nfts_solana_core::{
+    db::{Connection, DbArgs},
+    proto::{
+        nft_events::Event as NftEvent,
+        treasury_events::{Event as TreasuryEvent, SolanaTransactionResult, TransactionStatus},
+        NftEventKey, NftEvents, SolanaNftEventKey, SolanaNftEvents, SolanaPendingTransaction,
+        TreasuryEventKey, TreasuryEvents,
+    },
+    sea_orm::{ConnectionTrait, Database},
+    Outbox, Services,
+};
+use hub_core::{clap, prelude::*, tokio, uuid::Uuid};
+use migration::{Migrator, MigratorTrait};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const DEFAULT_VALIDATOR_URL: &str = "http://127.0.0.1:8899";
+const AIRDROP_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+const MAX_OUTBOX_EVENTS: u64 = 1_000;
+
+/// Consumer configuration, parsed the same way the service parses it
+#[derive(clap::Parser)]
+struct HarnessArgs {
+    #[command(flatten)]
+    solana: SolanaArgs,
+
+    #[command(flatten)]
+    test_cluster: TestClusterArgs,
+}
+
+/// A processor wired to a throwaway database and a funded local wallet. The
+/// wallet is both the treasury paying for transactions and the signer the
+/// treasury service would otherwise provide.
+pub struct TestContext {
+    pub db: Connection,
+    pub processor: Processor,
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    server_url: String,
+    database: String,
+}
+
+impl TestContext {
+    /// Create and migrate a database, fund a new payer on the validator and
+    /// build a processor against both.
+    pub async fn new() -> Result<Self> {
+        let admin_url =
+            env::var("DATABASE_URL").context("DATABASE_URL must point at a Postgres server")?;
+        let validator_url = env::var("SOLANA_TEST_VALIDATOR_URL")
+            .unwrap_or_else(|_| DEFAULT_VALIDATOR_URL.to_owned());
+
+        let (server_url, _) = admin_url
+            .rsplit_once('/')
+            .context("DATABASE_URL has no database name")?;
+        let server_url = server_url.to_owned();
+        let database = format!("nfts_solana_test_{}", Uuid::new_v4().simple());
+
+        Database::connect(&admin_url)
+            .await?
+            .execute_unprepared(&format!("CREATE DATABASE {database}"))
+            .await?;
+
+        let db = Connection::new(DbArgs {
+            max_connections: 5,
+            min_connections: 0,
+            connection_timeout: 10,
+            acquire_timeout: 10,
+            idle_timeout: 10,
+            statement_timeout: None,
+            database_url: format!("{server_url}/{database}"),
+            database_read_replica_urls: vec![],
+        })
+        .await?;
+
+        Migrator::up(db.get(), None).await?;
+
+        let rpc =
+            RpcClient::new_with_commitment(validator_url.clone(), CommitmentConfig::confirmed());
+        let payer = Keypair::new();
+        airdrop(&rpc, &payer.pubkey()).await?;
+
+        let payer_address = payer.pubkey().to_string();
+        let unused = Pubkey::new_unique().to_string();
+        let HarnessArgs {
+            solana,
+            test_cluster,
+        } = <HarnessArgs as clap::Parser>::try_parse_from([
+            "integration-tests",
+            "--solana-endpoint",
+            &validator_url,
+            "--solana-treasury-wallet-address",
+            &payer_address,
+            "--digital-asset-api-endpoint",
+            &validator_url,
+            "--tree-authority",
+            &unused,
+            "--merkle-tree",
+            &unused,
+            "--submit-commitment",
+            "confirmed",
+        ])?;
+
+        let metrics = Metrics::new()?;
+        let clusters = Clusters::new(solana, test_cluster, &metrics)?;
+        let processor = Processor::new(clusters, db.clone(), metrics, None, None, None);
+
+        Ok(Self {
+            db,
+            processor,
+            rpc,
+            payer,
+            project_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            server_url,
+            database,
+        })
+    }
+
+    /// Process a request from the nfts service for the record `id`
+    pub async fn send_nft_event(&self, id: Uuid, event: NftEvent) -> Result<()> {
+        let key = NftEventKey {
+            id: id.to_string(),
+            user_id: self.user_id.to_string(),
+            project_id: self.project_id.to_string(),
+            correlation_id: id.to_string(),
+        };
+
+        self.processor
+            .process(Services::Nfts(key, NftEvents { event: Some(event) }))
+            .await
+    }
+
+    /// Process a response from the treasury service for the record `id`
+    pub async fn send_treasury_event(&self, id: Uuid, event: TreasuryEvent) -> Result<()> {
+        let key = TreasuryEventKey {
+            id: id.to_string(),
+            user_id: self.user_id.to_string(),
+            project_id: self.project_id.to_string(),
+            correlation_id: id.to_string(),
+        };
+
+        self.processor
+            .process(Services::Treasury(key, TreasuryEvents {
+                event: Some(event),
+            }))
+            .await
+    }
+
+    /// Every event queued in the outbox, oldest first
+    pub async fn events(&self) -> Result<Vec<(SolanaNftEventKey, SolanaNftEvents)>> {
+        Outbox::find_pending(self.db.get(), MAX_OUTBOX_EVENTS)
+            .await?
+            .into_iter()
+            .map(|model| {
+                Ok((
+                    SolanaNftEventKey::decode(&*model.key)?,
+                    SolanaNftEvents::decode(&*model.payload)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Sign a pending transaction with the payer, standing in for the
+    /// treasury service. Signatures already present are kept.
+    pub fn sign(&self, pending: &SolanaPendingTransaction) -> SolanaTransactionResult {
+        let payer = self.payer.pubkey().to_string();
+        let signed_message_signatures = pending
+            .signatures_or_signers_public_keys
+            .iter()
+            .map(|s| {
+                if *s == payer {
+                    self.payer
+                        .sign_message(&pending.serialized_message)
+                        .to_string()
+                } else {
+                    s.clone()
+                }
+            })
+            .collect();
+
+        SolanaTransactionResult {
+            serialized_message: Some(pending.serialized_message.clone()),
+            signed_message_signatures,
+            status: TransactionStatus::Completed as i32,
+        }
+    }
+
+    /// Drop the database created for this context
+    pub async fn teardown(self) -> Result<()> {
+        let Self {
+            db,
+            server_url,
+            database,
+            ..
+        } = self;
+
+        drop(db);
+
+        Database::connect(format!("{server_url}/postgres"))
+            .await?
+            .execute_unprepared(&format!("DROP DATABASE IF EXISTS {database} WITH (FORCE)"))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Fund `address` and wait for the airdrop to be confirmed
+async fn airdrop(rpc: &RpcClient, address: &Pubkey) -> Result<()> {
+    let signature = rpc.request_airdrop(address, AIRDROP_LAMPORTS).await?;
+
+    for _ in 0..60 {
+        if rpc.confirm_transaction(&signature).await? {
+            return Ok(());
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    bail!("airdrop to {address} was not confirmed")
+}
@@ -0,0 +1,80 @@
+use holaplex_hub_nfts_solana_core::{
+    proto::{
+        nft_events::Event as NftEvent, solana_nft_events::Event as SolanaNftEvent,
+        treasury_events::Event as TreasuryEvent, Creator, MasterEdition,
+        MetaplexMasterEditionTransaction,
+    },
+    Collection,
+};
+use holaplex_hub_nfts_solana_integration_tests::TestContext;
+use hub_core::{prelude::*, uuid::Uuid};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+#[ignore = "requires Postgres and solana-test-validator"]
+async fn create_collection_lands_on_chain() -> Result<()> {
+    let ctx = TestContext::new().await?;
+    let id = Uuid::new_v4();
+    let owner = ctx.payer.pubkey().to_string();
+
+    ctx.send_nft_event(
+        id,
+        NftEvent::SolanaCreateCollection(MetaplexMasterEditionTransaction {
+            master_edition: Some(MasterEdition {
+                name: "Integration".to_owned(),
+                symbol: "TEST".to_owned(),
+                seller_fee_basis_points: 500,
+                metadata_uri: "https://example.com/collection.json".to_owned(),
+                creators: vec![Creator {
+                    address: owner.clone(),
+                    verified: true,
+                    share: 100,
+                }],
+                supply: Some(0),
+                owner_address: owner.clone(),
+            }),
+            ..Default::default()
+        }),
+    )
+    .await?;
+
+    let collection = Collection::find_by_id(ctx.db.get(), id)
+        .await?
+        .context("collection was not recorded")?;
+    assert_eq!(collection.owner, owner);
+
+    let pending = ctx
+        .events()
+        .await?
+        .into_iter()
+        .find_map(|(_, e)| match e.event {
+            Some(SolanaNftEvent::CreateCollectionSigningRequested(tx)) => Some(tx),
+            _ => None,
+        })
+        .context("no signing request was emitted")?;
+
+    ctx.send_treasury_event(
+        id,
+        TreasuryEvent::SolanaCreateCollectionSigned(ctx.sign(&pending)),
+    )
+    .await?;
+
+    let submitted = ctx
+        .events()
+        .await?
+        .into_iter()
+        .find_map(|(key, e)| match e.event {
+            Some(SolanaNftEvent::CreateCollectionSubmitted(tx)) => Some((key, tx)),
+            _ => None,
+        })
+        .context("collection was not submitted")?;
+    let (key, submitted) = submitted;
+
+    assert_eq!(key.id, id.to_string());
+    assert_eq!(submitted.address, collection.mint);
+
+    let metadata = ctx.rpc.get_account(&collection.metadata.parse()?).await?;
+    assert_eq!(metadata.owner, mpl_token_metadata::ID);
+
+    ctx.teardown().await
+}